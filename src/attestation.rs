@@ -1,52 +1,92 @@
 //! Capability attestation and verification functionality
 
-use sha2::{Sha256, Digest};
-use std::time::{SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashSet};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::{Duration, Instant};
 
+use crate::capabilities::Capabilities;
+use crate::clock;
 use crate::constants::*;
-use crate::types::{ToolCapability, CapabilityAttestation};
+use crate::descriptor::canonicalize;
+use crate::error::{CapabilityError, VerificationError};
+use crate::formats::{ArtifactFormat, ReportFormat};
+use crate::models::ModelCapability;
+use crate::payload::PayloadLimits;
+use crate::platform::Platform;
+use crate::priming::WarmAssetKind;
+use crate::registry::CapabilityRegistry;
+use crate::revocation_checker::RevocationStatus;
+use crate::sandbox::SandboxCapability;
+use crate::scope::CapabilityScope;
+use crate::signing::{
+    encode_fields, signed_message, AlgorithmRegistry, AttestationSigner, AttestationVerifier,
+};
+use crate::types::{
+    CapabilityAttestation, CapabilityExpiration, CapabilityPermissions, DeprecationInfo,
+    ExpirationTolerance, ToolCapability,
+};
+use crate::wasi::WasiModule;
 
 impl ToolCapability {
     /// Generate capability hash for attestation
     pub fn generate_capability_hash(&self) -> String {
         let mut hasher = Sha256::new();
-        hasher.update(self.tool_name.as_bytes());
-        hasher.update(self.required.to_string().as_bytes());
-        hasher.update(self.alternatives.join(",").as_bytes());
-        hasher.update(self.permissions.filesystem_access.to_string().as_bytes());
-        hasher.update(self.permissions.network_access.to_string().as_bytes());
-        hasher.update(self.permissions.process_spawn.to_string().as_bytes());
-        hasher.update(self.permissions.env_access.to_string().as_bytes());
-        hasher.update(self.permissions.system_access.to_string().as_bytes());
-        hasher.update(self.permissions.memory_limit_mb.to_string().as_bytes());
-        hasher.update(self.permissions.cpu_limit_percent.to_string().as_bytes());
-        hasher.update(self.permissions.timeout_seconds.to_string().as_bytes());
-        hasher.update(self.expiration.expires_at.to_string().as_bytes());
-        
+        let permissions = &self.permissions;
+        hasher.update(encode_fields([
+            self.tool_name.clone(),
+            self.required.to_string(),
+            encode_fields(&self.alternatives),
+            permissions.filesystem_access.to_string(),
+            permissions.network_access.to_string(),
+            permissions.process_spawn.to_string(),
+            permissions.env_access.to_string(),
+            permissions.system_access.to_string(),
+            permissions.memory_limit_mb.to_string(),
+            permissions.cpu_limit_percent.to_string(),
+            permissions.timeout_seconds.to_string(),
+            self.expiration.expires_at.to_string(),
+        ]));
+        // Optional sections are tagged so one cannot pass for another
+        let mut section = |tag: &str, value: &str| hasher.update(encode_fields([tag, value]));
+        if !self.permissions.filesystem_paths.is_empty() {
+            section("paths", &self.permissions.canonical_path_policy());
+        }
+        if !self.permissions.egress_allowlist.is_empty() {
+            section("egress", &self.permissions.canonical_egress_policy());
+        }
+        if !self.scope.is_unrestricted() {
+            section("scope", &self.scope.canonical());
+        }
+        if let Some(wasi) = &self.wasi {
+            section("wasi", &wasi.module_path);
+        }
+        if let Some(requirement) = &self.version_req {
+            section("version_req", &requirement.to_string());
+            // Checked against the constraint when no version is detected
+            if let Some(version) = &self.version {
+                section("version", version);
+            }
+        }
+        if let Some(binary_sha256) = &self.binary_sha256 {
+            section("binary_sha256", binary_sha256);
+        }
+        if !self.platforms.is_empty() {
+            section("platforms", &self.canonical_platforms());
+        }
+
         format!("{:x}", hasher.finalize())
     }
 
-    /// Create attestation for this capability
-    pub fn create_attestation(&self, signer_private_key: &str, attester: String) -> CapabilityAttestation {
-        let capability_hash = self.generate_capability_hash();
-        let timestamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
-        
-        // In a real implementation, use proper cryptographic signing
-        // For now, create a mock signature
-        let signature = format!("signature_{}_{}", capability_hash, timestamp);
-        let public_key = format!("pubkey_{}", signer_private_key);
-
-        CapabilityAttestation {
-            capability_hash,
-            signature,
-            public_key,
-            timestamp,
-            algorithm: DEFAULT_ATTESTATION_ALGORITHM.to_string(),
-            attester,
-        }
+    /// Create a mock attestation for this capability
+    #[cfg(feature = "test-signing")]
+    pub fn create_attestation(
+        &self,
+        signer_private_key: &str,
+        attester: String,
+    ) -> CapabilityAttestation {
+        crate::signing::CapabilitySigner::new(signer_private_key, attester).attest(self)
     }
 
     /// Verify the capability hash matches the attestation
@@ -59,42 +99,424 @@ impl ToolCapability {
         }
     }
 
-    /// Check if attestation is valid and not tampered with
+    /// Check if attestation is valid and not tampered with, using the built-in algorithms
+    #[deprecated(note = "use `verify_attestation_with` and an explicit verifier")]
     pub fn verify_attestation_integrity(&self) -> bool {
-        if let Some(attestation) = &self.attestation {
-            // Check if attestation is not expired
-            let current_time = SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap()
-                .as_secs();
-            
-            if current_time - attestation.timestamp > ATTESTATION_EXPIRY_DAYS * 24 * 60 * 60 {
-                return false; // Attestation expired
-            }
+        self.verify_attestation_with(AlgorithmRegistry::builtin())
+    }
 
-            // Verify attestation algorithm
-            if attestation.algorithm != DEFAULT_ATTESTATION_ALGORITHM {
-                return false;
-            }
+    /// Check the attestation signature with a verifier
+    pub fn verify_attestation_with(&self, verifier: &dyn AttestationVerifier) -> bool {
+        self.try_verify_attestation_with(verifier).is_ok()
+    }
 
-            // Verify capability hash matches
-            if !self.verify_capability_hash() {
-                return false;
-            }
+    /// Check the attestation signature with a verifier, reporting why it failed
+    ///
+    /// The attestation must also cover the current capability hash.
+    pub fn try_verify_attestation_with(
+        &self,
+        verifier: &dyn AttestationVerifier,
+    ) -> Result<(), VerificationError> {
+        let _clock = verifier.clock().map(clock::enter);
+        let attestation =
+            self.attestation
+                .as_ref()
+                .ok_or_else(|| VerificationError::MissingAttestation {
+                    tool: self.tool_name.clone(),
+                })?;
+        if !self.verify_capability_hash() {
+            return Err(VerificationError::HashMismatch {
+                tool: self.tool_name.clone(),
+            });
+        }
+        attestation.check_with(verifier, &self.tool_name)
+    }
 
-            // In a real implementation, verify the actual signature
-            // For now, just check that attestation exists and is not empty
-            !attestation.signature.is_empty() && !attestation.public_key.is_empty()
-        } else {
-            false // No attestation means not verified
+    /// Re-sign the current capability hash before the attestation expires
+    ///
+    /// The current attestation must still verify with `verifier` and cover the capability,
+    /// and the capability must be neither expired nor revoked, so renewal
+    /// never launders a tampered or withdrawn capability. Counter-signatures
+    /// cover the replaced attestation and are dropped.
+    pub fn renew_attestation(
+        &mut self,
+        signer: &dyn AttestationSigner,
+        verifier: &dyn AttestationVerifier,
+    ) -> Result<(), VerificationError> {
+        let expired = self
+            .try_is_expired()
+            .map_err(|e| VerificationError::from_clock(e, &self.tool_name))?;
+        if expired {
+            return Err(VerificationError::Expired {
+                tool: self.tool_name.clone(),
+            });
+        }
+        if self.is_revoked() {
+            return Err(VerificationError::Revoked {
+                tool: self.tool_name.clone(),
+                reason: self.expiration.revocation_reason.clone(),
+            });
+        }
+        self.try_verify_attestation_with(verifier)?;
+
+        self.attestation = Some(signer.attest(self));
+        self.countersignatures.clear();
+        self.verified = true;
+        Ok(())
+    }
+}
+
+impl CapabilityAttestation {
+    /// Age of the attestation in seconds
+    ///
+    /// Fails with `ClockSkew` if the clock is before the Unix epoch or the
+    /// attestation is dated further in the future than the tolerated skew.
+    pub fn age_seconds(&self) -> Result<u64, CapabilityError> {
+        clock::age(self.timestamp)
+    }
+
+    /// Check if the attestation is older than the attestation expiry
+    ///
+    /// An attestation whose age cannot be determined counts as expired.
+    pub fn is_expired(&self) -> bool {
+        self.age_seconds()
+            .map_or(true, |age| age > ATTESTATION_EXPIRY_DAYS * 24 * 60 * 60)
+    }
+
+    /// Check if the attestation is expired, granting a verifier's tolerances
+    pub fn is_expired_within(&self, tolerance: ExpirationTolerance) -> bool {
+        self.check_age("", tolerance).is_err()
+    }
+
+    /// Message covered by the attestation signature
+    ///
+    /// Covers the algorithm, attester, hash and timestamp, plus the counter
+    /// and nonce if present.
+    pub fn signed_message(&self) -> String {
+        let mut message = signed_message(
+            &self.algorithm,
+            &self.attester,
+            &self.capability_hash,
+            self.timestamp,
+        );
+        if let Some(counter) = self.counter {
+            message.push_str(&encode_fields([format!("c{}", counter)]));
+        }
+        if let Some(nonce) = &self.nonce {
+            message.push_str(&encode_fields([format!("n{}", nonce)]));
+        }
+        message
+    }
+
+    /// Check the age of the attestation and its signature with a verifier
+    ///
+    /// `subject` is the tool name (or worker ID) reported in errors. The age
+    /// is checked with the verifier's expiration tolerance.
+    pub(crate) fn check_with(
+        &self,
+        verifier: &dyn AttestationVerifier,
+        subject: &str,
+    ) -> Result<(), VerificationError> {
+        let _clock = verifier.clock().map(clock::enter);
+        self.check_age(subject, verifier.expiration_tolerance())?;
+
+        if !verifier.supports(&self.algorithm) {
+            return Err(VerificationError::UnsupportedAlgorithm {
+                tool: subject.to_string(),
+                algorithm: self.algorithm.clone(),
+            });
+        }
+        if self.signature.is_empty() || self.public_key.is_empty() {
+            return Err(VerificationError::MissingSignature {
+                tool: subject.to_string(),
+            });
+        }
+        if !verifier.verify_attestation(self) {
+            return Err(VerificationError::InvalidSignature {
+                tool: subject.to_string(),
+            });
+        }
+        if !verifier.check_freshness(self) {
+            return Err(VerificationError::StaleAttestation {
+                tool: subject.to_string(),
+            });
+        }
+        match verifier.check_revocation(self) {
+            Ok(RevocationStatus::Good) => Ok(()),
+            Ok(RevocationStatus::Revoked { reason }) => Err(VerificationError::Revoked {
+                tool: subject.to_string(),
+                reason: Some(reason),
+            }),
+            Err(error) => Err(VerificationError::RevocationUnavailable {
+                tool: subject.to_string(),
+                reason: error.to_string(),
+            }),
+        }
+    }
+
+    fn check_age(
+        &self,
+        subject: &str,
+        tolerance: ExpirationTolerance,
+    ) -> Result<(), VerificationError> {
+        let age = clock::unix_timestamp()
+            .and_then(|now| clock::age_within(self.timestamp, now, tolerance.skew_tolerance()))
+            .map_err(|e| VerificationError::from_clock(e, subject))?;
+        let max_age = (ATTESTATION_EXPIRY_DAYS * 24 * 60 * 60)
+            .saturating_add(tolerance.skew_tolerance())
+            .saturating_add(tolerance.grace_period());
+        if age > max_age {
+            return Err(VerificationError::AttestationExpired {
+                tool: subject.to_string(),
+            });
         }
+        Ok(())
     }
 }
 
+impl Capabilities {
+    /// Add a tool to a category, attested by the given signer
+    ///
+    /// Like `with_tool_in`, user-defined categories are declared on first use.
+    pub fn with_attested_tool(
+        mut self,
+        capability_type: &str,
+        tool: impl Into<String>,
+        required: bool,
+        signer: &dyn AttestationSigner,
+    ) -> Self {
+        let tool = ToolCapability::new(tool, required);
+        let attestation = signer.attest(&tool);
+        self.add_tools_from(capability_type, &[tool.with_attestation(attestation)]);
+        self
+    }
+
+    /// Attest every tool in this capability set with the given signer
+    pub fn attest_all_tools(mut self, signer: &dyn AttestationSigner) -> Self {
+        for tool in self.tools_iter_mut() {
+            tool.attestation = Some(signer.attest(tool));
+            tool.countersignatures.clear();
+            tool.verified = true;
+        }
+        self
+    }
+
+    /// SHA256 digest of the whole capability set
+    ///
+    /// The digest covers the declared contents of the set (tools, flags,
+    /// metadata, sections and tool attestations), but not the set attestation
+    /// itself or state the registry updates at runtime: tool `verified` and
+    /// `suspended`, `network.probed_at`, `warm_assets[].primed_at` and
+    /// `environment.captured_at`. Map keys are sorted and tools, tags and
+    /// computed flags are ordered by name, so it does not depend on
+    /// declaration or serialization order.
+    pub fn canonical_hash(&self) -> Result<String, serde_json::Error> {
+        let canonical = serde_json::to_value(CanonicalSet::of(self))?;
+        let mut hasher = Sha256::new();
+        hasher.update(canonicalize(canonical).to_string().as_bytes());
+        Ok(format!("{:x}", hasher.finalize()))
+    }
+
+    /// Attest the whole capability set with the given signer
+    ///
+    /// Attest tools first: any later change to the set, including new tool
+    /// attestations, invalidates the set attestation.
+    pub fn attest(mut self, signer: &dyn AttestationSigner) -> Result<Self, serde_json::Error> {
+        self.attestation = Some(signer.attest_hash(&self.canonical_hash()?));
+        Ok(self)
+    }
+
+    /// Check the set attestation signature with a verifier
+    pub fn verify_set_attestation_with(&self, verifier: &dyn AttestationVerifier) -> bool {
+        self.try_verify_set_attestation_with(verifier).is_ok()
+    }
+
+    /// Check the set attestation signature with a verifier, reporting why it failed
+    pub fn try_verify_set_attestation_with(
+        &self,
+        verifier: &dyn AttestationVerifier,
+    ) -> Result<(), VerificationError> {
+        let attestation =
+            self.attestation
+                .as_ref()
+                .ok_or_else(|| VerificationError::MissingAttestation {
+                    tool: self.id.clone(),
+                })?;
+        attestation.check_with(verifier, &self.id)?;
+
+        if self.canonical_hash().ok().as_ref() != Some(&attestation.capability_hash) {
+            return Err(VerificationError::HashMismatch {
+                tool: self.id.clone(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Declared contents of a capability set, as covered by `canonical_hash`
+#[derive(Serialize)]
+struct CanonicalSet<'a> {
+    id: &'a str,
+    tools: BTreeMap<String, Vec<CanonicalTool<'a>>>,
+    flags: BTreeMap<&'a str, bool>,
+    computed_flags: Vec<&'a str>,
+    metadata: BTreeMap<&'a str, &'a str>,
+    network_internet: bool,
+    network_endpoints: &'a [String],
+    sandbox: &'a SandboxCapability,
+    payload: &'a PayloadLimits,
+    accepts: &'a [ArtifactFormat],
+    emits: &'a [ReportFormat],
+    models: &'a [ModelCapability],
+    warm_assets: Vec<(&'a WarmAssetKind, &'a str)>,
+    scope: &'a CapabilityScope,
+    environment: Option<CanonicalEnvironment<'a>>,
+    template: Option<&'a str>,
+    tags: Vec<&'a str>,
+}
+
+impl<'a> CanonicalSet<'a> {
+    fn of(caps: &'a Capabilities) -> Self {
+        let tools = caps
+            .category_names()
+            .into_iter()
+            .map(|category| {
+                let mut tools: Vec<CanonicalTool> = caps
+                    .tools_in_category(&category)
+                    .iter()
+                    .map(CanonicalTool::of)
+                    .collect();
+                tools.sort_by(|a, b| a.tool_name.cmp(b.tool_name));
+                (category, tools)
+            })
+            .collect();
+
+        Self {
+            id: &caps.id,
+            tools,
+            flags: caps
+                .flags
+                .iter()
+                .map(|(flag, value)| (flag.as_str(), *value))
+                .collect(),
+            computed_flags: sorted(&caps.computed_flags),
+            metadata: caps
+                .metadata
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.as_str()))
+                .collect(),
+            network_internet: caps.network.internet,
+            network_endpoints: &caps.network.endpoints,
+            sandbox: &caps.sandbox,
+            payload: &caps.payload,
+            accepts: &caps.accepts,
+            emits: &caps.emits,
+            models: &caps.models,
+            warm_assets: caps
+                .warm_assets
+                .iter()
+                .map(|asset| (&asset.kind, asset.identifier.as_str()))
+                .collect(),
+            scope: &caps.scope,
+            environment: caps
+                .environment
+                .as_ref()
+                .map(|environment| CanonicalEnvironment {
+                    os: &environment.os,
+                    arch: &environment.arch,
+                    kernel: environment.kernel.as_deref(),
+                    container_image_digest: environment.container_image_digest.as_deref(),
+                    env_vars: &environment.env_vars,
+                }),
+            template: caps.template.as_deref(),
+            tags: sorted(&caps.tags),
+        }
+    }
+}
+
+/// Declared fields of a tool, as covered by `Capabilities::canonical_hash`
+#[derive(Serialize)]
+struct CanonicalTool<'a> {
+    tool_name: &'a str,
+    required: bool,
+    alternatives: &'a [String],
+    attestation: Option<&'a CapabilityAttestation>,
+    countersignatures: &'a [CapabilityAttestation],
+    permissions: &'a CapabilityPermissions,
+    expiration: &'a CapabilityExpiration,
+    version: Option<&'a str>,
+    checkpointing: bool,
+    max_concurrent: Option<u32>,
+    scope: &'a CapabilityScope,
+    wasi: Option<&'a WasiModule>,
+    version_req: Option<String>,
+    binary_sha256: Option<&'a str>,
+    platforms: &'a [Platform],
+    tags: Vec<&'a str>,
+    metadata: BTreeMap<&'a str, &'a str>,
+    preferences: &'a BTreeMap<String, i32>,
+    deprecated: Option<&'a DeprecationInfo>,
+}
+
+impl<'a> CanonicalTool<'a> {
+    fn of(tool: &'a ToolCapability) -> Self {
+        Self {
+            tool_name: &tool.tool_name,
+            required: tool.required,
+            alternatives: &tool.alternatives,
+            attestation: tool.attestation.as_ref(),
+            countersignatures: &tool.countersignatures,
+            permissions: &tool.permissions,
+            expiration: &tool.expiration,
+            version: tool.version.as_deref(),
+            checkpointing: tool.checkpointing,
+            max_concurrent: tool.max_concurrent,
+            scope: &tool.scope,
+            wasi: tool.wasi.as_ref(),
+            version_req: tool
+                .version_req
+                .as_ref()
+                .map(|requirement| requirement.to_string()),
+            binary_sha256: tool.binary_sha256.as_deref(),
+            platforms: &tool.platforms,
+            tags: sorted(&tool.tags),
+            metadata: tool
+                .metadata
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.as_str()))
+                .collect(),
+            preferences: &tool.preferences,
+            deprecated: tool.deprecated.as_ref(),
+        }
+    }
+}
+
+/// Declared fields of an environment snapshot (without its capture time)
+#[derive(Serialize)]
+struct CanonicalEnvironment<'a> {
+    os: &'a str,
+    arch: &'a str,
+    kernel: Option<&'a str>,
+    container_image_digest: Option<&'a str>,
+    env_vars: &'a BTreeMap<String, String>,
+}
+
+fn sorted(values: &[String]) -> Vec<&str> {
+    let mut values: Vec<&str> = values.iter().map(String::as_str).collect();
+    values.sort_unstable();
+    values
+}
+
 /// Attestation manager for handling multiple attestations
 pub struct AttestationManager {
     /// Map of tool names to their attestations
     attestations: std::collections::HashMap<String, CapabilityAttestation>,
+    /// What `sweep` does with expired attestations
+    sweep_action: SweepAction,
+    /// Tools whose expired attestations were flagged by `sweep`
+    flagged: HashSet<String>,
+    /// Channels notified of sweep events
+    subscribers: Vec<Sender<SweepEvent>>,
 }
 
 impl AttestationManager {
@@ -102,11 +524,21 @@ impl AttestationManager {
     pub fn new() -> Self {
         Self {
             attestations: std::collections::HashMap::new(),
+            sweep_action: SweepAction::default(),
+            flagged: HashSet::new(),
+            subscribers: Vec::new(),
         }
     }
 
+    /// Set what `sweep` does with expired attestations
+    pub fn with_sweep_action(mut self, action: SweepAction) -> Self {
+        self.sweep_action = action;
+        self
+    }
+
     /// Add an attestation for a tool
     pub fn add_attestation(&mut self, tool_name: String, attestation: CapabilityAttestation) {
+        self.flagged.remove(&tool_name);
         self.attestations.insert(tool_name, attestation);
     }
 
@@ -115,43 +547,84 @@ impl AttestationManager {
         self.attestations.get(tool_name)
     }
 
-    /// Verify all attestations are valid
+    /// Iterate over tool names and their attestations
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &CapabilityAttestation)> {
+        self.attestations.iter()
+    }
+
+    /// Verify all attestations with the built-in algorithms
+    #[deprecated(note = "use `verify_all_attestations_with` and an explicit verifier")]
     pub fn verify_all_attestations(&self) -> bool {
-        let current_time = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
-
-        for attestation in self.attestations.values() {
-            // Check if attestation is not expired
-            if current_time - attestation.timestamp > ATTESTATION_EXPIRY_DAYS * 24 * 60 * 60 {
-                return false;
-            }
+        self.verify_all_attestations_with(AlgorithmRegistry::builtin())
+    }
 
-            // Verify attestation algorithm
-            if attestation.algorithm != DEFAULT_ATTESTATION_ALGORITHM {
-                return false;
-            }
+    /// Verify all attestations with a verifier
+    pub fn verify_all_attestations_with(&self, verifier: &dyn AttestationVerifier) -> bool {
+        self.try_verify_all_attestations_with(verifier).is_ok()
+    }
 
-            // Check that signature and public key are not empty
-            if attestation.signature.is_empty() || attestation.public_key.is_empty() {
-                return false;
-            }
+    /// Verify all attestations with a verifier, reporting the first one that fails
+    ///
+    /// Attestations are checked in tool name order.
+    pub fn try_verify_all_attestations_with(
+        &self,
+        verifier: &dyn AttestationVerifier,
+    ) -> Result<(), VerificationError> {
+        let mut attestations: Vec<_> = self.attestations.iter().collect();
+        attestations.sort_by(|a, b| a.0.cmp(b.0));
+        for (tool_name, attestation) in attestations {
+            attestation.check_with(verifier, tool_name)?;
         }
+        Ok(())
+    }
+
+    /// Re-sign attestations that expire within a duration
+    ///
+    /// Each attestation must still verify with `verifier`; its capability hash is signed
+    /// again by `signer`. Returns an event per attestation renewed or that
+    /// could not be renewed (e.g., already expired or invalid), in tool name
+    /// order.
+    pub fn renew_expiring(
+        &mut self,
+        within: Duration,
+        signer: &dyn AttestationSigner,
+        verifier: &dyn AttestationVerifier,
+    ) -> Vec<RenewalEvent> {
+        let expiry_seconds = ATTESTATION_EXPIRY_DAYS * 24 * 60 * 60;
+        let mut tool_names: Vec<String> = self.attestations.keys().cloned().collect();
+        tool_names.sort();
 
-        true
+        let mut events = Vec::new();
+        for tool_name in tool_names {
+            let Some(attestation) = self.attestations.get_mut(&tool_name) else {
+                continue;
+            };
+            let expiring = attestation.check_with(verifier, &tool_name).and_then(|()| {
+                let age = attestation
+                    .age_seconds()
+                    .map_err(|e| VerificationError::from_clock(e, &tool_name))?;
+                Ok(age.saturating_add(within.as_secs()) >= expiry_seconds)
+            });
+            match expiring {
+                Ok(false) => {}
+                Ok(true) => {
+                    *attestation = signer.attest_hash(&attestation.capability_hash);
+                    events.push(RenewalEvent::Renewed { tool: tool_name });
+                }
+                Err(error) => events.push(RenewalEvent::Failed {
+                    tool: tool_name,
+                    error,
+                }),
+            }
+        }
+        events
     }
 
     /// Get expired attestations
     pub fn get_expired_attestations(&self) -> Vec<String> {
-        let current_time = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
-
         let mut expired = Vec::new();
         for (tool_name, attestation) in &self.attestations {
-            if current_time - attestation.timestamp > ATTESTATION_EXPIRY_DAYS * 24 * 60 * 60 {
+            if attestation.is_expired() {
                 expired.push(tool_name.clone());
             }
         }
@@ -159,13 +632,65 @@ impl AttestationManager {
         expired
     }
 
+    /// Prune or flag expired attestations, notifying subscribers
+    ///
+    /// Returns the events of this sweep in tool name order. A flagged
+    /// attestation is reported once and unflagged when it is replaced.
+    pub fn sweep(&mut self) -> Vec<SweepEvent> {
+        let mut expired = self.get_expired_attestations();
+        expired.sort();
+        self.flagged.retain(|tool_name| expired.contains(tool_name));
+
+        let mut events = Vec::new();
+        for tool_name in expired {
+            match self.sweep_action {
+                SweepAction::Prune => {
+                    if let Some(attestation) = self.attestations.remove(&tool_name) {
+                        events.push(SweepEvent::Pruned {
+                            tool: tool_name,
+                            attestation,
+                        });
+                    }
+                }
+                SweepAction::Flag => {
+                    if self.flagged.insert(tool_name.clone()) {
+                        events.push(SweepEvent::Flagged { tool: tool_name });
+                    }
+                }
+            }
+        }
+
+        self.subscribers.retain(|subscriber| {
+            events
+                .iter()
+                .all(|event| subscriber.send(event.clone()).is_ok())
+        });
+        events
+    }
+
+    /// Receive the events of every later sweep
+    pub fn subscribe(&mut self) -> Receiver<SweepEvent> {
+        let (sender, receiver) = mpsc::channel();
+        self.subscribers.push(sender);
+        receiver
+    }
+
+    /// Tools whose expired attestations were flagged, sorted
+    pub fn flagged_attestations(&self) -> Vec<String> {
+        let mut flagged: Vec<String> = self.flagged.iter().cloned().collect();
+        flagged.sort();
+        flagged
+    }
+
     /// Remove attestation for a tool
     pub fn remove_attestation(&mut self, tool_name: &str) -> Option<CapabilityAttestation> {
+        self.flagged.remove(tool_name);
         self.attestations.remove(tool_name)
     }
 
     /// Clear all attestations
     pub fn clear_all(&mut self) {
+        self.flagged.clear();
         self.attestations.clear();
     }
 
@@ -173,6 +698,217 @@ impl AttestationManager {
     pub fn count(&self) -> usize {
         self.attestations.len()
     }
+
+    /// Re-hash and re-sign the capabilities of every matching worker in a registry
+    ///
+    /// Existing attestations, including the set attestation, must verify with
+    /// `verifier` before they are replaced, and expired or revoked tools are
+    /// not attested; such tools are reported as failures instead. A worker
+    /// whose set attestation fails is skipped. Re-signed sets are registered
+    /// again with `try_register`, so counters and validation apply; a rejected
+    /// set is reported under the worker ID and keeps its old attestations.
+    /// Each new tool attestation is recorded in this manager under
+    /// `"<worker_id>/<tool_name>"`.
+    pub fn attest_all(
+        &mut self,
+        registry: &mut CapabilityRegistry,
+        signer: &dyn AttestationSigner,
+        verifier: &dyn AttestationVerifier,
+        filter: &dyn Fn(&Capabilities) -> bool,
+        options: &BatchAttestationOptions,
+        on_progress: &dyn Fn(&BatchAttestationProgress),
+    ) -> BatchAttestationSummary {
+        let mut worker_ids: Vec<String> = registry
+            .list_ids()
+            .into_iter()
+            .filter(|id| registry.get(id).map(filter).unwrap_or(false))
+            .collect();
+        worker_ids.sort();
+
+        let interval = options
+            .max_per_second
+            .filter(|rate| *rate > 0)
+            .map(|rate| Duration::from_secs_f64(1.0 / rate as f64));
+        let mut last_signed: Option<Instant> = None;
+        let mut throttle = || {
+            if let (Some(interval), Some(last)) = (interval, last_signed) {
+                let elapsed = last.elapsed();
+                if elapsed < interval {
+                    std::thread::sleep(interval - elapsed);
+                }
+            }
+            last_signed = Some(Instant::now());
+        };
+
+        let mut summary = BatchAttestationSummary {
+            workers_total: worker_ids.len(),
+            ..BatchAttestationSummary::default()
+        };
+
+        for worker_id in &worker_ids {
+            let Some(mut caps) = registry.get(worker_id).cloned() else {
+                continue;
+            };
+            let failure = |tool_name: &str, reason: String| BatchAttestationFailure {
+                worker_id: worker_id.clone(),
+                tool_name: tool_name.to_string(),
+                reason,
+            };
+
+            let set_attested = caps.attestation.is_some();
+            if set_attested {
+                if let Err(error) = caps.try_verify_set_attestation_with(verifier) {
+                    summary.failures.push(failure(worker_id, error.to_string()));
+                    summary.workers_processed += 1;
+                    on_progress(&summary.progress());
+                    continue;
+                }
+            }
+
+            let mut attested = Vec::new();
+            for tool in caps.tools_iter_mut() {
+                if tool.is_revoked() || tool.is_expired() {
+                    let reason = if tool.is_revoked() {
+                        "revoked"
+                    } else {
+                        "expired"
+                    };
+                    summary
+                        .failures
+                        .push(failure(&tool.tool_name, reason.to_string()));
+                    continue;
+                }
+
+                throttle();
+                if tool.attestation.is_some() {
+                    if let Err(error) = tool.renew_attestation(signer, verifier) {
+                        summary
+                            .failures
+                            .push(failure(&tool.tool_name, error.to_string()));
+                        continue;
+                    }
+                } else {
+                    tool.attestation = Some(signer.attest(tool));
+                    tool.countersignatures.clear();
+                    tool.verified = true;
+                }
+                if let Some(attestation) = &tool.attestation {
+                    attested.push((tool.tool_name.clone(), attestation.clone()));
+                }
+            }
+
+            let registered = if set_attested {
+                throttle();
+                caps.attest(signer).map_err(|error| error.to_string())
+            } else {
+                Ok(caps)
+            }
+            .and_then(|caps| {
+                registry.try_register(caps).map_err(|errors| {
+                    errors
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join("; ")
+                })
+            });
+            match registered {
+                Ok(()) => {
+                    summary.tools_attested += attested.len();
+                    for (tool_name, attestation) in attested {
+                        self.attestations
+                            .insert(format!("{}/{}", worker_id, tool_name), attestation);
+                    }
+                }
+                Err(reason) => summary.failures.push(failure(worker_id, reason)),
+            }
+
+            summary.workers_processed += 1;
+            on_progress(&summary.progress());
+        }
+
+        summary
+    }
+}
+
+/// What a sweep does with expired attestations
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SweepAction {
+    /// Remove them from the manager
+    #[default]
+    Prune,
+    /// Keep them but report them as flagged
+    Flag,
+}
+
+/// Expired attestation handled by a sweep
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SweepEvent {
+    /// The attestation was removed
+    Pruned {
+        tool: String,
+        attestation: CapabilityAttestation,
+    },
+    /// The attestation was flagged
+    Flagged { tool: String },
+}
+
+/// Outcome of renewing an attestation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RenewalEvent {
+    /// The attestation was re-signed
+    Renewed { tool: String },
+    /// The attestation could not be renewed
+    Failed {
+        tool: String,
+        error: VerificationError,
+    },
+}
+
+/// Options for batch attestation
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BatchAttestationOptions {
+    /// Maximum number of signatures per second (unlimited if `None`)
+    pub max_per_second: Option<u32>,
+}
+
+/// Progress of a batch attestation run, reported after each worker
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchAttestationProgress {
+    pub workers_total: usize,
+    pub workers_processed: usize,
+    pub tools_attested: usize,
+    pub failures: usize,
+}
+
+/// A tool that could not be attested during a batch run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchAttestationFailure {
+    pub worker_id: String,
+    pub tool_name: String,
+    pub reason: String,
+}
+
+/// Summary of a batch attestation run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BatchAttestationSummary {
+    pub workers_total: usize,
+    pub workers_processed: usize,
+    pub tools_attested: usize,
+    pub failures: Vec<BatchAttestationFailure>,
+}
+
+impl BatchAttestationSummary {
+    /// Progress reported after a worker
+    fn progress(&self) -> BatchAttestationProgress {
+        BatchAttestationProgress {
+            workers_total: self.workers_total,
+            workers_processed: self.workers_processed,
+            tools_attested: self.tools_attested,
+            failures: self.failures.len(),
+        }
+    }
 }
 
 impl Default for AttestationManager {
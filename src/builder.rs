@@ -0,0 +1,248 @@
+//! Fallible builder for capability sets
+//!
+//! The `with_*` builders on `Capabilities` accept anything. A
+//! `CapabilitiesBuilder` records the same declarations and checks them in
+//! `build`:
+//!
+//! ```rust
+//! use worker_capabilities::{Capabilities, CapabilityError};
+//!
+//! let caps = Capabilities::builder("worker-1")
+//!     .with_static_analysis("clippy", true)
+//!     .with_flag("ast_support")
+//!     .build()
+//!     .unwrap();
+//! assert!(caps.has_flag("ast_support"));
+//!
+//! let duplicate = Capabilities::builder("worker-1")
+//!     .with_static_analysis("clippy", true)
+//!     .with_static_analysis("clippy", false)
+//!     .build();
+//! assert!(matches!(duplicate, Err(CapabilityError::DuplicateTool { .. })));
+//! ```
+
+use std::collections::HashMap;
+
+use crate::capabilities::Capabilities;
+use crate::constants::*;
+use crate::defaults::CategoryDefaults;
+use crate::error::CapabilityError;
+use crate::types::ToolCapability;
+
+/// Builder for capability sets whose `build` rejects invalid declarations
+#[derive(Debug, Clone)]
+pub struct CapabilitiesBuilder {
+    caps: Capabilities,
+    category_defaults: CategoryDefaults,
+    default_category: Option<String>,
+    flag_values: HashMap<String, bool>,
+    conflicting_flags: Vec<String>,
+}
+
+impl CapabilitiesBuilder {
+    /// Start building a capability set
+    pub fn new(id: impl Into<String>) -> Self {
+        Self {
+            caps: Capabilities::new(id),
+            category_defaults: CategoryDefaults::default(),
+            default_category: None,
+            flag_values: HashMap::new(),
+            conflicting_flags: Vec::new(),
+        }
+    }
+
+    /// Apply per-category permission baselines to tools added afterwards
+    pub fn with_category_defaults(mut self, defaults: CategoryDefaults) -> Self {
+        self.category_defaults = defaults;
+        self
+    }
+
+    /// Create a tool with the baseline permissions of its category
+    fn new_tool(&self, category: &str, tool: impl Into<String>, required: bool) -> ToolCapability {
+        ToolCapability::new(tool, required)
+            .with_permissions(self.category_defaults.permissions_for(category))
+    }
+
+    /// Set the category `with_tool` adds to
+    pub fn with_default_category(mut self, category: impl Into<String>) -> Self {
+        self.default_category = Some(category.into());
+        self
+    }
+
+    /// Add a tool to the default category
+    ///
+    /// Without `with_default_category`, tools are added to `static_analysis`
+    /// (see `Capabilities::with_tool`).
+    pub fn with_tool(self, tool: impl Into<String>, required: bool) -> Self {
+        let category = self
+            .default_category
+            .clone()
+            .unwrap_or_else(|| CAPABILITY_STATIC_ANALYSIS.to_string());
+        self.with_tool_in(category, tool, required)
+    }
+
+    /// Add a static analysis tool
+    pub fn with_static_analysis(self, tool: impl Into<String>, required: bool) -> Self {
+        self.with_tool_in(CAPABILITY_STATIC_ANALYSIS, tool, required)
+    }
+
+    /// Add a security scanning tool
+    pub fn with_security_tool(self, tool: impl Into<String>, required: bool) -> Self {
+        self.with_tool_in(CAPABILITY_SECURITY_SCANNING, tool, required)
+    }
+
+    /// Add a dynamic analysis tool
+    pub fn with_dynamic_tool(self, tool: impl Into<String>, required: bool) -> Self {
+        self.with_tool_in(CAPABILITY_DYNAMIC_ANALYSIS, tool, required)
+    }
+
+    /// Add a fuzzing tool
+    pub fn with_fuzzing_tool(self, tool: impl Into<String>, required: bool) -> Self {
+        self.with_tool_in(CAPABILITY_FUZZING, tool, required)
+    }
+
+    /// Add a test framework tool
+    pub fn with_test_framework(self, tool: impl Into<String>, required: bool) -> Self {
+        self.with_tool_in(CAPABILITY_TEST_FRAMEWORK, tool, required)
+    }
+
+    /// Add a tool to a category by name, declaring user-defined categories on first use
+    pub fn with_tool_in(
+        self,
+        category: impl Into<String>,
+        tool: impl Into<String>,
+        required: bool,
+    ) -> Self {
+        let category = category.into();
+        let tool = self.new_tool(&category, tool, required);
+        self.with_tool_capability(category, tool)
+    }
+
+    /// Add several tools to a category by name, as `(tool, required)` pairs
+    pub fn with_tools<I, S>(mut self, category: &str, tools: I) -> Self
+    where
+        I: IntoIterator<Item = (S, bool)>,
+        S: Into<String>,
+    {
+        for (tool, required) in tools {
+            self = self.with_tool_in(category, tool, required);
+        }
+        self
+    }
+
+    /// Add a fully configured tool to a category
+    pub fn with_tool_capability(
+        mut self,
+        category: impl Into<String>,
+        tool: ToolCapability,
+    ) -> Self {
+        let category = category.into();
+        match self.caps.tools_in_category_mut(&category) {
+            Some(tools) => tools.push(tool),
+            None => self
+                .caps
+                .custom_tools
+                .entry(category)
+                .or_default()
+                .push(tool),
+        }
+        self
+    }
+
+    /// Add an optional static analysis tool with alternatives
+    pub fn with_alternative(
+        self,
+        tool: impl Into<String>,
+        alternatives: Vec<impl Into<String>>,
+    ) -> Self {
+        self.with_alternative_tool(tool, alternatives, false)
+    }
+
+    /// Add a required static analysis tool satisfied by the tool or any alternative
+    pub fn with_required_alternative(
+        self,
+        tool: impl Into<String>,
+        alternatives: Vec<impl Into<String>>,
+    ) -> Self {
+        self.with_alternative_tool(tool, alternatives, true)
+    }
+
+    fn with_alternative_tool(
+        self,
+        tool: impl Into<String>,
+        alternatives: Vec<impl Into<String>>,
+        required: bool,
+    ) -> Self {
+        let tool = self
+            .new_tool(CAPABILITY_STATIC_ANALYSIS, tool, required)
+            .with_alternatives(alternatives.into_iter().map(|a| a.into()).collect());
+        self.with_tool_capability(CAPABILITY_STATIC_ANALYSIS, tool)
+    }
+
+    /// Enable a flag
+    pub fn with_flag(self, flag: impl Into<String>) -> Self {
+        self.with_flag_value(flag, true)
+    }
+
+    /// Declare a flag as disabled
+    pub fn without_flag(self, flag: impl Into<String>) -> Self {
+        self.with_flag_value(flag, false)
+    }
+
+    /// Set a flag; setting the same flag to both values is a conflict
+    pub fn with_flag_value(mut self, flag: impl Into<String>, enabled: bool) -> Self {
+        let flag = flag.into();
+        if let Some(previous) = self.flag_values.insert(flag.clone(), enabled) {
+            if previous != enabled && !self.conflicting_flags.contains(&flag) {
+                self.conflicting_flags.push(flag.clone());
+            }
+        }
+        self.caps.flags.insert(flag, enabled);
+        self
+    }
+
+    /// Add a tag to the worker
+    pub fn with_tag(mut self, tag: impl Into<String>) -> Self {
+        self.caps = self.caps.with_tag(tag);
+        self
+    }
+
+    /// Add metadata
+    pub fn with_metadata(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.caps = self.caps.with_metadata(key, value);
+        self
+    }
+
+    /// Check the declarations and return the capability set
+    ///
+    /// Fails on a tool declared twice in a category, a flag set to both
+    /// values, or any violation of `Capabilities::validate` (empty ID,
+    /// exceeded limits).
+    pub fn build(self) -> Result<Capabilities, CapabilityError> {
+        for category in self.caps.category_names() {
+            let tools = self.caps.tools_in_category(&category);
+            for (index, tool) in tools.iter().enumerate() {
+                if tools[..index].iter().any(|t| t.tool_name == tool.tool_name) {
+                    return Err(CapabilityError::DuplicateTool {
+                        category,
+                        tool: tool.tool_name.clone(),
+                    });
+                }
+            }
+        }
+
+        if let Some(flag) = self.conflicting_flags.into_iter().next() {
+            return Err(CapabilityError::ConflictingFlag { flag });
+        }
+
+        self.caps.validate().map_err(CapabilityError::Invalid)?;
+        Ok(self.caps)
+    }
+}
+
+impl Capabilities {
+    /// Start a fallible builder for a capability set
+    pub fn builder(id: impl Into<String>) -> CapabilitiesBuilder {
+        CapabilitiesBuilder::new(id)
+    }
+}
@@ -0,0 +1,254 @@
+//! Signed capability bundles for air-gapped worker provisioning
+//!
+//! A `CapabilityBundle` packages a capability set and the attestations of its
+//! tools into one signed document. It can be baked into a worker image at
+//! build time and presented to the orchestrator on registration, which checks
+//! it against its `TrustStore` with `verify_and_extract`.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+use crate::attestation::AttestationManager;
+use crate::capabilities::Capabilities;
+use crate::clock::current_timestamp;
+use crate::constants::*;
+use crate::descriptor::canonicalize;
+use crate::signing::{signed_message, AttestationSigner, AttestationVerifier};
+use crate::trust::TrustStore;
+use crate::types::CapabilityAttestation;
+
+/// Capability set and tool attestations sealed by a single signature
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CapabilityBundle {
+    /// Document format version
+    pub format_version: u32,
+    /// Timestamp when the bundle was created
+    pub created_at: u64,
+    /// Capabilities of the worker
+    pub capabilities: Capabilities,
+    /// Attestations by tool name
+    #[serde(default)]
+    pub attestations: BTreeMap<String, CapabilityAttestation>,
+    /// SHA256 digest of the bundle contents
+    pub digest: String,
+    /// Signature over the digest
+    pub signature: String,
+    /// Public key of the signer
+    pub public_key: String,
+    /// Signer identity
+    pub signer: String,
+    /// Signature algorithm
+    #[serde(default)]
+    pub algorithm: String,
+}
+
+impl CapabilityBundle {
+    /// Package and sign a capability set with the attestations of its tools
+    ///
+    /// Attestations for tools not declared in `caps` are dropped.
+    pub fn create(
+        caps: Capabilities,
+        attestations: &AttestationManager,
+        signer: &dyn AttestationSigner,
+    ) -> Self {
+        let attestations = attestations
+            .iter()
+            .filter(|(tool_name, _)| caps.find_tool(tool_name).is_some())
+            .map(|(tool_name, attestation)| (tool_name.clone(), attestation.clone()))
+            .collect();
+
+        let mut bundle = Self {
+            format_version: CAPABILITY_BUNDLE_FORMAT_VERSION,
+            created_at: current_timestamp(),
+            capabilities: caps,
+            attestations,
+            digest: String::new(),
+            signature: String::new(),
+            public_key: signer.public_key(),
+            signer: signer.attester().to_string(),
+            algorithm: signer.algorithm().to_string(),
+        };
+        bundle.digest = bundle.compute_digest();
+        bundle.signature = signer.sign(bundle.signed_message().as_bytes());
+        bundle
+    }
+
+    /// Message covered by the bundle signature
+    fn signed_message(&self) -> String {
+        signed_message(&self.algorithm, &self.signer, &self.digest, self.created_at)
+    }
+
+    /// Compute the digest of the bundle contents (excluding the signature fields)
+    pub fn compute_digest(&self) -> String {
+        let contents = serde_json::json!({
+            "format_version": self.format_version,
+            "created_at": self.created_at,
+            "capabilities": self.capabilities,
+            "attestations": self.attestations,
+        });
+        let mut hasher = Sha256::new();
+        hasher.update(canonicalize(contents).to_string().as_bytes());
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// Check the digest and signature of the bundle
+    pub fn verify_signature(&self, verifier: &dyn AttestationVerifier) -> Result<(), BundleError> {
+        if self.format_version != CAPABILITY_BUNDLE_FORMAT_VERSION {
+            return Err(BundleError::UnsupportedFormat(self.format_version));
+        }
+        if self.digest != self.compute_digest() {
+            return Err(BundleError::DigestMismatch);
+        }
+        let message = self.signed_message();
+        if self.public_key.is_empty()
+            || !verifier.verify_as(
+                &self.algorithm,
+                &self.public_key,
+                message.as_bytes(),
+                &self.signature,
+            )
+        {
+            return Err(BundleError::InvalidSignature);
+        }
+        Ok(())
+    }
+
+    /// Verify the bundle against a trust store and return its capabilities
+    ///
+    /// The bundle must be signed by a trust anchor or an attester trusted for
+    /// all categories, and neither the worker nor any attested capability may
+    /// be revoked. Each attestation must cover the current hash of its tool;
+    /// with `require_trusted_attester` set, it must also come from an attester
+    /// trusted for the tool's category. The returned capabilities carry the
+    /// attestations on their tools.
+    pub fn verify_and_extract(
+        &self,
+        trust_store: &TrustStore,
+        verifier: &dyn AttestationVerifier,
+    ) -> Result<Capabilities, BundleError> {
+        self.verify_signature(verifier)?;
+
+        if !trust_store.is_trusted_signer(&self.signer, &self.public_key) {
+            return Err(BundleError::UntrustedSigner(self.signer.clone()));
+        }
+
+        let mut caps = self.capabilities.clone();
+        if trust_store.revocation_for(&caps.id, None).is_some() {
+            return Err(BundleError::Revoked(caps.id.clone()));
+        }
+
+        for category in caps.category_names() {
+            let Some(tools) = caps.tools_in_category_mut(&category) else {
+                continue;
+            };
+            for tool in tools.iter_mut() {
+                let Some(attestation) = self.attestations.get(&tool.tool_name) else {
+                    continue;
+                };
+                if attestation.capability_hash != tool.generate_capability_hash() {
+                    return Err(BundleError::AttestationMismatch(tool.tool_name.clone()));
+                }
+                if trust_store.settings.require_trusted_attester
+                    && !trust_store.is_trusted_attestation(attestation, &category)
+                {
+                    return Err(BundleError::UntrustedAttester(tool.tool_name.clone()));
+                }
+                if trust_store
+                    .revocation_for(&self.capabilities.id, Some(&attestation.capability_hash))
+                    .is_some()
+                {
+                    return Err(BundleError::Revoked(tool.tool_name.clone()));
+                }
+                tool.attestation = Some(attestation.clone());
+                tool.verified = true;
+            }
+        }
+
+        Ok(caps)
+    }
+
+    /// Serialize the bundle as JSON
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Parse a bundle from JSON
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// Write the bundle to a file
+    pub fn write_to(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let json = self.to_json().map_err(io::Error::other)?;
+        std::fs::write(path, json)
+    }
+
+    /// Read a bundle from a file
+    pub fn read_from(path: impl AsRef<Path>) -> Result<Self, BundleError> {
+        let json = std::fs::read_to_string(path).map_err(|e| BundleError::Io(e.to_string()))?;
+        Self::from_json(&json).map_err(|e| BundleError::Parse(e.to_string()))
+    }
+}
+
+/// Errors from reading or verifying a capability bundle
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BundleError {
+    /// The bundle uses an unknown document format
+    UnsupportedFormat(u32),
+    /// The bundle contents do not match its digest
+    DigestMismatch,
+    /// The bundle signature is missing or invalid
+    InvalidSignature,
+    /// The bundle was not signed by a trusted signer
+    UntrustedSigner(String),
+    /// The attestation of a tool does not cover its capability
+    AttestationMismatch(String),
+    /// The attestation of a tool is not from an attester trusted for its category
+    UntrustedAttester(String),
+    /// The worker or a tool capability has been revoked
+    Revoked(String),
+    /// The bundle file could not be read
+    Io(String),
+    /// The file is not a valid bundle
+    Parse(String),
+}
+
+impl fmt::Display for BundleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BundleError::UnsupportedFormat(version) => {
+                write!(
+                    f,
+                    "unsupported capability bundle format version {}",
+                    version
+                )
+            }
+            BundleError::DigestMismatch => {
+                write!(f, "capability bundle contents do not match its digest")
+            }
+            BundleError::InvalidSignature => write!(f, "capability bundle signature is invalid"),
+            BundleError::UntrustedSigner(signer) => {
+                write!(
+                    f,
+                    "capability bundle signed by untrusted signer '{}'",
+                    signer
+                )
+            }
+            BundleError::AttestationMismatch(tool) => {
+                write!(f, "attestation of '{}' does not match its capability", tool)
+            }
+            BundleError::UntrustedAttester(tool) => {
+                write!(f, "attestation of '{}' is from an untrusted attester", tool)
+            }
+            BundleError::Revoked(subject) => write!(f, "'{}' has been revoked", subject),
+            BundleError::Io(reason) => write!(f, "failed to read capability bundle: {}", reason),
+            BundleError::Parse(reason) => write!(f, "invalid capability bundle: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for BundleError {}
@@ -3,8 +3,19 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use crate::checker::ToolChecker;
 use crate::constants::*;
-use crate::types::{ToolCapability, CapabilityPermissions, CapabilityExpiration, CapabilitySecurityReport};
+use crate::environment::EnvironmentSnapshot;
+use crate::error::VerificationError;
+use crate::formats::{ArtifactFormat, ReportFormat};
+use crate::models::ModelCapability;
+use crate::network::NetworkReachability;
+use crate::payload::PayloadLimits;
+use crate::priming::WarmAsset;
+use crate::sandbox::SandboxCapability;
+use crate::scope::CapabilityScope;
+use crate::signing::{AlgorithmRegistry, AttestationVerifier};
+use crate::types::{CapabilityAttestation, CapabilitySecurityReport, ToolCapability};
 
 /// Capabilities for a worker or component
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,11 +38,67 @@ pub struct Capabilities {
     /// Test framework tools
     pub test_framework_tools: Vec<ToolCapability>,
 
+    /// Tools in user-defined categories, keyed by category name
+    #[serde(default)]
+    pub custom_tools: HashMap<String, Vec<ToolCapability>>,
+
     /// Additional capability flags
     pub flags: HashMap<String, bool>,
 
+    /// Flags computed by derivation rules rather than declared
+    #[serde(default)]
+    pub computed_flags: Vec<String>,
+
     /// Custom metadata
     pub metadata: HashMap<String, String>,
+
+    /// Networks and endpoints the worker can reach
+    #[serde(default)]
+    pub network: NetworkReachability,
+
+    /// Sandbox technology offered for isolated execution
+    #[serde(default)]
+    pub sandbox: SandboxCapability,
+
+    /// Largest job payload the worker can handle
+    #[serde(default)]
+    pub payload: PayloadLimits,
+
+    /// Input artifact formats the worker accepts
+    #[serde(default)]
+    pub accepts: Vec<ArtifactFormat>,
+
+    /// Report formats the worker can emit
+    #[serde(default)]
+    pub emits: Vec<ReportFormat>,
+
+    /// Language models available to the worker
+    #[serde(default)]
+    pub models: Vec<ModelCapability>,
+
+    /// Pre-warmed assets (cloned repositories, dependency caches, rule packs)
+    #[serde(default)]
+    pub warm_assets: Vec<WarmAsset>,
+
+    /// Repositories this worker may analyze
+    #[serde(default)]
+    pub scope: CapabilityScope,
+
+    /// Environment captured at discovery time
+    #[serde(default)]
+    pub environment: Option<EnvironmentSnapshot>,
+
+    /// Name of the registry template this set extends (resolved on registration)
+    #[serde(default)]
+    pub template: Option<String>,
+
+    /// Free-form labels of the worker (e.g., `gpu`, `eu-west`)
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    /// Attestation over the canonical hash of the whole set
+    #[serde(default)]
+    pub attestation: Option<CapabilityAttestation>,
 }
 
 impl Capabilities {
@@ -44,68 +111,153 @@ impl Capabilities {
             dynamic_analysis_tools: Vec::new(),
             fuzzing_tools: Vec::new(),
             test_framework_tools: Vec::new(),
+            custom_tools: HashMap::new(),
             flags: HashMap::new(),
+            computed_flags: Vec::new(),
             metadata: HashMap::new(),
+            network: NetworkReachability::default(),
+            sandbox: SandboxCapability::default(),
+            payload: PayloadLimits::default(),
+            accepts: Vec::new(),
+            emits: Vec::new(),
+            models: Vec::new(),
+            warm_assets: Vec::new(),
+            scope: CapabilityScope::default(),
+            environment: None,
+            template: None,
+            tags: Vec::new(),
+            attestation: None,
         }
     }
 
     /// Add a static analysis tool
     pub fn with_static_analysis(mut self, tool: impl Into<String>, required: bool) -> Self {
-        self.static_analysis_tools
-            .push(ToolCapability::new(tool, required));
+        let tool = ToolCapability::new(tool, required);
+        self.static_analysis_tools.push(tool);
         self
     }
 
     /// Add a security scanning tool
     pub fn with_security_tool(mut self, tool: impl Into<String>, required: bool) -> Self {
-        self.security_scanning_tools
-            .push(ToolCapability::new(tool, required));
+        let tool = ToolCapability::new(tool, required);
+        self.security_scanning_tools.push(tool);
         self
     }
 
     /// Add a dynamic analysis tool
     pub fn with_dynamic_tool(mut self, tool: impl Into<String>, required: bool) -> Self {
-        self.dynamic_analysis_tools
-            .push(ToolCapability::new(tool, required));
+        let tool = ToolCapability::new(tool, required);
+        self.dynamic_analysis_tools.push(tool);
         self
     }
 
     /// Add a fuzzing tool
     pub fn with_fuzzing_tool(mut self, tool: impl Into<String>, required: bool) -> Self {
-        self.fuzzing_tools
-            .push(ToolCapability::new(tool, required));
+        let tool = ToolCapability::new(tool, required);
+        self.fuzzing_tools.push(tool);
         self
     }
 
     /// Add a test framework tool
     pub fn with_test_framework(mut self, tool: impl Into<String>, required: bool) -> Self {
-        self.test_framework_tools
-            .push(ToolCapability::new(tool, required));
+        let tool = ToolCapability::new(tool, required);
+        self.test_framework_tools.push(tool);
         self
     }
 
-    /// Add a generic tool to any category
-    pub fn with_tool(mut self, tool: impl Into<String>, required: bool) -> Self {
-        self.static_analysis_tools
-            .push(ToolCapability::new(tool, required));
+    /// Declare a user-defined tool category
+    ///
+    /// Built-in category names are accepted and left unchanged.
+    pub fn with_category(mut self, category: impl Into<String>) -> Self {
+        let category = category.into();
+        if !CAPABILITY_TYPES.contains(&category.as_str()) {
+            self.custom_tools.entry(category).or_default();
+        }
         self
     }
 
-    /// Add a tool with alternatives
+    /// Add a tool to a category by name, declaring user-defined categories on first use
+    pub fn with_tool_in(
+        mut self,
+        category: impl Into<String>,
+        tool: impl Into<String>,
+        required: bool,
+    ) -> Self {
+        let category = category.into();
+        let tool = ToolCapability::new(tool, required);
+        match self.tools_in_category_mut(&category) {
+            Some(tools) => tools.push(tool),
+            None => self.custom_tools.entry(category).or_default().push(tool),
+        }
+        self
+    }
+
+    /// Add several tools to a category by name, as `(tool, required)` pairs
+    pub fn with_tools<I, S>(mut self, category: &str, tools: I) -> Self
+    where
+        I: IntoIterator<Item = (S, bool)>,
+        S: Into<String>,
+    {
+        for (tool, required) in tools {
+            self = self.with_tool_in(category, tool, required);
+        }
+        self
+    }
+
+    /// Add copies of fully configured tools to a category
+    pub fn add_tools_from(&mut self, category: &str, tools: &[ToolCapability]) {
+        match self.tools_in_category_mut(category) {
+            Some(existing) => existing.extend_from_slice(tools),
+            None => self
+                .custom_tools
+                .entry(category.to_string())
+                .or_default()
+                .extend_from_slice(tools),
+        }
+    }
+
+    /// Add a tool to `static_analysis`
+    ///
+    /// Deprecated: use `with_tool_in` or the per-category builders, or set a
+    /// default category with `CapabilitiesBuilder::with_default_category`
+    /// (e.g., `CAPABILITY_GENERIC`), as the next major release will add to
+    /// `CAPABILITY_GENERIC` instead.
+    #[deprecated(
+        note = "use `with_tool_in` or `CapabilitiesBuilder::with_default_category`; the next major release adds to `generic`"
+    )]
+    pub fn with_tool(self, tool: impl Into<String>, required: bool) -> Self {
+        self.with_tool_in(CAPABILITY_STATIC_ANALYSIS, tool, required)
+    }
+
+    /// Add an optional static analysis tool with alternatives
     pub fn with_alternative(
+        self,
+        tool: impl Into<String>,
+        alternatives: Vec<impl Into<String>>,
+    ) -> Self {
+        self.with_alternative_tool(tool, alternatives, false)
+    }
+
+    /// Add a required static analysis tool satisfied by the tool or any alternative
+    ///
+    /// `has_all_required_tools` then requires one of them, e.g. rustfmt OR cargo-fmt.
+    pub fn with_required_alternative(
+        self,
+        tool: impl Into<String>,
+        alternatives: Vec<impl Into<String>>,
+    ) -> Self {
+        self.with_alternative_tool(tool, alternatives, true)
+    }
+
+    fn with_alternative_tool(
         mut self,
         tool: impl Into<String>,
         alternatives: Vec<impl Into<String>>,
+        required: bool,
     ) -> Self {
-        self.static_analysis_tools.push(ToolCapability {
-            tool_name: tool.into(),
-            required: false,
-            alternatives: alternatives.into_iter().map(|a| a.into()).collect(),
-            attestation: None,
-            permissions: CapabilityPermissions::default(),
-            expiration: CapabilityExpiration::default(),
-            verified: false,
-        });
+        let tool = ToolCapability::new(tool, required)
+            .with_alternatives(alternatives.into_iter().map(|a| a.into()).collect());
+        self.static_analysis_tools.push(tool);
         self
     }
 
@@ -121,16 +273,75 @@ impl Capabilities {
         self
     }
 
+    /// Set network reachability
+    pub fn with_network(mut self, network: NetworkReachability) -> Self {
+        self.network = network;
+        self
+    }
+
+    /// Set sandbox capability
+    pub fn with_sandbox(mut self, sandbox: SandboxCapability) -> Self {
+        self.sandbox = sandbox;
+        self
+    }
+
+    /// Set payload limits
+    pub fn with_payload_limits(mut self, payload: PayloadLimits) -> Self {
+        self.payload = payload;
+        self
+    }
+
+    /// Declare an accepted input artifact format
+    pub fn accepting(mut self, format: ArtifactFormat) -> Self {
+        if !self.accepts.contains(&format) {
+            self.accepts.push(format);
+        }
+        self
+    }
+
+    /// Check if the worker accepts an input artifact format
+    pub fn accepts_format(&self, format: &ArtifactFormat) -> bool {
+        self.accepts.contains(format)
+    }
+
+    /// Declare an emitted report format
+    pub fn emitting(mut self, format: ReportFormat) -> Self {
+        if !self.emits.contains(&format) {
+            self.emits.push(format);
+        }
+        self
+    }
+
+    /// Check if the worker can emit a report format
+    pub fn emits_format(&self, format: &ReportFormat) -> bool {
+        self.emits.contains(format)
+    }
+
+    /// Declare an available language model
+    pub fn with_model(mut self, model: ModelCapability) -> Self {
+        self.models.push(model);
+        self
+    }
+
+    /// Declare a pre-warmed asset
+    pub fn with_warm_asset(mut self, asset: WarmAsset) -> Self {
+        self.warm_assets.push(asset);
+        self
+    }
+
+    /// Restrict the repositories this worker may analyze
+    pub fn with_scope(mut self, scope: CapabilityScope) -> Self {
+        self.scope = scope;
+        self
+    }
+
     /// Check if a capability is available
-    pub fn has_capability(&self, capability_type: &str, tool_checker: &dyn Fn(&str) -> bool) -> bool {
-        let tools = match capability_type {
-            CAPABILITY_STATIC_ANALYSIS => &self.static_analysis_tools,
-            CAPABILITY_SECURITY_SCANNING => &self.security_scanning_tools,
-            CAPABILITY_DYNAMIC_ANALYSIS => &self.dynamic_analysis_tools,
-            CAPABILITY_FUZZING => &self.fuzzing_tools,
-            CAPABILITY_TEST_FRAMEWORK => &self.test_framework_tools,
-            _ => return false,
-        };
+    pub fn has_capability(
+        &self,
+        capability_type: &str,
+        tool_checker: &(impl ToolChecker + ?Sized),
+    ) -> bool {
+        let tools = self.tools_in_category(capability_type);
 
         if tools.is_empty() {
             return false;
@@ -140,15 +351,211 @@ impl Capabilities {
         tools.iter().any(|cap| cap.is_satisfied(tool_checker))
     }
 
-    /// Check if all required tools are available
-    pub fn has_all_required_tools(&self, tool_checker: &dyn Fn(&str) -> bool) -> bool {
-        let all_tools = self
-            .static_analysis_tools
+    /// Name of the tool that satisfies a capability category
+    ///
+    /// Tools are tried in declaration order, each trying its candidates in preference order.
+    pub fn satisfied_by(
+        &self,
+        capability_type: &str,
+        tool_checker: &(impl ToolChecker + ?Sized),
+    ) -> Option<&str> {
+        self.tools_in_category(capability_type)
+            .iter()
+            .find_map(|tool| tool.satisfied_by(tool_checker))
+    }
+
+    /// Check if a capability is available for a target repository
+    ///
+    /// Both the worker scope and the scope of the satisfying tool must allow the target.
+    pub fn has_capability_for_target(
+        &self,
+        capability_type: &str,
+        tool_checker: &(impl ToolChecker + ?Sized),
+        repository: &str,
+    ) -> bool {
+        self.scope.allows(repository)
+            && self
+                .tools_in_category(capability_type)
+                .iter()
+                .any(|tool| tool.scope.allows(repository) && tool.is_satisfied(tool_checker))
+    }
+
+    /// Get the tools of a capability category
+    pub fn tools_in_category(&self, capability_type: &str) -> &[ToolCapability] {
+        match capability_type {
+            CAPABILITY_STATIC_ANALYSIS => &self.static_analysis_tools,
+            CAPABILITY_SECURITY_SCANNING => &self.security_scanning_tools,
+            CAPABILITY_DYNAMIC_ANALYSIS => &self.dynamic_analysis_tools,
+            CAPABILITY_FUZZING => &self.fuzzing_tools,
+            CAPABILITY_TEST_FRAMEWORK => &self.test_framework_tools,
+            custom => self
+                .custom_tools
+                .get(custom)
+                .map(Vec::as_slice)
+                .unwrap_or(&[]),
+        }
+    }
+
+    /// Names of all categories: the built-in ones followed by user-defined ones in sorted order
+    pub fn category_names(&self) -> Vec<String> {
+        let mut custom: Vec<String> = self.custom_tools.keys().cloned().collect();
+        custom.sort();
+        CAPABILITY_TYPES
+            .iter()
+            .map(|category| category.to_string())
+            .chain(custom)
+            .collect()
+    }
+
+    /// Get the mutable tool list of a capability category
+    pub fn tools_in_category_mut(
+        &mut self,
+        capability_type: &str,
+    ) -> Option<&mut Vec<ToolCapability>> {
+        match capability_type {
+            CAPABILITY_STATIC_ANALYSIS => Some(&mut self.static_analysis_tools),
+            CAPABILITY_SECURITY_SCANNING => Some(&mut self.security_scanning_tools),
+            CAPABILITY_DYNAMIC_ANALYSIS => Some(&mut self.dynamic_analysis_tools),
+            CAPABILITY_FUZZING => Some(&mut self.fuzzing_tools),
+            CAPABILITY_TEST_FRAMEWORK => Some(&mut self.test_framework_tools),
+            custom => self.custom_tools.get_mut(custom),
+        }
+    }
+
+    /// Iterate over the tools of every category
+    pub(crate) fn tools_iter(&self) -> impl Iterator<Item = &ToolCapability> {
+        self.static_analysis_tools
             .iter()
             .chain(&self.security_scanning_tools)
             .chain(&self.dynamic_analysis_tools)
             .chain(&self.fuzzing_tools)
-            .chain(&self.test_framework_tools);
+            .chain(&self.test_framework_tools)
+            .chain(self.custom_tools.values().flatten())
+    }
+
+    /// Iterate mutably over the tools of every category
+    pub(crate) fn tools_iter_mut(&mut self) -> impl Iterator<Item = &mut ToolCapability> {
+        self.static_analysis_tools
+            .iter_mut()
+            .chain(&mut self.security_scanning_tools)
+            .chain(&mut self.dynamic_analysis_tools)
+            .chain(&mut self.fuzzing_tools)
+            .chain(&mut self.test_framework_tools)
+            .chain(self.custom_tools.values_mut().flatten())
+    }
+
+    /// Iterate over the tools of every category with their category names
+    ///
+    /// Built-in categories come first, followed by user-defined ones in sorted order.
+    pub fn iter_tools(&self) -> impl Iterator<Item = (&str, &ToolCapability)> {
+        let mut custom: Vec<(&String, &Vec<ToolCapability>)> = self.custom_tools.iter().collect();
+        custom.sort_by(|a, b| a.0.cmp(b.0));
+        let builtin = [
+            (CAPABILITY_STATIC_ANALYSIS, &self.static_analysis_tools),
+            (CAPABILITY_SECURITY_SCANNING, &self.security_scanning_tools),
+            (CAPABILITY_DYNAMIC_ANALYSIS, &self.dynamic_analysis_tools),
+            (CAPABILITY_FUZZING, &self.fuzzing_tools),
+            (CAPABILITY_TEST_FRAMEWORK, &self.test_framework_tools),
+        ];
+        builtin
+            .into_iter()
+            .chain(
+                custom
+                    .into_iter()
+                    .map(|(category, tools)| (category.as_str(), tools)),
+            )
+            .flat_map(|(category, tools)| tools.iter().map(move |tool| (category, tool)))
+    }
+
+    /// Iterate mutably over the tools of every category with their category names
+    pub fn iter_tools_mut(&mut self) -> impl Iterator<Item = (&str, &mut ToolCapability)> {
+        let mut custom: Vec<(&String, &mut Vec<ToolCapability>)> =
+            self.custom_tools.iter_mut().collect();
+        custom.sort_by(|a, b| a.0.cmp(b.0));
+        let builtin = [
+            (CAPABILITY_STATIC_ANALYSIS, &mut self.static_analysis_tools),
+            (
+                CAPABILITY_SECURITY_SCANNING,
+                &mut self.security_scanning_tools,
+            ),
+            (
+                CAPABILITY_DYNAMIC_ANALYSIS,
+                &mut self.dynamic_analysis_tools,
+            ),
+            (CAPABILITY_FUZZING, &mut self.fuzzing_tools),
+            (CAPABILITY_TEST_FRAMEWORK, &mut self.test_framework_tools),
+        ];
+        builtin
+            .into_iter()
+            .chain(
+                custom
+                    .into_iter()
+                    .map(|(category, tools)| (category.as_str(), tools)),
+            )
+            .flat_map(|(category, tools)| tools.iter_mut().map(move |tool| (category, tool)))
+    }
+
+    /// Find a tool by name in any category
+    pub fn find_tool(&self, tool_name: &str) -> Option<&ToolCapability> {
+        self.tools_iter().find(|tool| tool.tool_name == tool_name)
+    }
+
+    /// Find a mutable tool by name in any category
+    pub fn find_tool_mut(&mut self, tool_name: &str) -> Option<&mut ToolCapability> {
+        self.tools_iter_mut()
+            .find(|tool| tool.tool_name == tool_name)
+    }
+
+    /// Remove a tool from every category it is declared in
+    ///
+    /// Returns `true` if any tool was removed.
+    pub fn remove_tool(&mut self, tool_name: &str) -> bool {
+        let mut removed = false;
+        for category in self.category_names() {
+            if let Some(tools) = self.tools_in_category_mut(&category) {
+                let before = tools.len();
+                tools.retain(|tool| tool.tool_name != tool_name);
+                removed |= tools.len() != before;
+            }
+        }
+        removed
+    }
+
+    /// Apply a change to a tool in every category it is declared in
+    ///
+    /// Returns `true` if any tool was found.
+    pub fn update_tool(
+        &mut self,
+        tool_name: &str,
+        mut update: impl FnMut(&mut ToolCapability),
+    ) -> bool {
+        let mut found = false;
+        for (_, tool) in self.iter_tools_mut() {
+            if tool.tool_name == tool_name {
+                update(tool);
+                found = true;
+            }
+        }
+        found
+    }
+
+    /// Replace a tool in every category it is declared in
+    ///
+    /// Returns `true` if any tool was replaced; the tool is not added otherwise.
+    pub fn replace_tool(&mut self, tool_name: &str, replacement: ToolCapability) -> bool {
+        self.update_tool(tool_name, |tool| *tool = replacement.clone())
+    }
+
+    /// Tools with metadata matching a key-value pair, in any category
+    pub fn tools_with_metadata(&self, key: &str, value: &str) -> Vec<&ToolCapability> {
+        self.tools_iter()
+            .filter(|tool| tool.get_metadata(key).is_some_and(|v| v == value))
+            .collect()
+    }
+
+    /// Check if all required tools are available
+    pub fn has_all_required_tools(&self, tool_checker: &(impl ToolChecker + ?Sized)) -> bool {
+        let all_tools = self.tools_iter();
 
         for tool in all_tools {
             if tool.required && !tool.is_satisfied(tool_checker) {
@@ -163,14 +570,7 @@ impl Capabilities {
     pub fn all_tools(&self) -> Vec<String> {
         let mut tools = Vec::new();
 
-        for cap in self
-            .static_analysis_tools
-            .iter()
-            .chain(&self.security_scanning_tools)
-            .chain(&self.dynamic_analysis_tools)
-            .chain(&self.fuzzing_tools)
-            .chain(&self.test_framework_tools)
-        {
+        for cap in self.tools_iter() {
             tools.push(cap.tool_name.clone());
             tools.extend(cap.alternatives.clone());
         }
@@ -188,85 +588,100 @@ impl Capabilities {
         self.metadata.get(key)
     }
 
-    /// Verify all capabilities are attested and not expired/revoked
+    /// Verify all capabilities are attested and not expired/revoked, using the built-in algorithms
+    #[deprecated(note = "use `verify_all_capabilities_with` and an explicit verifier")]
     pub fn verify_all_capabilities(&self) -> bool {
-        let all_tools = self
-            .static_analysis_tools
-            .iter()
-            .chain(&self.security_scanning_tools)
-            .chain(&self.dynamic_analysis_tools)
-            .chain(&self.fuzzing_tools)
-            .chain(&self.test_framework_tools);
+        self.verify_all_capabilities_with(AlgorithmRegistry::builtin())
+    }
 
-        for tool in all_tools {
+    /// Verify all capabilities, checking attestation signatures with a verifier
+    pub fn verify_all_capabilities_with(&self, verifier: &dyn AttestationVerifier) -> bool {
+        self.try_verify_all_capabilities_with(verifier).is_ok()
+    }
+
+    /// Verify all capabilities with a verifier, reporting the first one that fails
+    pub fn try_verify_all_capabilities_with(
+        &self,
+        verifier: &dyn AttestationVerifier,
+    ) -> Result<(), VerificationError> {
+        for tool in self.tools_iter() {
             // Check if capability is expired
-            if tool.is_expired() {
-                return false;
+            let expired = tool
+                .try_is_expired_within(verifier.expiration_tolerance())
+                .map_err(|e| VerificationError::from_clock(e, &tool.tool_name))?;
+            if expired {
+                return Err(VerificationError::Expired {
+                    tool: tool.tool_name.clone(),
+                });
             }
 
             // Check if capability is revoked
             if tool.is_revoked() {
-                return false;
+                return Err(VerificationError::Revoked {
+                    tool: tool.tool_name.clone(),
+                    reason: tool.expiration.revocation_reason.clone(),
+                });
             }
 
-            // Check if capability is attested (if attestation is required)
-            if !tool.verify_attestation() {
-                return false;
-            }
+            // Check if capability is attested and covers the current hash
+            tool.try_verify_attestation_with(verifier)?;
         }
 
-        true
+        Ok(())
+    }
+
+    /// Verify only that every capability is attested and matches its attested hash
+    ///
+    /// Cheaper than `verify_all_capabilities`; used when the registry sheds load.
+    pub fn verify_all_digests(&self) -> bool {
+        self.tools_iter().all(|tool| tool.verify_capability_hash())
     }
 
     /// Check if worker has required permissions for a capability
-    pub fn has_required_permissions(&self, capability_type: &str, required_permission: &str) -> bool {
-        let tools = match capability_type {
-            CAPABILITY_STATIC_ANALYSIS => &self.static_analysis_tools,
-            CAPABILITY_SECURITY_SCANNING => &self.security_scanning_tools,
-            CAPABILITY_DYNAMIC_ANALYSIS => &self.dynamic_analysis_tools,
-            CAPABILITY_FUZZING => &self.fuzzing_tools,
-            CAPABILITY_TEST_FRAMEWORK => &self.test_framework_tools,
-            _ => return false,
-        };
+    pub fn has_required_permissions(
+        &self,
+        capability_type: &str,
+        required_permission: &str,
+    ) -> bool {
+        let tools = self.tools_in_category(capability_type);
 
         // At least one tool must have the required permission
-        tools.iter().any(|tool| tool.has_permission(required_permission))
+        tools
+            .iter()
+            .any(|tool| tool.has_permission(required_permission))
     }
 
     /// Revoke all capabilities
     pub fn revoke_all_capabilities(&mut self, reason: String, revoked_by: String) {
-        for tool in self
-            .static_analysis_tools
-            .iter_mut()
-            .chain(&mut self.security_scanning_tools)
-            .chain(&mut self.dynamic_analysis_tools)
-            .chain(&mut self.fuzzing_tools)
-            .chain(&mut self.test_framework_tools)
-        {
+        for tool in self.tools_iter_mut() {
             tool.revoke(reason.clone(), revoked_by.clone());
         }
     }
 
-    /// Get security report for all capabilities
+    /// Get security report for all capabilities, verifying with the built-in algorithms
+    #[deprecated(note = "use `security_report_with` and an explicit verifier")]
     pub fn get_security_report(&self) -> HashMap<String, CapabilitySecurityReport> {
+        self.security_report_with(AlgorithmRegistry::builtin())
+    }
+
+    /// Get security report for all capabilities, verifying attestations with a verifier
+    pub fn security_report_with(
+        &self,
+        verifier: &dyn AttestationVerifier,
+    ) -> HashMap<String, CapabilitySecurityReport> {
         let mut report = HashMap::new();
-        let all_tools = self
-            .static_analysis_tools
-            .iter()
-            .chain(&self.security_scanning_tools)
-            .chain(&self.dynamic_analysis_tools)
-            .chain(&self.fuzzing_tools)
-            .chain(&self.test_framework_tools);
+        let all_tools = self.tools_iter();
 
         for tool in all_tools {
             let security_report = CapabilitySecurityReport {
                 tool_name: tool.tool_name.clone(),
                 has_attestation: tool.attestation.is_some(),
-                attestation_verified: tool.verify_attestation(),
+                attestation_verified: tool.verify_attestation_with(verifier),
                 is_expired: tool.is_expired(),
                 is_revoked: tool.is_revoked(),
                 permissions: tool.permissions.clone(),
                 expiration: tool.expiration.clone(),
+                deprecated: tool.deprecated.clone(),
             };
             report.insert(tool.tool_name.clone(), security_report);
         }
@@ -274,38 +689,33 @@ impl Capabilities {
         report
     }
 
-    /// Get capability statistics
+    /// Get capability statistics, verifying with the built-in algorithms
+    #[deprecated(note = "use `statistics_with` and an explicit verifier")]
     pub fn get_statistics(&self) -> CapabilityStatistics {
-        let total_tools = self.static_analysis_tools.len()
-            + self.security_scanning_tools.len()
-            + self.dynamic_analysis_tools.len()
-            + self.fuzzing_tools.len()
-            + self.test_framework_tools.len();
-
-        let required_tools = self
-            .static_analysis_tools
-            .iter()
-            .chain(&self.security_scanning_tools)
-            .chain(&self.dynamic_analysis_tools)
-            .chain(&self.fuzzing_tools)
-            .chain(&self.test_framework_tools)
-            .filter(|tool| tool.required)
-            .count();
+        self.statistics_with(AlgorithmRegistry::builtin())
+    }
+
+    /// Get capability statistics, counting tools whose attestation verifies with a verifier
+    pub fn statistics_with(&self, verifier: &dyn AttestationVerifier) -> CapabilityStatistics {
+        let total_tools = self.tools_iter().count();
+
+        let required_tools = self.tools_iter().filter(|tool| tool.required).count();
 
         let verified_tools = self
-            .static_analysis_tools
-            .iter()
-            .chain(&self.security_scanning_tools)
-            .chain(&self.dynamic_analysis_tools)
-            .chain(&self.fuzzing_tools)
-            .chain(&self.test_framework_tools)
-            .filter(|tool| tool.verify_attestation())
+            .tools_iter()
+            .filter(|tool| tool.verify_attestation_with(verifier))
+            .count();
+
+        let deprecated_tools = self
+            .tools_iter()
+            .filter(|tool| tool.is_deprecated())
             .count();
 
         CapabilityStatistics {
             total_tools,
             required_tools,
             verified_tools,
+            deprecated_tools,
             flags_count: self.flags.len(),
             metadata_count: self.metadata.len(),
         }
@@ -318,6 +728,8 @@ pub struct CapabilityStatistics {
     pub total_tools: usize,
     pub required_tools: usize,
     pub verified_tools: usize,
+    #[serde(default)]
+    pub deprecated_tools: usize,
     pub flags_count: usize,
     pub metadata_count: usize,
 }
@@ -0,0 +1,227 @@
+//! Attestation chains and counter-signatures
+//!
+//! A tool's attestation can be endorsed by further attesters, modelling a
+//! delegation of trust (tool vendor → platform team → deployment). The
+//! attestation is the first link of the chain; each counter-signature signs
+//! the `chain_hash` of the link before it.
+//!
+//! ```rust
+//! use worker_capabilities::{
+//!     AttestationSigner, AttesterEntry, Ed25519Signer, Ed25519Verifier, ToolCapability,
+//!     TrustPolicy,
+//! };
+//!
+//! let vendor = Ed25519Signer::new([1; 32], "vendor");
+//! let platform = Ed25519Signer::new([2; 32], "platform-team");
+//!
+//! let tool = ToolCapability::new("semgrep", true)
+//!     .countersign(&vendor)
+//!     .countersign(&platform);
+//! assert_eq!(tool.chain().len(), 2);
+//!
+//! let policy = TrustPolicy::new()
+//!     .with_attester(AttesterEntry::new("vendor", vendor.public_key()))
+//!     .with_attester(AttesterEntry::new("platform-team", platform.public_key()));
+//! assert!(tool.verify_chain_with(&policy, &Ed25519Verifier));
+//! ```
+//!
+//! A tool can require sign-off from k of n attesters. The verifier's
+//! `TrustPolicy` sets the threshold and pins the attesters' keys; only links
+//! whose attester and key match a pinned entry count:
+//!
+//! ```rust
+//! use worker_capabilities::{
+//!     AttestationSigner, AttesterEntry, Ed25519Signer, Ed25519Verifier, ToolCapability,
+//!     TrustPolicy,
+//! };
+//!
+//! let security = Ed25519Signer::new([1; 32], "security");
+//! let platform = Ed25519Signer::new([2; 32], "platform");
+//! let policy = TrustPolicy::new()
+//!     .with_attester(AttesterEntry::new("security", security.public_key()))
+//!     .with_attester(AttesterEntry::new("platform", platform.public_key()))
+//!     .with_threshold("docker", 2, ["security", "platform"]);
+//!
+//! let tool = ToolCapability::new("docker", true).countersign(&security);
+//! assert!(!tool.verify_chain_with(&policy, &Ed25519Verifier));
+//!
+//! let tool = tool.countersign(&platform);
+//! assert!(tool.verify_chain_with(&policy, &Ed25519Verifier));
+//! ```
+
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+
+use crate::capabilities::Capabilities;
+use crate::error::VerificationError;
+use crate::signing::{AttestationSigner, AttestationVerifier};
+use crate::trust::TrustPolicy;
+use crate::types::{CapabilityAttestation, ToolCapability};
+
+impl CapabilityAttestation {
+    /// Hash counter-signed by the next link of a chain
+    ///
+    /// Covers the attested hash, signature, public key and attester.
+    pub fn chain_hash(&self) -> String {
+        let mut hasher = Sha256::new();
+        for field in [
+            &self.capability_hash,
+            &self.signature,
+            &self.public_key,
+            &self.attester,
+        ] {
+            hasher.update(field.as_bytes());
+            hasher.update(b"\n");
+        }
+        format!("{:x}", hasher.finalize())
+    }
+}
+
+impl ToolCapability {
+    /// Endorse the tool's attestation chain with another signer
+    ///
+    /// An unattested tool is attested by the signer instead.
+    pub fn countersign(mut self, signer: &dyn AttestationSigner) -> Self {
+        match self.chain().last() {
+            Some(link) => {
+                let countersignature = signer.attest_hash(&link.chain_hash());
+                self.countersignatures.push(countersignature);
+            }
+            None => {
+                self.attestation = Some(signer.attest(&self));
+                self.verified = true;
+            }
+        }
+        self
+    }
+
+    /// The attestation followed by its counter-signatures
+    pub fn chain(&self) -> Vec<&CapabilityAttestation> {
+        self.attestation
+            .iter()
+            .chain(&self.countersignatures)
+            .collect()
+    }
+
+    /// Check every link of the attestation chain against a policy
+    pub fn verify_chain_with(
+        &self,
+        policy: &TrustPolicy,
+        verifier: &dyn AttestationVerifier,
+    ) -> bool {
+        self.try_verify_chain_with(policy, verifier).is_ok()
+    }
+
+    /// Check every link of the attestation chain, reporting the first failure
+    ///
+    /// The attestation must cover the current capability hash, each
+    /// counter-signature must cover the previous link, and every link must be
+    /// signed by an attester the policy trusts. The policy's threshold for
+    /// the tool must be met. Category restrictions of the policy are not
+    /// applied here; use `Capabilities::try_verify_chains_with`.
+    pub fn try_verify_chain_with(
+        &self,
+        policy: &TrustPolicy,
+        verifier: &dyn AttestationVerifier,
+    ) -> Result<(), VerificationError> {
+        self.check_chain(verifier, |link| policy.trusts(link))?;
+        self.check_threshold(policy, verifier)
+    }
+
+    /// Check that enough attesters pinned by a policy signed the attestation chain
+    ///
+    /// Only links whose attester and key match a pinned entry count; each
+    /// attester and each key counts once. Counting stops at the first link
+    /// that does not verify or cover the previous one.
+    pub(crate) fn check_threshold(
+        &self,
+        policy: &TrustPolicy,
+        verifier: &dyn AttestationVerifier,
+    ) -> Result<(), VerificationError> {
+        let Some(threshold) = policy.threshold(&self.tool_name) else {
+            return Ok(());
+        };
+
+        let chain = self.chain();
+        let mut attesters = HashSet::new();
+        let mut keys = HashSet::new();
+        for (link, attestation) in chain.iter().enumerate() {
+            if link > 0
+                && (attestation.capability_hash != chain[link - 1].chain_hash()
+                    || attestation.is_expired_within(verifier.expiration_tolerance())
+                    || !verifier.verify_attestation(attestation))
+            {
+                break;
+            }
+            if threshold.attesters.contains(&attestation.attester)
+                && policy.pins(attestation)
+                && keys.insert(attestation.public_key.as_str())
+            {
+                attesters.insert(attestation.attester.as_str());
+            }
+        }
+
+        if attesters.len() < threshold.required {
+            return Err(VerificationError::ThresholdNotMet {
+                tool: self.tool_name.clone(),
+                required: threshold.required,
+                attested: attesters.len(),
+            });
+        }
+        Ok(())
+    }
+
+    fn check_chain(
+        &self,
+        verifier: &dyn AttestationVerifier,
+        trusted: impl Fn(&CapabilityAttestation) -> bool,
+    ) -> Result<(), VerificationError> {
+        self.try_verify_attestation_with(verifier)?;
+
+        let chain = self.chain();
+        for (link, attestation) in chain.iter().enumerate() {
+            if link > 0 {
+                attestation.check_with(verifier, &self.tool_name)?;
+                if attestation.capability_hash != chain[link - 1].chain_hash() {
+                    return Err(VerificationError::BrokenChain {
+                        tool: self.tool_name.clone(),
+                        link,
+                    });
+                }
+            }
+            if !trusted(attestation) {
+                return Err(VerificationError::UntrustedAttester {
+                    tool: self.tool_name.clone(),
+                    attester: attestation.attester.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Capabilities {
+    /// Check the attestation chains of all tools against a policy
+    pub fn verify_chains_with(
+        &self,
+        policy: &TrustPolicy,
+        verifier: &dyn AttestationVerifier,
+    ) -> bool {
+        self.try_verify_chains_with(policy, verifier).is_ok()
+    }
+
+    /// Check the attestation chains of all tools, reporting the first failure
+    ///
+    /// Every link must come from an attester trusted for the tool's category.
+    pub fn try_verify_chains_with(
+        &self,
+        policy: &TrustPolicy,
+        verifier: &dyn AttestationVerifier,
+    ) -> Result<(), VerificationError> {
+        for (category, tool) in self.iter_tools() {
+            tool.check_chain(verifier, |link| policy.allows(link, category))?;
+            tool.check_threshold(policy, verifier)?;
+        }
+        Ok(())
+    }
+}
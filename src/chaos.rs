@@ -0,0 +1,273 @@
+//! Fault injection for resilience testing
+//!
+//! A `ChaosInjector` wraps arbitrary operations (tool checks, registry
+//! lookups, transport calls) and, with configurable probabilities, delays
+//! them, fails them, or serves the result of an earlier call instead of a
+//! fresh one. Randomness comes from a seeded generator so runs are
+//! reproducible. Only available with the `chaos` feature.
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::capabilities::Capabilities;
+use crate::checker::ToolChecker;
+use crate::registry::CapabilityRegistry;
+
+/// Probabilities and parameters of injected faults
+#[derive(Debug, Clone)]
+pub struct ChaosConfig {
+    /// Delay added to an operation when latency is injected
+    pub latency: Duration,
+    /// Probability (0.0-1.0) of injecting latency
+    pub latency_probability: f64,
+    /// Probability (0.0-1.0) of failing an operation
+    pub error_probability: f64,
+    /// Probability (0.0-1.0) of serving a previous result instead of a fresh one
+    pub stale_probability: f64,
+    /// Seed of the random generator
+    pub seed: u64,
+}
+
+impl Default for ChaosConfig {
+    fn default() -> Self {
+        Self {
+            latency: Duration::ZERO,
+            latency_probability: 0.0,
+            error_probability: 0.0,
+            stale_probability: 0.0,
+            seed: 0x5eed,
+        }
+    }
+}
+
+impl ChaosConfig {
+    /// Create a configuration that injects nothing
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inject a delay with the given probability
+    pub fn with_latency(mut self, latency: Duration, probability: f64) -> Self {
+        self.latency = latency;
+        self.latency_probability = probability;
+        self
+    }
+
+    /// Fail operations with the given probability
+    pub fn with_errors(mut self, probability: f64) -> Self {
+        self.error_probability = probability;
+        self
+    }
+
+    /// Serve previous results with the given probability
+    pub fn with_stale_data(mut self, probability: f64) -> Self {
+        self.stale_probability = probability;
+        self
+    }
+
+    /// Set the seed of the random generator
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+}
+
+/// Error injected in place of an operation's result
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChaosError;
+
+impl fmt::Display for ChaosError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "injected fault")
+    }
+}
+
+impl std::error::Error for ChaosError {}
+
+/// Counts of injected faults
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ChaosStats {
+    /// Operations delayed
+    pub delayed: u64,
+    /// Operations failed
+    pub failed: u64,
+    /// Operations answered with a previous result
+    pub stale: u64,
+}
+
+/// Injects faults into wrapped operations
+#[derive(Debug)]
+pub struct ChaosInjector {
+    config: ChaosConfig,
+    state: Mutex<u64>,
+    snapshots: Mutex<HashMap<String, Box<dyn Any + Send>>>,
+    delayed: AtomicU64,
+    failed: AtomicU64,
+    stale: AtomicU64,
+}
+
+impl ChaosInjector {
+    /// Create an injector from a configuration
+    pub fn new(config: ChaosConfig) -> Self {
+        Self {
+            state: Mutex::new(config.seed),
+            config,
+            snapshots: Mutex::new(HashMap::new()),
+            delayed: AtomicU64::new(0),
+            failed: AtomicU64::new(0),
+            stale: AtomicU64::new(0),
+        }
+    }
+
+    /// Counts of faults injected so far
+    pub fn stats(&self) -> ChaosStats {
+        ChaosStats {
+            delayed: self.delayed.load(Ordering::Relaxed),
+            failed: self.failed.load(Ordering::Relaxed),
+            stale: self.stale.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Run an operation, possibly delaying it or failing it
+    pub fn call<T>(&self, op: impl FnOnce() -> T) -> Result<T, ChaosError> {
+        if self.roll(self.config.latency_probability) {
+            self.delayed.fetch_add(1, Ordering::Relaxed);
+            std::thread::sleep(self.config.latency);
+        }
+        if self.roll(self.config.error_probability) {
+            self.failed.fetch_add(1, Ordering::Relaxed);
+            return Err(ChaosError);
+        }
+        Ok(op())
+    }
+
+    /// Run an operation like `call`, possibly answering with the previous result for the same key
+    pub fn call_cached<T: Clone + Send + 'static>(
+        &self,
+        key: &str,
+        op: impl FnOnce() -> T,
+    ) -> Result<T, ChaosError> {
+        if self.roll(self.config.stale_probability) {
+            let snapshots = self.snapshots.lock().unwrap_or_else(|e| e.into_inner());
+            if let Some(previous) = snapshots
+                .get(key)
+                .and_then(|value| value.downcast_ref::<T>())
+            {
+                self.stale.fetch_add(1, Ordering::Relaxed);
+                return Ok(previous.clone());
+            }
+        }
+
+        let value = self.call(op)?;
+        self.snapshots
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(key.to_string(), Box::new(value.clone()));
+        Ok(value)
+    }
+
+    fn roll(&self, probability: f64) -> bool {
+        if probability <= 0.0 {
+            return false;
+        }
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        // splitmix64
+        *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = *state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+        ((z >> 11) as f64 / (1u64 << 53) as f64) < probability
+    }
+}
+
+/// Tool checker with injected faults
+///
+/// Failed checks report the tool as unavailable.
+#[derive(Debug)]
+pub struct ChaosChecker<F> {
+    checker: F,
+    injector: ChaosInjector,
+}
+
+impl<F: Fn(&str) -> bool> ChaosChecker<F> {
+    /// Wrap a tool checker
+    pub fn new(checker: F, config: ChaosConfig) -> Self {
+        Self {
+            checker,
+            injector: ChaosInjector::new(config),
+        }
+    }
+
+    /// Check a tool, possibly delayed, failed or answered from a previous check
+    pub fn check(&self, tool: &str) -> bool {
+        self.injector
+            .call_cached(tool, || (self.checker)(tool))
+            .unwrap_or(false)
+    }
+
+    /// Counts of faults injected so far
+    pub fn stats(&self) -> ChaosStats {
+        self.injector.stats()
+    }
+}
+
+impl<F: Fn(&str) -> bool> ToolChecker for ChaosChecker<F> {
+    fn is_available(&self, tool: &str) -> bool {
+        self.check(tool)
+    }
+}
+
+/// Registry lookups with injected faults, simulating a flapping store
+#[derive(Debug)]
+pub struct ChaosRegistry<'a> {
+    registry: &'a CapabilityRegistry,
+    injector: ChaosInjector,
+}
+
+impl<'a> ChaosRegistry<'a> {
+    /// Wrap a registry
+    pub fn new(registry: &'a CapabilityRegistry, config: ChaosConfig) -> Self {
+        Self {
+            registry,
+            injector: ChaosInjector::new(config),
+        }
+    }
+
+    /// Get capabilities by ID
+    pub fn get(&self, id: &str) -> Result<Option<Capabilities>, ChaosError> {
+        self.injector
+            .call_cached(&format!("get:{}", id), || self.registry.get(id).cloned())
+    }
+
+    /// List all registered capability IDs
+    pub fn list_ids(&self) -> Result<Vec<String>, ChaosError> {
+        self.injector
+            .call_cached("list_ids", || self.registry.list_ids())
+    }
+
+    /// Find workers with a specific capability
+    pub fn find_with_capability(
+        &self,
+        capability_type: &str,
+        tool_checker: &(impl ToolChecker + ?Sized),
+    ) -> Result<Vec<String>, ChaosError> {
+        self.injector
+            .call_cached(&format!("find:{}", capability_type), || {
+                self.registry
+                    .find_with_capability(capability_type, tool_checker)
+                    .into_iter()
+                    .map(|caps| caps.id.clone())
+                    .collect()
+            })
+    }
+
+    /// Counts of faults injected so far
+    pub fn stats(&self) -> ChaosStats {
+        self.injector.stats()
+    }
+}
@@ -0,0 +1,806 @@
+//! Tool availability checkers
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{mpsc, Arc, Mutex, MutexGuard, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::clock::{self, current_timestamp};
+use crate::constants::TOOL_CHECK_POOL_THREADS;
+use crate::pinning::cached_sha256_file;
+
+/// Result of checking a single tool
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolStatus {
+    /// Whether the tool is available
+    pub available: bool,
+    /// Detected version of the tool (if known)
+    #[serde(default)]
+    pub version: Option<String>,
+    /// Location the tool was found at (if known)
+    #[serde(default)]
+    pub path: Option<PathBuf>,
+    /// SHA256 of the executable found, as lowercase hex (if known)
+    #[serde(default)]
+    pub sha256: Option<String>,
+    /// Unix timestamp of the check
+    pub checked_at: u64,
+}
+
+impl ToolStatus {
+    /// Status of an available tool, checked now
+    pub fn found() -> Self {
+        Self {
+            available: true,
+            version: None,
+            path: None,
+            sha256: None,
+            checked_at: current_timestamp(),
+        }
+    }
+
+    /// Status of a missing tool, checked now
+    pub fn missing() -> Self {
+        Self {
+            available: false,
+            ..Self::found()
+        }
+    }
+
+    /// Set the detected version
+    pub fn with_version(mut self, version: impl Into<String>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    /// Set the location the tool was found at
+    pub fn with_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// Set the SHA256 of the executable found
+    pub fn with_sha256(mut self, sha256: impl Into<String>) -> Self {
+        self.sha256 = Some(sha256.into().trim().to_ascii_lowercase());
+        self
+    }
+}
+
+/// Checks whether tools are available on a worker
+///
+/// Implemented for every `Fn(&str) -> bool` closure, including trait
+/// objects, so existing closure checkers keep working. Implement it directly
+/// for checkers that carry state or configuration, or that can detect
+/// installed versions.
+///
+/// ```rust
+/// use worker_capabilities::{Capabilities, ToolCapability};
+///
+/// let mut caps = Capabilities::new("worker-1");
+/// caps.add_tools_from("static_analysis", &[ToolCapability::new("clippy", true)]);
+///
+/// let checker: &dyn Fn(&str) -> bool = &|tool| tool == "clippy";
+/// assert!(caps.has_capability("static_analysis", checker));
+/// ```
+pub trait ToolChecker {
+    /// Check if a tool is available
+    fn is_available(&self, tool: &str) -> bool;
+
+    /// Version of an available tool, if the checker can detect it
+    ///
+    /// When this returns `None`, version constraints are evaluated against
+    /// the version declared on the capability.
+    fn detected_version(&self, _tool: &str) -> Option<String> {
+        None
+    }
+
+    /// Structured status of a tool
+    ///
+    /// Defaults to combining `is_available` and `detected_version`; checkers
+    /// that know where a tool was found should override it.
+    fn status(&self, tool: &str) -> ToolStatus {
+        if self.is_available(tool) {
+            ToolStatus {
+                version: self.detected_version(tool),
+                ..ToolStatus::found()
+            }
+        } else {
+            ToolStatus::missing()
+        }
+    }
+
+    /// Status of a tool, or `None` if the checker could not answer (e.g., it timed out)
+    ///
+    /// Defaults to `status`; `ToolCheckerExt::with_fallback` consults its
+    /// fallback only when this returns `None`.
+    fn try_status(&self, tool: &str) -> Option<ToolStatus> {
+        Some(self.status(tool))
+    }
+
+    /// SHA256 of the executable the checker resolved for a tool, if it can tell
+    ///
+    /// Defaults to the hash reported by `status`. Pinned tools (see
+    /// `ToolCapability::with_binary_sha256`) only match checkers that report it.
+    fn binary_sha256(&self, tool: &str) -> Option<String> {
+        self.status(tool).sha256
+    }
+
+    /// Key identifying this checker's answers when caching match results
+    ///
+    /// Checkers without a key (the default, including closures) are never
+    /// answered from cache. Keys include the checker's configuration (search
+    /// paths, images, endpoints), so equal keys must mean equal answers.
+    fn cache_key(&self) -> Option<String> {
+        None
+    }
+}
+
+impl<F: Fn(&str) -> bool + ?Sized> ToolChecker for F {
+    fn is_available(&self, tool: &str) -> bool {
+        self(tool)
+    }
+}
+
+/// Checker backed by a closure returning a full `ToolStatus`
+#[derive(Debug, Clone)]
+pub struct StatusChecker<F> {
+    check: F,
+}
+
+impl<F: Fn(&str) -> ToolStatus> StatusChecker<F> {
+    /// Wrap a closure returning the status of a tool
+    pub fn new(check: F) -> Self {
+        Self { check }
+    }
+}
+
+impl<F: Fn(&str) -> ToolStatus> ToolChecker for StatusChecker<F> {
+    fn is_available(&self, tool: &str) -> bool {
+        (self.check)(tool).available
+    }
+
+    fn detected_version(&self, tool: &str) -> Option<String> {
+        (self.check)(tool).version
+    }
+
+    fn status(&self, tool: &str) -> ToolStatus {
+        (self.check)(tool)
+    }
+}
+
+/// Checker that looks for tool executables in a list of directories
+#[derive(Debug, Clone, Default)]
+pub struct PathChecker {
+    dirs: Vec<PathBuf>,
+}
+
+impl PathChecker {
+    /// Search the directories listed in the `PATH` environment variable
+    pub fn from_env() -> Self {
+        let dirs = std::env::var_os("PATH")
+            .map(|path| std::env::split_paths(&path).collect())
+            .unwrap_or_default();
+        Self { dirs }
+    }
+
+    /// Search the given directories, in order
+    pub fn with_dirs<I, P>(dirs: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<PathBuf>,
+    {
+        Self {
+            dirs: dirs.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// First location of a tool executable
+    pub fn locate(&self, tool: &str) -> Option<PathBuf> {
+        if tool.is_empty() || Path::new(tool).components().count() != 1 {
+            return None;
+        }
+        self.dirs
+            .iter()
+            .map(|dir| dir.join(tool))
+            .find(|candidate| candidate.is_file())
+    }
+}
+
+impl ToolChecker for PathChecker {
+    fn is_available(&self, tool: &str) -> bool {
+        self.locate(tool).is_some()
+    }
+
+    fn status(&self, tool: &str) -> ToolStatus {
+        match self.locate(tool) {
+            Some(path) => {
+                let status = ToolStatus::found();
+                let status = match cached_sha256_file(&path) {
+                    Ok(sha256) => status.with_sha256(sha256),
+                    Err(_) => status,
+                };
+                status.with_path(path)
+            }
+            None => ToolStatus::missing(),
+        }
+    }
+
+    fn cache_key(&self) -> Option<String> {
+        Some(format!("path:{:?}", self.dirs))
+    }
+}
+
+/// Checker that caches the results of another checker for a limited time
+///
+/// Available tools are cached for the TTL; missing tools are cached for the
+/// negative TTL (the same TTL unless configured otherwise).
+#[derive(Debug)]
+pub struct CachedChecker<T> {
+    inner: T,
+    ttl: Duration,
+    negative_ttl: Option<Duration>,
+    cache: Mutex<HashMap<String, (Instant, ToolStatus)>>,
+}
+
+impl<T: ToolChecker> CachedChecker<T> {
+    /// Cache the results of a checker for `ttl`
+    pub fn new(inner: T, ttl: Duration) -> Self {
+        Self {
+            inner,
+            ttl,
+            negative_ttl: Some(ttl),
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Set how long available tools are cached
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Set how long missing tools are cached
+    pub fn with_negative_ttl(mut self, ttl: Duration) -> Self {
+        self.negative_ttl = Some(ttl);
+        self
+    }
+
+    /// Never cache missing tools, so newly installed tools are found immediately
+    pub fn without_negative_caching(mut self) -> Self {
+        self.negative_ttl = None;
+        self
+    }
+
+    /// Forget the cached result for a tool
+    pub fn invalidate(&self, tool: &str) {
+        self.entries().remove(tool);
+    }
+
+    /// Forget all cached results
+    pub fn invalidate_all(&self) {
+        self.entries().clear();
+    }
+
+    /// The wrapped checker
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    #[cfg(feature = "http-checker")]
+    pub(crate) fn inner_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    fn entries(&self) -> MutexGuard<'_, HashMap<String, (Instant, ToolStatus)>> {
+        self.cache.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn ttl_for(&self, status: &ToolStatus) -> Option<Duration> {
+        if status.available {
+            Some(self.ttl)
+        } else {
+            self.negative_ttl
+        }
+    }
+}
+
+impl<T: ToolChecker> ToolChecker for CachedChecker<T> {
+    fn is_available(&self, tool: &str) -> bool {
+        self.status(tool).available
+    }
+
+    fn detected_version(&self, tool: &str) -> Option<String> {
+        self.status(tool).version
+    }
+
+    fn status(&self, tool: &str) -> ToolStatus {
+        self.try_status(tool).unwrap_or_else(ToolStatus::missing)
+    }
+
+    /// Unanswered checks are passed through and never cached
+    fn try_status(&self, tool: &str) -> Option<ToolStatus> {
+        if let Some((cached_at, status)) = self.entries().get(tool) {
+            if self
+                .ttl_for(status)
+                .is_some_and(|ttl| cached_at.elapsed() < ttl)
+            {
+                return Some(status.clone());
+            }
+        }
+
+        let status = self.inner.try_status(tool)?;
+        if self.ttl_for(&status).is_some() {
+            self.entries()
+                .insert(tool.to_string(), (Instant::now(), status.clone()));
+        }
+        Some(status)
+    }
+
+    fn cache_key(&self) -> Option<String> {
+        self.inner.cache_key()
+    }
+}
+
+/// Combinators for composing tool checkers
+///
+/// Chains read in priority order, e.g. a local cache, then `PATH`, then a
+/// remote inventory that may be slow:
+///
+/// ```rust
+/// use std::time::Duration;
+/// use worker_capabilities::{PathChecker, ToolChecker, ToolCheckerExt};
+///
+/// let cache = |tool: &str| tool == "clippy";
+/// let remote = |tool: &str| tool == "semgrep";
+/// let checker = cache
+///     .or(PathChecker::from_env())
+///     .or(remote.with_timeout(Duration::from_secs(2)));
+///
+/// assert!(checker.is_available("clippy"));
+/// assert!(checker.is_available("semgrep"));
+/// ```
+pub trait ToolCheckerExt: ToolChecker + Sized {
+    /// Available if this checker or, failing that, `other` finds the tool
+    fn or<C: ToolChecker>(self, other: C) -> Or<Self, C> {
+        Or {
+            first: self,
+            second: other,
+        }
+    }
+
+    /// Available only if both this checker and `other` find the tool
+    fn and<C: ToolChecker>(self, other: C) -> And<Self, C> {
+        And {
+            first: self,
+            second: other,
+        }
+    }
+
+    /// Try this checker, then `other`, giving each at most `timeout` per tool
+    ///
+    /// Add further fallbacks with `FirstSuccess::with_checker`.
+    fn first_success_with_timeout<C>(self, other: C, timeout: Duration) -> FirstSuccess
+    where
+        Self: Send + Sync + 'static,
+        C: ToolChecker + Send + Sync + 'static,
+    {
+        FirstSuccess::new(timeout)
+            .with_checker(self)
+            .with_checker(other)
+    }
+
+    /// Give this checker at most `timeout` per tool
+    ///
+    /// A check that does not finish in time is unanswered: it reports the
+    /// tool as missing, and `try_status` returns `None`.
+    fn with_timeout(self, timeout: Duration) -> WithTimeout
+    where
+        Self: Send + Sync + 'static,
+    {
+        WithTimeout {
+            checker: Arc::new(self),
+            timeout,
+        }
+    }
+
+    /// Consult `fallback` only when this checker cannot answer
+    ///
+    /// Unlike `or`, a definite "missing" from this checker is final; the
+    /// fallback is used for checks that time out (see `with_timeout`).
+    fn with_fallback<C: ToolChecker>(self, fallback: C) -> Fallback<Self, C> {
+        Fallback {
+            primary: self,
+            fallback,
+        }
+    }
+}
+
+impl<T: ToolChecker> ToolCheckerExt for T {}
+
+/// Checker falling back to a second checker (see `ToolCheckerExt::or`)
+#[derive(Debug, Clone)]
+pub struct Or<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A: ToolChecker, B: ToolChecker> ToolChecker for Or<A, B> {
+    fn is_available(&self, tool: &str) -> bool {
+        self.first.is_available(tool) || self.second.is_available(tool)
+    }
+
+    fn detected_version(&self, tool: &str) -> Option<String> {
+        self.status(tool).version
+    }
+
+    fn status(&self, tool: &str) -> ToolStatus {
+        let status = self.first.status(tool);
+        if status.available {
+            status
+        } else {
+            self.second.status(tool)
+        }
+    }
+
+    /// Unanswered only if the second checker is consulted and cannot answer
+    fn try_status(&self, tool: &str) -> Option<ToolStatus> {
+        match self.first.try_status(tool) {
+            Some(status) if status.available => Some(status),
+            _ => self.second.try_status(tool),
+        }
+    }
+
+    fn cache_key(&self) -> Option<String> {
+        Some(format!(
+            "or({},{})",
+            self.first.cache_key()?,
+            self.second.cache_key()?
+        ))
+    }
+}
+
+/// Checker requiring both checkers to succeed (see `ToolCheckerExt::and`)
+#[derive(Debug, Clone)]
+pub struct And<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A: ToolChecker, B: ToolChecker> ToolChecker for And<A, B> {
+    fn is_available(&self, tool: &str) -> bool {
+        self.first.is_available(tool) && self.second.is_available(tool)
+    }
+
+    fn detected_version(&self, tool: &str) -> Option<String> {
+        self.status(tool).version
+    }
+
+    fn status(&self, tool: &str) -> ToolStatus {
+        let first = self.first.status(tool);
+        if !first.available {
+            return first;
+        }
+        let second = self.second.status(tool);
+        if !second.available {
+            return second;
+        }
+        ToolStatus {
+            version: first.version.or(second.version),
+            sha256: first.sha256.or(second.sha256),
+            path: first.path.or(second.path),
+            ..first
+        }
+    }
+
+    /// Unanswered if either checker cannot answer before the tool is ruled out
+    fn try_status(&self, tool: &str) -> Option<ToolStatus> {
+        let first = self.first.try_status(tool)?;
+        if !first.available {
+            return Some(first);
+        }
+        let second = self.second.try_status(tool)?;
+        if !second.available {
+            return Some(second);
+        }
+        Some(ToolStatus {
+            version: first.version.or(second.version),
+            sha256: first.sha256.or(second.sha256),
+            path: first.path.or(second.path),
+            ..first
+        })
+    }
+
+    fn cache_key(&self) -> Option<String> {
+        Some(format!(
+            "and({},{})",
+            self.first.cache_key()?,
+            self.second.cache_key()?
+        ))
+    }
+}
+
+/// Checker bounded by a timeout (see `ToolCheckerExt::with_timeout`)
+#[derive(Clone)]
+pub struct WithTimeout {
+    checker: Arc<dyn ToolChecker + Send + Sync>,
+    timeout: Duration,
+}
+
+impl std::fmt::Debug for WithTimeout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WithTimeout")
+            .field("timeout", &self.timeout)
+            .finish()
+    }
+}
+
+impl ToolChecker for WithTimeout {
+    fn is_available(&self, tool: &str) -> bool {
+        self.status(tool).available
+    }
+
+    fn detected_version(&self, tool: &str) -> Option<String> {
+        self.status(tool).version
+    }
+
+    fn status(&self, tool: &str) -> ToolStatus {
+        self.try_status(tool).unwrap_or_else(ToolStatus::missing)
+    }
+
+    fn try_status(&self, tool: &str) -> Option<ToolStatus> {
+        status_within_timeout(&self.checker, tool, self.timeout)
+    }
+
+    fn cache_key(&self) -> Option<String> {
+        self.checker.cache_key()
+    }
+}
+
+/// Checker consulted when another cannot answer (see `ToolCheckerExt::with_fallback`)
+#[derive(Debug, Clone)]
+pub struct Fallback<A, B> {
+    primary: A,
+    fallback: B,
+}
+
+impl<A: ToolChecker, B: ToolChecker> ToolChecker for Fallback<A, B> {
+    fn is_available(&self, tool: &str) -> bool {
+        self.status(tool).available
+    }
+
+    fn detected_version(&self, tool: &str) -> Option<String> {
+        self.status(tool).version
+    }
+
+    fn status(&self, tool: &str) -> ToolStatus {
+        self.try_status(tool).unwrap_or_else(ToolStatus::missing)
+    }
+
+    fn try_status(&self, tool: &str) -> Option<ToolStatus> {
+        self.primary
+            .try_status(tool)
+            .or_else(|| self.fallback.try_status(tool))
+    }
+
+    fn cache_key(&self) -> Option<String> {
+        Some(format!(
+            "fallback({},{})",
+            self.primary.cache_key()?,
+            self.fallback.cache_key()?
+        ))
+    }
+}
+
+/// Checkers tried in priority order, each bounded by a timeout
+///
+/// A checker that does not answer in time counts as not finding the tool;
+/// its check finishes on the shared checker pool and its result is discarded.
+#[derive(Clone)]
+pub struct FirstSuccess {
+    checkers: Vec<Arc<dyn ToolChecker + Send + Sync>>,
+    timeout: Duration,
+}
+
+impl FirstSuccess {
+    /// Create an empty chain giving each checker at most `timeout` per tool
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            checkers: Vec::new(),
+            timeout,
+        }
+    }
+
+    /// Append a checker with lower priority than those already added
+    pub fn with_checker(mut self, checker: impl ToolChecker + Send + Sync + 'static) -> Self {
+        self.checkers.push(Arc::new(checker));
+        self
+    }
+}
+
+impl std::fmt::Debug for FirstSuccess {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FirstSuccess")
+            .field("checkers", &self.checkers.len())
+            .field("timeout", &self.timeout)
+            .finish()
+    }
+}
+
+impl ToolChecker for FirstSuccess {
+    fn is_available(&self, tool: &str) -> bool {
+        self.status(tool).available
+    }
+
+    fn detected_version(&self, tool: &str) -> Option<String> {
+        self.status(tool).version
+    }
+
+    fn status(&self, tool: &str) -> ToolStatus {
+        self.checkers
+            .iter()
+            .filter_map(|checker| status_within_timeout(checker, tool, self.timeout))
+            .find(|status| status.available)
+            .unwrap_or_else(ToolStatus::missing)
+    }
+
+    fn cache_key(&self) -> Option<String> {
+        let keys: Option<Vec<String>> = self
+            .checkers
+            .iter()
+            .map(|checker| checker.cache_key())
+            .collect();
+        Some(format!("first({})", keys?.join(",")))
+    }
+}
+
+/// Check a tool on the shared checker pool, giving up after `timeout`
+///
+/// The pool has `TOOL_CHECK_POOL_THREADS` threads. A check that times out
+/// finishes there and its result is discarded; a check still queued when
+/// its caller gave up is skipped. Checks read the caller's clock.
+fn status_within_timeout(
+    checker: &Arc<dyn ToolChecker + Send + Sync>,
+    tool: &str,
+    timeout: Duration,
+) -> Option<ToolStatus> {
+    let (sender, receiver) = mpsc::channel();
+    let checker = Arc::clone(checker);
+    let tool = tool.to_string();
+    let deadline = Instant::now() + timeout;
+    let clock = clock::current();
+    check_pool()
+        .send(Box::new(move || {
+            if Instant::now() < deadline {
+                let status = clock::with_inherited(clock, || checker.try_status(&tool));
+                let _ = sender.send(status);
+            }
+        }))
+        .ok()?;
+    receiver.recv_timeout(timeout).ok().flatten()
+}
+
+type CheckJob = Box<dyn FnOnce() + Send>;
+
+/// Queue of the threads running timeout-bounded checks
+fn check_pool() -> &'static mpsc::Sender<CheckJob> {
+    static POOL: OnceLock<mpsc::Sender<CheckJob>> = OnceLock::new();
+    POOL.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel::<CheckJob>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        for _ in 0..TOOL_CHECK_POOL_THREADS {
+            let receiver = Arc::clone(&receiver);
+            std::thread::spawn(move || loop {
+                let job = receiver.lock().unwrap_or_else(|e| e.into_inner()).recv();
+                match job {
+                    Ok(job) => job(),
+                    Err(_) => return,
+                }
+            });
+        }
+        sender
+    })
+}
+
+/// Run a command and return its combined output if it exits successfully in time
+pub(crate) fn run_with_timeout(
+    program: &str,
+    args: &[String],
+    timeout: Duration,
+) -> Option<String> {
+    run_until(program, args, timeout).ok()
+}
+
+/// Why a command run by `run_until` produced no output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RunError {
+    /// The command could not start or exited unsuccessfully
+    Failed,
+    /// The command did not finish in time and was killed
+    TimedOut,
+}
+
+/// Run a command and return its combined output, telling failures from timeouts
+///
+/// Output is drained on reader threads while waiting, so a command that
+/// writes more than a pipe buffer cannot stall until the timeout.
+pub(crate) fn run_until(
+    program: &str,
+    args: &[String],
+    timeout: Duration,
+) -> Result<String, RunError> {
+    let mut command = Command::new(program);
+    command
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut command, 0);
+    let mut child = command.spawn().map_err(|_| RunError::Failed)?;
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() < deadline => std::thread::sleep(Duration::from_millis(10)),
+            Ok(None) => {
+                kill(&mut child);
+                return Err(RunError::TimedOut);
+            }
+            Err(_) => {
+                kill(&mut child);
+                return Err(RunError::Failed);
+            }
+        }
+    };
+    if !status.success() {
+        return Err(RunError::Failed);
+    }
+
+    // Descendants may keep the pipes open past the exit; wait for them only until the deadline
+    let remaining = || deadline.saturating_duration_since(Instant::now());
+    let stdout = stdout
+        .recv_timeout(remaining())
+        .map_err(|_| RunError::TimedOut)?;
+    let stderr = stderr
+        .recv_timeout(remaining())
+        .map_err(|_| RunError::TimedOut)?;
+
+    let mut text = String::from_utf8_lossy(&stdout).into_owned();
+    text.push('\n');
+    text.push_str(&String::from_utf8_lossy(&stderr));
+    Ok(text)
+}
+
+/// Kill and reap a child, along with the processes it started on Unix
+///
+/// Descendants holding the output pipes open would otherwise keep the
+/// reader threads alive after the child is gone.
+fn kill(child: &mut std::process::Child) {
+    #[cfg(unix)]
+    let _ = Command::new("kill")
+        .args(["-KILL", "--", &format!("-{}", child.id())])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+/// Read a child's pipe to the end on a background thread
+fn drain(pipe: Option<impl Read + Send + 'static>) -> mpsc::Receiver<Vec<u8>> {
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        let mut output = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut output);
+        }
+        let _ = sender.send(output);
+    });
+    receiver
+}
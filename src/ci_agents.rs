@@ -0,0 +1,143 @@
+//! Bridge between Jenkins/Buildkite agent labels and capability sets
+//!
+//! Jenkins labels use `<category>:<tool>` for tools and `<key>=<value>` for
+//! metadata; every other label becomes a flag. Buildkite tags use
+//! `tool.<category>.<tool>=true` for tools and `flag.<name>=true` for flags;
+//! every other tag (including `queue`) becomes metadata.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::capabilities::Capabilities;
+use crate::registry::CapabilityRegistry;
+use crate::types::ToolCapability;
+
+/// CI system whose agents are bridged
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CiSystem {
+    /// Jenkins agent labels
+    Jenkins,
+    /// Buildkite agent tags
+    Buildkite,
+}
+
+fn add_tool(caps: &mut Capabilities, category: &str, tool: &str) -> bool {
+    match caps.tools_in_category_mut(category) {
+        Some(tools) => {
+            tools.push(ToolCapability::new(tool, false));
+            true
+        }
+        None => false,
+    }
+}
+
+impl Capabilities {
+    /// Build a capability set from the labels or tags of a CI agent
+    ///
+    /// Tools are imported as optional tools.
+    pub fn from_ci_agent(system: CiSystem, agent_name: impl Into<String>, tags: &[String]) -> Self {
+        let mut caps = Capabilities::new(agent_name);
+
+        for tag in tags
+            .iter()
+            .map(|tag| tag.trim())
+            .filter(|tag| !tag.is_empty())
+        {
+            match system {
+                CiSystem::Jenkins => {
+                    if let Some((category, tool)) = tag.split_once(':') {
+                        if add_tool(&mut caps, category, tool) {
+                            continue;
+                        }
+                    }
+                    match tag.split_once('=') {
+                        Some((key, value)) => {
+                            caps.metadata.insert(key.to_string(), value.to_string());
+                        }
+                        None => {
+                            caps.flags.insert(tag.to_string(), true);
+                        }
+                    }
+                }
+                CiSystem::Buildkite => {
+                    let (key, value) = tag.split_once('=').unwrap_or((tag, "true"));
+                    if let Some((category, tool)) = key
+                        .strip_prefix("tool.")
+                        .and_then(|rest| rest.split_once('.'))
+                    {
+                        if add_tool(&mut caps, category, tool) {
+                            continue;
+                        }
+                    }
+                    match key.strip_prefix("flag.") {
+                        Some(flag) => {
+                            caps.flags.insert(flag.to_string(), value == "true");
+                        }
+                        None => {
+                            caps.metadata.insert(key.to_string(), value.to_string());
+                        }
+                    }
+                }
+            }
+        }
+
+        caps
+    }
+
+    /// Generate the labels or tags a CI agent needs to advertise this capability set
+    pub fn to_ci_agent_tags(&self, system: CiSystem) -> Vec<String> {
+        let mut tags = Vec::new();
+
+        for category in self.category_names() {
+            for tool in self.tools_in_category(&category) {
+                tags.push(match system {
+                    CiSystem::Jenkins => format!("{}:{}", category, tool.tool_name),
+                    CiSystem::Buildkite => format!("tool.{}.{}=true", category, tool.tool_name),
+                });
+            }
+        }
+
+        let mut flags: Vec<(&String, &bool)> = self.flags.iter().collect();
+        flags.sort();
+        for (flag, enabled) in flags {
+            match system {
+                CiSystem::Jenkins if *enabled => tags.push(flag.clone()),
+                CiSystem::Jenkins => {}
+                CiSystem::Buildkite => tags.push(format!("flag.{}={}", flag, enabled)),
+            }
+        }
+
+        let mut metadata: Vec<(&String, &String)> = self.metadata.iter().collect();
+        metadata.sort();
+        for (key, value) in metadata {
+            tags.push(format!("{}={}", key, value));
+        }
+
+        tags
+    }
+}
+
+impl CapabilityRegistry {
+    /// Register CI agents from their labels or tags
+    pub fn import_ci_agents(&mut self, system: CiSystem, agents: &HashMap<String, Vec<String>>) {
+        for (agent_name, tags) in agents {
+            self.register(Capabilities::from_ci_agent(
+                system,
+                agent_name.clone(),
+                tags,
+            ));
+        }
+    }
+
+    /// Generate the labels or tags every registered worker should advertise to a CI system
+    pub fn export_ci_agent_tags(&self, system: CiSystem) -> HashMap<String, Vec<String>> {
+        self.list_ids()
+            .into_iter()
+            .filter_map(|id| {
+                let tags = self.get(&id)?.to_ci_agent_tags(system);
+                Some((id, tags))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,230 @@
+//! Wall-clock access that tolerates clocks going backwards
+//!
+//! Timestamps are read through `unix_timestamp`, which reports a clock set
+//! before the Unix epoch as `CapabilityError::ClockSkew` instead of panicking.
+//! Tests can substitute a `MockClock` for the current thread with `with_clock`,
+//! or give one to a registry or verifier with their `with_clock` builders;
+//! the crate's background tasks, parallel and timeout-bounded checks inherit
+//! it:
+//!
+//! ```rust
+//! use std::time::Duration;
+//! use worker_capabilities::clock::{self, MockClock};
+//!
+//! let clock = MockClock::at_unix(1_000);
+//! clock::with_clock(clock.clone(), || {
+//!     assert_eq!(clock::unix_timestamp(), Ok(1_000));
+//!     clock.rewind(Duration::from_secs(2_000));
+//!     assert!(clock::unix_timestamp().is_err());
+//! });
+//! ```
+
+use std::cell::RefCell;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::constants::*;
+use crate::error::CapabilityError;
+
+/// Source of wall-clock time
+pub trait Clock {
+    /// Current time
+    fn now(&self) -> SystemTime;
+}
+
+/// Clock that can be carried across threads
+pub type SharedClock = Arc<dyn Clock + Send + Sync>;
+
+impl<C: Clock + ?Sized> Clock for Arc<C> {
+    fn now(&self) -> SystemTime {
+        (**self).now()
+    }
+}
+
+/// The system clock
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// Manually controlled clock for tests
+///
+/// Clones share the same time, so a test can keep a handle and move the
+/// clock while code under test reads it.
+#[derive(Debug, Clone)]
+pub struct MockClock {
+    now: Arc<Mutex<SystemTime>>,
+}
+
+impl MockClock {
+    /// Create a clock stopped at the given time
+    pub fn new(now: SystemTime) -> Self {
+        Self {
+            now: Arc::new(Mutex::new(now)),
+        }
+    }
+
+    /// Create a clock stopped at a Unix timestamp in seconds
+    pub fn at_unix(seconds: u64) -> Self {
+        Self::new(UNIX_EPOCH + Duration::from_secs(seconds))
+    }
+
+    /// Set the current time
+    pub fn set(&self, now: SystemTime) {
+        *self.now.lock().unwrap_or_else(|e| e.into_inner()) = now;
+    }
+
+    /// Move the clock forward
+    pub fn advance(&self, by: Duration) {
+        let mut now = self.now.lock().unwrap_or_else(|e| e.into_inner());
+        *now += by;
+    }
+
+    /// Move the clock backward (possibly before the Unix epoch)
+    pub fn rewind(&self, by: Duration) {
+        let mut now = self.now.lock().unwrap_or_else(|e| e.into_inner());
+        *now -= by;
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> SystemTime {
+        *self.now.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+thread_local! {
+    static THREAD_CLOCK: RefCell<Option<SharedClock>> = const { RefCell::new(None) };
+}
+
+/// Restores the previous thread clock when dropped, including on panic
+pub(crate) struct ClockGuard(Option<SharedClock>);
+
+impl Drop for ClockGuard {
+    fn drop(&mut self) {
+        let previous = self.0.take();
+        THREAD_CLOCK.with(|clock| *clock.borrow_mut() = previous);
+    }
+}
+
+/// Run `f` with `clock` as the time source of the current thread
+///
+/// Background tasks started by `f` (schedulers, sweepers, watchers, metrics
+/// writers) and parallel checks inherit the clock; other threads spawned by
+/// `f` keep using the system clock unless they call `with_inherited`.
+pub fn with_clock<R>(clock: impl Clock + Send + Sync + 'static, f: impl FnOnce() -> R) -> R {
+    let _guard = enter(Arc::new(clock));
+    f()
+}
+
+/// Install `clock` as the time source of the current thread until the guard is dropped
+pub(crate) fn enter(clock: SharedClock) -> ClockGuard {
+    let previous = THREAD_CLOCK.with(|current| current.borrow_mut().replace(clock));
+    ClockGuard(previous)
+}
+
+/// Clock given to a registry or verifier with its `with_clock` builder
+///
+/// Components without one read the thread clock.
+#[derive(Clone, Default)]
+pub(crate) struct InjectedClock(Option<SharedClock>);
+
+impl InjectedClock {
+    pub(crate) fn new(clock: impl Clock + Send + Sync + 'static) -> Self {
+        Self(Some(Arc::new(clock)))
+    }
+
+    /// The injected clock, or else the clock installed for the current thread
+    pub(crate) fn current(&self) -> Option<SharedClock> {
+        self.0.clone().or_else(current)
+    }
+
+    /// Install the injected clock for the current thread until the guard is dropped
+    ///
+    /// Does nothing without an injected clock.
+    pub(crate) fn enter(&self) -> Option<ClockGuard> {
+        self.0.clone().map(enter)
+    }
+}
+
+impl fmt::Debug for InjectedClock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(_) => write!(f, "InjectedClock(..)"),
+            None => write!(f, "InjectedClock(thread)"),
+        }
+    }
+}
+
+/// Clock installed for the current thread, if any
+///
+/// Capture it before spawning a thread and pass it to `with_inherited`.
+pub fn current() -> Option<SharedClock> {
+    THREAD_CLOCK.with(|clock| clock.borrow().clone())
+}
+
+/// Run `f` with a clock captured by `current`, or the system clock if none
+pub fn with_inherited<R>(clock: Option<SharedClock>, f: impl FnOnce() -> R) -> R {
+    match clock {
+        Some(clock) => with_clock(clock, f),
+        None => f(),
+    }
+}
+
+/// Current time from the thread clock (the system clock unless overridden)
+pub fn now() -> SystemTime {
+    THREAD_CLOCK
+        .with(|clock| clock.borrow().as_ref().map(|clock| clock.now()))
+        .unwrap_or_else(SystemTime::now)
+}
+
+/// Current Unix timestamp in seconds
+///
+/// Fails with `ClockSkew` if the clock is set before the Unix epoch.
+pub fn unix_timestamp() -> Result<u64, CapabilityError> {
+    now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .map_err(|e| CapabilityError::ClockSkew {
+            skew_seconds: e.duration().as_secs(),
+        })
+}
+
+/// Current Unix timestamp in seconds (0 if the clock is before the Unix epoch)
+pub(crate) fn current_timestamp() -> u64 {
+    unix_timestamp().unwrap_or(0)
+}
+
+/// Age of a recorded timestamp at `now`
+///
+/// Timestamps up to `MAX_CLOCK_SKEW_SECONDS` in the future count as age zero,
+/// which absorbs small corrections of either clock; anything further ahead
+/// fails with `ClockSkew`.
+pub fn age_at(timestamp: u64, now: u64) -> Result<u64, CapabilityError> {
+    age_within(timestamp, now, 0)
+}
+
+/// Age of a recorded timestamp at `now`, tolerating more skew
+///
+/// Timestamps up to `skew_tolerance` (or `MAX_CLOCK_SKEW_SECONDS`, if larger)
+/// in the future count as age zero.
+pub fn age_within(timestamp: u64, now: u64, skew_tolerance: u64) -> Result<u64, CapabilityError> {
+    match timestamp.checked_sub(now) {
+        Some(ahead) if ahead > MAX_CLOCK_SKEW_SECONDS.max(skew_tolerance) => {
+            Err(CapabilityError::ClockSkew {
+                skew_seconds: ahead,
+            })
+        }
+        _ => Ok(now.saturating_sub(timestamp)),
+    }
+}
+
+/// Age of a recorded timestamp now
+pub fn age(timestamp: u64) -> Result<u64, CapabilityError> {
+    age_at(timestamp, unix_timestamp()?)
+}
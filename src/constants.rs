@@ -12,6 +12,10 @@ pub const MAX_EXPIRATION_DAYS: u64 = 365;
 // Attestation constants
 pub const ATTESTATION_EXPIRY_DAYS: u64 = 365;
 pub const DEFAULT_ATTESTATION_ALGORITHM: &str = "SHA256-RSA";
+pub const ED25519_ATTESTATION_ALGORITHM: &str = "Ed25519";
+pub const JWS_ALGORITHM_PREFIX: &str = "JWS/";
+pub const X509_ALGORITHM_PREFIX: &str = "X509/";
+pub const SIGSTORE_ATTESTATION_ALGORITHM: &str = "Sigstore";
 
 // Security limits
 pub const MAX_TOOL_NAME_LENGTH: usize = 256;
@@ -22,9 +26,120 @@ pub const MAX_METADATA_ENTRIES: usize = 50;
 // Hash constants
 pub const CAPABILITY_HASH_LENGTH: usize = 64; // SHA256 hex length
 
+// Verification scheduler constants
+pub const DEFAULT_VERIFICATION_INTERVAL_SECONDS: u64 = 300;
+
+// Clock constants
+pub const MAX_CLOCK_SKEW_SECONDS: u64 = 300;
+pub const MAX_EXPIRATION_TOLERANCE_SECONDS: u64 = 24 * 60 * 60; // 1 day
+
+// Trust constants
+pub const TRUST_BUNDLE_FORMAT_VERSION: u32 = 1;
+
+// Capability bundle constants
+pub const CAPABILITY_BUNDLE_FORMAT_VERSION: u32 = 1;
+
+// Protocol constants
+pub const PROTOCOL_VERSION: u32 = 2;
+pub const MIN_PROTOCOL_VERSION: u32 = 1;
+
+// Descriptor constants
+pub const CAPABILITY_DESCRIPTOR_PATH: &str = "/.well-known/worker-capabilities";
+pub const DEFAULT_DESCRIPTOR_FETCH_TIMEOUT_SECONDS: u64 = 10;
+
+// Sigstore constants
+pub const DEFAULT_FULCIO_URL: &str = "https://fulcio.sigstore.dev";
+pub const DEFAULT_REKOR_URL: &str = "https://rekor.sigstore.dev";
+pub const DEFAULT_SIGSTORE_TIMEOUT_SECONDS: u64 = 30;
+
+// in-toto constants
+pub const IN_TOTO_STATEMENT_TYPE: &str = "https://in-toto.io/Statement/v1";
+pub const IN_TOTO_STATEMENT_TYPES: &[&str] =
+    &[IN_TOTO_STATEMENT_TYPE, "https://in-toto.io/Statement/v0.1"];
+pub const IN_TOTO_PAYLOAD_TYPE: &str = "application/vnd.in-toto+json";
+pub const CAPABILITY_PREDICATE_TYPE: &str =
+    "https://github.com/redasgard/worker-capabilities/capability/v1";
+
+// Tool checker constants
+pub const TOOL_CHECK_POOL_THREADS: usize = 8;
+
+// Version probe constants
+pub const DEFAULT_VERSION_PROBE_ARG: &str = "--version";
+pub const DEFAULT_VERSION_PATTERN: &str = r"v?(\d+\.\d+(?:\.\d+)?(?:-[0-9A-Za-z.-]+)?)";
+pub const DEFAULT_VERSION_PROBE_TIMEOUT_SECONDS: u64 = 5;
+
+// Container checker constants
+pub const DEFAULT_CONTAINER_RUNTIME: &str = "docker";
+pub const DEFAULT_CONTAINER_CHECK_TIMEOUT_SECONDS: u64 = 30;
+
+// HTTP checker constants
+pub const HTTP_CHECKER_TOOLS_PATH: &str = "/tools/";
+pub const DEFAULT_HTTP_CHECKER_TIMEOUT_SECONDS: u64 = 5;
+pub const DEFAULT_HTTP_CHECKER_RETRIES: u32 = 2;
+pub const DEFAULT_HTTP_CHECKER_RETRY_BACKOFF_MILLIS: u64 = 200;
+pub const DEFAULT_HTTP_CHECKER_CACHE_TTL_SECONDS: u64 = 60;
+
+// Revocation checker constants
+pub const REVOCATION_CHECK_PATH: &str = "/revocations/";
+pub const REVOCATION_WORKER_CHECK_PATH: &str = "/revocations/workers/";
+pub const DEFAULT_REVOCATION_CHECK_TIMEOUT_SECONDS: u64 = 5;
+pub const DEFAULT_REVOCATION_CHECK_RETRIES: u32 = 2;
+pub const DEFAULT_REVOCATION_CHECK_RETRY_BACKOFF_MILLIS: u64 = 200;
+pub const DEFAULT_REVOCATION_CHECK_CACHE_TTL_SECONDS: u64 = 300;
+
+// Discovery constants
+pub const DISCOVERY_META_OS: &str = "platform.os";
+pub const DISCOVERY_META_ARCH: &str = "platform.arch";
+pub const DISCOVERY_META_FAMILY: &str = "platform.family";
+
+// Environment snapshot constants
+pub const CONTAINER_IMAGE_DIGEST_ENV_VAR: &str = "CONTAINER_IMAGE_DIGEST";
+pub const ENVIRONMENT_SNAPSHOT_ENV_VARS: &[&str] = &[
+    "CI",
+    "LANG",
+    "TZ",
+    "RUSTUP_TOOLCHAIN",
+    "JAVA_HOME",
+    "GOPATH",
+    "VIRTUAL_ENV",
+    "NODE_ENV",
+];
+
+// Metrics constants
+pub const METRICS_PREFIX: &str = "worker_capabilities";
+pub const METRICS_EXPIRING_SOON_SECONDS: u64 = 60 * 60;
+
 // Registry limits
 pub const MAX_REGISTERED_WORKERS: usize = 1000;
 pub const MAX_TOOLS_PER_WORKER: usize = 100;
+pub const MAX_REGISTRATION_REJECTIONS: usize = 100;
+pub const MAX_AUDIT_LOG_EVENTS: usize = 1000;
+
+// Session constants
+pub const DEFAULT_SESSION_HEARTBEAT_TIMEOUT_SECONDS: u64 = 300;
+pub const SESSION_RETENTION_SECONDS: u64 = 60 * 60;
+
+// Priming constants
+pub const DEFAULT_WARM_ASSET_MAX_AGE_SECONDS: u64 = 24 * 60 * 60;
+
+// Load shedding constants
+pub const DEFAULT_MATCH_CACHE_TTL_SECONDS: u64 = 30;
+
+// Match result cache constants
+pub const DEFAULT_MATCH_RESULT_CACHE_CAPACITY: usize = 1024;
+pub const DEFAULT_MATCH_RESULT_CACHE_TTL_SECONDS: u64 = 30;
+
+// Retry budget constants
+pub const DEFAULT_RETRY_BUDGET_CAPACITY: f64 = 10.0;
+pub const DEFAULT_RETRY_BUDGET_REFILL_PER_SECOND: f64 = 0.1;
+
+// Resource limit enforcement constants
+pub const DEFAULT_CGROUP_ROOT: &str = "/sys/fs/cgroup";
+pub const CGROUP_NAME_PREFIX: &str = "worker-capabilities";
+pub const CGROUP_CPU_PERIOD_MICROS: u64 = 100_000;
+
+// WASI execution constants
+pub const WASI_EPOCH_TICK_MILLIS: u64 = 100;
 
 // Permission constants
 pub const PERMISSION_FILESYSTEM_ACCESS: &str = "filesystem_access";
@@ -39,3 +154,14 @@ pub const CAPABILITY_SECURITY_SCANNING: &str = "security_scanning";
 pub const CAPABILITY_DYNAMIC_ANALYSIS: &str = "dynamic_analysis";
 pub const CAPABILITY_FUZZING: &str = "fuzzing";
 pub const CAPABILITY_TEST_FRAMEWORK: &str = "test_framework";
+
+pub const CAPABILITY_TYPES: [&str; 5] = [
+    CAPABILITY_STATIC_ANALYSIS,
+    CAPABILITY_SECURITY_SCANNING,
+    CAPABILITY_DYNAMIC_ANALYSIS,
+    CAPABILITY_FUZZING,
+    CAPABILITY_TEST_FRAMEWORK,
+];
+
+/// User-defined category for tools that fit no built-in category
+pub const CAPABILITY_GENERIC: &str = "generic";
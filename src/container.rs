@@ -0,0 +1,158 @@
+//! Tool checker that looks for tools inside container images or running containers
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use crate::checker::{run_until, RunError, ToolChecker, ToolStatus};
+use crate::constants::*;
+use crate::version::parse_tool_version;
+
+/// Shell script run inside the container: print the tool path, its SHA256
+/// (an empty line without `sha256sum`), then its version output
+const CONTAINER_PROBE_SCRIPT: &str = r#"p=$(command -v "$1") || exit 127; echo "$p"; echo "$(sha256sum "$p" 2>/dev/null | cut -c1-64)"; "$1" --version 2>&1 </dev/null || true"#;
+
+/// Where tools are looked up
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ContainerTarget {
+    /// A throwaway container started from an image for each check
+    Image(String),
+    /// An already running container
+    Container(String),
+}
+
+/// Checker that runs `docker`/`podman` to find tools inside a container
+///
+/// Each check starts a process (and, for images, a container), so wrap the
+/// checker in a `CachedChecker` when checking many tools repeatedly.
+#[derive(Debug, Clone)]
+pub struct ContainerToolChecker {
+    runtime: String,
+    target: ContainerTarget,
+    timeout: Duration,
+}
+
+impl ContainerToolChecker {
+    /// Check tools in containers started from an image
+    pub fn image(image: impl Into<String>) -> Self {
+        Self::new(ContainerTarget::Image(image.into()))
+    }
+
+    /// Check tools in a running container
+    pub fn container(name: impl Into<String>) -> Self {
+        Self::new(ContainerTarget::Container(name.into()))
+    }
+
+    fn new(target: ContainerTarget) -> Self {
+        Self {
+            runtime: DEFAULT_CONTAINER_RUNTIME.to_string(),
+            target,
+            timeout: Duration::from_secs(DEFAULT_CONTAINER_CHECK_TIMEOUT_SECONDS),
+        }
+    }
+
+    /// Set the container runtime executable (e.g., `podman`)
+    pub fn with_runtime(mut self, runtime: impl Into<String>) -> Self {
+        self.runtime = runtime.into();
+        self
+    }
+
+    /// Set how long a check may run before it is abandoned
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// The image or container checked
+    pub fn target(&self) -> &ContainerTarget {
+        &self.target
+    }
+
+    /// Runtime arguments that run the probe script for a tool in container `name`
+    fn probe_args(&self, tool: &str, name: &str) -> Vec<String> {
+        let mut args: Vec<String> = match &self.target {
+            ContainerTarget::Image(image) => vec![
+                "run".into(),
+                "--rm".into(),
+                format!("--name={}", name),
+                "--network=none".into(),
+                "--entrypoint".into(),
+                "sh".into(),
+                image.clone(),
+            ],
+            ContainerTarget::Container(name) => vec!["exec".into(), name.clone(), "sh".into()],
+        };
+        args.extend([
+            "-c".to_string(),
+            CONTAINER_PROBE_SCRIPT.to_string(),
+            "sh".to_string(),
+            tool.to_string(),
+        ]);
+        args
+    }
+}
+
+impl ToolChecker for ContainerToolChecker {
+    fn is_available(&self, tool: &str) -> bool {
+        self.status(tool).available
+    }
+
+    fn detected_version(&self, tool: &str) -> Option<String> {
+        self.status(tool).version
+    }
+
+    fn status(&self, tool: &str) -> ToolStatus {
+        if tool.is_empty() {
+            return ToolStatus::missing();
+        }
+
+        let name = probe_container_name();
+        let output = match run_until(&self.runtime, &self.probe_args(tool, &name), self.timeout) {
+            Ok(output) => output,
+            Err(RunError::TimedOut) => {
+                // Killing the runtime client leaves a started container running
+                if let ContainerTarget::Image(_) = self.target {
+                    let remove = ["rm".to_string(), "-f".to_string(), name];
+                    let _ = run_until(&self.runtime, &remove, self.timeout);
+                }
+                return ToolStatus::missing();
+            }
+            Err(RunError::Failed) => return ToolStatus::missing(),
+        };
+
+        let mut lines = output.lines();
+        let mut status = ToolStatus::found();
+        if let Some(path) = lines.next().map(str::trim).filter(|path| !path.is_empty()) {
+            status = status.with_path(path);
+        }
+        if let Some(sha256) = lines
+            .next()
+            .map(str::trim)
+            .filter(|line| is_sha256_hex(line))
+        {
+            status = status.with_sha256(sha256);
+        }
+        if let Some(version) = lines.find_map(parse_tool_version) {
+            status = status.with_version(version.to_string());
+        }
+        status
+    }
+
+    fn cache_key(&self) -> Option<String> {
+        Some(format!("container:{}:{:?}", self.runtime, self.target))
+    }
+}
+
+/// Unique name for a throwaway probe container
+fn probe_container_name() -> String {
+    static NEXT: AtomicU64 = AtomicU64::new(0);
+    format!(
+        "worker-capabilities-probe-{}-{}",
+        std::process::id(),
+        NEXT.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+/// Whether a probe output line is a hex SHA256 digest
+fn is_sha256_hex(line: &str) -> bool {
+    line.len() == 64 && line.bytes().all(|byte| byte.is_ascii_hexdigit())
+}
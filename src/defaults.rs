@@ -0,0 +1,74 @@
+//! Per-category permission baselines applied when tools are added
+//!
+//! Baselines are applied by `CapabilitiesBuilder`:
+//!
+//! ```rust
+//! use worker_capabilities::{Capabilities, CategoryDefaults};
+//!
+//! let caps = Capabilities::builder("worker-1")
+//!     .with_category_defaults(CategoryDefaults::recommended())
+//!     .with_fuzzing_tool("afl", true)
+//!     .build()
+//!     .unwrap();
+//! assert!(caps.find_tool("afl").unwrap().permissions.process_spawn);
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::constants::*;
+use crate::types::{CapabilityPermissions, PathRule};
+
+/// Permission baselines keyed by capability category
+///
+/// Categories without a baseline use `CapabilityPermissions::default()`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CategoryDefaults {
+    /// Baseline permissions per category
+    pub permissions: HashMap<String, CapabilityPermissions>,
+}
+
+impl CategoryDefaults {
+    /// Create an empty configuration
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Recommended baselines for the built-in categories
+    ///
+    /// Fuzzing tools may spawn processes and read and write below `/corpus`;
+    /// static analysis tools never get network access.
+    pub fn recommended() -> Self {
+        let fuzzing = CapabilityPermissions {
+            filesystem_access: true,
+            process_spawn: true,
+            ..CapabilityPermissions::default()
+        }
+        .with_path(PathRule::read_write("/corpus"));
+
+        let static_analysis = CapabilityPermissions {
+            network_access: false,
+            egress_allowlist: Vec::new(),
+            ..CapabilityPermissions::default()
+        };
+
+        Self::new()
+            .with_category(CAPABILITY_FUZZING, fuzzing)
+            .with_category(CAPABILITY_STATIC_ANALYSIS, static_analysis)
+    }
+
+    /// Set the baseline permissions of a category
+    pub fn with_category(
+        mut self,
+        category: impl Into<String>,
+        permissions: CapabilityPermissions,
+    ) -> Self {
+        self.permissions.insert(category.into(), permissions);
+        self
+    }
+
+    /// Baseline permissions for a category
+    pub fn permissions_for(&self, category: &str) -> CapabilityPermissions {
+        self.permissions.get(category).cloned().unwrap_or_default()
+    }
+}
@@ -0,0 +1,102 @@
+//! Rule-based derivation of capability flags from available tools
+
+use serde::{Deserialize, Serialize};
+
+use crate::capabilities::Capabilities;
+use crate::checker::ToolChecker;
+use crate::well_known::{AST_SUPPORT, DOCKER_SUPPORT, FIRECRACKER_SUPPORT, GVISOR_SUPPORT};
+
+/// Rule that sets a flag when any of a set of tools is available
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FlagRule {
+    /// Flag computed by this rule
+    pub flag: String,
+    /// Tools of which at least one must be available
+    pub any_of: Vec<String>,
+}
+
+impl FlagRule {
+    /// Create a rule for a flag with no tools (never set)
+    pub fn new(flag: impl Into<String>) -> Self {
+        Self {
+            flag: flag.into(),
+            any_of: Vec::new(),
+        }
+    }
+
+    /// Set the flag when any of the given tools is available
+    pub fn when_any_of<I, S>(mut self, tools: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.any_of.extend(tools.into_iter().map(Into::into));
+        self
+    }
+
+    /// Recommended rules for the well-known flags
+    pub fn recommended() -> Vec<FlagRule> {
+        vec![
+            FlagRule::new(AST_SUPPORT).when_any_of(["rust-analyzer", "tree-sitter"]),
+            FlagRule::new(DOCKER_SUPPORT).when_any_of(["docker", "podman"]),
+            FlagRule::new(GVISOR_SUPPORT).when_any_of(["runsc"]),
+            FlagRule::new(FIRECRACKER_SUPPORT).when_any_of(["firecracker"]),
+        ]
+    }
+
+    /// Evaluate the rule against a tool checker
+    pub fn evaluate(&self, tool_checker: &(impl ToolChecker + ?Sized)) -> bool {
+        self.any_of
+            .iter()
+            .any(|tool| tool_checker.is_available(tool))
+    }
+}
+
+impl Capabilities {
+    /// Recompute flags from derivation rules, probing tools with the checker
+    ///
+    /// Flags covered by a rule are overwritten and marked as computed; flags
+    /// computed by a previous run but no longer covered by any rule are removed.
+    pub fn derive_flags(&mut self, rules: &[FlagRule], tool_checker: &(impl ToolChecker + ?Sized)) {
+        let derived: Vec<(String, bool)> = rules
+            .iter()
+            .map(|rule| (rule.flag.clone(), rule.evaluate(tool_checker)))
+            .collect();
+        self.apply_derived_flags(derived);
+    }
+
+    /// Recompute flags from derivation rules using the usable declared tools
+    pub fn derive_declared_flags(&mut self, rules: &[FlagRule]) {
+        let declared = |name: &str| {
+            self.tools_iter().any(|tool| {
+                tool.satisfied_by(&|candidate: &str| candidate == name)
+                    .is_some()
+            })
+        };
+        let derived: Vec<(String, bool)> = rules
+            .iter()
+            .map(|rule| (rule.flag.clone(), rule.evaluate(&declared)))
+            .collect();
+        self.apply_derived_flags(derived);
+    }
+
+    fn apply_derived_flags(&mut self, derived: Vec<(String, bool)>) {
+        for flag in std::mem::take(&mut self.computed_flags) {
+            self.flags.remove(&flag);
+        }
+
+        for (flag, enabled) in derived {
+            if self.is_computed_flag(&flag) {
+                *self.flags.entry(flag).or_insert(false) |= enabled;
+            } else {
+                self.flags.insert(flag.clone(), enabled);
+                self.computed_flags.push(flag);
+            }
+        }
+    }
+
+    /// Check if a flag was computed by a derivation rule rather than declared
+    pub fn is_computed_flag(&self, flag: &str) -> bool {
+        self.computed_flags.iter().any(|computed| computed == flag)
+    }
+}
@@ -0,0 +1,203 @@
+//! Self-description payload returned by worker agents
+//!
+//! Workers serve a `CapabilityDescriptor` from the well-known path
+//! `/.well-known/worker-capabilities`. The descriptor carries the capability
+//! set, its digest, an optional attestation over the digest, the schema
+//! version and the crate version that produced it. With the `http` feature,
+//! `fetch_descriptor` retrieves and verifies a descriptor from a worker.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::fmt;
+
+use crate::capabilities::Capabilities;
+use crate::constants::*;
+use crate::signing::{AttestationSigner, AttestationVerifier};
+use crate::types::CapabilityAttestation;
+
+/// Capability self-description of a worker
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CapabilityDescriptor {
+    /// Protocol schema version of the document
+    pub schema_version: u32,
+    /// Version of this crate that produced the document
+    pub crate_version: String,
+    /// Capabilities of the worker
+    pub capabilities: Capabilities,
+    /// SHA256 digest of the canonical capabilities document
+    pub digest: String,
+    /// Attestation over the digest (if signed)
+    #[serde(default)]
+    pub attestation: Option<CapabilityAttestation>,
+}
+
+impl CapabilityDescriptor {
+    /// Describe a capability set
+    ///
+    /// Fails if the capability set cannot be serialized for its digest.
+    pub fn new(capabilities: Capabilities) -> Result<Self, serde_json::Error> {
+        let digest = descriptor_digest(&capabilities)?;
+        Ok(Self {
+            schema_version: PROTOCOL_VERSION,
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            capabilities,
+            digest,
+            attestation: None,
+        })
+    }
+
+    /// Attest the descriptor digest with a signer
+    pub fn signed(mut self, signer: &dyn AttestationSigner) -> Self {
+        self.attestation = Some(signer.attest_hash(&self.digest));
+        self
+    }
+
+    /// Verify the schema version, digest and attestation signature (if present)
+    pub fn verify(&self, verifier: &dyn AttestationVerifier) -> Result<(), DescriptorError> {
+        self.verify_contents()?;
+
+        if let Some(attestation) = &self.attestation {
+            if attestation.capability_hash != self.digest
+                || attestation
+                    .check_with(verifier, &self.capabilities.id)
+                    .is_err()
+            {
+                return Err(DescriptorError::InvalidAttestation);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Verify the descriptor and require it to be attested
+    pub fn verify_attested(
+        &self,
+        verifier: &dyn AttestationVerifier,
+    ) -> Result<(), DescriptorError> {
+        if self.attestation.is_none() {
+            return Err(DescriptorError::MissingAttestation);
+        }
+        self.verify(verifier)
+    }
+
+    fn verify_contents(&self) -> Result<(), DescriptorError> {
+        if !(MIN_PROTOCOL_VERSION..=PROTOCOL_VERSION).contains(&self.schema_version) {
+            return Err(DescriptorError::UnsupportedSchema(self.schema_version));
+        }
+        if descriptor_digest(&self.capabilities).ok().as_ref() != Some(&self.digest) {
+            return Err(DescriptorError::DigestMismatch);
+        }
+        Ok(())
+    }
+
+    /// Serialize the descriptor as JSON
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    /// Parse a descriptor from JSON
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
+
+impl Capabilities {
+    /// Build the self-description payload of this capability set
+    pub fn describe(&self) -> Result<CapabilityDescriptor, serde_json::Error> {
+        CapabilityDescriptor::new(self.clone())
+    }
+}
+
+/// Errors from fetching or verifying a descriptor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DescriptorError {
+    /// The schema version is not supported by this crate
+    UnsupportedSchema(u32),
+    /// The capabilities do not match the digest
+    DigestMismatch,
+    /// The attestation does not cover the digest or is invalid
+    InvalidAttestation,
+    /// An attestation was required but the descriptor is unsigned
+    MissingAttestation,
+    /// The descriptor could not be retrieved
+    Transport(String),
+    /// The response is not a valid descriptor
+    Parse(String),
+}
+
+impl fmt::Display for DescriptorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DescriptorError::UnsupportedSchema(version) => {
+                write!(f, "unsupported descriptor schema version {}", version)
+            }
+            DescriptorError::DigestMismatch => {
+                write!(f, "capabilities do not match descriptor digest")
+            }
+            DescriptorError::InvalidAttestation => write!(f, "descriptor attestation is invalid"),
+            DescriptorError::MissingAttestation => write!(f, "descriptor is not attested"),
+            DescriptorError::Transport(reason) => {
+                write!(f, "failed to fetch descriptor: {}", reason)
+            }
+            DescriptorError::Parse(reason) => write!(f, "invalid descriptor: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for DescriptorError {}
+
+/// URL of the descriptor endpoint of a worker
+pub fn descriptor_url(base_url: &str) -> String {
+    format!(
+        "{}{}",
+        base_url.trim_end_matches('/'),
+        CAPABILITY_DESCRIPTOR_PATH
+    )
+}
+
+/// Fetch a worker's descriptor from its well-known endpoint and verify it
+#[cfg(feature = "http")]
+pub fn fetch_descriptor(
+    base_url: &str,
+    verifier: &dyn AttestationVerifier,
+) -> Result<CapabilityDescriptor, DescriptorError> {
+    let body = ureq::get(&descriptor_url(base_url))
+        .timeout(std::time::Duration::from_secs(
+            DEFAULT_DESCRIPTOR_FETCH_TIMEOUT_SECONDS,
+        ))
+        .call()
+        .map_err(|e| DescriptorError::Transport(e.to_string()))?
+        .into_string()
+        .map_err(|e| DescriptorError::Transport(e.to_string()))?;
+
+    let descriptor = CapabilityDescriptor::from_json(&body)
+        .map_err(|e| DescriptorError::Parse(e.to_string()))?;
+    descriptor.verify(verifier)?;
+    Ok(descriptor)
+}
+
+fn descriptor_digest(capabilities: &Capabilities) -> Result<String, serde_json::Error> {
+    let canonical = canonicalize(serde_json::to_value(capabilities)?).to_string();
+    let mut hasher = Sha256::new();
+    hasher.update(canonical.as_bytes());
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Sort object keys recursively so the serialized document is independent of map ordering
+pub(crate) fn canonicalize(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<(String, Value)> = map.into_iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(k, v)| (k, canonicalize(v)))
+                    .collect(),
+            )
+        }
+        Value::Array(values) => Value::Array(values.into_iter().map(canonicalize).collect()),
+        other => other,
+    }
+}
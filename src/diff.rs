@@ -0,0 +1,229 @@
+//! Differences between two versions of a capability set
+//!
+//! `Capabilities::diff` compares an earlier declaration of a worker (e.g.,
+//! the previous heartbeat) with a later one, listing tool, permission, flag
+//! and metadata changes and whether the tools need to be re-attested.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::capabilities::Capabilities;
+use crate::types::{CapabilityPermissions, ToolCapability};
+
+/// Change of a keyed value between two capability sets
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ValueChange<T> {
+    /// Present only in the later set
+    Added { current: T },
+    /// Present only in the earlier set
+    Removed { previous: T },
+    /// Present in both with different values
+    Changed { previous: T, current: T },
+}
+
+/// Tool declared in both sets with different declarations
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolChange {
+    /// Category of the tool
+    pub category: String,
+    /// Name of the tool
+    pub tool: String,
+    /// Names of the changed fields (e.g., `version`, `permissions`)
+    pub fields: Vec<String>,
+    /// Whether the change alters the capability hash covered by attestations
+    pub hash_changed: bool,
+    /// Permission change (if the permissions changed)
+    #[serde(default)]
+    pub permissions: Option<ValueChange<CapabilityPermissions>>,
+}
+
+/// Differences between an earlier and a later capability set
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CapabilityDiff {
+    /// Tools only in the later set as (category, tool name)
+    pub added_tools: Vec<(String, String)>,
+    /// Tools only in the earlier set as (category, tool name)
+    pub removed_tools: Vec<(String, String)>,
+    /// Tools whose declaration changed
+    pub changed_tools: Vec<ToolChange>,
+    /// Flag changes by flag name
+    pub flags: BTreeMap<String, ValueChange<bool>>,
+    /// Metadata changes by key
+    pub metadata: BTreeMap<String, ValueChange<String>>,
+}
+
+impl CapabilityDiff {
+    /// Check if the two sets declare the same tools, flags and metadata
+    pub fn is_empty(&self) -> bool {
+        self.added_tools.is_empty()
+            && self.removed_tools.is_empty()
+            && self.changed_tools.is_empty()
+            && self.flags.is_empty()
+            && self.metadata.is_empty()
+    }
+
+    /// Check if tools were added or changed in ways their attestations do not cover
+    pub fn requires_reattestation(&self) -> bool {
+        !self.added_tools.is_empty() || self.changed_tools.iter().any(|tool| tool.hash_changed)
+    }
+}
+
+impl Capabilities {
+    /// Compare this (earlier) capability set with a later one
+    pub fn diff(&self, other: &Capabilities) -> CapabilityDiff {
+        let mut diff = CapabilityDiff::default();
+
+        let categories: BTreeSet<String> = self
+            .category_names()
+            .into_iter()
+            .chain(other.category_names())
+            .collect();
+        for category in categories {
+            let previous = self.tools_in_category(&category);
+            let current = other.tools_in_category(&category);
+
+            for tool in previous {
+                match current.iter().find(|t| t.tool_name == tool.tool_name) {
+                    Some(later) => {
+                        if let Some(change) = diff_tool(&category, tool, later) {
+                            diff.changed_tools.push(change);
+                        }
+                    }
+                    None => diff
+                        .removed_tools
+                        .push((category.clone(), tool.tool_name.clone())),
+                }
+            }
+            for tool in current {
+                if !previous.iter().any(|t| t.tool_name == tool.tool_name) {
+                    diff.added_tools
+                        .push((category.clone(), tool.tool_name.clone()));
+                }
+            }
+        }
+
+        diff.flags = diff_map(&self.flags, &other.flags);
+        diff.metadata = diff_map(&self.metadata, &other.metadata);
+        diff
+    }
+}
+
+/// Changes between two declarations of a tool (`None` if identical)
+fn diff_tool(
+    category: &str,
+    previous: &ToolCapability,
+    current: &ToolCapability,
+) -> Option<ToolChange> {
+    let mut fields = Vec::new();
+    let mut check = |name: &str, changed: bool| {
+        if changed {
+            fields.push(name.to_string());
+        }
+    };
+    check("required", previous.required != current.required);
+    check(
+        "alternatives",
+        previous.alternatives != current.alternatives,
+    );
+    check("permissions", previous.permissions != current.permissions);
+    check(
+        "expiration",
+        previous.expiration.expires_at != current.expiration.expires_at
+            || previous.expiration.revoked != current.expiration.revoked,
+    );
+    check("version", previous.version != current.version);
+    check("version_req", previous.version_req != current.version_req);
+    check(
+        "checkpointing",
+        previous.checkpointing != current.checkpointing,
+    );
+    check(
+        "max_concurrent",
+        previous.max_concurrent != current.max_concurrent,
+    );
+    check("suspended", previous.suspended != current.suspended);
+    check("scope", previous.scope != current.scope);
+    check("wasi", previous.wasi != current.wasi);
+    check(
+        "binary_sha256",
+        previous.binary_sha256 != current.binary_sha256,
+    );
+    check("platforms", previous.platforms != current.platforms);
+    check("tags", previous.tags != current.tags);
+    check("metadata", previous.metadata != current.metadata);
+    check("preferences", previous.preferences != current.preferences);
+    check("deprecated", previous.deprecated != current.deprecated);
+    check(
+        "attestation",
+        previous.attestation.as_ref().map(|a| &a.signature)
+            != current.attestation.as_ref().map(|a| &a.signature),
+    );
+    check(
+        "countersignatures",
+        previous.countersignatures.len() != current.countersignatures.len()
+            || previous
+                .countersignatures
+                .iter()
+                .zip(&current.countersignatures)
+                .any(|(a, b)| a.signature != b.signature),
+    );
+
+    if fields.is_empty() {
+        return None;
+    }
+
+    let permissions_changed = previous.permissions != current.permissions;
+    let permissions = permissions_changed.then(|| ValueChange::Changed {
+        previous: previous.permissions.clone(),
+        current: current.permissions.clone(),
+    });
+    Some(ToolChange {
+        category: category.to_string(),
+        tool: current.tool_name.clone(),
+        fields,
+        hash_changed: previous.generate_capability_hash() != current.generate_capability_hash(),
+        permissions,
+    })
+}
+
+/// Changes between two keyed maps
+fn diff_map<T: Clone + PartialEq>(
+    previous: &HashMap<String, T>,
+    current: &HashMap<String, T>,
+) -> BTreeMap<String, ValueChange<T>> {
+    let mut changes = BTreeMap::new();
+    for (key, before) in previous {
+        match current.get(key) {
+            Some(after) if after != before => {
+                changes.insert(
+                    key.clone(),
+                    ValueChange::Changed {
+                        previous: before.clone(),
+                        current: after.clone(),
+                    },
+                );
+            }
+            Some(_) => {}
+            None => {
+                changes.insert(
+                    key.clone(),
+                    ValueChange::Removed {
+                        previous: before.clone(),
+                    },
+                );
+            }
+        }
+    }
+    for (key, after) in current {
+        if !previous.contains_key(key) {
+            changes.insert(
+                key.clone(),
+                ValueChange::Added {
+                    current: after.clone(),
+                },
+            );
+        }
+    }
+    changes
+}
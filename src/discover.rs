@@ -0,0 +1,147 @@
+//! Autodiscovery of capabilities from the local environment
+//!
+//! Probes `PATH` for well-known analysis tools and toolchains and records the
+//! platform, so workers can self-register without hand-written builders.
+//! Versions are detected when the `probe` feature is enabled.
+
+use crate::capabilities::Capabilities;
+use crate::checker::{PathChecker, ToolChecker};
+use crate::constants::*;
+use crate::derive::FlagRule;
+use crate::environment::EnvironmentSnapshot;
+use crate::types::ToolCapability;
+
+/// Well-known tools as (category, tool name, executables providing it)
+pub(crate) const KNOWN_TOOLS: &[(&str, &str, &[&str])] = &[
+    (
+        CAPABILITY_STATIC_ANALYSIS,
+        "clippy",
+        &["cargo-clippy", "clippy-driver"],
+    ),
+    (
+        CAPABILITY_STATIC_ANALYSIS,
+        "rust-analyzer",
+        &["rust-analyzer"],
+    ),
+    (CAPABILITY_STATIC_ANALYSIS, "tree-sitter", &["tree-sitter"]),
+    (CAPABILITY_STATIC_ANALYSIS, "semgrep", &["semgrep"]),
+    (CAPABILITY_STATIC_ANALYSIS, "codeql", &["codeql"]),
+    (CAPABILITY_STATIC_ANALYSIS, "eslint", &["eslint"]),
+    (CAPABILITY_STATIC_ANALYSIS, "pylint", &["pylint"]),
+    (CAPABILITY_STATIC_ANALYSIS, "slither", &["slither"]),
+    (
+        CAPABILITY_SECURITY_SCANNING,
+        "cargo-audit",
+        &["cargo-audit"],
+    ),
+    (CAPABILITY_SECURITY_SCANNING, "trivy", &["trivy"]),
+    (CAPABILITY_SECURITY_SCANNING, "bandit", &["bandit"]),
+    (CAPABILITY_SECURITY_SCANNING, "gosec", &["gosec"]),
+    (CAPABILITY_SECURITY_SCANNING, "mythril", &["myth"]),
+    (CAPABILITY_DYNAMIC_ANALYSIS, "valgrind", &["valgrind"]),
+    (CAPABILITY_DYNAMIC_ANALYSIS, "strace", &["strace"]),
+    (CAPABILITY_FUZZING, "cargo-fuzz", &["cargo-fuzz"]),
+    (CAPABILITY_FUZZING, "afl", &["afl-fuzz"]),
+    (CAPABILITY_FUZZING, "honggfuzz", &["honggfuzz"]),
+    (
+        CAPABILITY_TEST_FRAMEWORK,
+        "cargo-nextest",
+        &["cargo-nextest"],
+    ),
+    (CAPABILITY_TEST_FRAMEWORK, "pytest", &["pytest"]),
+    (CAPABILITY_TEST_FRAMEWORK, "jest", &["jest"]),
+];
+
+/// Toolchains recorded in metadata as (name, executable)
+const KNOWN_TOOLCHAINS: &[(&str, &str)] = &[
+    ("rust", "rustc"),
+    ("cargo", "cargo"),
+    ("go", "go"),
+    ("python", "python3"),
+    ("node", "node"),
+    ("java", "java"),
+];
+
+/// Scan the local machine and build its capability set
+///
+/// The set is named after the host and tools are declared optional.
+pub fn scan() -> Capabilities {
+    #[cfg(feature = "probe")]
+    {
+        use crate::checker::ToolCheckerExt;
+        scan_with(
+            local_hostname(),
+            &PathChecker::from_env().and(crate::probe::ProbingToolChecker::new()),
+        )
+    }
+    #[cfg(not(feature = "probe"))]
+    {
+        scan_with(local_hostname(), &PathChecker::from_env())
+    }
+}
+
+/// Build a capability set from the tools and toolchains a checker finds
+///
+/// Detected versions are set on the tools and recorded in metadata as
+/// `tool.<name>.version` and `toolchain.<name>.version`; flags are derived
+/// with `FlagRule::recommended`. The local environment is captured with
+/// `EnvironmentSnapshot::capture`.
+pub fn scan_with(
+    id: impl Into<String>,
+    tool_checker: &(impl ToolChecker + ?Sized),
+) -> Capabilities {
+    let mut caps = Capabilities::new(id)
+        .with_metadata(DISCOVERY_META_OS, std::env::consts::OS)
+        .with_metadata(DISCOVERY_META_ARCH, std::env::consts::ARCH)
+        .with_metadata(DISCOVERY_META_FAMILY, std::env::consts::FAMILY)
+        .with_environment(EnvironmentSnapshot::capture());
+
+    for (category, tool_name, executables) in KNOWN_TOOLS {
+        let Some(status) = executables
+            .iter()
+            .map(|executable| tool_checker.status(executable))
+            .find(|status| status.available)
+        else {
+            continue;
+        };
+
+        let mut tool = ToolCapability::new(*tool_name, false).with_alternatives(
+            executables
+                .iter()
+                .filter(|executable| *executable != tool_name)
+                .map(|executable| executable.to_string())
+                .collect(),
+        );
+        if let Some(version) = status.version {
+            caps.metadata
+                .insert(format!("tool.{}.version", tool_name), version.clone());
+            tool = tool.with_version(version);
+        }
+        if let Some(tools) = caps.tools_in_category_mut(category) {
+            tools.push(tool);
+        }
+    }
+
+    for (name, executable) in KNOWN_TOOLCHAINS {
+        let status = tool_checker.status(executable);
+        if status.available {
+            caps.metadata.insert(
+                format!("toolchain.{}.version", name),
+                status.version.unwrap_or_default(),
+            );
+        }
+    }
+
+    caps.derive_flags(&FlagRule::recommended(), tool_checker);
+    caps
+}
+
+/// Host name of the local machine (`localhost` if unknown)
+fn local_hostname() -> String {
+    std::env::var("HOSTNAME")
+        .ok()
+        .or_else(|| std::fs::read_to_string("/etc/hostname").ok())
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "localhost".to_string())
+}
@@ -0,0 +1,519 @@
+//! Human-readable output for capabilities, reports and statistics
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::capabilities::Capabilities;
+use crate::clock;
+use crate::constants::*;
+use crate::explain::{CapabilityReport, ToolCheckFailure};
+use crate::expr::CapabilityExpr;
+use crate::registry::RegistryStatistics;
+use crate::requirements::MatchResult;
+use crate::signing::AlgorithmRegistry;
+use crate::types::{CapabilitySecurityReport, ExpirationTolerance, ToolCapability};
+
+/// Render rows as a plain-text table with aligned columns
+pub fn render_table(headers: &[&str], rows: &[Vec<String>]) -> String {
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.len()).collect();
+    for row in rows {
+        for (i, cell) in row.iter().enumerate() {
+            if i < widths.len() {
+                widths[i] = widths[i].max(cell.len());
+            }
+        }
+    }
+
+    let format_row = |cells: &[String]| {
+        cells
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| {
+                format!(
+                    "{:<width$}",
+                    cell,
+                    width = widths.get(i).copied().unwrap_or(0)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+
+    let header: Vec<String> = headers.iter().map(|h| h.to_string()).collect();
+    let separator: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
+
+    let mut lines = vec![format_row(&header), format_row(&separator)];
+    for row in rows {
+        lines.push(format_row(row));
+    }
+
+    lines.join("\n")
+}
+
+fn yes_no(value: bool) -> String {
+    if value { "yes" } else { "no" }.to_string()
+}
+
+impl fmt::Display for Capabilities {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let stats = self.statistics_with(AlgorithmRegistry::builtin());
+        write!(
+            f,
+            "{}: {} tools ({} required, {} verified), {} flags",
+            self.id,
+            stats.total_tools,
+            stats.required_tools,
+            stats.verified_tools,
+            stats.flags_count
+        )
+    }
+}
+
+impl fmt::Display for ToolCapability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.tool_name)?;
+        if let Some(version) = &self.version {
+            write!(f, " {}", version)?;
+        }
+        write!(
+            f,
+            " ({}",
+            if self.required {
+                "required"
+            } else {
+                "optional"
+            }
+        )?;
+        if !self.alternatives.is_empty() {
+            write!(f, ", alternatives: {}", self.alternatives.join(", "))?;
+        }
+        if self.is_revoked() {
+            write!(f, ", revoked")?;
+        } else if self.suspended {
+            write!(f, ", suspended")?;
+        }
+        if self.is_deprecated() {
+            write!(f, ", deprecated")?;
+        }
+        write!(f, ")")
+    }
+}
+
+impl Capabilities {
+    /// Multi-line summary with a table of tools per category, flags and metadata
+    pub fn summary(&self) -> String {
+        self.render(false)
+    }
+
+    /// Multi-line report like `summary`, with the time left until each tool expires
+    pub fn render_summary(&self) -> String {
+        self.render(true)
+    }
+
+    /// Render the summary, optionally with an expiry column
+    fn render(&self, with_expiry: bool) -> String {
+        let now = with_expiry.then(|| clock::unix_timestamp().ok());
+        let mut out = format!("Capabilities: {}\n", self.id);
+
+        let rows: Vec<Vec<String>> = self
+            .category_names()
+            .into_iter()
+            .flat_map(|category| {
+                self.tools_in_category(&category).iter().map(move |tool| {
+                    let mut row = vec![
+                        category.clone(),
+                        tool.tool_name.clone(),
+                        yes_no(tool.required),
+                        tool.version.clone().unwrap_or_else(|| "-".to_string()),
+                        if tool.alternatives.is_empty() {
+                            "-".to_string()
+                        } else {
+                            tool.alternatives.join(", ")
+                        },
+                        yes_no(tool.verify_attestation_with(AlgorithmRegistry::builtin())),
+                    ];
+                    if let Some(now) = now {
+                        row.push(expiry_countdown(tool, now));
+                    }
+                    row
+                })
+            })
+            .collect();
+
+        if rows.is_empty() {
+            out.push_str("\nNo tools declared\n");
+        } else {
+            let mut headers = vec![
+                "CATEGORY",
+                "TOOL",
+                "REQUIRED",
+                "VERSION",
+                "ALTERNATIVES",
+                "ATTESTED",
+            ];
+            if with_expiry {
+                headers.push("EXPIRES");
+            }
+            out.push('\n');
+            out.push_str(&render_table(&headers, &rows));
+            out.push('\n');
+        }
+
+        let mut flags: Vec<&str> = self
+            .flags
+            .iter()
+            .filter(|(_, enabled)| **enabled)
+            .map(|(flag, _)| flag.as_str())
+            .collect();
+        flags.sort();
+        if !flags.is_empty() {
+            out.push_str(&format!("\nFlags: {}\n", flags.join(", ")));
+        }
+
+        let mut metadata: Vec<(&String, &String)> = self.metadata.iter().collect();
+        metadata.sort();
+        if !metadata.is_empty() {
+            out.push_str("\nMetadata:\n");
+            for (key, value) in metadata {
+                out.push_str(&format!("  {} = {}\n", key, value));
+            }
+        }
+
+        out
+    }
+}
+
+/// Time left until a tool expires, or why it is no longer usable
+fn expiry_countdown(tool: &ToolCapability, now: Option<u64>) -> String {
+    if tool.is_revoked() {
+        return "revoked".to_string();
+    }
+    match now {
+        Some(now)
+            if tool
+                .expiration
+                .is_expired_at(now, ExpirationTolerance::default()) =>
+        {
+            "expired".to_string()
+        }
+        Some(now) => format!(
+            "in {}",
+            format_duration(tool.expiration.expires_at.saturating_sub(now))
+        ),
+        None => "unknown (clock skew)".to_string(),
+    }
+}
+
+/// Format a number of seconds with its two largest units (e.g., `2d 3h`, `5m 10s`)
+fn format_duration(seconds: u64) -> String {
+    let units = [("d", 24 * 60 * 60), ("h", 60 * 60), ("m", 60), ("s", 1)];
+    let parts: Vec<String> = units
+        .iter()
+        .scan(seconds, |left, (unit, size)| {
+            let count = *left / size;
+            *left %= size;
+            Some((count, unit))
+        })
+        .skip_while(|(count, _)| *count == 0)
+        .take(2)
+        .filter(|(count, _)| *count > 0)
+        .map(|(count, unit)| format!("{}{}", count, unit))
+        .collect();
+    if parts.is_empty() {
+        "0s".to_string()
+    } else {
+        parts.join(" ")
+    }
+}
+
+impl fmt::Display for CapabilitySecurityReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: attested={} verified={} expired={} revoked={}",
+            self.tool_name,
+            yes_no(self.has_attestation),
+            yes_no(self.attestation_verified),
+            yes_no(self.is_expired),
+            yes_no(self.is_revoked)
+        )
+    }
+}
+
+impl CapabilitySecurityReport {
+    /// Multi-line summary of the report
+    pub fn summary(&self) -> String {
+        let mut out = format!("Security report: {}\n", self.tool_name);
+        out.push_str(&format!(
+            "  Attested:    {}\n",
+            yes_no(self.has_attestation)
+        ));
+        out.push_str(&format!(
+            "  Verified:    {}\n",
+            yes_no(self.attestation_verified)
+        ));
+        out.push_str(&format!("  Expired:     {}\n", yes_no(self.is_expired)));
+        out.push_str(&format!("  Revoked:     {}\n", yes_no(self.is_revoked)));
+        if let Some(reason) = &self.expiration.revocation_reason {
+            out.push_str(&format!("  Revoked for: {}\n", reason));
+        }
+        out.push_str(&format!("  Expires at:  {}\n", self.expiration.expires_at));
+        if let Some(deprecation) = &self.deprecated {
+            out.push_str(&format!("  Deprecated:  {}\n", deprecation.reason));
+            if let Some(replacement) = &deprecation.replacement {
+                out.push_str(&format!("  Replace by:  {}\n", replacement));
+            }
+            if let Some(sunset_at) = deprecation.sunset_at {
+                out.push_str(&format!("  Sunset at:   {}\n", sunset_at));
+            }
+        }
+
+        let permissions = [
+            (
+                PERMISSION_FILESYSTEM_ACCESS,
+                self.permissions.filesystem_access,
+            ),
+            (PERMISSION_NETWORK_ACCESS, self.permissions.network_access),
+            (PERMISSION_PROCESS_SPAWN, self.permissions.process_spawn),
+            (PERMISSION_ENV_ACCESS, self.permissions.env_access),
+            (PERMISSION_SYSTEM_ACCESS, self.permissions.system_access),
+        ];
+        let granted: Vec<&str> = permissions
+            .iter()
+            .filter(|(_, granted)| *granted)
+            .map(|(name, _)| *name)
+            .collect();
+        out.push_str(&format!(
+            "  Permissions: {}\n",
+            if granted.is_empty() {
+                "none".to_string()
+            } else {
+                granted.join(", ")
+            }
+        ));
+        out.push_str(&format!(
+            "  Limits:      {} MB, {}% CPU, {}s\n",
+            self.permissions.memory_limit_mb,
+            self.permissions.cpu_limit_percent,
+            self.permissions.timeout_seconds
+        ));
+
+        out
+    }
+
+    /// Render a set of security reports (e.g., from `Capabilities::get_security_report`) as a table
+    pub fn table(reports: &HashMap<String, CapabilitySecurityReport>) -> String {
+        let mut names: Vec<&String> = reports.keys().collect();
+        names.sort();
+
+        let rows: Vec<Vec<String>> = names
+            .into_iter()
+            .map(|name| {
+                let report = &reports[name];
+                vec![
+                    report.tool_name.clone(),
+                    yes_no(report.has_attestation),
+                    yes_no(report.attestation_verified),
+                    yes_no(report.is_expired),
+                    yes_no(report.is_revoked),
+                ]
+            })
+            .collect();
+
+        render_table(
+            &["TOOL", "ATTESTED", "VERIFIED", "EXPIRED", "REVOKED"],
+            &rows,
+        )
+    }
+}
+
+impl fmt::Display for RegistryStatistics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} workers ({} verified), {} tools ({} required, {} verified), {} active sessions",
+            self.total_workers,
+            self.verified_workers,
+            self.total_tools,
+            self.total_required_tools,
+            self.total_verified_tools,
+            self.active_sessions
+        )
+    }
+}
+
+impl RegistryStatistics {
+    /// Multi-line summary table of the statistics
+    pub fn summary(&self) -> String {
+        let rows = vec![
+            vec!["Workers".to_string(), self.total_workers.to_string()],
+            vec![
+                "Verified workers".to_string(),
+                self.verified_workers.to_string(),
+            ],
+            vec!["Tools".to_string(), self.total_tools.to_string()],
+            vec![
+                "Required tools".to_string(),
+                self.total_required_tools.to_string(),
+            ],
+            vec![
+                "Verified tools".to_string(),
+                self.total_verified_tools.to_string(),
+            ],
+            vec![
+                "Deprecated tools".to_string(),
+                self.total_deprecated_tools.to_string(),
+            ],
+            vec![
+                "Active sessions".to_string(),
+                self.active_sessions.to_string(),
+            ],
+            vec![
+                "Rejected registrations".to_string(),
+                self.rejected_registrations.to_string(),
+            ],
+        ];
+        format!(
+            "Registry statistics\n\n{}\n",
+            render_table(&["METRIC", "VALUE"], &rows)
+        )
+    }
+}
+
+impl fmt::Display for MatchResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts: Vec<String> = self
+            .clauses
+            .iter()
+            .map(|clause| {
+                format!(
+                    "{}: {}",
+                    clause.clause.any_of.join("|"),
+                    clause.tool.as_deref().unwrap_or("unmatched")
+                )
+            })
+            .collect();
+        if !self.environment_met {
+            parts.push("environment not met".to_string());
+        }
+
+        write!(
+            f,
+            "{}: {}",
+            self.worker_id,
+            if self.is_match() { "match" } else { "no match" }
+        )?;
+        if !parts.is_empty() {
+            write!(f, " ({})", parts.join("; "))?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for CapabilityExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let join = |f: &mut fmt::Formatter<'_>, exprs: &[CapabilityExpr], op: &str| {
+            let parts: Vec<String> = exprs
+                .iter()
+                .map(|expr| match expr {
+                    CapabilityExpr::All(_) | CapabilityExpr::Any(_) => format!("({})", expr),
+                    _ => expr.to_string(),
+                })
+                .collect();
+            write!(f, "{}", parts.join(op))
+        };
+
+        match self {
+            CapabilityExpr::All(exprs) if exprs.is_empty() => write!(f, "true"),
+            CapabilityExpr::Any(exprs) if exprs.is_empty() => write!(f, "false"),
+            CapabilityExpr::All(exprs) => join(f, exprs, " AND "),
+            CapabilityExpr::Any(exprs) => join(f, exprs, " OR "),
+            CapabilityExpr::Not(expr) => match expr.as_ref() {
+                CapabilityExpr::All(_) | CapabilityExpr::Any(_) => write!(f, "NOT ({})", expr),
+                _ => write!(f, "NOT {}", expr),
+            },
+            CapabilityExpr::Flag(flag) => write!(f, "flag:{}", flag),
+            CapabilityExpr::Tool(tool) => write!(f, "tool:{}", tool),
+            CapabilityExpr::Category(capability_type) => write!(f, "{}", capability_type),
+        }
+    }
+}
+
+impl fmt::Display for ToolCheckFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ToolCheckFailure::Expired { expires_at } => write!(f, "expired at {}", expires_at),
+            ToolCheckFailure::Revoked { reason, .. } => match reason {
+                Some(reason) => write!(f, "revoked: {}", reason),
+                None => write!(f, "revoked"),
+            },
+            ToolCheckFailure::Suspended => write!(f, "suspended"),
+            ToolCheckFailure::VersionMismatch { required, found } => match found {
+                Some(found) => write!(f, "version {} does not satisfy {}", found, required),
+                None => write!(f, "no declared version to check against {}", required),
+            },
+            ToolCheckFailure::BinaryMismatch { tool } => {
+                write!(f, "{} does not match the pinned SHA256", tool)
+            }
+            ToolCheckFailure::NotInstalled { checked } => {
+                write!(f, "not installed (checked {})", checked.join(", "))
+            }
+        }
+    }
+}
+
+impl fmt::Display for CapabilityReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rows: Vec<Vec<String>> = self
+            .tools
+            .iter()
+            .map(|tool| {
+                vec![
+                    tool.category.clone(),
+                    tool.tool_name.clone(),
+                    yes_no(tool.required),
+                    match (
+                        &tool.matched,
+                        tool.status
+                            .as_ref()
+                            .and_then(|status| status.version.as_ref()),
+                    ) {
+                        (Some(matched), Some(version)) => format!("{} {}", matched, version),
+                        (Some(matched), None) => matched.clone(),
+                        (None, _) => "-".to_string(),
+                    },
+                    tool.status
+                        .as_ref()
+                        .and_then(|status| status.path.as_ref())
+                        .map(|path| path.display().to_string())
+                        .unwrap_or_else(|| "-".to_string()),
+                    tool.failure
+                        .as_ref()
+                        .map(|failure| failure.to_string())
+                        .unwrap_or_else(|| "ok".to_string()),
+                ]
+            })
+            .collect();
+
+        writeln!(
+            f,
+            "Capability check: {} ({})\n",
+            self.worker_id,
+            if self.all_required_satisfied() {
+                "all required tools satisfied"
+            } else {
+                "missing required tools"
+            }
+        )?;
+        write!(
+            f,
+            "{}",
+            render_table(
+                &["CATEGORY", "TOOL", "REQUIRED", "MATCHED", "LOCATION", "STATUS"],
+                &rows
+            )
+        )
+    }
+}
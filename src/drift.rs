@@ -0,0 +1,257 @@
+//! Drift detection between declared and observed capabilities
+//!
+//! A `DriftDetector` re-runs a checker against a capability set and reports
+//! changes as typed events: declared tools disappearing (and coming back),
+//! detected versions changing, and undeclared candidate tools appearing.
+//! `DriftWatcher` runs a detector periodically against a registered worker.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, RwLock};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use crate::capabilities::Capabilities;
+use crate::checker::{ToolChecker, ToolStatus};
+use crate::clock;
+use crate::discover::KNOWN_TOOLS;
+use crate::registry::CapabilityRegistry;
+use crate::types::ToolCapability;
+
+/// Change between the declared and observed state of a worker
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DriftEvent {
+    /// A declared tool (and all its alternatives) is no longer available
+    ToolDisappeared {
+        worker_id: String,
+        category: String,
+        tool: String,
+    },
+    /// A declared tool that had disappeared is available again
+    ToolReappeared {
+        worker_id: String,
+        category: String,
+        tool: String,
+    },
+    /// The detected version of a tool differs from the last known one
+    VersionChanged {
+        worker_id: String,
+        tool: String,
+        previous: String,
+        current: String,
+    },
+    /// A candidate tool that is not declared has been found
+    NewToolFound {
+        worker_id: String,
+        category: String,
+        tool: String,
+        version: Option<String>,
+    },
+}
+
+/// Last observation of a tool
+#[derive(Debug, Clone)]
+struct Observation {
+    available: bool,
+    version: Option<String>,
+}
+
+/// Detector comparing declared capabilities with what a checker observes
+///
+/// The first check compares against the declared state (tools present, at
+/// their declared version); later checks compare against the previous
+/// observation, so each change is reported once.
+#[derive(Debug)]
+pub struct DriftDetector<C> {
+    checker: C,
+    candidates: Vec<(String, ToolCapability)>,
+    observed: HashMap<(String, String), Observation>,
+}
+
+impl<C: ToolChecker> DriftDetector<C> {
+    /// Create a detector using a checker, with no candidate tools
+    pub fn new(checker: C) -> Self {
+        Self {
+            checker,
+            candidates: Vec::new(),
+            observed: HashMap::new(),
+        }
+    }
+
+    /// Watch for an undeclared tool appearing in a category
+    pub fn with_candidate(mut self, category: impl Into<String>, tool: ToolCapability) -> Self {
+        self.candidates.push((category.into(), tool));
+        self
+    }
+
+    /// Watch for the well-known tools used by autodiscovery
+    pub fn with_known_tools(mut self) -> Self {
+        for (category, tool_name, executables) in KNOWN_TOOLS {
+            let alternatives = executables
+                .iter()
+                .filter(|executable| *executable != tool_name)
+                .map(|executable| executable.to_string())
+                .collect();
+            self.candidates.push((
+                category.to_string(),
+                ToolCapability::new(*tool_name, false).with_alternatives(alternatives),
+            ));
+        }
+        self
+    }
+
+    /// The checker used for observations
+    pub fn checker(&self) -> &C {
+        &self.checker
+    }
+
+    /// Forget previous observations so the next check compares against the declared state
+    pub fn reset(&mut self) {
+        self.observed.clear();
+    }
+
+    /// Check a capability set and return the changes since the last check
+    pub fn check(&mut self, caps: &Capabilities) -> Vec<DriftEvent> {
+        let mut events = Vec::new();
+
+        for category in caps.category_names() {
+            for tool in caps.tools_in_category(&category) {
+                let status = self.observe(tool);
+                let key = (caps.id.clone(), tool.tool_name.clone());
+                let previous = self.observed.get(&key).cloned().unwrap_or(Observation {
+                    available: true,
+                    version: tool.version.clone(),
+                });
+
+                match (previous.available, status.available) {
+                    (true, false) => events.push(DriftEvent::ToolDisappeared {
+                        worker_id: caps.id.clone(),
+                        category: category.clone(),
+                        tool: tool.tool_name.clone(),
+                    }),
+                    (false, true) => events.push(DriftEvent::ToolReappeared {
+                        worker_id: caps.id.clone(),
+                        category: category.clone(),
+                        tool: tool.tool_name.clone(),
+                    }),
+                    _ => {}
+                }
+
+                let mut version = previous.version;
+                if let Some(current) = status.version {
+                    if let Some(previous) = version.as_ref().filter(|v| **v != current) {
+                        events.push(DriftEvent::VersionChanged {
+                            worker_id: caps.id.clone(),
+                            tool: tool.tool_name.clone(),
+                            previous: previous.clone(),
+                            current: current.clone(),
+                        });
+                    }
+                    version = Some(current);
+                }
+
+                self.observed.insert(
+                    key,
+                    Observation {
+                        available: status.available,
+                        version,
+                    },
+                );
+            }
+        }
+
+        for (category, candidate) in &self.candidates {
+            if caps.find_tool(&candidate.tool_name).is_some() {
+                continue;
+            }
+            let status = self.observe(candidate);
+            let key = (caps.id.clone(), candidate.tool_name.clone());
+            let was_available = self
+                .observed
+                .get(&key)
+                .is_some_and(|observation| observation.available);
+
+            if status.available && !was_available {
+                events.push(DriftEvent::NewToolFound {
+                    worker_id: caps.id.clone(),
+                    category: category.clone(),
+                    tool: candidate.tool_name.clone(),
+                    version: status.version.clone(),
+                });
+            }
+            self.observed.insert(
+                key,
+                Observation {
+                    available: status.available,
+                    version: status.version,
+                },
+            );
+        }
+
+        events
+    }
+
+    /// Status of the first of a tool's names (primary, then alternatives) that is available
+    fn observe(&self, tool: &ToolCapability) -> ToolStatus {
+        std::iter::once(&tool.tool_name)
+            .chain(&tool.alternatives)
+            .map(|name| self.checker.status(name))
+            .find(|status| status.available)
+            .unwrap_or_else(ToolStatus::missing)
+    }
+}
+
+/// Background thread running a drift detector against a registered worker
+#[derive(Debug)]
+pub struct DriftWatcher {
+    stop: Sender<()>,
+    thread: JoinHandle<()>,
+}
+
+impl DriftWatcher {
+    /// Check the worker now and then every `interval`, passing each event to `on_event`
+    ///
+    /// Ticks where the worker is not registered are skipped.
+    pub fn spawn<C, F>(
+        registry: Arc<RwLock<CapabilityRegistry>>,
+        worker_id: impl Into<String>,
+        mut detector: DriftDetector<C>,
+        interval: Duration,
+        mut on_event: F,
+    ) -> Self
+    where
+        C: ToolChecker + Send + 'static,
+        F: FnMut(DriftEvent) + Send + 'static,
+    {
+        let worker_id = worker_id.into();
+        let clock = clock::current();
+        let (stop, stopped) = mpsc::channel();
+        let thread = std::thread::spawn(move || {
+            clock::with_inherited(clock, || loop {
+                let caps = registry
+                    .read()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .get(&worker_id)
+                    .cloned();
+                if let Some(caps) = caps {
+                    detector.check(&caps).into_iter().for_each(&mut on_event);
+                }
+
+                match stopped.recv_timeout(interval) {
+                    Err(RecvTimeoutError::Timeout) => continue,
+                    _ => break,
+                }
+            })
+        });
+
+        Self { stop, thread }
+    }
+
+    /// Stop the watcher and wait for it to finish
+    pub fn stop(self) {
+        let _ = self.stop.send(());
+        let _ = self.thread.join();
+    }
+}
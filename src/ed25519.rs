@@ -0,0 +1,102 @@
+//! Ed25519 attestation signing (requires the `ed25519` feature)
+//!
+//! `Ed25519Signer` signs the message covered by an attestation (the
+//! capability hash and timestamp) with ed25519-dalek; keys and signatures are
+//! recorded as lowercase hex. `Ed25519Verifier` checks attestations using the
+//! `Ed25519` algorithm:
+//!
+//! ```rust
+//! use worker_capabilities::{AttestationSigner, Ed25519Signer, Ed25519Verifier, ToolCapability};
+//!
+//! let signer = Ed25519Signer::new([7; 32], "ci");
+//! let tool = ToolCapability::new("clippy", true);
+//! let mut tool = tool.clone().with_attestation(signer.attest(&tool));
+//! assert!(tool.verify_attestation_with(&Ed25519Verifier));
+//!
+//! // Backdating the attestation invalidates the signature
+//! tool.attestation.as_mut().unwrap().timestamp -= 1;
+//! assert!(!tool.verify_attestation_with(&Ed25519Verifier));
+//! ```
+
+use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
+use std::fmt;
+
+use crate::constants::ED25519_ATTESTATION_ALGORITHM;
+use crate::signing::{from_hex, to_hex, AttestationSigner, AttestationVerifier};
+
+/// Signer producing Ed25519 attestations
+#[derive(Clone)]
+pub struct Ed25519Signer {
+    signing_key: SigningKey,
+    attester: String,
+}
+
+impl Ed25519Signer {
+    /// Create a signer from a 32-byte secret key and attester identity
+    pub fn new(secret_key: [u8; 32], attester: impl Into<String>) -> Self {
+        Self {
+            signing_key: SigningKey::from_bytes(&secret_key),
+            attester: attester.into(),
+        }
+    }
+}
+
+impl fmt::Debug for Ed25519Signer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Never print the secret key
+        f.debug_struct("Ed25519Signer")
+            .field("public_key", &AttestationSigner::public_key(self))
+            .field("attester", &self.attester)
+            .finish()
+    }
+}
+
+impl AttestationSigner for Ed25519Signer {
+    fn attester(&self) -> &str {
+        &self.attester
+    }
+
+    fn algorithm(&self) -> &str {
+        ED25519_ATTESTATION_ALGORITHM
+    }
+
+    fn public_key(&self) -> String {
+        to_hex(self.signing_key.verifying_key().as_bytes())
+    }
+
+    fn sign(&self, message: &[u8]) -> String {
+        to_hex(&self.sign_raw(message))
+    }
+
+    fn sign_raw(&self, message: &[u8]) -> Vec<u8> {
+        self.signing_key.sign(message).to_bytes().to_vec()
+    }
+}
+
+/// Verifier for Ed25519 signatures with hex-encoded keys and signatures
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Ed25519Verifier;
+
+impl AttestationVerifier for Ed25519Verifier {
+    fn supports(&self, algorithm: &str) -> bool {
+        algorithm == ED25519_ATTESTATION_ALGORITHM
+    }
+
+    fn verify(&self, public_key: &str, message: &[u8], signature: &str) -> bool {
+        from_hex(signature)
+            .is_some_and(|signature| self.verify_raw(public_key, message, &signature))
+    }
+
+    fn verify_raw(&self, public_key: &str, message: &[u8], signature: &[u8]) -> bool {
+        let Some(public_key) = from_hex(public_key).and_then(|key| <[u8; 32]>::try_from(key).ok())
+        else {
+            return false;
+        };
+        let Ok(signature) = Signature::from_slice(signature) else {
+            return false;
+        };
+        VerifyingKey::from_bytes(&public_key)
+            .and_then(|key| key.verify_strict(message, &signature))
+            .is_ok()
+    }
+}
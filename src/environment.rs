@@ -0,0 +1,134 @@
+//! Snapshot of the environment a worker runs in
+//!
+//! An `EnvironmentSnapshot` records the platform, kernel, container image and
+//! a small allowlist of environment variables at discovery time. It travels
+//! with the capability set, so schedulers can match jobs on platform and
+//! auditors can reconstruct where a job ran.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use crate::capabilities::Capabilities;
+use crate::checker::run_with_timeout;
+use crate::clock::current_timestamp;
+use crate::constants::*;
+
+/// Platform and configuration of a worker at a point in time
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EnvironmentSnapshot {
+    /// Operating system (as in `std::env::consts::OS`, e.g. `linux`)
+    pub os: String,
+    /// CPU architecture (as in `std::env::consts::ARCH`, e.g. `x86_64`)
+    pub arch: String,
+    /// Kernel release (if known)
+    #[serde(default)]
+    pub kernel: Option<String>,
+    /// Digest of the container image the worker runs in (if any)
+    #[serde(default)]
+    pub container_image_digest: Option<String>,
+    /// Captured environment variables
+    #[serde(default)]
+    pub env_vars: BTreeMap<String, String>,
+    /// Timestamp when the snapshot was taken
+    #[serde(default)]
+    pub captured_at: u64,
+}
+
+impl EnvironmentSnapshot {
+    /// Create a snapshot for a platform
+    pub fn new(os: impl Into<String>, arch: impl Into<String>) -> Self {
+        Self {
+            os: os.into(),
+            arch: arch.into(),
+            kernel: None,
+            container_image_digest: None,
+            env_vars: BTreeMap::new(),
+            captured_at: current_timestamp(),
+        }
+    }
+
+    /// Capture the local environment with the default variable allowlist
+    pub fn capture() -> Self {
+        Self::capture_with_env_vars(ENVIRONMENT_SNAPSHOT_ENV_VARS)
+    }
+
+    /// Capture the local environment, recording only the named variables that are set
+    ///
+    /// The container image digest is read from `CONTAINER_IMAGE_DIGEST`.
+    pub fn capture_with_env_vars(names: &[&str]) -> Self {
+        let mut snapshot = Self::new(std::env::consts::OS, std::env::consts::ARCH);
+        snapshot.kernel = local_kernel_release();
+        snapshot.container_image_digest = std::env::var(CONTAINER_IMAGE_DIGEST_ENV_VAR)
+            .ok()
+            .filter(|digest| !digest.is_empty());
+        for name in names {
+            if let Ok(value) = std::env::var(name) {
+                snapshot.env_vars.insert(name.to_string(), value);
+            }
+        }
+        snapshot
+    }
+
+    /// Set the kernel release
+    pub fn with_kernel(mut self, kernel: impl Into<String>) -> Self {
+        self.kernel = Some(kernel.into());
+        self
+    }
+
+    /// Set the container image digest
+    pub fn with_container_image_digest(mut self, digest: impl Into<String>) -> Self {
+        self.container_image_digest = Some(digest.into());
+        self
+    }
+
+    /// Record an environment variable
+    pub fn with_env_var(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.env_vars.insert(name.into(), value.into());
+        self
+    }
+
+    /// Value of a captured environment variable
+    pub fn env_var(&self, name: &str) -> Option<&str> {
+        self.env_vars.get(name).map(String::as_str)
+    }
+}
+
+impl Capabilities {
+    /// Attach an environment snapshot
+    pub fn with_environment(mut self, environment: EnvironmentSnapshot) -> Self {
+        self.environment = Some(environment);
+        self
+    }
+
+    /// Operating system from the environment snapshot, or else discovery metadata
+    pub fn os(&self) -> Option<&str> {
+        self.environment
+            .as_ref()
+            .map(|environment| environment.os.as_str())
+            .or_else(|| self.get_metadata(DISCOVERY_META_OS).map(String::as_str))
+    }
+
+    /// CPU architecture from the environment snapshot, or else discovery metadata
+    pub fn arch(&self) -> Option<&str> {
+        self.environment
+            .as_ref()
+            .map(|environment| environment.arch.as_str())
+            .or_else(|| self.get_metadata(DISCOVERY_META_ARCH).map(String::as_str))
+    }
+}
+
+/// Kernel release of the local machine
+fn local_kernel_release() -> Option<String> {
+    std::fs::read_to_string("/proc/sys/kernel/osrelease")
+        .ok()
+        .or_else(|| {
+            run_with_timeout(
+                "uname",
+                &["-r".to_string()],
+                Duration::from_secs(DEFAULT_VERSION_PROBE_TIMEOUT_SECONDS),
+            )
+        })
+        .map(|release| release.trim().to_string())
+        .filter(|release| !release.is_empty())
+}
@@ -0,0 +1,238 @@
+//! Semantic equality of capability sets
+//!
+//! `Capabilities` and `ToolCapability` compare by meaning rather than by
+//! layout: tools compare regardless of their order within a category, and an
+//! empty user-defined category equals an absent one. `semantic_eq` can also
+//! leave out volatile fields (attestation timestamps and signatures,
+//! expiration and probe timestamps), so two declarations of the same worker
+//! made at different times compare equal:
+//!
+//! ```rust
+//! use std::collections::HashSet;
+//! use worker_capabilities::{Capabilities, EqualityOptions};
+//!
+//! let a = Capabilities::new("worker-1")
+//!     .with_static_analysis("clippy", true)
+//!     .with_static_analysis("semgrep", false);
+//! let b = Capabilities::new("worker-1")
+//!     .with_static_analysis("semgrep", false)
+//!     .with_static_analysis("clippy", true);
+//! assert!(a.semantic_eq(&b, EqualityOptions::ignoring_volatile()));
+//!
+//! let workers: HashSet<Capabilities> = [a.clone(), a].into_iter().collect();
+//! assert_eq!(workers.len(), 1);
+//! ```
+
+use std::hash::{Hash, Hasher};
+
+use crate::capabilities::Capabilities;
+use crate::types::{CapabilityAttestation, CapabilityExpiration, ToolCapability};
+
+/// Fields taken into account by a semantic comparison
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EqualityOptions {
+    /// Ignore timestamps, signatures and the `verified` marker
+    pub ignore_volatile: bool,
+}
+
+impl EqualityOptions {
+    /// Compare every field (used by `PartialEq`)
+    pub fn strict() -> Self {
+        Self::default()
+    }
+
+    /// Ignore fields that change between otherwise identical declarations
+    pub fn ignoring_volatile() -> Self {
+        Self {
+            ignore_volatile: true,
+        }
+    }
+}
+
+impl ToolCapability {
+    /// Compare two tool declarations
+    pub fn semantic_eq(&self, other: &ToolCapability, options: EqualityOptions) -> bool {
+        let stable = self.tool_name == other.tool_name
+            && self.required == other.required
+            && self.alternatives == other.alternatives
+            && self.permissions == other.permissions
+            && self.version == other.version
+            && self.checkpointing == other.checkpointing
+            && self.max_concurrent == other.max_concurrent
+            && self.suspended == other.suspended
+            && self.scope == other.scope
+            && self.wasi == other.wasi
+            && self.version_req == other.version_req
+            && self.binary_sha256 == other.binary_sha256
+            && self.platforms == other.platforms
+            && same_elements(&self.tags, &other.tags, |a, b| a == b)
+            && self.metadata == other.metadata
+            && self.preferences == other.preferences
+            && self.deprecated == other.deprecated;
+        if !stable {
+            return false;
+        }
+
+        if !options.ignore_volatile {
+            return self.attestation == other.attestation
+                && self.countersignatures == other.countersignatures
+                && self.expiration == other.expiration
+                && self.verified == other.verified;
+        }
+        attestations_match(self.attestation.as_ref(), other.attestation.as_ref())
+            && self.countersignatures.len() == other.countersignatures.len()
+            && self
+                .countersignatures
+                .iter()
+                .zip(&other.countersignatures)
+                .all(|(a, b)| attestations_match(Some(a), Some(b)))
+            && expirations_match(&self.expiration, &other.expiration)
+    }
+}
+
+impl PartialEq for ToolCapability {
+    fn eq(&self, other: &Self) -> bool {
+        self.semantic_eq(other, EqualityOptions::strict())
+    }
+}
+
+impl Eq for ToolCapability {}
+
+impl Hash for ToolCapability {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Only fields compared in every mode, so equal tools hash alike
+        self.tool_name.hash(state);
+        self.required.hash(state);
+        self.alternatives.hash(state);
+        self.permissions.hash(state);
+        self.version.hash(state);
+    }
+}
+
+impl Capabilities {
+    /// Compare two capability sets, ignoring the order of tools within a category
+    pub fn semantic_eq(&self, other: &Capabilities, options: EqualityOptions) -> bool {
+        if self.id != other.id
+            || self.flags != other.flags
+            || self.metadata != other.metadata
+            || self.sandbox != other.sandbox
+            || self.payload != other.payload
+            || self.accepts != other.accepts
+            || self.emits != other.emits
+            || self.models != other.models
+            || self.scope != other.scope
+            || self.template != other.template
+            || !same_elements(&self.computed_flags, &other.computed_flags, |a, b| a == b)
+            || !same_elements(&self.tags, &other.tags, |a, b| a == b)
+        {
+            return false;
+        }
+
+        let stable = if options.ignore_volatile {
+            self.network.internet == other.network.internet
+                && self.network.endpoints == other.network.endpoints
+                && same_elements(&self.warm_assets, &other.warm_assets, |a, b| {
+                    a.kind == b.kind && a.identifier == b.identifier
+                })
+                && match (&self.environment, &other.environment) {
+                    (Some(a), Some(b)) => {
+                        a.os == b.os
+                            && a.arch == b.arch
+                            && a.kernel == b.kernel
+                            && a.container_image_digest == b.container_image_digest
+                            && a.env_vars == b.env_vars
+                    }
+                    (a, b) => a.is_none() && b.is_none(),
+                }
+                && attestations_match(self.attestation.as_ref(), other.attestation.as_ref())
+        } else {
+            self.network == other.network
+                && self.warm_assets == other.warm_assets
+                && self.environment == other.environment
+                && self.attestation == other.attestation
+        };
+        if !stable {
+            return false;
+        }
+
+        self.category_names()
+            .into_iter()
+            .chain(other.category_names())
+            .all(|category| {
+                same_elements(
+                    self.tools_in_category(&category),
+                    other.tools_in_category(&category),
+                    |a, b| a.semantic_eq(b, options),
+                )
+            })
+    }
+}
+
+impl PartialEq for Capabilities {
+    fn eq(&self, other: &Self) -> bool {
+        self.semantic_eq(other, EqualityOptions::strict())
+    }
+}
+
+impl Eq for Capabilities {}
+
+impl Hash for Capabilities {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+
+        let mut tools: Vec<(&str, &str)> = self
+            .iter_tools()
+            .map(|(category, tool)| (category, tool.tool_name.as_str()))
+            .collect();
+        tools.sort();
+        tools.hash(state);
+
+        let mut flags: Vec<_> = self.flags.iter().collect();
+        flags.sort();
+        flags.hash(state);
+
+        let mut metadata: Vec<_> = self.metadata.iter().collect();
+        metadata.sort();
+        metadata.hash(state);
+    }
+}
+
+/// Compare attestations by what they vouch for, ignoring when they were signed
+fn attestations_match(
+    a: Option<&CapabilityAttestation>,
+    b: Option<&CapabilityAttestation>,
+) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => {
+            a.capability_hash == b.capability_hash
+                && a.public_key == b.public_key
+                && a.algorithm == b.algorithm
+                && a.attester == b.attester
+        }
+        (a, b) => a.is_none() && b.is_none(),
+    }
+}
+
+/// Compare expirations by revocation state, ignoring timestamps
+fn expirations_match(a: &CapabilityExpiration, b: &CapabilityExpiration) -> bool {
+    a.revoked == b.revoked
+        && a.revocation_reason == b.revocation_reason
+        && a.revoked_by == b.revoked_by
+}
+
+/// Check if two lists hold the same elements in any order
+fn same_elements<T>(a: &[T], b: &[T], eq: impl Fn(&T, &T) -> bool) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut matched = vec![false; b.len()];
+    a.iter().all(
+        |x| match (0..b.len()).find(|&i| !matched[i] && eq(x, &b[i])) {
+            Some(i) => {
+                matched[i] = true;
+                true
+            }
+            None => false,
+        },
+    )
+}
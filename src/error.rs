@@ -0,0 +1,161 @@
+//! Crate-wide error type
+
+use std::fmt;
+
+use crate::validation::ValidationError;
+
+/// Errors shared across capability operations
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CapabilityError {
+    /// The system clock is before the Unix epoch, or a recorded timestamp
+    /// lies further in the future than the tolerated skew
+    ClockSkew { skew_seconds: u64 },
+    /// A tool is declared more than once in the same category
+    DuplicateTool { category: String, tool: String },
+    /// A flag was set both enabled and disabled
+    ConflictingFlag { flag: String },
+    /// The capability set violates the security limits
+    Invalid(Vec<ValidationError>),
+}
+
+impl fmt::Display for CapabilityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CapabilityError::ClockSkew { skew_seconds } => {
+                write!(f, "clock skew of {} seconds detected", skew_seconds)
+            }
+            CapabilityError::DuplicateTool { category, tool } => {
+                write!(f, "tool '{}' declared twice in '{}'", tool, category)
+            }
+            CapabilityError::ConflictingFlag { flag } => {
+                write!(f, "flag '{}' set both enabled and disabled", flag)
+            }
+            CapabilityError::Invalid(errors) => {
+                let errors: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+                write!(f, "invalid capability set: {}", errors.join("; "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for CapabilityError {}
+
+/// Reason a capability failed verification
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerificationError {
+    /// The tool carries no attestation
+    MissingAttestation { tool: String },
+    /// The capability is past its expiration
+    Expired { tool: String },
+    /// The attestation is older than the attestation expiry
+    AttestationExpired { tool: String },
+    /// The capability was revoked
+    Revoked {
+        tool: String,
+        reason: Option<String>,
+    },
+    /// The attestation uses an unsupported algorithm
+    UnsupportedAlgorithm { tool: String, algorithm: String },
+    /// The attestation has an empty signature or public key
+    MissingSignature { tool: String },
+    /// The attestation signature does not verify against its public key
+    InvalidSignature { tool: String },
+    /// The attestation is validly signed but replayed or too old for the verifier
+    StaleAttestation { tool: String },
+    /// The revocation status of the attestation could not be determined
+    RevocationUnavailable { tool: String, reason: String },
+    /// The attestation does not cover the current capability hash
+    HashMismatch { tool: String },
+    /// A counter-signature does not cover the previous link of the attestation chain
+    BrokenChain { tool: String, link: usize },
+    /// The attestation comes from an attester not trusted for the tool's category
+    UntrustedAttester { tool: String, attester: String },
+    /// Fewer configured attesters signed the attestation chain than the threshold requires
+    ThresholdNotMet {
+        tool: String,
+        required: usize,
+        attested: usize,
+    },
+    /// The clock is before the Unix epoch or the attestation is dated in the future
+    ClockSkew { skew_seconds: u64 },
+}
+
+impl fmt::Display for VerificationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerificationError::MissingAttestation { tool } => {
+                write!(f, "tool '{}' is not attested", tool)
+            }
+            VerificationError::Expired { tool } => write!(f, "tool '{}' has expired", tool),
+            VerificationError::AttestationExpired { tool } => {
+                write!(f, "attestation of tool '{}' has expired", tool)
+            }
+            VerificationError::Revoked { tool, reason } => match reason {
+                Some(reason) => write!(f, "tool '{}' was revoked: {}", tool, reason),
+                None => write!(f, "tool '{}' was revoked", tool),
+            },
+            VerificationError::UnsupportedAlgorithm { tool, algorithm } => write!(
+                f,
+                "attestation of tool '{}' uses unsupported algorithm '{}'",
+                tool, algorithm
+            ),
+            VerificationError::MissingSignature { tool } => {
+                write!(f, "attestation of tool '{}' is not signed", tool)
+            }
+            VerificationError::InvalidSignature { tool } => {
+                write!(f, "attestation signature of tool '{}' is invalid", tool)
+            }
+            VerificationError::StaleAttestation { tool } => {
+                write!(f, "attestation of tool '{}' is not fresh", tool)
+            }
+            VerificationError::RevocationUnavailable { tool, reason } => write!(
+                f,
+                "revocation status of tool '{}' is unavailable: {}",
+                tool, reason
+            ),
+            VerificationError::HashMismatch { tool } => write!(
+                f,
+                "attestation of tool '{}' does not match its capability hash",
+                tool
+            ),
+            VerificationError::BrokenChain { tool, link } => write!(
+                f,
+                "counter-signature {} of tool '{}' does not cover the previous link",
+                link, tool
+            ),
+            VerificationError::UntrustedAttester { tool, attester } => write!(
+                f,
+                "tool '{}' is attested by untrusted attester '{}'",
+                tool, attester
+            ),
+            VerificationError::ThresholdNotMet {
+                tool,
+                required,
+                attested,
+            } => write!(
+                f,
+                "tool '{}' is attested by {} of {} required attesters",
+                tool, attested, required
+            ),
+            VerificationError::ClockSkew { skew_seconds } => {
+                write!(f, "clock skew of {} seconds detected", skew_seconds)
+            }
+        }
+    }
+}
+
+impl std::error::Error for VerificationError {}
+
+impl VerificationError {
+    /// Translate a clock failure, treating any other error as expiry of the tool
+    pub(crate) fn from_clock(error: CapabilityError, tool: &str) -> Self {
+        match error {
+            CapabilityError::ClockSkew { skew_seconds } => {
+                VerificationError::ClockSkew { skew_seconds }
+            }
+            _ => VerificationError::Expired {
+                tool: tool.to_string(),
+            },
+        }
+    }
+}
@@ -0,0 +1,174 @@
+//! Detailed explanations of capability checks
+
+use serde::{Deserialize, Serialize};
+
+use crate::capabilities::Capabilities;
+use crate::checker::{ToolChecker, ToolStatus};
+use crate::types::ToolCapability;
+
+/// Reason a tool check failed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolCheckFailure {
+    /// The capability expired at the given timestamp
+    Expired { expires_at: u64 },
+    /// The capability was revoked
+    Revoked {
+        reason: Option<String>,
+        revoked_by: Option<String>,
+    },
+    /// The tool was suspended (e.g., demoted for a poor success rate)
+    Suspended,
+    /// The declared version does not satisfy the version constraint
+    VersionMismatch {
+        required: String,
+        found: Option<String>,
+    },
+    /// The installed executable does not match the pinned SHA256
+    BinaryMismatch { tool: String },
+    /// Neither the tool nor any alternative is installed
+    NotInstalled { checked: Vec<String> },
+}
+
+/// Outcome of checking a single tool
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolExplanation {
+    /// Category the tool is declared in
+    pub category: String,
+    /// Declared tool name
+    pub tool_name: String,
+    /// Whether the tool is required
+    pub required: bool,
+    /// Tool name (primary or alternative) that satisfied the check
+    pub matched: Option<String>,
+    /// Status of the matched tool (where it was found, which version)
+    #[serde(default)]
+    pub status: Option<ToolStatus>,
+    /// Why the check failed (if it did)
+    pub failure: Option<ToolCheckFailure>,
+}
+
+impl ToolExplanation {
+    /// Check if the tool was satisfied
+    pub fn is_satisfied(&self) -> bool {
+        self.matched.is_some()
+    }
+
+    /// Check if the tool was matched through an alternative
+    pub fn matched_alternative(&self) -> bool {
+        self.matched
+            .as_ref()
+            .is_some_and(|matched| *matched != self.tool_name)
+    }
+}
+
+/// Explanation of a full capability check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CapabilityReport {
+    /// Worker the check was run for
+    pub worker_id: String,
+    /// Per-tool outcomes, grouped by category
+    pub tools: Vec<ToolExplanation>,
+}
+
+impl CapabilityReport {
+    /// Check if every required tool was satisfied
+    pub fn all_required_satisfied(&self) -> bool {
+        self.tools
+            .iter()
+            .all(|tool| !tool.required || tool.is_satisfied())
+    }
+
+    /// Required tools that were not satisfied
+    pub fn missing_required(&self) -> Vec<&ToolExplanation> {
+        self.tools
+            .iter()
+            .filter(|tool| tool.required && !tool.is_satisfied())
+            .collect()
+    }
+}
+
+impl ToolCapability {
+    /// Check this tool and explain the outcome
+    pub fn explain(
+        &self,
+        category: &str,
+        tool_checker: &(impl ToolChecker + ?Sized),
+    ) -> ToolExplanation {
+        let matched = self.satisfied_by(tool_checker).map(str::to_string);
+
+        let failure = if matched.is_some() {
+            None
+        } else if self.is_revoked() {
+            Some(ToolCheckFailure::Revoked {
+                reason: self.expiration.revocation_reason.clone(),
+                revoked_by: self.expiration.revoked_by.clone(),
+            })
+        } else if self.is_expired() {
+            Some(ToolCheckFailure::Expired {
+                expires_at: self.expiration.expires_at,
+            })
+        } else if self.suspended {
+            Some(ToolCheckFailure::Suspended)
+        } else if let Some(installed) = std::iter::once(&self.tool_name)
+            .chain(&self.alternatives)
+            .find(|name| {
+                tool_checker.is_available(name) && self.checked_version_matches(tool_checker, name)
+            })
+        {
+            Some(ToolCheckFailure::BinaryMismatch {
+                tool: installed.clone(),
+            })
+        } else if let Some(installed) = std::iter::once(&self.tool_name)
+            .chain(&self.alternatives)
+            .find(|name| tool_checker.is_available(name))
+        {
+            Some(ToolCheckFailure::VersionMismatch {
+                required: self
+                    .version_req
+                    .as_ref()
+                    .map(|requirement| requirement.to_string())
+                    .unwrap_or_default(),
+                found: tool_checker
+                    .detected_version(installed)
+                    .or_else(|| self.version.clone()),
+            })
+        } else {
+            Some(ToolCheckFailure::NotInstalled {
+                checked: std::iter::once(&self.tool_name)
+                    .chain(&self.alternatives)
+                    .cloned()
+                    .collect(),
+            })
+        };
+
+        ToolExplanation {
+            category: category.to_string(),
+            tool_name: self.tool_name.clone(),
+            required: self.required,
+            status: matched.as_deref().map(|tool| tool_checker.status(tool)),
+            matched,
+            failure,
+        }
+    }
+}
+
+impl Capabilities {
+    /// Check every tool and explain which were satisfied and why others failed
+    pub fn explain(&self, tool_checker: &(impl ToolChecker + ?Sized)) -> CapabilityReport {
+        let tools = self
+            .category_names()
+            .iter()
+            .flat_map(|category| {
+                self.tools_in_category(category)
+                    .iter()
+                    .map(move |tool| tool.explain(category, tool_checker))
+            })
+            .collect();
+
+        CapabilityReport {
+            worker_id: self.id.clone(),
+            tools,
+        }
+    }
+}
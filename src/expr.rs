@@ -0,0 +1,95 @@
+//! Boolean capability expressions for scheduling predicates
+
+use serde::{Deserialize, Serialize};
+
+use crate::capabilities::Capabilities;
+use crate::checker::ToolChecker;
+
+/// Boolean expression over the capabilities of a worker
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CapabilityExpr {
+    /// Every sub-expression holds (true when empty)
+    All(Vec<CapabilityExpr>),
+    /// At least one sub-expression holds (false when empty)
+    Any(Vec<CapabilityExpr>),
+    /// The sub-expression does not hold
+    Not(Box<CapabilityExpr>),
+    /// A flag is enabled
+    Flag(String),
+    /// A tool (declared by name or as an alternative) is available
+    Tool(String),
+    /// A capability category has an available tool
+    Category(String),
+}
+
+impl CapabilityExpr {
+    /// Expression holding when all sub-expressions hold
+    pub fn all(exprs: impl IntoIterator<Item = CapabilityExpr>) -> Self {
+        CapabilityExpr::All(exprs.into_iter().collect())
+    }
+
+    /// Expression holding when any sub-expression holds
+    pub fn any(exprs: impl IntoIterator<Item = CapabilityExpr>) -> Self {
+        CapabilityExpr::Any(exprs.into_iter().collect())
+    }
+
+    /// Negate an expression
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(expr: CapabilityExpr) -> Self {
+        CapabilityExpr::Not(Box::new(expr))
+    }
+
+    /// Expression holding when a flag is enabled
+    pub fn flag(flag: impl Into<String>) -> Self {
+        CapabilityExpr::Flag(flag.into())
+    }
+
+    /// Expression holding when a tool is available
+    pub fn tool(tool: impl Into<String>) -> Self {
+        CapabilityExpr::Tool(tool.into())
+    }
+
+    /// Expression holding when a capability category has an available tool
+    pub fn category(capability_type: impl Into<String>) -> Self {
+        CapabilityExpr::Category(capability_type.into())
+    }
+
+    /// Evaluate the expression against a capability set
+    pub fn evaluate(
+        &self,
+        capabilities: &Capabilities,
+        tool_checker: &(impl ToolChecker + ?Sized),
+    ) -> bool {
+        match self {
+            CapabilityExpr::All(exprs) => exprs
+                .iter()
+                .all(|expr| expr.evaluate(capabilities, tool_checker)),
+            CapabilityExpr::Any(exprs) => exprs
+                .iter()
+                .any(|expr| expr.evaluate(capabilities, tool_checker)),
+            CapabilityExpr::Not(expr) => !expr.evaluate(capabilities, tool_checker),
+            CapabilityExpr::Flag(flag) => capabilities.has_flag(flag),
+            CapabilityExpr::Tool(name) => capabilities.tools_iter().any(|tool| {
+                (tool.tool_name == *name || tool.alternatives.contains(name))
+                    && !(tool.is_expired() || tool.is_revoked() || tool.suspended)
+                    && tool_checker.is_available(name)
+                    && tool.checked_version_matches(tool_checker, name)
+            }),
+            CapabilityExpr::Category(capability_type) => {
+                capabilities.has_capability(capability_type, tool_checker)
+            }
+        }
+    }
+}
+
+impl Capabilities {
+    /// Check if this capability set satisfies a capability expression
+    pub fn matches_expr(
+        &self,
+        expr: &CapabilityExpr,
+        tool_checker: &(impl ToolChecker + ?Sized),
+    ) -> bool {
+        expr.evaluate(self, tool_checker)
+    }
+}
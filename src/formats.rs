@@ -0,0 +1,87 @@
+//! Input artifact and report format declarations
+
+use serde::{Deserialize, Serialize};
+
+/// Kind of input artifact a worker can accept
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArtifactFormat {
+    /// Source code tarball
+    SourceTarball,
+    /// Git repository URL
+    GitUrl,
+    /// Container image reference
+    ContainerImage,
+    /// Compiled binary
+    CompiledBinary,
+    /// Solidity compiler JSON (standard JSON input/output)
+    SolidityJson,
+    /// Any other format identified by name
+    Other(String),
+}
+
+impl ArtifactFormat {
+    /// Parse a format from its identifier
+    pub fn parse(name: &str) -> Self {
+        match name.to_ascii_lowercase().as_str() {
+            "source_tarball" => ArtifactFormat::SourceTarball,
+            "git_url" => ArtifactFormat::GitUrl,
+            "container_image" => ArtifactFormat::ContainerImage,
+            "compiled_binary" => ArtifactFormat::CompiledBinary,
+            "solidity_json" => ArtifactFormat::SolidityJson,
+            _ => ArtifactFormat::Other(name.to_string()),
+        }
+    }
+
+    /// Identifier of this format
+    pub fn as_str(&self) -> &str {
+        match self {
+            ArtifactFormat::SourceTarball => "source_tarball",
+            ArtifactFormat::GitUrl => "git_url",
+            ArtifactFormat::ContainerImage => "container_image",
+            ArtifactFormat::CompiledBinary => "compiled_binary",
+            ArtifactFormat::SolidityJson => "solidity_json",
+            ArtifactFormat::Other(name) => name,
+        }
+    }
+}
+
+/// Report format a worker can emit
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportFormat {
+    /// Static Analysis Results Interchange Format
+    Sarif,
+    /// Tool-specific JSON
+    Json,
+    /// JUnit XML test report
+    JunitXml,
+    /// CycloneDX software bill of materials
+    CycloneDx,
+    /// Any other format identified by name
+    Other(String),
+}
+
+impl ReportFormat {
+    /// Parse a format from its identifier
+    pub fn parse(name: &str) -> Self {
+        match name.to_ascii_lowercase().as_str() {
+            "sarif" => ReportFormat::Sarif,
+            "json" => ReportFormat::Json,
+            "junit_xml" => ReportFormat::JunitXml,
+            "cyclonedx" => ReportFormat::CycloneDx,
+            _ => ReportFormat::Other(name.to_string()),
+        }
+    }
+
+    /// Identifier of this format
+    pub fn as_str(&self) -> &str {
+        match self {
+            ReportFormat::Sarif => "sarif",
+            ReportFormat::Json => "json",
+            ReportFormat::JunitXml => "junit_xml",
+            ReportFormat::CycloneDx => "cyclonedx",
+            ReportFormat::Other(name) => name,
+        }
+    }
+}
@@ -0,0 +1,205 @@
+//! Time-bound permission grants layered over a worker's base capabilities
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::Duration;
+
+use crate::capabilities::Capabilities;
+use crate::clock::current_timestamp;
+use crate::constants::*;
+use crate::types::{CapabilityPermissions, EgressRule, PathRule};
+
+/// Permissions added on top of a capability set
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PermissionDelta {
+    /// Permission names granted to every tool (e.g. `network_access`)
+    pub permissions: Vec<String>,
+    /// Egress rules added to every tool
+    pub egress_allowlist: Vec<EgressRule>,
+    /// Filesystem path rules added to every tool
+    pub filesystem_paths: Vec<PathRule>,
+    /// Whether general outbound internet access is granted to the worker
+    pub internet: bool,
+}
+
+impl PermissionDelta {
+    /// Create an empty delta
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Grant a permission to every tool
+    pub fn grant(mut self, permission: impl Into<String>) -> Self {
+        self.permissions.push(permission.into());
+        self
+    }
+
+    /// Add an egress rule to every tool
+    pub fn with_egress(mut self, rule: EgressRule) -> Self {
+        self.egress_allowlist.push(rule);
+        self
+    }
+
+    /// Add a filesystem path rule to every tool
+    pub fn with_path(mut self, rule: PathRule) -> Self {
+        self.filesystem_paths.push(rule);
+        self
+    }
+
+    /// Grant general outbound internet access (and `network_access` to every tool)
+    pub fn with_internet(mut self) -> Self {
+        self.internet = true;
+        self.grant(PERMISSION_NETWORK_ACCESS)
+    }
+
+    /// Apply this delta to a set of tool permissions
+    pub fn apply_to_permissions(&self, permissions: &mut CapabilityPermissions) {
+        for permission in &self.permissions {
+            match permission.as_str() {
+                PERMISSION_FILESYSTEM_ACCESS => permissions.filesystem_access = true,
+                PERMISSION_NETWORK_ACCESS => permissions.network_access = true,
+                PERMISSION_PROCESS_SPAWN => permissions.process_spawn = true,
+                PERMISSION_ENV_ACCESS => permissions.env_access = true,
+                PERMISSION_SYSTEM_ACCESS => permissions.system_access = true,
+                _ => {}
+            }
+        }
+        // An empty list means unrestricted: adding rules to it would narrow access,
+        // so rules only widen lists that already restrict it
+        if !permissions.egress_allowlist.is_empty() {
+            permissions
+                .egress_allowlist
+                .extend(self.egress_allowlist.iter().cloned());
+        }
+        if !permissions.filesystem_paths.is_empty() {
+            permissions
+                .filesystem_paths
+                .extend(self.filesystem_paths.iter().cloned());
+        }
+    }
+}
+
+/// Temporary grant applied over a worker's capabilities at match time
+///
+/// Grants are held by the registry next to the base declaration and are never
+/// written into it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GrantOverlay {
+    /// Grant identifier (assigned by the registry)
+    pub id: String,
+    /// Permissions granted
+    pub delta: PermissionDelta,
+    /// Who issued the grant
+    pub granted_by: String,
+    /// Timestamp when the grant was issued
+    pub granted_at: u64,
+    /// Timestamp after which the grant no longer applies
+    pub expires_at: u64,
+    /// Job the grant was issued for (if any)
+    pub job_id: Option<String>,
+}
+
+impl GrantOverlay {
+    /// Create a grant that expires after `ttl`
+    pub fn temporary(delta: PermissionDelta, ttl: Duration, granted_by: impl Into<String>) -> Self {
+        let now = current_timestamp();
+        Self {
+            id: String::new(),
+            delta,
+            granted_by: granted_by.into(),
+            granted_at: now,
+            expires_at: now.saturating_add(ttl.as_secs()),
+            job_id: None,
+        }
+    }
+
+    /// Set the job the grant was issued for
+    pub fn for_job(mut self, job_id: impl Into<String>) -> Self {
+        self.job_id = Some(job_id.into());
+        self
+    }
+
+    /// Check if the grant has expired
+    pub fn is_expired(&self) -> bool {
+        current_timestamp() >= self.expires_at
+    }
+
+    /// Capability set with this grant applied
+    pub fn apply(&self, base: &Capabilities) -> Capabilities {
+        let mut caps = base.clone();
+        for tool in caps.tools_iter_mut() {
+            self.delta.apply_to_permissions(&mut tool.permissions);
+        }
+        if self.delta.internet {
+            caps.network.internet = true;
+        }
+        caps
+    }
+}
+
+/// Kind of audited grant event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditEventKind {
+    /// A grant was issued
+    GrantIssued {
+        granted_by: String,
+        expires_at: u64,
+        job_id: Option<String>,
+    },
+    /// A grant was applied while matching
+    GrantApplied,
+    /// A grant expired and was removed
+    GrantExpired,
+    /// A grant was revoked before expiring
+    GrantRevoked { revoked_by: String },
+}
+
+/// Entry in the registry's audit log
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEvent {
+    /// Timestamp of the event
+    pub timestamp: u64,
+    /// Worker the event concerns
+    pub worker_id: String,
+    /// Grant the event concerns
+    pub grant_id: String,
+    /// What happened
+    pub kind: AuditEventKind,
+}
+
+impl AuditEvent {
+    /// Create an event at the current time
+    pub(crate) fn now(worker_id: &str, grant_id: &str, kind: AuditEventKind) -> Self {
+        Self {
+            timestamp: current_timestamp(),
+            worker_id: worker_id.to_string(),
+            grant_id: grant_id.to_string(),
+            kind,
+        }
+    }
+}
+
+/// Errors from issuing or revoking grants
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GrantError {
+    /// No worker with this ID is registered
+    UnknownWorker(String),
+    /// No active grant with this ID exists
+    UnknownGrant(String),
+    /// The grant had already expired when issued
+    AlreadyExpired,
+}
+
+impl fmt::Display for GrantError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GrantError::UnknownWorker(id) => write!(f, "unknown worker {}", id),
+            GrantError::UnknownGrant(id) => write!(f, "unknown grant {}", id),
+            GrantError::AlreadyExpired => write!(f, "grant has already expired"),
+        }
+    }
+}
+
+impl std::error::Error for GrantError {}
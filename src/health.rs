@@ -0,0 +1,102 @@
+//! Worker health derived from periodic re-verification
+//!
+//! A `WorkerHealthReport` records whether a worker's attestations still
+//! verify (unattested tools are not penalized) and which of its tools are
+//! unavailable (missing, expired, revoked or suspended). The registry keeps
+//! the latest report per worker, reports status changes as
+//! `WorkerHealthTransition`s, and skips workers whose latest report is
+//! unhealthy in its `find_*` matchers.
+
+use serde::{Deserialize, Serialize};
+
+use crate::capabilities::Capabilities;
+use crate::checker::ToolChecker;
+use crate::clock::current_timestamp;
+use crate::signing::AttestationVerifier;
+
+/// Health status of a worker
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkerHealth {
+    /// Attestations verify and every tool is available
+    Healthy,
+    /// Some optional tools are unavailable
+    Degraded,
+    /// Attestations fail to verify or a required tool is unavailable
+    Unhealthy,
+}
+
+/// Outcome of re-verifying a worker
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkerHealthReport {
+    /// Worker that was verified
+    pub worker_id: String,
+    /// Resulting health status
+    pub status: WorkerHealth,
+    /// Timestamp of the verification
+    pub checked_at: u64,
+    /// Whether the attestations of attested tools still verify
+    pub attestations_valid: bool,
+    /// Required tools that are unavailable
+    pub unavailable_required: Vec<String>,
+    /// Optional tools that are unavailable
+    pub unavailable_optional: Vec<String>,
+}
+
+impl WorkerHealthReport {
+    /// Verify attestations, expirations and tool availability of a capability set
+    ///
+    /// Attestation signatures are checked with `verifier`.
+    pub fn check(
+        caps: &Capabilities,
+        tool_checker: &(impl ToolChecker + ?Sized),
+        verifier: &dyn AttestationVerifier,
+    ) -> Self {
+        let attestations_valid = caps
+            .tools_iter()
+            .filter(|tool| tool.attestation.is_some())
+            .all(|tool| tool.verify_attestation_with(verifier));
+        let (unavailable_required, unavailable_optional): (Vec<_>, Vec<_>) = caps
+            .explain(tool_checker)
+            .tools
+            .into_iter()
+            .filter(|tool| !tool.is_satisfied())
+            .partition(|tool| tool.required);
+
+        let status = if !attestations_valid || !unavailable_required.is_empty() {
+            WorkerHealth::Unhealthy
+        } else if !unavailable_optional.is_empty() {
+            WorkerHealth::Degraded
+        } else {
+            WorkerHealth::Healthy
+        };
+
+        Self {
+            worker_id: caps.id.clone(),
+            status,
+            checked_at: current_timestamp(),
+            attestations_valid,
+            unavailable_required: unavailable_required
+                .into_iter()
+                .map(|tool| tool.tool_name)
+                .collect(),
+            unavailable_optional: unavailable_optional
+                .into_iter()
+                .map(|tool| tool.tool_name)
+                .collect(),
+        }
+    }
+}
+
+/// Change of a worker's health status
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkerHealthTransition {
+    /// Worker whose status changed
+    pub worker_id: String,
+    /// Previous status (`None` on the first verification)
+    pub from: Option<WorkerHealth>,
+    /// New status
+    pub to: WorkerHealth,
+    /// Report that caused the change
+    pub report: WorkerHealthReport,
+}
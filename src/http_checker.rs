@@ -0,0 +1,187 @@
+//! Tool checker querying a remote agent over HTTP
+//!
+//! The agent answers `GET <base>/tools/{name}` with `200` and a JSON body
+//! such as `{"available": true, "version": "1.2.0", "path": "/usr/bin/tool"}`,
+//! or `404` when the tool is not installed. Agents serving pinned tools add
+//! the executable's `"sha256"`.
+
+use serde::Deserialize;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::checker::{CachedChecker, ToolChecker, ToolStatus};
+use crate::constants::*;
+
+/// Response body of the tool endpoint
+#[derive(Debug, Deserialize)]
+struct ToolResponse {
+    available: bool,
+    #[serde(default)]
+    version: Option<String>,
+    #[serde(default)]
+    path: Option<PathBuf>,
+    #[serde(default)]
+    sha256: Option<String>,
+}
+
+/// Uncached HTTP client behind `HttpToolChecker`
+#[derive(Debug, Clone)]
+struct HttpToolClient {
+    base_url: String,
+    timeout: Duration,
+    retries: u32,
+    retry_backoff: Duration,
+}
+
+impl HttpToolClient {
+    fn tool_url(&self, tool: &str) -> String {
+        format!(
+            "{}{}{}",
+            self.base_url.trim_end_matches('/'),
+            HTTP_CHECKER_TOOLS_PATH,
+            encode_path_segment(tool)
+        )
+    }
+
+    /// Query the agent, retrying transport errors and server errors
+    fn fetch(&self, tool: &str) -> ToolStatus {
+        let url = self.tool_url(tool);
+        for attempt in 0..=self.retries {
+            if attempt > 0 {
+                std::thread::sleep(self.retry_backoff * attempt);
+            }
+
+            match ureq::get(&url).timeout(self.timeout).call() {
+                Ok(response) => {
+                    return response
+                        .into_string()
+                        .ok()
+                        .and_then(|body| serde_json::from_str::<ToolResponse>(&body).ok())
+                        .map(|body| ToolStatus {
+                            available: body.available,
+                            version: body.version,
+                            path: body.path,
+                            sha256: body.sha256.map(|sha256| sha256.to_ascii_lowercase()),
+                            ..ToolStatus::found()
+                        })
+                        .unwrap_or_else(ToolStatus::missing);
+                }
+                Err(ureq::Error::Status(code, _)) if code < 500 => return ToolStatus::missing(),
+                Err(_) => continue,
+            }
+        }
+        ToolStatus::missing()
+    }
+}
+
+impl ToolChecker for HttpToolClient {
+    fn is_available(&self, tool: &str) -> bool {
+        self.fetch(tool).available
+    }
+
+    fn detected_version(&self, tool: &str) -> Option<String> {
+        self.fetch(tool).version
+    }
+
+    fn status(&self, tool: &str) -> ToolStatus {
+        self.fetch(tool)
+    }
+
+    fn cache_key(&self) -> Option<String> {
+        Some(format!("http:{}", self.base_url))
+    }
+}
+
+/// Checker asking a remote agent whether tools are available
+///
+/// Requests time out, are retried on transport and server errors, and
+/// responses are cached (see `with_cache_ttl`). An agent that stays
+/// unreachable reports every tool as missing.
+#[derive(Debug)]
+pub struct HttpToolChecker {
+    client: CachedChecker<HttpToolClient>,
+}
+
+impl HttpToolChecker {
+    /// Create a checker for the agent at `base_url`
+    pub fn new(base_url: impl Into<String>) -> Self {
+        let client = HttpToolClient {
+            base_url: base_url.into(),
+            timeout: Duration::from_secs(DEFAULT_HTTP_CHECKER_TIMEOUT_SECONDS),
+            retries: DEFAULT_HTTP_CHECKER_RETRIES,
+            retry_backoff: Duration::from_millis(DEFAULT_HTTP_CHECKER_RETRY_BACKOFF_MILLIS),
+        };
+        Self {
+            client: CachedChecker::new(
+                client,
+                Duration::from_secs(DEFAULT_HTTP_CHECKER_CACHE_TTL_SECONDS),
+            ),
+        }
+    }
+
+    /// Set the timeout of a single request
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.client.inner_mut().timeout = timeout;
+        self
+    }
+
+    /// Set how often a failed request is retried, and the backoff between attempts
+    pub fn with_retries(mut self, retries: u32, backoff: Duration) -> Self {
+        let client = self.client.inner_mut();
+        client.retries = retries;
+        client.retry_backoff = backoff;
+        self
+    }
+
+    /// Set how long responses are cached
+    pub fn with_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.client = self.client.with_ttl(ttl).with_negative_ttl(ttl);
+        self
+    }
+
+    /// URL queried for a tool
+    pub fn tool_url(&self, tool: &str) -> String {
+        self.client.inner().tool_url(tool)
+    }
+
+    /// Forget the cached response for a tool
+    pub fn invalidate(&self, tool: &str) {
+        self.client.invalidate(tool);
+    }
+
+    /// Forget all cached responses
+    pub fn invalidate_all(&self) {
+        self.client.invalidate_all();
+    }
+}
+
+impl ToolChecker for HttpToolChecker {
+    fn is_available(&self, tool: &str) -> bool {
+        self.client.is_available(tool)
+    }
+
+    fn detected_version(&self, tool: &str) -> Option<String> {
+        self.client.detected_version(tool)
+    }
+
+    fn status(&self, tool: &str) -> ToolStatus {
+        self.client.status(tool)
+    }
+
+    fn cache_key(&self) -> Option<String> {
+        self.client.cache_key()
+    }
+}
+
+/// Percent-encode a tool name for use as a URL path segment
+fn encode_path_segment(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
@@ -0,0 +1,245 @@
+//! in-toto attestation export (requires the `in-toto` feature)
+//!
+//! A tool capability maps to an in-toto Statement whose subject is the tool,
+//! identified by its capability hash (`sha256` digest), and whose predicate
+//! is the capability itself, attestation and counter-signatures included.
+//! Statements travel in DSSE envelopes, so supply-chain tooling that reads
+//! in-toto (SLSA verifiers, policy engines, attestation stores) consumes
+//! worker capability claims without a custom parser.
+//!
+//! ```rust
+//! use worker_capabilities::{
+//!     AttestationSigner, DsseEnvelope, Ed25519Signer, Ed25519Verifier, ToolCapability,
+//! };
+//!
+//! let signer = Ed25519Signer::new([7; 32], "ci");
+//! let tool = ToolCapability::new("cargo", true);
+//! let envelope = tool.to_in_toto_statement().sign(&signer);
+//!
+//! let envelope = DsseEnvelope::from_json(&envelope.to_json().unwrap()).unwrap();
+//! let statement = envelope.verify(&Ed25519Verifier, &signer.public_key()).unwrap();
+//! let imported = ToolCapability::from_in_toto_statement(&statement).unwrap();
+//! assert_eq!(imported, tool);
+//! ```
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::constants::*;
+use crate::signing::{AttestationSigner, AttestationVerifier};
+use crate::types::ToolCapability;
+
+/// in-toto Statement about capabilities
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InTotoStatement {
+    /// Statement type (`https://in-toto.io/Statement/v1`)
+    #[serde(rename = "_type")]
+    pub statement_type: String,
+    /// Artifacts the statement is about
+    pub subject: Vec<InTotoSubject>,
+    /// Type of the predicate
+    #[serde(rename = "predicateType")]
+    pub predicate_type: String,
+    /// Claims about the subjects
+    pub predicate: Value,
+}
+
+/// Artifact of an in-toto Statement
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InTotoSubject {
+    /// Artifact name
+    pub name: String,
+    /// Artifact digests by algorithm (e.g., `sha256`)
+    pub digest: BTreeMap<String, String>,
+}
+
+/// DSSE envelope carrying a signed payload
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DsseEnvelope {
+    /// Media type of the payload
+    pub payload_type: String,
+    /// Base64 payload
+    pub payload: String,
+    /// Signatures over the payload
+    pub signatures: Vec<DsseSignature>,
+}
+
+/// Signature of a DSSE envelope
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DsseSignature {
+    /// Identifier of the signing key
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub keyid: String,
+    /// Base64 signature over the pre-authentication encoding
+    pub sig: String,
+}
+
+/// Errors from importing in-toto statements and envelopes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InTotoError {
+    /// The statement or payload type is not supported
+    UnsupportedType(String),
+    /// The statement or predicate could not be parsed
+    Parse(String),
+    /// No subject matches the capability hash of the predicate
+    SubjectMismatch {
+        /// Tool name of the predicate
+        tool: String,
+    },
+    /// No signature verifies against the public key
+    InvalidSignature,
+}
+
+impl fmt::Display for InTotoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InTotoError::UnsupportedType(kind) => write!(f, "unsupported in-toto type '{}'", kind),
+            InTotoError::Parse(reason) => write!(f, "invalid in-toto statement: {}", reason),
+            InTotoError::SubjectMismatch { tool } => {
+                write!(f, "no statement subject matches capability '{}'", tool)
+            }
+            InTotoError::InvalidSignature => write!(f, "envelope signature is invalid"),
+        }
+    }
+}
+
+impl std::error::Error for InTotoError {}
+
+impl ToolCapability {
+    /// Statement whose subject is this tool's capability hash
+    pub fn to_in_toto_statement(&self) -> InTotoStatement {
+        InTotoStatement {
+            statement_type: IN_TOTO_STATEMENT_TYPE.to_string(),
+            subject: vec![InTotoSubject {
+                name: self.tool_name.clone(),
+                digest: BTreeMap::from([("sha256".to_string(), self.generate_capability_hash())]),
+            }],
+            predicate_type: CAPABILITY_PREDICATE_TYPE.to_string(),
+            predicate: serde_json::to_value(self).unwrap_or_default(),
+        }
+    }
+
+    /// Import the capability of a statement
+    ///
+    /// A subject must name the tool with its current capability hash.
+    /// Attestations are imported as-is; verify them separately.
+    pub fn from_in_toto_statement(statement: &InTotoStatement) -> Result<Self, InTotoError> {
+        if !IN_TOTO_STATEMENT_TYPES.contains(&statement.statement_type.as_str()) {
+            return Err(InTotoError::UnsupportedType(
+                statement.statement_type.clone(),
+            ));
+        }
+        if statement.predicate_type != CAPABILITY_PREDICATE_TYPE {
+            return Err(InTotoError::UnsupportedType(
+                statement.predicate_type.clone(),
+            ));
+        }
+
+        let tool: ToolCapability = serde_json::from_value(statement.predicate.clone())
+            .map_err(|e| InTotoError::Parse(e.to_string()))?;
+        let hash = tool.generate_capability_hash();
+        let covered = statement.subject.iter().any(|subject| {
+            subject.name == tool.tool_name
+                && subject.digest.get("sha256").map(String::as_str) == Some(hash.as_str())
+        });
+        if !covered {
+            return Err(InTotoError::SubjectMismatch {
+                tool: tool.tool_name,
+            });
+        }
+        Ok(tool)
+    }
+}
+
+impl InTotoStatement {
+    /// Sign the statement into a DSSE envelope
+    ///
+    /// The key id is the signer's public key.
+    pub fn sign(&self, signer: &dyn AttestationSigner) -> DsseEnvelope {
+        let payload = serde_json::to_vec(self).unwrap_or_default();
+        let signature =
+            signer.sign_raw(&pre_authentication_encoding(IN_TOTO_PAYLOAD_TYPE, &payload));
+
+        DsseEnvelope {
+            payload_type: IN_TOTO_PAYLOAD_TYPE.to_string(),
+            payload: STANDARD.encode(payload),
+            signatures: vec![DsseSignature {
+                keyid: signer.public_key(),
+                sig: STANDARD.encode(signature),
+            }],
+        }
+    }
+
+    /// Serialize the statement as JSON
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    /// Parse a statement from JSON
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
+
+impl DsseEnvelope {
+    /// Decode the statement without checking signatures
+    pub fn statement(&self) -> Result<InTotoStatement, InTotoError> {
+        if self.payload_type != IN_TOTO_PAYLOAD_TYPE {
+            return Err(InTotoError::UnsupportedType(self.payload_type.clone()));
+        }
+        let payload = STANDARD
+            .decode(&self.payload)
+            .map_err(|e| InTotoError::Parse(e.to_string()))?;
+        serde_json::from_slice(&payload).map_err(|e| InTotoError::Parse(e.to_string()))
+    }
+
+    /// Decode the statement if a signature verifies against a public key
+    pub fn verify(
+        &self,
+        verifier: &dyn AttestationVerifier,
+        public_key: &str,
+    ) -> Result<InTotoStatement, InTotoError> {
+        let payload = STANDARD
+            .decode(&self.payload)
+            .map_err(|e| InTotoError::Parse(e.to_string()))?;
+        let message = pre_authentication_encoding(&self.payload_type, &payload);
+
+        let signed = self.signatures.iter().any(|signature| {
+            STANDARD
+                .decode(&signature.sig)
+                .is_ok_and(|sig| verifier.verify_raw(public_key, &message, &sig))
+        });
+        if !signed {
+            return Err(InTotoError::InvalidSignature);
+        }
+        self.statement()
+    }
+
+    /// Serialize the envelope as JSON
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    /// Parse an envelope from JSON
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
+
+/// DSSE pre-authentication encoding of a payload
+fn pre_authentication_encoding(payload_type: &str, payload: &[u8]) -> Vec<u8> {
+    let mut message = format!(
+        "DSSEv1 {} {} {} ",
+        payload_type.len(),
+        payload_type,
+        payload.len()
+    )
+    .into_bytes();
+    message.extend_from_slice(payload);
+    message
+}
@@ -0,0 +1,194 @@
+//! Schema-agnostic introspection of capability documents for generic UIs
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::capabilities::Capabilities;
+use crate::types::ToolCapability;
+
+/// Labels for keys that do not read well when humanized
+const FIELD_LABELS: &[(&str, &str)] = &[
+    ("id", "ID"),
+    ("cpu_limit_percent", "CPU limit (%)"),
+    ("memory_limit_mb", "Memory limit (MB)"),
+    ("timeout_seconds", "Timeout (seconds)"),
+    ("wasi", "WASI module"),
+];
+
+/// Keys used to label the entries of a list
+const ITEM_LABEL_KEYS: &[&str] = &["tool_name", "name", "id", "identifier", "host", "prefix"];
+
+/// Kind of value a field holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldKind {
+    /// Nested section with named fields
+    Section,
+    /// Ordered list of entries
+    List,
+    /// Boolean value
+    Bool,
+    /// Numeric value
+    Number,
+    /// Text value
+    Text,
+    /// Unset optional value
+    Empty,
+}
+
+/// Typed descriptor of a field and its current value
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldDescriptor {
+    /// JSON pointer of the field within the document
+    pub path: String,
+    /// Field name (or list index)
+    pub key: String,
+    /// Human-readable label
+    pub label: String,
+    /// Kind of value
+    pub kind: FieldKind,
+    /// Current value of scalar fields
+    pub value: Option<Value>,
+    /// Nested fields of sections (sorted by key) and entries of lists
+    pub children: Vec<FieldDescriptor>,
+}
+
+impl FieldDescriptor {
+    /// Describe a value at a JSON pointer
+    fn from_value(path: String, key: String, label: String, value: &Value) -> Self {
+        let (kind, children) = match value {
+            Value::Object(map) => {
+                let children = map
+                    .iter()
+                    .map(|(child, value)| {
+                        Self::from_value(
+                            format!("{}/{}", path, escape_pointer(child)),
+                            child.clone(),
+                            field_label(child),
+                            value,
+                        )
+                    })
+                    .collect();
+                (FieldKind::Section, children)
+            }
+            Value::Array(items) => {
+                let children = items
+                    .iter()
+                    .enumerate()
+                    .map(|(index, value)| {
+                        Self::from_value(
+                            format!("{}/{}", path, index),
+                            index.to_string(),
+                            item_label(index, value),
+                            value,
+                        )
+                    })
+                    .collect();
+                (FieldKind::List, children)
+            }
+            Value::Bool(_) => (FieldKind::Bool, Vec::new()),
+            Value::Number(_) => (FieldKind::Number, Vec::new()),
+            Value::String(_) => (FieldKind::Text, Vec::new()),
+            Value::Null => (FieldKind::Empty, Vec::new()),
+        };
+
+        let value = match kind {
+            FieldKind::Section | FieldKind::List => None,
+            _ => Some(value.clone()),
+        };
+
+        Self {
+            path,
+            key,
+            label,
+            kind,
+            value,
+            children,
+        }
+    }
+
+    /// Describe every top-level field of a serializable document
+    fn fields_of(document: &impl Serialize) -> Vec<FieldDescriptor> {
+        serde_json::to_value(document)
+            .map(|value| {
+                Self::from_value(String::new(), String::new(), String::new(), &value).children
+            })
+            .unwrap_or_default()
+    }
+
+    /// Find a descendant (or this field) by JSON pointer
+    pub fn find(&self, path: &str) -> Option<&FieldDescriptor> {
+        if self.path == path {
+            return Some(self);
+        }
+        self.children.iter().find_map(|child| child.find(path))
+    }
+
+    /// Scalar fields below this one (or this field itself), in key order
+    pub fn leaves(&self) -> Vec<&FieldDescriptor> {
+        if self.children.is_empty() && self.value.is_some() {
+            return vec![self];
+        }
+        self.children
+            .iter()
+            .flat_map(FieldDescriptor::leaves)
+            .collect()
+    }
+
+    /// Current value rendered as plain text (empty for sections and lists)
+    pub fn display_value(&self) -> String {
+        match &self.value {
+            Some(Value::String(text)) => text.clone(),
+            Some(Value::Null) | None => String::new(),
+            Some(value) => value.to_string(),
+        }
+    }
+}
+
+impl Capabilities {
+    /// Descriptors of every section and field of this capability set
+    pub fn fields(&self) -> Vec<FieldDescriptor> {
+        FieldDescriptor::fields_of(self)
+    }
+}
+
+impl ToolCapability {
+    /// Descriptor of this tool and all of its fields, labelled with the tool name
+    pub fn describe(&self) -> FieldDescriptor {
+        FieldDescriptor {
+            path: String::new(),
+            key: self.tool_name.clone(),
+            label: self.tool_name.clone(),
+            kind: FieldKind::Section,
+            value: None,
+            children: FieldDescriptor::fields_of(self),
+        }
+    }
+}
+
+/// Human-readable label for a field name
+pub fn field_label(key: &str) -> String {
+    if let Some((_, label)) = FIELD_LABELS.iter().find(|(name, _)| *name == key) {
+        return label.to_string();
+    }
+
+    let words = key.replace('_', " ");
+    let mut chars = words.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn item_label(index: usize, value: &Value) -> String {
+    ITEM_LABEL_KEYS
+        .iter()
+        .find_map(|key| value.get(key).and_then(Value::as_str))
+        .map(str::to_string)
+        .or_else(|| value.as_str().map(str::to_string))
+        .unwrap_or_else(|| format!("#{}", index + 1))
+}
+
+fn escape_pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
@@ -0,0 +1,273 @@
+//! JWS/JWT attestation format (requires the `jws` feature)
+//!
+//! Attestations can be issued as compact JWS tokens whose claims carry the
+//! capability hash (`cap_hash`), the attester (`iss`), the signing time
+//! (`iat`) and the expiry (`exp`). A token-backed attestation keeps the token
+//! in its `signature` field and records the algorithm as `JWS/<alg>`, so it
+//! travels and verifies like any other attestation once a `JwsVerifier` is
+//! registered for that algorithm. Tokens issued by an external identity
+//! provider are checked with `verify_jws` or imported with
+//! `CapabilityAttestation::from_jws`. With the `ed25519` feature, `JWS/EdDSA`
+//! is one of the built-in algorithms.
+//!
+//! ```rust
+//! use worker_capabilities::{
+//!     verify_jws, AttestationSigner, CapabilityAttestation, Ed25519Signer, Ed25519Verifier,
+//!     JwsVerifier, ToolCapability,
+//! };
+//!
+//! let signer = Ed25519Signer::new([7; 32], "ci");
+//! let tool = ToolCapability::new("clippy", true);
+//! let attestation = CapabilityAttestation::issue_jws(&signer, &tool.generate_capability_hash());
+//! assert_eq!(attestation.algorithm, "JWS/EdDSA");
+//!
+//! // The token verifies on its own...
+//! let token = attestation.to_jws().unwrap();
+//! let claims = verify_jws(token, &signer.public_key(), &Ed25519Verifier).unwrap();
+//! assert_eq!(claims.iss, "ci");
+//!
+//! // ...and as the tool's attestation
+//! let tool = tool.with_attestation(attestation);
+//! assert!(tool.verify_attestation_with(&JwsVerifier::new(Ed25519Verifier)));
+//! ```
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::clock;
+use crate::clock::current_timestamp;
+use crate::constants::*;
+use crate::signing::{AttestationSigner, AttestationVerifier};
+use crate::types::CapabilityAttestation;
+
+/// Header of a capability attestation token
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JwsHeader {
+    /// JWS algorithm (e.g., `EdDSA`, `ES256`, `RS256`)
+    pub alg: String,
+    /// Token type
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub typ: Option<String>,
+    /// Identifier of the signing key
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kid: Option<String>,
+}
+
+/// Claims of a capability attestation token
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JwsClaims {
+    /// Attester identity
+    pub iss: String,
+    /// Attested capability hash
+    pub cap_hash: String,
+    /// Timestamp when the token was issued
+    pub iat: u64,
+    /// Timestamp when the token expires
+    pub exp: u64,
+}
+
+/// Errors from parsing or verifying an attestation token
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JwsError {
+    /// The token is not a valid compact JWS with attestation claims
+    Malformed(String),
+    /// No verifier supports the token's algorithm
+    UnsupportedAlgorithm(String),
+    /// The signature does not verify against the public key
+    InvalidSignature,
+    /// The token is past its expiry (or the clock cannot be read)
+    Expired,
+}
+
+impl fmt::Display for JwsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JwsError::Malformed(reason) => write!(f, "malformed attestation token: {}", reason),
+            JwsError::UnsupportedAlgorithm(alg) => {
+                write!(f, "unsupported token algorithm '{}'", alg)
+            }
+            JwsError::InvalidSignature => write!(f, "attestation token signature is invalid"),
+            JwsError::Expired => write!(f, "attestation token has expired"),
+        }
+    }
+}
+
+impl std::error::Error for JwsError {}
+
+impl CapabilityAttestation {
+    /// Attest a capability hash as a JWS token signed by a signer
+    pub fn issue_jws(signer: &dyn AttestationSigner, capability_hash: &str) -> Self {
+        let timestamp = current_timestamp();
+        let header = JwsHeader {
+            alg: jws_algorithm(signer.algorithm()).to_string(),
+            typ: Some("JWT".to_string()),
+            kid: None,
+        };
+        let claims = JwsClaims {
+            iss: signer.attester().to_string(),
+            cap_hash: capability_hash.to_string(),
+            iat: timestamp,
+            exp: timestamp.saturating_add(ATTESTATION_EXPIRY_DAYS * 24 * 60 * 60),
+        };
+
+        let signing_input = format!("{}.{}", encode_part(&header), encode_part(&claims));
+        let signature = URL_SAFE_NO_PAD.encode(signer.sign_raw(signing_input.as_bytes()));
+
+        Self {
+            capability_hash: claims.cap_hash,
+            signature: format!("{}.{}", signing_input, signature),
+            public_key: signer.public_key(),
+            timestamp,
+            algorithm: format!("{}{}", JWS_ALGORITHM_PREFIX, header.alg),
+            attester: claims.iss,
+            nonce: None,
+            counter: None,
+        }
+    }
+
+    /// Import a token signed with a public key as an attestation
+    ///
+    /// The token is parsed but not verified; verify the attestation with a
+    /// `JwsVerifier` (or call `verify_jws` first).
+    pub fn from_jws(token: &str, public_key: impl Into<String>) -> Result<Self, JwsError> {
+        let (header, claims, _, _) = parse(token)?;
+        Ok(Self {
+            capability_hash: claims.cap_hash,
+            signature: token.to_string(),
+            public_key: public_key.into(),
+            timestamp: claims.iat,
+            algorithm: format!("{}{}", JWS_ALGORITHM_PREFIX, header.alg),
+            attester: claims.iss,
+            nonce: None,
+            counter: None,
+        })
+    }
+
+    /// The JWS token of a token-backed attestation
+    pub fn to_jws(&self) -> Option<&str> {
+        self.algorithm
+            .starts_with(JWS_ALGORITHM_PREFIX)
+            .then_some(self.signature.as_str())
+    }
+}
+
+/// Verify a token against a public key and return its claims
+///
+/// The verifier checks the raw signature for the token's algorithm, named
+/// as the crate does (`Ed25519` for `EdDSA` tokens).
+pub fn verify_jws(
+    token: &str,
+    public_key: &str,
+    verifier: &dyn AttestationVerifier,
+) -> Result<JwsClaims, JwsError> {
+    let (header, claims, signing_input, signature) = parse(token)?;
+
+    let algorithm = crate_algorithm(&header.alg);
+    if !verifier.supports(algorithm) {
+        return Err(JwsError::UnsupportedAlgorithm(header.alg));
+    }
+    if !verifier.verify_raw_as(algorithm, public_key, signing_input.as_bytes(), &signature) {
+        return Err(JwsError::InvalidSignature);
+    }
+    match clock::unix_timestamp() {
+        Ok(now) if now < claims.exp => Ok(claims),
+        _ => Err(JwsError::Expired),
+    }
+}
+
+/// Verifier for token-backed attestations
+///
+/// Register it under `JWS/<alg>` with a verifier for the underlying
+/// algorithm. The token claims must match the attestation fields.
+#[derive(Debug, Clone, Default)]
+pub struct JwsVerifier<V> {
+    inner: V,
+}
+
+impl<V: AttestationVerifier> JwsVerifier<V> {
+    /// Verify tokens whose raw signatures `inner` checks
+    pub fn new(inner: V) -> Self {
+        Self { inner }
+    }
+}
+
+impl<V: AttestationVerifier> AttestationVerifier for JwsVerifier<V> {
+    fn supports(&self, algorithm: &str) -> bool {
+        algorithm
+            .strip_prefix(JWS_ALGORITHM_PREFIX)
+            .is_some_and(|alg| self.inner.supports(crate_algorithm(alg)))
+    }
+
+    fn verify(&self, public_key: &str, message: &[u8], signature: &str) -> bool {
+        self.inner.verify(public_key, message, signature)
+    }
+
+    fn verify_attestation(&self, attestation: &CapabilityAttestation) -> bool {
+        // The token's own algorithm must be the one the attestation declares
+        let declared = attestation.algorithm.strip_prefix(JWS_ALGORITHM_PREFIX);
+        if !self.supports(&attestation.algorithm)
+            || !parse(&attestation.signature)
+                .is_ok_and(|(header, ..)| declared == Some(&header.alg))
+        {
+            return false;
+        }
+        verify_jws(&attestation.signature, &attestation.public_key, &self.inner).is_ok_and(
+            |claims| {
+                claims.cap_hash == attestation.capability_hash
+                    && claims.iat == attestation.timestamp
+                    && claims.iss == attestation.attester
+            },
+        )
+    }
+}
+
+/// JWS name of a crate algorithm
+fn jws_algorithm(algorithm: &str) -> &str {
+    match algorithm {
+        ED25519_ATTESTATION_ALGORITHM => "EdDSA",
+        other => other,
+    }
+}
+
+/// Crate name of a JWS algorithm
+fn crate_algorithm(alg: &str) -> &str {
+    match alg {
+        "EdDSA" => ED25519_ATTESTATION_ALGORITHM,
+        other => other,
+    }
+}
+
+fn encode_part(value: &impl Serialize) -> String {
+    URL_SAFE_NO_PAD.encode(serde_json::to_vec(value).unwrap_or_default())
+}
+
+/// Split a compact token into header, claims, signing input and raw signature
+fn parse(token: &str) -> Result<(JwsHeader, JwsClaims, String, Vec<u8>), JwsError> {
+    let malformed = |reason: &str| JwsError::Malformed(reason.to_string());
+
+    let mut parts = token.split('.');
+    let (Some(header), Some(claims), Some(signature), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(malformed("expected three dot-separated parts"));
+    };
+
+    let decode = |part: &str| {
+        URL_SAFE_NO_PAD
+            .decode(part)
+            .map_err(|e| JwsError::Malformed(e.to_string()))
+    };
+    let header_json: JwsHeader =
+        serde_json::from_slice(&decode(header)?).map_err(|e| JwsError::Malformed(e.to_string()))?;
+    let claims_json: JwsClaims =
+        serde_json::from_slice(&decode(claims)?).map_err(|e| JwsError::Malformed(e.to_string()))?;
+
+    Ok((
+        header_json,
+        claims_json,
+        format!("{}.{}", header, claims),
+        decode(signature)?,
+    ))
+}
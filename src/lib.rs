@@ -12,16 +12,28 @@
 //! - **Type-Safe**: Strongly typed capability definitions
 //! - **Security Features**: Attestation, expiration, revocation, permissions
 //!
+//! ## Module Layout
+//!
+//! Each type is defined in exactly one module and re-exported from the crate
+//! root, so `worker_capabilities::Capabilities` and
+//! `worker_capabilities::capabilities::Capabilities` are the same type. The
+//! [`prelude`] collects the types most callers need. Root re-exports and the
+//! prelude follow semver: removals and renames only happen in major releases.
+//! Well-known flag and metadata names live in [`well_known`], which is not
+//! re-exported so its short constant names stay namespaced. The flat 0.1
+//! types live in [`v1`], with `From` conversions into the current model.
+//! Ready-made capability sets for common ecosystems live in [`profiles`].
+//!
 //! ## Quick Start
 //!
 //! ```rust
-//! use worker_capabilities::{Capabilities, ToolCapability};
+//! use worker_capabilities::prelude::*;
 //!
 //! # fn main() {
 //! // Define capabilities for a worker
 //! let caps = Capabilities::new("rust-analyzer")
-//!     .with_tool("clippy", true)          // Required tool
-//!     .with_tool("cargo-audit", false)    // Optional tool
+//!     .with_static_analysis("clippy", true)       // Required tool
+//!     .with_static_analysis("cargo-audit", false) // Optional tool
 //!     .with_alternative("rustfmt", vec!["rustfmt", "cargo-fmt"])
 //!     .with_flag("ast_support")
 //!     .with_flag("llm_support");
@@ -39,22 +51,159 @@
 //! ```
 
 pub mod attestation;
+pub mod builder;
+pub mod bundle;
 pub mod capabilities;
+pub mod chain;
+#[cfg(feature = "chaos")]
+pub mod chaos;
+pub mod checker;
+pub mod ci_agents;
+pub mod clock;
 pub mod constants;
+pub mod container;
+pub mod defaults;
+pub mod derive;
+pub mod descriptor;
+pub mod diff;
+pub mod discover;
+pub mod display;
+pub mod drift;
+#[cfg(feature = "ed25519")]
+pub mod ed25519;
+pub mod environment;
+pub mod equality;
+pub mod error;
+pub mod explain;
+pub mod expr;
+pub mod formats;
+pub mod grants;
+pub mod health;
+#[cfg(feature = "http-checker")]
+pub mod http_checker;
+#[cfg(feature = "in-toto")]
+pub mod in_toto;
+pub mod introspect;
+#[cfg(feature = "jws")]
+pub mod jws;
+pub mod limits;
+pub mod match_cache;
+pub mod merge;
+pub mod metrics;
+pub mod models;
+pub mod network;
+pub mod nomad;
+#[cfg(feature = "parallel")]
+pub mod parallel;
+pub mod payload;
+pub mod pinning;
+pub mod platform;
+pub mod prelude;
+pub mod priming;
+#[cfg(feature = "probe")]
+pub mod probe;
+pub mod profiles;
+pub mod protocol;
 pub mod registry;
+pub mod requirements;
+pub mod retry;
+pub mod revocation;
+pub mod revocation_checker;
+pub mod sandbox;
+pub mod sarif;
+#[cfg(feature = "tokio")]
+pub mod scheduler;
+pub mod scope;
+pub mod session;
+pub mod shedding;
+pub mod signing;
+#[cfg(feature = "sigstore")]
+pub mod sigstore;
+pub mod subset;
+pub mod tags;
+pub mod telemetry;
+pub mod template;
+pub mod trust;
 pub mod types;
+pub mod v1;
+pub mod validation;
+pub mod version;
+pub mod view;
+pub mod wasi;
+pub mod well_known;
+#[cfg(feature = "x509")]
+pub mod x509;
 
 // Re-export main types and functions
 pub use attestation::*;
+pub use builder::*;
+pub use bundle::*;
 pub use capabilities::*;
+#[cfg(feature = "chaos")]
+pub use chaos::*;
+pub use checker::*;
+pub use ci_agents::*;
 pub use constants::*;
+pub use container::*;
+pub use defaults::*;
+pub use derive::*;
+pub use descriptor::*;
+pub use diff::*;
+pub use discover::*;
+pub use display::*;
+pub use drift::*;
+#[cfg(feature = "ed25519")]
+pub use ed25519::*;
+pub use environment::*;
+pub use equality::*;
+pub use error::*;
+pub use explain::*;
+pub use expr::*;
+pub use formats::*;
+pub use grants::*;
+pub use health::*;
+#[cfg(feature = "http-checker")]
+pub use http_checker::*;
+#[cfg(feature = "in-toto")]
+pub use in_toto::*;
+pub use introspect::*;
+#[cfg(feature = "jws")]
+pub use jws::*;
+pub use limits::*;
+pub use match_cache::*;
+pub use merge::*;
+pub use metrics::*;
+pub use models::*;
+pub use network::*;
+pub use nomad::*;
+pub use payload::*;
+pub use pinning::*;
+pub use platform::*;
+pub use priming::*;
+#[cfg(feature = "probe")]
+pub use probe::*;
+pub use protocol::*;
 pub use registry::*;
+pub use requirements::*;
+pub use retry::*;
+pub use revocation::*;
+pub use revocation_checker::*;
+pub use sandbox::*;
+pub use sarif::*;
+#[cfg(feature = "tokio")]
+pub use scheduler::*;
+pub use scope::*;
+pub use session::*;
+pub use shedding::*;
+pub use signing::*;
+#[cfg(feature = "sigstore")]
+pub use sigstore::*;
+pub use telemetry::*;
+pub use trust::*;
 pub use types::*;
-
-// Re-export commonly used types for convenience
-pub use capabilities::{Capabilities, CapabilityStatistics};
-pub use registry::{CapabilityRegistry, RegistryStatistics};
-pub use types::{
-    ToolCapability, CapabilityAttestation, CapabilityPermissions, 
-    CapabilityExpiration, CapabilitySecurityReport
-};
\ No newline at end of file
+pub use validation::*;
+pub use version::*;
+pub use view::*;
+pub use wasi::*;
+#[cfg(feature = "x509")]
+pub use x509::*;
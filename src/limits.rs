@@ -0,0 +1,326 @@
+//! Enforcement of declared resource limits on spawned tool processes
+//!
+//! On Linux with cgroups v2, `CapabilityPermissions::apply_limits` moves the
+//! child into a dedicated cgroup with `memory.max` and `cpu.max` set from the
+//! declared limits. Elsewhere (or when the cgroup hierarchy is not writable)
+//! limits are applied best-effort: the timeout is still enforced and, on
+//! Linux, peak memory is sampled from `/proc`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Child;
+use std::time::{Duration, Instant};
+
+use crate::constants::*;
+use crate::types::CapabilityPermissions;
+
+/// Mechanism used to enforce resource limits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EnforcementBackend {
+    /// Limits enforced by the kernel through a cgroups v2 group
+    CgroupV2,
+    /// Only the timeout is enforced; memory is sampled where possible
+    BestEffort,
+}
+
+/// A declared limit that the process exceeded
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LimitViolation {
+    /// The process ran past its timeout
+    Timeout {
+        limit_seconds: u64,
+        elapsed_seconds: u64,
+    },
+    /// The process hit its memory limit
+    Memory {
+        limit_mb: u64,
+        peak_mb: Option<u64>,
+        oom_kills: u64,
+    },
+    /// The process was throttled to stay within its CPU limit
+    CpuThrottled {
+        limit_percent: u8,
+        throttled_periods: u64,
+    },
+}
+
+/// Declared limits that cannot be applied
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LimitError {
+    /// The memory limit in bytes overflows the platform's limit type
+    MemoryLimitTooLarge { limit_mb: u64 },
+}
+
+impl fmt::Display for LimitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LimitError::MemoryLimitTooLarge { limit_mb } => {
+                write!(f, "memory limit of {} MB is too large", limit_mb)
+            }
+        }
+    }
+}
+
+impl std::error::Error for LimitError {}
+
+/// Guard over a child process with resource limits applied
+///
+/// The cgroup (if any) is removed when the guard is dropped.
+#[derive(Debug)]
+pub struct ResourceLimitGuard {
+    pid: u32,
+    backend: EnforcementBackend,
+    cgroup_path: Option<PathBuf>,
+    started_at: Instant,
+    memory_limit_mb: u64,
+    cpu_limit_percent: u8,
+    timeout_seconds: u64,
+}
+
+impl CapabilityPermissions {
+    /// Declared memory limit in bytes
+    pub fn memory_limit_bytes(&self) -> Result<u64, LimitError> {
+        self.memory_limit_mb
+            .checked_mul(1024 * 1024)
+            .ok_or(LimitError::MemoryLimitTooLarge {
+                limit_mb: self.memory_limit_mb,
+            })
+    }
+
+    /// Apply the declared memory, CPU and timeout limits to a spawned child
+    pub fn apply_limits(&self, child: &Child) -> Result<ResourceLimitGuard, LimitError> {
+        self.apply_limits_in(child, Path::new(DEFAULT_CGROUP_ROOT))
+    }
+
+    /// Apply the declared limits using a specific cgroups v2 mount point
+    ///
+    /// Fails without touching the child if a limit cannot be represented.
+    pub fn apply_limits_in(
+        &self,
+        child: &Child,
+        cgroup_root: &Path,
+    ) -> Result<ResourceLimitGuard, LimitError> {
+        let memory_limit_bytes = self.memory_limit_bytes()?;
+        let pid = child.id();
+        let cgroup_path = create_cgroup(cgroup_root, pid, memory_limit_bytes, self).ok();
+
+        Ok(ResourceLimitGuard {
+            pid,
+            backend: if cgroup_path.is_some() {
+                EnforcementBackend::CgroupV2
+            } else {
+                EnforcementBackend::BestEffort
+            },
+            cgroup_path,
+            started_at: Instant::now(),
+            memory_limit_mb: self.memory_limit_mb,
+            cpu_limit_percent: self.cpu_limit_percent,
+            timeout_seconds: self.timeout_seconds,
+        })
+    }
+}
+
+impl ResourceLimitGuard {
+    /// Process id of the guarded child
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    /// Mechanism enforcing the limits
+    pub fn backend(&self) -> EnforcementBackend {
+        self.backend
+    }
+
+    /// Path of the cgroup the child was moved into (if any)
+    pub fn cgroup_path(&self) -> Option<&Path> {
+        self.cgroup_path.as_deref()
+    }
+
+    /// Time since the limits were applied
+    pub fn elapsed(&self) -> Duration {
+        self.started_at.elapsed()
+    }
+
+    /// Check if the timeout has passed
+    pub fn is_timed_out(&self) -> bool {
+        self.elapsed() > Duration::from_secs(self.timeout_seconds)
+    }
+
+    /// Kill the child if it ran past its timeout
+    ///
+    /// Returns true if the child was killed.
+    pub fn enforce_timeout(&self, child: &mut Child) -> io::Result<bool> {
+        if !self.is_timed_out() || child.try_wait()?.is_some() {
+            return Ok(false);
+        }
+        child.kill()?;
+        Ok(true)
+    }
+
+    /// Wait for the child to exit, killing it once the timeout passes
+    pub fn wait(
+        &self,
+        child: &mut Child,
+        poll_interval: Duration,
+    ) -> io::Result<Vec<LimitViolation>> {
+        while child.try_wait()?.is_none() {
+            if self.enforce_timeout(child)? {
+                child.wait()?;
+                break;
+            }
+            std::thread::sleep(poll_interval);
+        }
+        Ok(self.violations())
+    }
+
+    /// Peak memory usage of the child in MB (if observable)
+    pub fn peak_memory_mb(&self) -> Option<u64> {
+        match &self.cgroup_path {
+            Some(path) => read_trimmed(&path.join("memory.peak"))
+                .and_then(|value| value.parse::<u64>().ok())
+                .map(|bytes| bytes / (1024 * 1024)),
+            None => {
+                read_trimmed(Path::new(&format!("/proc/{}/status", self.pid))).and_then(|status| {
+                    status
+                        .lines()
+                        .find_map(|line| line.strip_prefix("VmHWM:"))
+                        .and_then(|kb| kb.trim().trim_end_matches("kB").trim().parse::<u64>().ok())
+                        .map(|kb| kb / 1024)
+                })
+            }
+        }
+    }
+
+    /// Limits the child has exceeded so far
+    pub fn violations(&self) -> Vec<LimitViolation> {
+        let mut violations = Vec::new();
+
+        if self.is_timed_out() {
+            violations.push(LimitViolation::Timeout {
+                limit_seconds: self.timeout_seconds,
+                elapsed_seconds: self.elapsed().as_secs(),
+            });
+        }
+
+        let peak_mb = self.peak_memory_mb();
+        let (memory_events, oom_kills) = match &self.cgroup_path {
+            Some(path) => {
+                let events = read_key_values(&path.join("memory.events"));
+                (
+                    events.get("max").copied().unwrap_or(0),
+                    events.get("oom_kill").copied().unwrap_or(0),
+                )
+            }
+            None => (0, 0),
+        };
+        let over_limit = peak_mb.is_some_and(|peak| peak > self.memory_limit_mb);
+        if memory_events > 0 || oom_kills > 0 || over_limit {
+            violations.push(LimitViolation::Memory {
+                limit_mb: self.memory_limit_mb,
+                peak_mb,
+                oom_kills,
+            });
+        }
+
+        if let Some(path) = &self.cgroup_path {
+            let throttled_periods = read_key_values(&path.join("cpu.stat"))
+                .get("nr_throttled")
+                .copied()
+                .unwrap_or(0);
+            if throttled_periods > 0 {
+                violations.push(LimitViolation::CpuThrottled {
+                    limit_percent: self.cpu_limit_percent,
+                    throttled_periods,
+                });
+            }
+        }
+
+        violations
+    }
+}
+
+impl Drop for ResourceLimitGuard {
+    fn drop(&mut self) {
+        // Fails while processes remain in the group; the kernel keeps it until then
+        if let Some(path) = &self.cgroup_path {
+            let _ = fs::remove_dir(path);
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn create_cgroup(
+    root: &Path,
+    pid: u32,
+    memory_limit_bytes: u64,
+    permissions: &CapabilityPermissions,
+) -> io::Result<PathBuf> {
+    if !root.join("cgroup.controllers").exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "cgroups v2 hierarchy not mounted",
+        ));
+    }
+
+    let path = root.join(format!("{}-{}", CGROUP_NAME_PREFIX, pid));
+    fs::create_dir(&path)?;
+
+    let setup = || -> io::Result<()> {
+        if permissions.memory_limit_mb > 0 {
+            fs::write(path.join("memory.max"), memory_limit_bytes.to_string())?;
+        }
+        if permissions.cpu_limit_percent > 0 {
+            let quota = CGROUP_CPU_PERIOD_MICROS * permissions.cpu_limit_percent as u64 / 100;
+            fs::write(
+                path.join("cpu.max"),
+                format!("{} {}", quota, CGROUP_CPU_PERIOD_MICROS),
+            )?;
+        }
+        fs::write(path.join("cgroup.procs"), pid.to_string())
+    };
+
+    if let Err(err) = setup() {
+        let _ = fs::remove_dir(&path);
+        return Err(err);
+    }
+    Ok(path)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn create_cgroup(
+    _root: &Path,
+    _pid: u32,
+    _memory_limit_bytes: u64,
+    _permissions: &CapabilityPermissions,
+) -> io::Result<PathBuf> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "cgroups are only available on Linux",
+    ))
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path)
+        .ok()
+        .map(|content| content.trim().to_string())
+}
+
+fn read_key_values(path: &Path) -> HashMap<String, u64> {
+    read_trimmed(path)
+        .map(|content| {
+            content
+                .lines()
+                .filter_map(|line| {
+                    let (key, value) = line.split_once(' ')?;
+                    Some((key.to_string(), value.trim().parse().ok()?))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
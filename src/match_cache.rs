@@ -0,0 +1,180 @@
+//! Cache of match results for repeated requirement sets
+//!
+//! Schedulers tend to issue the same requirements over and over. A
+//! `MatchCache` attached to a registry stores ranked results keyed by the
+//! requirements digest and the registry revision, so any registry change
+//! invalidates them. Entries also expire after a TTL, because retry budgets
+//! refill and warm assets age without a change event. Load shedding
+//! (`CapabilityRegistry::find_with_capability_shed`) stores its results in
+//! the same cache and may serve them past the TTL.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+use crate::clock::current_timestamp;
+use crate::constants::*;
+use crate::descriptor::canonicalize;
+use crate::requirements::Requirements;
+
+/// Hit-rate metrics of a match cache
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MatchCacheStats {
+    /// Lookups answered from the cache
+    pub hits: u64,
+    /// Lookups that had to be computed
+    pub misses: u64,
+    /// Times the cache was invalidated by a registry change
+    pub invalidations: u64,
+    /// Cached requirement sets
+    pub entries: usize,
+}
+
+impl MatchCacheStats {
+    /// Fraction of lookups answered from the cache (0.0 without lookups)
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+}
+
+#[derive(Debug)]
+struct CachedMatch {
+    revision: u64,
+    cached_at: u64,
+    worker_ids: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+struct MatchCacheState {
+    entries: HashMap<String, CachedMatch>,
+    stats: MatchCacheStats,
+}
+
+/// Match results keyed by (requirements digest, registry revision)
+#[derive(Debug)]
+pub struct MatchCache {
+    capacity: usize,
+    ttl_seconds: u64,
+    state: Mutex<MatchCacheState>,
+}
+
+impl Default for MatchCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_MATCH_RESULT_CACHE_CAPACITY)
+    }
+}
+
+impl MatchCache {
+    /// Create a cache holding at most `capacity` requirement sets
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            ttl_seconds: DEFAULT_MATCH_RESULT_CACHE_TTL_SECONDS,
+            state: Mutex::new(MatchCacheState::default()),
+        }
+    }
+
+    /// Expire entries after `seconds` even without a registry change
+    pub fn with_ttl(mut self, seconds: u64) -> Self {
+        self.ttl_seconds = seconds;
+        self
+    }
+
+    /// Cached result for a requirements digest at a registry revision
+    pub fn get(&self, digest: &str, revision: u64) -> Option<Vec<String>> {
+        self.get_within(digest, revision, Some(self.ttl_seconds))
+            .map(|(_, worker_ids)| worker_ids)
+    }
+
+    /// Cached result and its age in seconds, if no older than `max_age_seconds`
+    ///
+    /// Without a maximum age, results of any age at the revision are returned.
+    pub(crate) fn get_within(
+        &self,
+        key: &str,
+        revision: u64,
+        max_age_seconds: Option<u64>,
+    ) -> Option<(u64, Vec<String>)> {
+        let now = current_timestamp();
+        let mut state = self.state();
+        let cached = state
+            .entries
+            .get(key)
+            .map(|entry| (now.saturating_sub(entry.cached_at), entry))
+            .filter(|(age, entry)| {
+                entry.revision == revision && max_age_seconds.is_none_or(|max| *age <= max)
+            })
+            .map(|(age, entry)| (age, entry.worker_ids.clone()));
+
+        if cached.is_some() {
+            state.stats.hits += 1;
+        } else {
+            state.stats.misses += 1;
+        }
+        cached
+    }
+
+    /// Store the result for a requirements digest at a registry revision
+    ///
+    /// When the cache is full, entries from older revisions are dropped
+    /// first, then the whole cache.
+    pub fn insert(&self, digest: impl Into<String>, revision: u64, worker_ids: Vec<String>) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut state = self.state();
+        let digest = digest.into();
+        if state.entries.len() >= self.capacity && !state.entries.contains_key(&digest) {
+            state.entries.retain(|_, entry| entry.revision == revision);
+            if state.entries.len() >= self.capacity {
+                state.entries.clear();
+            }
+        }
+        state.entries.insert(
+            digest,
+            CachedMatch {
+                revision,
+                cached_at: current_timestamp(),
+                worker_ids,
+            },
+        );
+    }
+
+    /// Drop every cached result
+    pub fn invalidate(&self) {
+        let mut state = self.state();
+        state.entries.clear();
+        state.stats.invalidations += 1;
+    }
+
+    /// Current hit-rate metrics
+    pub fn stats(&self) -> MatchCacheStats {
+        let state = self.state();
+        MatchCacheStats {
+            entries: state.entries.len(),
+            ..state.stats
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, MatchCacheState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Requirements {
+    /// SHA256 digest of the requirements, independent of field order
+    pub fn digest(&self) -> String {
+        let canonical = serde_json::to_value(self)
+            .map(|value| canonicalize(value).to_string())
+            .unwrap_or_default();
+        let mut hasher = Sha256::new();
+        hasher.update(canonical.as_bytes());
+        format!("{:x}", hasher.finalize())
+    }
+}
@@ -0,0 +1,320 @@
+//! Merging capability sets composed from a base and overlays
+//!
+//! `Capabilities::merge` folds an overlay into a base set:
+//!
+//! - Tools are unioned per category. A tool declared in both is required if
+//!   either requires it, keeps the alternatives and tags of both, gets the
+//!   stricter of the two permission sets and the earlier expiration, and
+//!   takes the overlay's version. Its attestation is dropped if it no longer
+//!   covers the merged tool. Preference weights and deprecations from the
+//!   overlay override.
+//! - The set attestation of the base is dropped if it no longer covers the
+//!   merged set; the overlay's is never taken.
+//! - Flags and metadata from the overlay override the base, as does the
+//!   metadata of tools declared in both.
+//! - Accepted and emitted formats and worker tags are unioned.
+//! - Other sections (network, sandbox, payload, models, warm assets, scope,
+//!   environment) are kept from the base.
+//!
+//! Every rule that changes or discards a value is listed in the `MergeReport`.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::capabilities::Capabilities;
+use crate::tags::add_tag;
+use crate::types::{AccessMode, CapabilityPermissions, PathRule, ToolCapability};
+
+/// Conflict resolved while merging two capability sets
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum MergeConflict {
+    /// A tool declared in both sets with different permissions got the stricter ones
+    PermissionsNarrowed { category: String, tool: String },
+    /// A tool declared in both sets with different versions took the overlay's
+    VersionOverridden {
+        category: String,
+        tool: String,
+        previous: String,
+        current: String,
+    },
+    /// The attestation of a tool no longer covered the merged tool and was dropped
+    AttestationDropped { category: String, tool: String },
+    /// The set attestation no longer covered the merged set and was dropped
+    SetAttestationDropped,
+    /// A flag was overridden by the overlay
+    FlagOverridden {
+        flag: String,
+        previous: bool,
+        current: bool,
+    },
+    /// A metadata value was overridden by the overlay
+    MetadataOverridden {
+        key: String,
+        previous: String,
+        current: String,
+    },
+    /// A metadata value of a tool declared in both sets was overridden by the overlay
+    ToolMetadataOverridden {
+        category: String,
+        tool: String,
+        key: String,
+        previous: String,
+        current: String,
+    },
+}
+
+/// Outcome of merging two capability sets
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MergeReport {
+    /// Tools added from the overlay as (category, tool name)
+    pub added_tools: Vec<(String, String)>,
+    /// Conflicts and how they were resolved
+    pub conflicts: Vec<MergeConflict>,
+}
+
+impl MergeReport {
+    /// Check if the merge resolved no conflicts
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+}
+
+impl Capabilities {
+    /// Merge an overlay into this capability set (see the module docs for the rules)
+    pub fn merge(&mut self, other: &Capabilities) -> MergeReport {
+        let mut report = MergeReport::default();
+
+        for category in other.category_names() {
+            for overlay in other.tools_in_category(&category) {
+                let tools = match self.tools_in_category_mut(&category) {
+                    Some(tools) => tools,
+                    None => self.custom_tools.entry(category.clone()).or_default(),
+                };
+                match tools
+                    .iter_mut()
+                    .find(|tool| tool.tool_name == overlay.tool_name)
+                {
+                    Some(tool) => merge_tool(&category, tool, overlay, &mut report),
+                    None => {
+                        tools.push(overlay.clone());
+                        report
+                            .added_tools
+                            .push((category.clone(), overlay.tool_name.clone()));
+                    }
+                }
+            }
+        }
+
+        let mut flags: Vec<_> = other.flags.iter().collect();
+        flags.sort();
+        for (flag, &current) in flags {
+            if let Some(previous) = self.flags.insert(flag.clone(), current) {
+                if previous != current {
+                    report.conflicts.push(MergeConflict::FlagOverridden {
+                        flag: flag.clone(),
+                        previous,
+                        current,
+                    });
+                }
+            }
+        }
+
+        let mut metadata: Vec<_> = other.metadata.iter().collect();
+        metadata.sort();
+        for (key, current) in metadata {
+            if let Some(previous) = self.metadata.insert(key.clone(), current.clone()) {
+                if previous != *current {
+                    report.conflicts.push(MergeConflict::MetadataOverridden {
+                        key: key.clone(),
+                        previous,
+                        current: current.clone(),
+                    });
+                }
+            }
+        }
+
+        for format in &other.accepts {
+            if !self.accepts.contains(format) {
+                self.accepts.push(format.clone());
+            }
+        }
+        for format in &other.emits {
+            if !self.emits.contains(format) {
+                self.emits.push(format.clone());
+            }
+        }
+        for tag in &other.tags {
+            add_tag(&mut self.tags, tag.clone());
+        }
+
+        let hash = self.canonical_hash().ok();
+        if self
+            .attestation
+            .as_ref()
+            .is_some_and(|attestation| hash.as_ref() != Some(&attestation.capability_hash))
+        {
+            self.attestation = None;
+            report.conflicts.push(MergeConflict::SetAttestationDropped);
+        }
+
+        report
+    }
+}
+
+/// Merge an overlay declaration into a tool declared in both sets
+fn merge_tool(
+    category: &str,
+    tool: &mut ToolCapability,
+    overlay: &ToolCapability,
+    report: &mut MergeReport,
+) {
+    tool.required |= overlay.required;
+    for alternative in &overlay.alternatives {
+        if !tool.alternatives.contains(alternative) {
+            tool.alternatives.push(alternative.clone());
+        }
+    }
+    for tag in &overlay.tags {
+        add_tag(&mut tool.tags, tag.clone());
+    }
+    tool.preferences.extend(overlay.preferences.clone());
+    if overlay.deprecated.is_some() {
+        tool.deprecated = overlay.deprecated.clone();
+    }
+
+    let mut metadata: Vec<_> = overlay.metadata.iter().collect();
+    metadata.sort();
+    for (key, current) in metadata {
+        if let Some(previous) = tool.metadata.insert(key.clone(), current.clone()) {
+            if previous != *current {
+                report
+                    .conflicts
+                    .push(MergeConflict::ToolMetadataOverridden {
+                        category: category.to_string(),
+                        tool: tool.tool_name.clone(),
+                        key: key.clone(),
+                        previous,
+                        current: current.clone(),
+                    });
+            }
+        }
+    }
+
+    if tool.permissions != overlay.permissions {
+        report.conflicts.push(MergeConflict::PermissionsNarrowed {
+            category: category.to_string(),
+            tool: tool.tool_name.clone(),
+        });
+        tool.permissions = tool.permissions.intersect(&overlay.permissions);
+    }
+
+    if let Some(current) = &overlay.version {
+        if let Some(previous) = tool.version.as_ref().filter(|v| *v != current) {
+            report.conflicts.push(MergeConflict::VersionOverridden {
+                category: category.to_string(),
+                tool: tool.tool_name.clone(),
+                previous: previous.clone(),
+                current: current.clone(),
+            });
+        }
+        tool.version = Some(current.clone());
+    }
+
+    let expires_at = tool
+        .expiration
+        .expires_at
+        .min(overlay.expiration.expires_at);
+    if overlay.expiration.revoked && !tool.expiration.revoked {
+        tool.expiration = overlay.expiration.clone();
+    }
+    tool.expiration.expires_at = expires_at;
+
+    let hash = tool.generate_capability_hash();
+    if tool
+        .attestation
+        .as_ref()
+        .is_some_and(|attestation| attestation.capability_hash != hash)
+    {
+        tool.attestation = None;
+        tool.countersignatures.clear();
+        tool.verified = false;
+        report.conflicts.push(MergeConflict::AttestationDropped {
+            category: category.to_string(),
+            tool: tool.tool_name.clone(),
+        });
+    }
+}
+
+impl CapabilityPermissions {
+    /// Permissions allowed by both this and another permission set
+    ///
+    /// Access flags must be granted by both, limits take the lower value, and
+    /// path and egress policies keep only what both allow. Access whose
+    /// policies have nothing in common is revoked rather than left unrestricted.
+    pub fn intersect(&self, other: &CapabilityPermissions) -> CapabilityPermissions {
+        let mut permissions = CapabilityPermissions {
+            filesystem_access: self.filesystem_access && other.filesystem_access,
+            network_access: self.network_access && other.network_access,
+            process_spawn: self.process_spawn && other.process_spawn,
+            env_access: self.env_access && other.env_access,
+            system_access: self.system_access && other.system_access,
+            memory_limit_mb: self.memory_limit_mb.min(other.memory_limit_mb),
+            cpu_limit_percent: self.cpu_limit_percent.min(other.cpu_limit_percent),
+            timeout_seconds: self.timeout_seconds.min(other.timeout_seconds),
+            filesystem_paths: intersect_paths(&self.filesystem_paths, &other.filesystem_paths),
+            egress_allowlist: intersect_policies(&self.egress_allowlist, &other.egress_allowlist),
+        };
+
+        let paths_disjoint = !self.filesystem_paths.is_empty()
+            && !other.filesystem_paths.is_empty()
+            && permissions.filesystem_paths.is_empty();
+        if paths_disjoint {
+            permissions.filesystem_access = false;
+        }
+        let egress_disjoint = !self.egress_allowlist.is_empty()
+            && !other.egress_allowlist.is_empty()
+            && permissions.egress_allowlist.is_empty();
+        if egress_disjoint {
+            permissions.network_access = false;
+        }
+        permissions
+    }
+}
+
+/// Path rules allowed by both policies (an empty policy allows everything)
+fn intersect_paths(a: &[PathRule], b: &[PathRule]) -> Vec<PathRule> {
+    if a.is_empty() {
+        return b.to_vec();
+    }
+    if b.is_empty() {
+        return a.to_vec();
+    }
+
+    let mut rules: Vec<PathRule> = Vec::new();
+    for (rule, others) in a.iter().map(|r| (r, b)).chain(b.iter().map(|r| (r, a))) {
+        let prefix = Path::new(&rule.prefix);
+        if !others.iter().any(|o| o.covers(prefix, AccessMode::Read)) {
+            continue;
+        }
+        let narrowed = PathRule {
+            writable: rule.writable && others.iter().any(|o| o.covers(prefix, AccessMode::Write)),
+            ..rule.clone()
+        };
+        if !rules.contains(&narrowed) {
+            rules.push(narrowed);
+        }
+    }
+    rules
+}
+
+/// Rules present in both policies (an empty policy allows everything)
+fn intersect_policies<T: Clone + PartialEq>(a: &[T], b: &[T]) -> Vec<T> {
+    if a.is_empty() {
+        return b.to_vec();
+    }
+    if b.is_empty() {
+        return a.to_vec();
+    }
+    a.iter().filter(|rule| b.contains(rule)).cloned().collect()
+}
@@ -0,0 +1,340 @@
+//! Registry metrics exported as node_exporter textfiles or JSON
+
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, RwLock};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use crate::clock;
+use crate::clock::current_timestamp;
+use crate::constants::*;
+use crate::match_cache::MatchCacheStats;
+use crate::registry::CapabilityRegistry;
+
+/// Point-in-time metrics of a registry
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MetricsSnapshot {
+    /// Timestamp the snapshot was taken
+    pub timestamp: u64,
+    /// Registered workers
+    pub workers: usize,
+    /// Workers whose capabilities all verify
+    pub verified_workers: usize,
+    /// Workers with at least one capability failing verification
+    pub verification_failures: usize,
+    /// Declared tools across all workers
+    pub tools: usize,
+    /// Required tools across all workers
+    pub required_tools: usize,
+    /// Tools whose capability has expired
+    pub expired_tools: usize,
+    /// Tools whose capability expires within the expiring-soon window
+    pub expiring_soon_tools: usize,
+    /// Revoked tools
+    pub revoked_tools: usize,
+    /// Tools suspended for poor job outcomes
+    pub suspended_tools: usize,
+    /// Active sessions
+    pub active_sessions: usize,
+    /// Capability sets `register` refused since the registry was created
+    #[serde(default)]
+    pub rejected_registrations: usize,
+    /// Active temporary grants
+    pub active_grants: usize,
+    /// Workers whose retry budget is exhausted
+    pub retry_budget_exhausted: usize,
+    /// Verification result per worker
+    pub worker_verified: Vec<(String, bool)>,
+    /// Hit-rate metrics of the match cache (if one is attached)
+    #[serde(default)]
+    pub match_cache: Option<MatchCacheStats>,
+}
+
+impl MetricsSnapshot {
+    /// Render in the Prometheus text exposition format used by node_exporter textfiles
+    pub fn to_prometheus_text(&self) -> String {
+        let gauges: [(&str, &str, usize); 13] = [
+            ("workers", "Registered workers", self.workers),
+            (
+                "verified_workers",
+                "Workers whose capabilities all verify",
+                self.verified_workers,
+            ),
+            (
+                "verification_failures",
+                "Workers with capabilities failing verification",
+                self.verification_failures,
+            ),
+            ("tools", "Declared tools", self.tools),
+            ("required_tools", "Required tools", self.required_tools),
+            (
+                "expired_tools",
+                "Tools with expired capabilities",
+                self.expired_tools,
+            ),
+            (
+                "expiring_soon_tools",
+                "Tools with capabilities expiring soon",
+                self.expiring_soon_tools,
+            ),
+            ("revoked_tools", "Revoked tools", self.revoked_tools),
+            (
+                "suspended_tools",
+                "Tools suspended for poor job outcomes",
+                self.suspended_tools,
+            ),
+            ("active_sessions", "Active sessions", self.active_sessions),
+            (
+                "rejected_registrations",
+                "Capability sets refused by register",
+                self.rejected_registrations,
+            ),
+            (
+                "active_grants",
+                "Active temporary grants",
+                self.active_grants,
+            ),
+            (
+                "retry_budget_exhausted_workers",
+                "Workers with an exhausted retry budget",
+                self.retry_budget_exhausted,
+            ),
+        ];
+
+        let mut out = String::new();
+        for (name, help, value) in gauges {
+            let _ = writeln!(out, "# HELP {}_{} {}", METRICS_PREFIX, name, help);
+            let _ = writeln!(out, "# TYPE {}_{} gauge", METRICS_PREFIX, name);
+            let _ = writeln!(out, "{}_{} {}", METRICS_PREFIX, name, value);
+        }
+
+        let _ = writeln!(
+            out,
+            "# HELP {}_worker_verified Whether a worker's capabilities all verify",
+            METRICS_PREFIX
+        );
+        let _ = writeln!(out, "# TYPE {}_worker_verified gauge", METRICS_PREFIX);
+        for (worker_id, verified) in &self.worker_verified {
+            let _ = writeln!(
+                out,
+                "{}_worker_verified{{worker=\"{}\"}} {}",
+                METRICS_PREFIX,
+                escape_label(worker_id),
+                u8::from(*verified)
+            );
+        }
+
+        if let Some(stats) = &self.match_cache {
+            let counters = [
+                (
+                    "match_cache_hits_total",
+                    "Match lookups answered from the cache",
+                    stats.hits,
+                ),
+                (
+                    "match_cache_misses_total",
+                    "Match lookups computed",
+                    stats.misses,
+                ),
+                (
+                    "match_cache_invalidations_total",
+                    "Match cache invalidations by registry changes",
+                    stats.invalidations,
+                ),
+            ];
+            for (name, help, value) in counters {
+                let _ = writeln!(out, "# HELP {}_{} {}", METRICS_PREFIX, name, help);
+                let _ = writeln!(out, "# TYPE {}_{} counter", METRICS_PREFIX, name);
+                let _ = writeln!(out, "{}_{} {}", METRICS_PREFIX, name, value);
+            }
+            let _ = writeln!(
+                out,
+                "# HELP {}_match_cache_entries Cached requirement sets",
+                METRICS_PREFIX
+            );
+            let _ = writeln!(out, "# TYPE {}_match_cache_entries gauge", METRICS_PREFIX);
+            let _ = writeln!(
+                out,
+                "{}_match_cache_entries {}",
+                METRICS_PREFIX, stats.entries
+            );
+        }
+
+        let _ = writeln!(
+            out,
+            "# HELP {}_snapshot_timestamp_seconds Time the metrics were collected",
+            METRICS_PREFIX
+        );
+        let _ = writeln!(
+            out,
+            "# TYPE {}_snapshot_timestamp_seconds gauge",
+            METRICS_PREFIX
+        );
+        let _ = writeln!(
+            out,
+            "{}_snapshot_timestamp_seconds {}",
+            METRICS_PREFIX, self.timestamp
+        );
+        out
+    }
+
+    /// Render as pretty-printed JSON
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+/// Output format of exported metrics
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MetricsFormat {
+    /// Prometheus text exposition format (node_exporter textfile collector)
+    Prometheus,
+    /// JSON document
+    Json,
+}
+
+impl CapabilityRegistry {
+    /// Collect a metrics snapshot of the registry
+    pub fn metrics_snapshot(&self) -> MetricsSnapshot {
+        let _clock = self.clock().map(clock::enter);
+        let now = current_timestamp();
+        let stats = self.get_statistics();
+        let mut snapshot = MetricsSnapshot {
+            timestamp: now,
+            workers: stats.total_workers,
+            verified_workers: stats.verified_workers,
+            tools: stats.total_tools,
+            required_tools: stats.total_required_tools,
+            active_sessions: stats.active_sessions,
+            rejected_registrations: stats.rejected_registrations,
+            match_cache: self.match_cache_stats(),
+            ..MetricsSnapshot::default()
+        };
+
+        let mut worker_ids = self.list_ids();
+        worker_ids.sort();
+        for worker_id in worker_ids {
+            let Some(caps) = self.get(&worker_id) else {
+                continue;
+            };
+
+            let verified = self.verifies(caps);
+            if !verified {
+                snapshot.verification_failures += 1;
+            }
+            snapshot.active_grants += self.active_grants(&worker_id).len();
+            if !self.has_retry_budget(&worker_id) {
+                snapshot.retry_budget_exhausted += 1;
+            }
+
+            for tool in caps.tools_iter() {
+                if tool.is_revoked() {
+                    snapshot.revoked_tools += 1;
+                } else if tool.is_expired() {
+                    snapshot.expired_tools += 1;
+                } else if tool.expiration.expires_at
+                    <= now.saturating_add(METRICS_EXPIRING_SOON_SECONDS)
+                {
+                    snapshot.expiring_soon_tools += 1;
+                }
+                if tool.suspended {
+                    snapshot.suspended_tools += 1;
+                }
+            }
+
+            snapshot.worker_verified.push((worker_id, verified));
+        }
+
+        snapshot
+    }
+
+    /// Render the registry metrics in the given format
+    pub fn render_metrics(&self, format: MetricsFormat) -> String {
+        let snapshot = self.metrics_snapshot();
+        match format {
+            MetricsFormat::Prometheus => snapshot.to_prometheus_text(),
+            MetricsFormat::Json => snapshot.to_json().unwrap_or_default(),
+        }
+    }
+
+    /// Write metrics for the node_exporter textfile collector
+    ///
+    /// The file is written to a temporary sibling and renamed into place so the
+    /// collector never reads a partial file.
+    pub fn write_metrics_textfile(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        write_atomically(
+            path.as_ref(),
+            &self.render_metrics(MetricsFormat::Prometheus),
+        )
+    }
+
+    /// Write metrics as a JSON document
+    pub fn write_metrics_json(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        write_atomically(path.as_ref(), &self.render_metrics(MetricsFormat::Json))
+    }
+}
+
+/// Background writer refreshing a metrics file on an interval
+#[derive(Debug)]
+pub struct MetricsFileWriter {
+    stop: Sender<()>,
+    thread: JoinHandle<()>,
+}
+
+impl MetricsFileWriter {
+    /// Write metrics of a shared registry to `path` now and then every `interval`
+    ///
+    /// Write errors are ignored and retried on the next tick.
+    pub fn spawn(
+        registry: Arc<RwLock<CapabilityRegistry>>,
+        path: impl Into<PathBuf>,
+        format: MetricsFormat,
+        interval: Duration,
+    ) -> Self {
+        let path = path.into();
+        let clock = clock::current();
+        let (stop, stopped) = mpsc::channel();
+        let thread = std::thread::spawn(move || {
+            clock::with_inherited(clock, || loop {
+                let contents = registry
+                    .read()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .render_metrics(format);
+                let _ = write_atomically(&path, &contents);
+
+                match stopped.recv_timeout(interval) {
+                    Err(RecvTimeoutError::Timeout) => continue,
+                    _ => break,
+                }
+            })
+        });
+
+        Self { stop, thread }
+    }
+
+    /// Stop the writer and wait for it to finish
+    pub fn stop(self) {
+        let _ = self.stop.send(());
+        let _ = self.thread.join();
+    }
+}
+
+fn write_atomically(path: &Path, contents: &str) -> io::Result<()> {
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(".tmp");
+    let temp = PathBuf::from(temp);
+    std::fs::write(&temp, contents)?;
+    std::fs::rename(&temp, path)
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
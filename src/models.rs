@@ -0,0 +1,131 @@
+//! Language model capability declarations
+
+use serde::{Deserialize, Serialize};
+
+/// Where a model is served from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelDeployment {
+    /// Model runs on the worker itself
+    Local,
+    /// Model is reached through a remote API
+    Api,
+}
+
+/// A language model available to a worker
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelCapability {
+    /// Model family (e.g., "llama", "mistral", "gpt")
+    pub family: String,
+    /// Specific model name (if known)
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Maximum context window in tokens
+    pub context_window: u32,
+    /// Where the model is served from
+    pub deployment: ModelDeployment,
+    /// Token rate limit per minute (if limited)
+    #[serde(default)]
+    pub tokens_per_minute: Option<u64>,
+}
+
+impl ModelCapability {
+    /// Create a locally served model capability
+    pub fn local(family: impl Into<String>, context_window: u32) -> Self {
+        Self {
+            family: family.into(),
+            model: None,
+            context_window,
+            deployment: ModelDeployment::Local,
+            tokens_per_minute: None,
+        }
+    }
+
+    /// Create an API-served model capability
+    pub fn api(family: impl Into<String>, context_window: u32) -> Self {
+        Self {
+            deployment: ModelDeployment::Api,
+            ..Self::local(family, context_window)
+        }
+    }
+
+    /// Set the specific model name
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.model = Some(model.into());
+        self
+    }
+
+    /// Set the token rate limit per minute
+    pub fn with_rate_limit(mut self, tokens_per_minute: u64) -> Self {
+        self.tokens_per_minute = Some(tokens_per_minute);
+        self
+    }
+}
+
+/// Requirement on a language model available to a worker
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ModelRequirement {
+    /// Required model family (any family if `None`)
+    pub family: Option<String>,
+    /// Minimum context window in tokens
+    pub min_context_window: u32,
+    /// Required deployment (any deployment if `None`)
+    pub deployment: Option<ModelDeployment>,
+    /// Minimum token rate per minute (unlimited models always qualify)
+    pub min_tokens_per_minute: Option<u64>,
+}
+
+impl ModelRequirement {
+    /// Require any model
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Require a model family
+    pub fn family(mut self, family: impl Into<String>) -> Self {
+        self.family = Some(family.into());
+        self
+    }
+
+    /// Require a minimum context window
+    pub fn min_context(mut self, tokens: u32) -> Self {
+        self.min_context_window = tokens;
+        self
+    }
+
+    /// Require a locally served model
+    pub fn local(mut self) -> Self {
+        self.deployment = Some(ModelDeployment::Local);
+        self
+    }
+
+    /// Require a minimum token rate per minute
+    pub fn min_rate(mut self, tokens_per_minute: u64) -> Self {
+        self.min_tokens_per_minute = Some(tokens_per_minute);
+        self
+    }
+
+    /// Check if a model satisfies this requirement
+    pub fn is_satisfied_by(&self, model: &ModelCapability) -> bool {
+        if let Some(family) = &self.family {
+            if !family.eq_ignore_ascii_case(&model.family) {
+                return false;
+            }
+        }
+
+        if let Some(deployment) = self.deployment {
+            if deployment != model.deployment {
+                return false;
+            }
+        }
+
+        if let (Some(min), Some(limit)) = (self.min_tokens_per_minute, model.tokens_per_minute) {
+            if limit < min {
+                return false;
+            }
+        }
+
+        model.context_window >= self.min_context_window
+    }
+}
@@ -0,0 +1,78 @@
+//! Network reachability declarations for workers
+
+use serde::{Deserialize, Serialize};
+
+use crate::clock::current_timestamp;
+
+/// Networks and endpoints a worker is able to reach
+///
+/// An empty endpoint list with `internet` set to `false` describes an
+/// air-gapped worker.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetworkReachability {
+    /// Whether the worker has general outbound internet access
+    pub internet: bool,
+    /// Named endpoints the worker can reach (e.g., "github.com", "internal-artifactory")
+    pub endpoints: Vec<String>,
+    /// Timestamp of the last reachability probe (if probed)
+    pub probed_at: Option<u64>,
+}
+
+impl NetworkReachability {
+    /// Create a reachability declaration for an air-gapped worker
+    pub fn air_gapped() -> Self {
+        Self::default()
+    }
+
+    /// Create a reachability declaration for a worker with internet access
+    pub fn internet() -> Self {
+        Self {
+            internet: true,
+            ..Self::default()
+        }
+    }
+
+    /// Add a reachable endpoint
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        let endpoint = endpoint.into();
+        if !self.can_reach(&endpoint) {
+            self.endpoints.push(endpoint);
+        }
+        self
+    }
+
+    /// Probe a list of endpoints and record the ones that are reachable
+    ///
+    /// The `probe` callback performs the actual connectivity check, in the
+    /// same way a tool checker answers tool availability.
+    pub fn probe(
+        internet_endpoint: Option<&str>,
+        endpoints: &[&str],
+        probe: &dyn Fn(&str) -> bool,
+    ) -> Self {
+        let internet = internet_endpoint.map(probe).unwrap_or(false);
+        let endpoints = endpoints
+            .iter()
+            .filter(|endpoint| probe(endpoint))
+            .map(|endpoint| endpoint.to_string())
+            .collect();
+
+        Self {
+            internet,
+            endpoints,
+            probed_at: Some(current_timestamp()),
+        }
+    }
+
+    /// Check if the worker has no network access at all
+    pub fn is_air_gapped(&self) -> bool {
+        !self.internet && self.endpoints.is_empty()
+    }
+
+    /// Check if a named endpoint is reachable
+    pub fn can_reach(&self, endpoint: &str) -> bool {
+        self.endpoints
+            .iter()
+            .any(|known| known.eq_ignore_ascii_case(endpoint))
+    }
+}
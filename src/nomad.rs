@@ -0,0 +1,245 @@
+//! Interop with HashiCorp Nomad node attributes, meta and job constraints
+//!
+//! Capabilities are carried in node meta under the `capability.` prefix:
+//!
+//! | Meta key                          | Value                                   |
+//! |-----------------------------------|-----------------------------------------|
+//! | `capability.tools.<category>`     | comma-separated tool names              |
+//! | `capability.flags`                | comma-separated flag names              |
+//! | `capability.network.internet`     | `true` / `false`                        |
+//! | `capability.network.endpoints`    | comma-separated endpoint names          |
+//! | `capability.sandbox`              | `none`, `docker`, `gvisor`, `firecracker` |
+//! | `capability.accepts`              | comma-separated artifact formats        |
+//! | `capability.emits`                | comma-separated report formats          |
+//!
+//! Any other meta key is imported as capability metadata.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::capabilities::Capabilities;
+use crate::formats::{ArtifactFormat, ReportFormat};
+use crate::requirements::Requirements;
+use crate::sandbox::{SandboxCapability, SandboxTechnology};
+
+const META_PREFIX: &str = "capability.";
+const META_TOOLS_PREFIX: &str = "capability.tools.";
+const META_FLAGS: &str = "capability.flags";
+const META_INTERNET: &str = "capability.network.internet";
+const META_ENDPOINTS: &str = "capability.network.endpoints";
+const META_SANDBOX: &str = "capability.sandbox";
+const META_ACCEPTS: &str = "capability.accepts";
+const META_EMITS: &str = "capability.emits";
+
+/// A Nomad job placement constraint
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NomadConstraint {
+    /// Left-hand target (e.g., `${meta.capability.sandbox}`)
+    #[serde(rename = "LTarget")]
+    pub l_target: String,
+    /// Constraint operator (e.g., `=`, `set_contains`, `regexp`)
+    #[serde(rename = "Operand")]
+    pub operand: String,
+    /// Right-hand target value
+    #[serde(rename = "RTarget")]
+    pub r_target: String,
+}
+
+impl NomadConstraint {
+    fn new(l_target: impl Into<String>, operand: &str, r_target: impl Into<String>) -> Self {
+        Self {
+            l_target: l_target.into(),
+            operand: operand.to_string(),
+            r_target: r_target.into(),
+        }
+    }
+}
+
+fn split_list(value: &str) -> impl Iterator<Item = &str> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+}
+
+fn sandbox_name(technology: SandboxTechnology) -> String {
+    serde_json::to_value(technology)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+impl Capabilities {
+    /// Import capabilities from a Nomad node's attributes and meta
+    ///
+    /// Tools listed in node meta are imported as optional tools. The
+    /// `kernel.name` and `cpu.arch` attributes become `platform` and `arch`
+    /// metadata, and a detected Docker driver implies Docker sandboxing when
+    /// no sandbox is declared in meta.
+    pub fn from_nomad_node(
+        node_id: impl Into<String>,
+        attributes: &HashMap<String, String>,
+        meta: &HashMap<String, String>,
+    ) -> Self {
+        let mut caps = Capabilities::new(node_id);
+
+        for (key, value) in meta {
+            if let Some(category) = key.strip_prefix(META_TOOLS_PREFIX) {
+                for tool in split_list(value) {
+                    caps = caps.with_tool_in(category, tool, false);
+                }
+                continue;
+            }
+
+            match key.as_str() {
+                META_FLAGS => {
+                    for flag in split_list(value) {
+                        caps.flags.insert(flag.to_string(), true);
+                    }
+                }
+                META_INTERNET => caps.network.internet = value.trim() == "true",
+                META_ENDPOINTS => {
+                    caps.network.endpoints = split_list(value).map(str::to_string).collect();
+                }
+                META_SANDBOX => {
+                    let technology = serde_json::from_value(serde_json::Value::String(
+                        value.trim().to_ascii_lowercase(),
+                    ))
+                    .unwrap_or_default();
+                    caps.sandbox = SandboxCapability::new(technology);
+                }
+                META_ACCEPTS => {
+                    caps.accepts = split_list(value).map(ArtifactFormat::parse).collect();
+                }
+                META_EMITS => {
+                    caps.emits = split_list(value).map(ReportFormat::parse).collect();
+                }
+                _ if key.starts_with(META_PREFIX) => {}
+                _ => {
+                    caps.metadata.insert(key.clone(), value.clone());
+                }
+            }
+        }
+
+        if let Some(os) = attributes.get("kernel.name") {
+            caps.metadata.insert("platform".to_string(), os.clone());
+        }
+        if let Some(arch) = attributes.get("cpu.arch") {
+            caps.metadata.insert("arch".to_string(), arch.clone());
+        }
+        if !meta.contains_key(META_SANDBOX)
+            && attributes.get("driver.docker").map(String::as_str) == Some("1")
+        {
+            caps.sandbox = SandboxCapability::new(SandboxTechnology::Docker);
+        }
+
+        caps
+    }
+
+    /// Export this capability set as Nomad node meta
+    pub fn to_nomad_meta(&self) -> HashMap<String, String> {
+        let mut meta = self.metadata.clone();
+
+        for category in self.category_names() {
+            let tools = self.tools_in_category(&category);
+            if !tools.is_empty() {
+                let names: Vec<&str> = tools.iter().map(|tool| tool.tool_name.as_str()).collect();
+                meta.insert(
+                    format!("{}{}", META_TOOLS_PREFIX, category),
+                    names.join(","),
+                );
+            }
+        }
+
+        let mut flags: Vec<&str> = self
+            .flags
+            .iter()
+            .filter(|(_, enabled)| **enabled)
+            .map(|(flag, _)| flag.as_str())
+            .collect();
+        if !flags.is_empty() {
+            flags.sort();
+            meta.insert(META_FLAGS.to_string(), flags.join(","));
+        }
+
+        meta.insert(META_INTERNET.to_string(), self.network.internet.to_string());
+        if !self.network.endpoints.is_empty() {
+            meta.insert(META_ENDPOINTS.to_string(), self.network.endpoints.join(","));
+        }
+        meta.insert(
+            META_SANDBOX.to_string(),
+            sandbox_name(self.sandbox.technology),
+        );
+        if !self.accepts.is_empty() {
+            let formats: Vec<&str> = self.accepts.iter().map(ArtifactFormat::as_str).collect();
+            meta.insert(META_ACCEPTS.to_string(), formats.join(","));
+        }
+        if !self.emits.is_empty() {
+            let formats: Vec<&str> = self.emits.iter().map(ReportFormat::as_str).collect();
+            meta.insert(META_EMITS.to_string(), formats.join(","));
+        }
+
+        meta
+    }
+}
+
+impl Requirements {
+    /// Express these requirements as Nomad job constraints
+    ///
+    /// Constraints target the node meta written by `Capabilities::to_nomad_meta`.
+    pub fn to_nomad_constraints(&self) -> Vec<NomadConstraint> {
+        let mut constraints = Vec::new();
+
+        if self.internet {
+            constraints.push(NomadConstraint::new(
+                format!("${{meta.{}}}", META_INTERNET),
+                "=",
+                "true",
+            ));
+        }
+
+        if !self.endpoints.is_empty() {
+            constraints.push(NomadConstraint::new(
+                format!("${{meta.{}}}", META_ENDPOINTS),
+                "set_contains",
+                self.endpoints.join(","),
+            ));
+        }
+
+        if let Some(level) = self.min_isolation {
+            let technologies: Vec<String> = [
+                SandboxTechnology::None,
+                SandboxTechnology::Docker,
+                SandboxTechnology::GVisor,
+                SandboxTechnology::Firecracker,
+            ]
+            .into_iter()
+            .filter(|tech| tech.isolation_level() >= level)
+            .map(sandbox_name)
+            .collect();
+            constraints.push(NomadConstraint::new(
+                format!("${{meta.{}}}", META_SANDBOX),
+                "regexp",
+                format!("^({})$", technologies.join("|")),
+            ));
+        }
+
+        if let Some(format) = &self.input_format {
+            constraints.push(NomadConstraint::new(
+                format!("${{meta.{}}}", META_ACCEPTS),
+                "set_contains",
+                format.as_str(),
+            ));
+        }
+
+        if let Some(format) = &self.output_format {
+            constraints.push(NomadConstraint::new(
+                format!("${{meta.{}}}", META_EMITS),
+                "set_contains",
+                format.as_str(),
+            ));
+        }
+
+        constraints
+    }
+}
@@ -0,0 +1,88 @@
+//! Parallel variants of capability checks for large fleets
+//!
+//! Checks on the rayon thread pool use the clock of the calling thread, or
+//! the registry's clock if it was given one.
+
+use rayon::prelude::*;
+use std::collections::HashMap;
+
+use crate::capabilities::Capabilities;
+use crate::checker::ToolChecker;
+use crate::clock;
+use crate::registry::CapabilityRegistry;
+
+impl Capabilities {
+    /// Check if all required tools are available, checking tools in parallel
+    pub fn has_all_required_tools_par(
+        &self,
+        tool_checker: &(impl ToolChecker + Sync + ?Sized),
+    ) -> bool {
+        let clock = clock::current();
+        self.tools_iter()
+            .collect::<Vec<_>>()
+            .into_par_iter()
+            .all(|tool| {
+                clock::with_inherited(clock.clone(), || {
+                    !tool.required || tool.is_satisfied(tool_checker)
+                })
+            })
+    }
+}
+
+impl CapabilityRegistry {
+    /// Find workers with a specific capability, evaluating workers in parallel
+    ///
+    /// Same semantics as `find_with_capability`; results are sorted by worker ID.
+    pub fn find_with_capability_par(
+        &self,
+        capability_type: &str,
+        tool_checker: &(impl ToolChecker + Sync + ?Sized),
+    ) -> Vec<&Capabilities> {
+        let clock = self.clock();
+        let mut found: Vec<&Capabilities> = self
+            .workers()
+            .into_par_iter()
+            .filter(|caps| {
+                clock::with_inherited(clock.clone(), || {
+                    self.is_selectable(&caps.id)
+                        && caps.tools_in_category(capability_type).iter().any(|tool| {
+                            tool.is_satisfied(tool_checker) && self.has_free_slot(&caps.id, tool)
+                        })
+                })
+            })
+            .collect();
+        found.sort_by(|a, b| a.id.cmp(&b.id));
+        found
+    }
+
+    /// Verify all workers' capabilities in parallel
+    pub fn verify_all_workers_par(&self) -> HashMap<String, bool> {
+        let clock = self.clock();
+        self.workers()
+            .into_par_iter()
+            .map(|caps| {
+                let verified = clock::with_inherited(clock.clone(), || self.verifies(caps));
+                (caps.id.clone(), verified)
+            })
+            .collect()
+    }
+
+    /// Find workers with all required tools, evaluating workers in parallel
+    ///
+    /// Results are sorted by worker ID.
+    pub fn find_workers_with_all_required_tools_par(
+        &self,
+        tool_checker: &(impl ToolChecker + Sync + ?Sized),
+    ) -> Vec<&Capabilities> {
+        let clock = self.clock();
+        let mut found: Vec<&Capabilities> = self
+            .workers()
+            .into_par_iter()
+            .filter(|caps| {
+                clock::with_inherited(clock.clone(), || caps.has_all_required_tools(tool_checker))
+            })
+            .collect();
+        found.sort_by(|a, b| a.id.cmp(&b.id));
+        found
+    }
+}
@@ -0,0 +1,50 @@
+//! Job payload limits a worker can admit
+//!
+//! Workers declare the largest input artifact and repository they can handle
+//! so oversized jobs are routed elsewhere before any transfer starts.
+
+use serde::{Deserialize, Serialize};
+
+/// Largest job payload a worker can handle (unlimited where `None`)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PayloadLimits {
+    /// Maximum size of an input artifact in bytes
+    pub max_input_size_bytes: Option<u64>,
+    /// Maximum number of files in a repository
+    pub max_repo_file_count: Option<u64>,
+}
+
+impl PayloadLimits {
+    /// Create limits that admit any payload
+    pub fn unlimited() -> Self {
+        Self::default()
+    }
+
+    /// Limit the size of input artifacts in bytes
+    pub fn with_max_input_size(mut self, bytes: u64) -> Self {
+        self.max_input_size_bytes = Some(bytes);
+        self
+    }
+
+    /// Limit the size of input artifacts in MB
+    pub fn with_max_input_size_mb(self, megabytes: u64) -> Self {
+        self.with_max_input_size(megabytes.saturating_mul(1024 * 1024))
+    }
+
+    /// Limit the number of files in a repository
+    pub fn with_max_repo_file_count(mut self, files: u64) -> Self {
+        self.max_repo_file_count = Some(files);
+        self
+    }
+
+    /// Check if an input artifact of the given size in bytes is admitted
+    pub fn admits_input_size(&self, bytes: u64) -> bool {
+        self.max_input_size_bytes.is_none_or(|max| bytes <= max)
+    }
+
+    /// Check if a repository with the given number of files is admitted
+    pub fn admits_repo_file_count(&self, files: u64) -> bool {
+        self.max_repo_file_count.is_none_or(|max| files <= max)
+    }
+}
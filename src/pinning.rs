@@ -0,0 +1,103 @@
+//! Pinning tools to the SHA256 of their executable
+//!
+//! A pinned tool only satisfies a capability check when the executable the
+//! checker resolved hashes to the expected value, so a worker cannot claim a
+//! tool while shipping a tampered binary.
+
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::SystemTime;
+
+use crate::checker::{PathChecker, ToolChecker};
+use crate::types::ToolCapability;
+
+/// SHA256 of a file as lowercase hex
+pub fn sha256_file(path: impl AsRef<Path>) -> io::Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Hashes of executables, keyed by path and invalidated when size or mtime change
+type HashCache = HashMap<PathBuf, (SystemTime, u64, String)>;
+
+fn hash_cache() -> &'static Mutex<HashCache> {
+    static CACHE: OnceLock<Mutex<HashCache>> = OnceLock::new();
+    CACHE.get_or_init(Default::default)
+}
+
+/// SHA256 of a file, reusing the last hash while its size and mtime are unchanged
+pub(crate) fn cached_sha256_file(path: &Path) -> io::Result<String> {
+    let metadata = std::fs::metadata(path)?;
+    let modified = metadata.modified()?;
+    let len = metadata.len();
+
+    if let Some((cached_modified, cached_len, sha256)) = hash_cache()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(path)
+    {
+        if *cached_modified == modified && *cached_len == len {
+            return Ok(sha256.clone());
+        }
+    }
+
+    let sha256 = sha256_file(path)?;
+    hash_cache()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(path.to_path_buf(), (modified, len, sha256.clone()));
+    Ok(sha256)
+}
+
+impl ToolCapability {
+    /// Pin the tool to the SHA256 of its executable (hex, case-insensitive)
+    ///
+    /// The pin applies to whichever executable satisfies the capability,
+    /// including alternatives.
+    pub fn with_binary_sha256(mut self, sha256: impl Into<String>) -> Self {
+        self.binary_sha256 = Some(sha256.into().trim().to_ascii_lowercase());
+        self
+    }
+
+    /// Check the local executable at `path` (or else found on `PATH`) against the pin
+    ///
+    /// Always true for unpinned tools. A pinned tool whose executable cannot
+    /// be located or read does not match. Hashes are cached per path until
+    /// the file's size or mtime changes.
+    pub fn binary_matches(&self, path: Option<&Path>, tool: &str) -> bool {
+        let Some(expected) = &self.binary_sha256 else {
+            return true;
+        };
+
+        let resolved = match path {
+            Some(path) => Some(path.to_path_buf()),
+            None => PathChecker::from_env().locate(tool),
+        };
+        resolved
+            .and_then(|path| cached_sha256_file(&path).ok())
+            .is_some_and(|actual| actual.eq_ignore_ascii_case(expected))
+    }
+
+    /// Check the executable a checker resolved for a tool against the pin
+    ///
+    /// The checker reports the hash (see `ToolChecker::binary_sha256`), so
+    /// remote and container checkers are never answered from local files.
+    /// Checkers that cannot report a hash do not match pinned tools.
+    pub fn checked_binary_matches(
+        &self,
+        tool_checker: &(impl ToolChecker + ?Sized),
+        tool: &str,
+    ) -> bool {
+        let Some(expected) = &self.binary_sha256 else {
+            return true;
+        };
+        tool_checker
+            .binary_sha256(tool)
+            .is_some_and(|actual| actual.eq_ignore_ascii_case(expected))
+    }
+}
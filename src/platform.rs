@@ -0,0 +1,121 @@
+//! Platform constraints for tools and capability sets
+//!
+//! Tools can be restricted to the platforms they run on, and capability sets
+//! declare the platform of their worker. Platform-aware matching skips
+//! workers and tools that cannot run the requested platform's artifacts.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::capabilities::Capabilities;
+use crate::checker::ToolChecker;
+use crate::environment::EnvironmentSnapshot;
+use crate::signing::encode_fields;
+use crate::types::ToolCapability;
+use crate::well_known::PLATFORM;
+
+/// Operating system and CPU architecture
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Platform {
+    /// Operating system (as in `std::env::consts::OS`, e.g. `linux`)
+    pub os: String,
+    /// CPU architecture (as in `std::env::consts::ARCH`, e.g. `x86_64`)
+    pub arch: String,
+}
+
+impl Platform {
+    /// Create a platform
+    pub fn new(os: impl Into<String>, arch: impl Into<String>) -> Self {
+        Self {
+            os: os.into(),
+            arch: arch.into(),
+        }
+    }
+
+    /// Platform of the local machine
+    pub fn current() -> Self {
+        Self::new(std::env::consts::OS, std::env::consts::ARCH)
+    }
+
+    /// Check against an optional operating system and architecture
+    pub fn matches(&self, os: Option<&str>, arch: Option<&str>) -> bool {
+        os.is_none_or(|os| self.os == os) && arch.is_none_or(|arch| self.arch == arch)
+    }
+}
+
+impl fmt::Display for Platform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.os, self.arch)
+    }
+}
+
+impl ToolCapability {
+    /// Add a platform the tool runs on (any platform if none are added)
+    pub fn with_platform(mut self, os: impl Into<String>, arch: impl Into<String>) -> Self {
+        self.platforms.push(Platform::new(os, arch));
+        self
+    }
+
+    /// Check if the tool runs on a platform
+    pub fn supports_platform(&self, platform: &Platform) -> bool {
+        self.runs_on(Some(&platform.os), Some(&platform.arch))
+    }
+
+    /// Check if the tool runs on an optional operating system and architecture
+    pub(crate) fn runs_on(&self, os: Option<&str>, arch: Option<&str>) -> bool {
+        self.platforms.is_empty()
+            || self
+                .platforms
+                .iter()
+                .any(|platform| platform.matches(os, arch))
+    }
+
+    /// Platforms of the tool in a stable order for hashing
+    pub(crate) fn canonical_platforms(&self) -> String {
+        let mut platforms: Vec<String> = self.platforms.iter().map(Platform::to_string).collect();
+        platforms.sort();
+        platforms.dedup();
+        encode_fields(platforms)
+    }
+}
+
+impl Capabilities {
+    /// Set the platform of the worker
+    ///
+    /// Recorded in the environment snapshot and as `<os>-<arch>` in the
+    /// `platform` metadata key.
+    pub fn with_platform(mut self, os: impl Into<String>, arch: impl Into<String>) -> Self {
+        let platform = Platform::new(os, arch);
+        self.metadata
+            .insert(PLATFORM.to_string(), platform.to_string());
+        match &mut self.environment {
+            Some(environment) => {
+                environment.os = platform.os;
+                environment.arch = platform.arch;
+            }
+            None => self.environment = Some(EnvironmentSnapshot::new(platform.os, platform.arch)),
+        }
+        self
+    }
+
+    /// Check if the worker runs on a platform
+    ///
+    /// Workers that do not declare their platform do not match.
+    pub fn supports_platform(&self, platform: &Platform) -> bool {
+        self.os() == Some(platform.os.as_str()) && self.arch() == Some(platform.arch.as_str())
+    }
+
+    /// Check for a capability with a tool that runs on a platform
+    pub fn has_capability_for_platform(
+        &self,
+        capability_type: &str,
+        tool_checker: &(impl ToolChecker + ?Sized),
+        platform: &Platform,
+    ) -> bool {
+        self.supports_platform(platform)
+            && self
+                .tools_in_category(capability_type)
+                .iter()
+                .any(|tool| tool.supports_platform(platform) && tool.is_satisfied(tool_checker))
+    }
+}
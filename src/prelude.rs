@@ -0,0 +1,28 @@
+//! Commonly used types, importable in one line
+//!
+//! ```rust
+//! use worker_capabilities::prelude::*;
+//!
+//! let caps = Capabilities::new("worker-1").with_static_analysis("clippy", true);
+//! let mut registry = CapabilityRegistry::new();
+//! registry.register(caps);
+//! ```
+//!
+//! Items are only added to the prelude in minor releases and only removed in
+//! major releases.
+
+pub use crate::attestation::AttestationManager;
+pub use crate::capabilities::Capabilities;
+pub use crate::checker::{ToolChecker, ToolCheckerExt, ToolStatus};
+pub use crate::defaults::CategoryDefaults;
+pub use crate::explain::CapabilityReport;
+pub use crate::expr::CapabilityExpr;
+pub use crate::registry::CapabilityRegistry;
+pub use crate::requirements::{MatchResult, Requirements};
+pub use crate::scope::CapabilityScope;
+#[cfg(feature = "test-signing")]
+pub use crate::signing::CapabilitySigner;
+pub use crate::signing::{AttestationSigner, AttestationVerifier};
+pub use crate::types::{
+    CapabilityAttestation, CapabilityExpiration, CapabilityPermissions, ToolCapability,
+};
@@ -0,0 +1,52 @@
+//! Warm cache and priming declarations
+
+use serde::{Deserialize, Serialize};
+
+use crate::clock::current_timestamp;
+
+/// Kind of pre-warmed asset held by a worker
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WarmAssetKind {
+    /// Cloned repository
+    Repository,
+    /// Prebuilt dependency cache for a repository
+    DependencyCache,
+    /// Downloaded analyzer rule pack
+    RulePack,
+    /// Any other asset identified by name
+    Other(String),
+}
+
+/// A pre-warmed asset with its freshness timestamp
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WarmAsset {
+    /// Kind of asset
+    pub kind: WarmAssetKind,
+    /// Identifier of the asset (repository URL, rule pack name, ...)
+    pub identifier: String,
+    /// Timestamp when the asset was last refreshed
+    pub primed_at: u64,
+}
+
+impl WarmAsset {
+    /// Declare an asset primed now
+    pub fn new(kind: WarmAssetKind, identifier: impl Into<String>) -> Self {
+        Self {
+            kind,
+            identifier: identifier.into(),
+            primed_at: current_timestamp(),
+        }
+    }
+
+    /// Set the timestamp when the asset was last refreshed
+    pub fn primed_at(mut self, timestamp: u64) -> Self {
+        self.primed_at = timestamp;
+        self
+    }
+
+    /// Check if the asset was refreshed within `max_age_seconds` of `now`
+    pub fn is_fresh(&self, now: u64, max_age_seconds: u64) -> bool {
+        now.saturating_sub(self.primed_at) <= max_age_seconds
+    }
+}
@@ -0,0 +1,174 @@
+//! Tool checker that probes installed versions by running the tools
+
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::checker::{run_with_timeout, PathChecker, ToolChecker, ToolStatus};
+use crate::constants::*;
+use crate::pinning::cached_sha256_file;
+
+/// How to obtain the version of one tool
+#[derive(Debug, Clone)]
+pub struct VersionProbe {
+    /// Program and arguments to run
+    pub command: Vec<String>,
+    /// Pattern whose first capture group (or whole match) is the version
+    pub pattern: Regex,
+}
+
+/// Checker that runs a version command per tool and parses its output
+///
+/// A tool is available when its version command runs and exits successfully.
+/// Detected versions feed into semver constraints declared with
+/// `ToolCapability::with_version_req`. Results are cached until
+/// `clear_cache` is called.
+#[derive(Debug)]
+pub struct ProbingToolChecker {
+    probes: HashMap<String, VersionProbe>,
+    default_args: Vec<String>,
+    default_pattern: Regex,
+    timeout: Duration,
+    paths: PathChecker,
+    cache: Mutex<HashMap<String, ToolStatus>>,
+}
+
+impl Default for ProbingToolChecker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProbingToolChecker {
+    /// Create a checker running `<tool> --version` for every tool
+    pub fn new() -> Self {
+        Self {
+            probes: HashMap::new(),
+            default_args: vec![DEFAULT_VERSION_PROBE_ARG.to_string()],
+            default_pattern: Regex::new(DEFAULT_VERSION_PATTERN)
+                .expect("default version pattern is valid"),
+            timeout: Duration::from_secs(DEFAULT_VERSION_PROBE_TIMEOUT_SECONDS),
+            paths: PathChecker::from_env(),
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Set the command used to probe a tool (e.g., `["cargo", "clippy", "--version"]`)
+    pub fn with_command<I, S>(mut self, tool: impl Into<String>, command: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let tool = tool.into();
+        let command = command.into_iter().map(Into::into).collect();
+        let pattern = self.pattern_for(&tool).clone();
+        self.probes.insert(tool, VersionProbe { command, pattern });
+        self
+    }
+
+    /// Set the pattern used to extract the version of a tool
+    pub fn with_pattern(
+        mut self,
+        tool: impl Into<String>,
+        pattern: &str,
+    ) -> Result<Self, regex::Error> {
+        let tool = tool.into();
+        let pattern = Regex::new(pattern)?;
+        let command = self.command_for(&tool);
+        self.probes.insert(tool, VersionProbe { command, pattern });
+        Ok(self)
+    }
+
+    /// Set the pattern used for tools without their own pattern
+    pub fn with_default_pattern(mut self, pattern: &str) -> Result<Self, regex::Error> {
+        self.default_pattern = Regex::new(pattern)?;
+        Ok(self)
+    }
+
+    /// Set how long a version command may run before it is killed
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Forget cached probe results
+    pub fn clear_cache(&self) {
+        self.cache.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+
+    /// Run the version command of a tool (bypassing the cache)
+    pub fn probe(&self, tool: &str) -> ToolStatus {
+        let command = self.command_for(tool);
+        let Some((program, args)) = command.split_first() else {
+            return ToolStatus::missing();
+        };
+
+        let Some(output) = run_with_timeout(program, args, self.timeout) else {
+            return ToolStatus::missing();
+        };
+
+        let mut status = ToolStatus::found();
+        if let Some(version) = extract_version(self.pattern_for(tool), &output) {
+            status = status.with_version(version);
+        }
+        if let Some(path) = self.paths.locate(program) {
+            if let Ok(sha256) = cached_sha256_file(&path) {
+                status = status.with_sha256(sha256);
+            }
+            status = status.with_path(path);
+        }
+        status
+    }
+
+    fn command_for(&self, tool: &str) -> Vec<String> {
+        match self.probes.get(tool) {
+            Some(probe) => probe.command.clone(),
+            None => std::iter::once(tool.to_string())
+                .chain(self.default_args.iter().cloned())
+                .collect(),
+        }
+    }
+
+    fn pattern_for(&self, tool: &str) -> &Regex {
+        self.probes
+            .get(tool)
+            .map(|probe| &probe.pattern)
+            .unwrap_or(&self.default_pattern)
+    }
+}
+
+impl ToolChecker for ProbingToolChecker {
+    fn is_available(&self, tool: &str) -> bool {
+        self.status(tool).available
+    }
+
+    fn detected_version(&self, tool: &str) -> Option<String> {
+        self.status(tool).version
+    }
+
+    fn status(&self, tool: &str) -> ToolStatus {
+        if let Some(status) = self
+            .cache
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(tool)
+        {
+            return status.clone();
+        }
+        let status = self.probe(tool);
+        self.cache
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(tool.to_string(), status.clone());
+        status
+    }
+}
+
+fn extract_version(pattern: &Regex, output: &str) -> Option<String> {
+    let captures = pattern.captures(output)?;
+    captures
+        .get(1)
+        .or_else(|| captures.get(0))
+        .map(|m| m.as_str().to_string())
+}
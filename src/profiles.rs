@@ -0,0 +1,96 @@
+//! Ready-made capability sets for common ecosystems
+//!
+//! Each profile declares the standard tools of an ecosystem with their usual
+//! alternatives and can be extended with the regular builders:
+//!
+//! ```rust
+//! use worker_capabilities::profiles;
+//!
+//! let caps = profiles::rust_worker("rust-worker-01").with_dynamic_tool("valgrind", false);
+//!
+//! let installed = |tool: &str| tool == "cargo-clippy" || tool == "cargo-deny";
+//! assert!(caps.has_capability("static_analysis", &installed));
+//! assert!(caps.has_capability("security_scanning", &installed));
+//! ```
+
+use crate::capabilities::Capabilities;
+use crate::constants::*;
+use crate::types::ToolCapability;
+use crate::well_known;
+
+/// Rust toolchain worker: clippy, rust-analyzer, cargo-audit, cargo-fuzz and cargo-nextest
+pub fn rust_worker(id: impl Into<String>) -> Capabilities {
+    Capabilities::new(id)
+        .with_flag(well_known::AST_SUPPORT)
+        .with_profile_tool(
+            CAPABILITY_STATIC_ANALYSIS,
+            "clippy",
+            true,
+            &["cargo-clippy", "clippy-driver"],
+        )
+        .with_profile_tool(CAPABILITY_STATIC_ANALYSIS, "rust-analyzer", false, &[])
+        .with_profile_tool(
+            CAPABILITY_SECURITY_SCANNING,
+            "cargo-audit",
+            true,
+            &["cargo-deny"],
+        )
+        .with_profile_tool(CAPABILITY_FUZZING, "cargo-fuzz", false, &["cargo-afl"])
+        .with_profile_tool(
+            CAPABILITY_TEST_FRAMEWORK,
+            "cargo-nextest",
+            false,
+            &["cargo"],
+        )
+}
+
+/// Python worker: pylint, mypy, bandit, pip-audit, atheris and pytest
+pub fn python_worker(id: impl Into<String>) -> Capabilities {
+    Capabilities::new(id)
+        .with_flag(well_known::AST_SUPPORT)
+        .with_profile_tool(
+            CAPABILITY_STATIC_ANALYSIS,
+            "pylint",
+            true,
+            &["ruff", "flake8"],
+        )
+        .with_profile_tool(CAPABILITY_STATIC_ANALYSIS, "mypy", false, &["pyright"])
+        .with_profile_tool(CAPABILITY_SECURITY_SCANNING, "bandit", true, &["semgrep"])
+        .with_profile_tool(
+            CAPABILITY_SECURITY_SCANNING,
+            "pip-audit",
+            false,
+            &["safety"],
+        )
+        .with_profile_tool(CAPABILITY_FUZZING, "atheris", false, &[])
+        .with_profile_tool(CAPABILITY_TEST_FRAMEWORK, "pytest", true, &[])
+}
+
+/// Solidity worker: slither, mythril, echidna and foundry
+pub fn solidity_worker(id: impl Into<String>) -> Capabilities {
+    Capabilities::new(id)
+        .with_flag(well_known::EVM_SUPPORT)
+        .with_flag(well_known::AST_SUPPORT)
+        .with_profile_tool(CAPABILITY_STATIC_ANALYSIS, "slither", true, &[])
+        .with_profile_tool(CAPABILITY_SECURITY_SCANNING, "mythril", true, &["myth"])
+        .with_profile_tool(CAPABILITY_FUZZING, "echidna", false, &["medusa"])
+        .with_profile_tool(CAPABILITY_TEST_FRAMEWORK, "forge", false, &["hardhat"])
+}
+
+impl Capabilities {
+    /// Add a tool with alternatives to a built-in category
+    fn with_profile_tool(
+        mut self,
+        category: &str,
+        tool: &str,
+        required: bool,
+        alternatives: &[&str],
+    ) -> Self {
+        let tool = ToolCapability::new(tool, required)
+            .with_alternatives(alternatives.iter().map(|a| a.to_string()).collect());
+        if let Some(tools) = self.tools_in_category_mut(category) {
+            tools.push(tool);
+        }
+        self
+    }
+}
@@ -0,0 +1,229 @@
+//! Protocol version negotiation and down-levelling of capability documents
+//!
+//! Version 1 is the original schema: tool lists, flags and metadata, with the
+//! basic permission set per tool. Version 2 adds network reachability,
+//! sandboxing, artifact formats, models, warm assets, scoping, custom
+//! categories, computed flags and the extended tool and permission fields.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fmt;
+
+use crate::capabilities::Capabilities;
+use crate::constants::*;
+use crate::types::{CapabilityPermissions, ToolCapability};
+
+const V1_CAPABILITIES_FIELDS: &[&str] = &[
+    "id",
+    "static_analysis_tools",
+    "security_scanning_tools",
+    "dynamic_analysis_tools",
+    "fuzzing_tools",
+    "test_framework_tools",
+    "flags",
+    "metadata",
+];
+
+const V1_TOOL_FIELDS: &[&str] = &[
+    "tool_name",
+    "required",
+    "alternatives",
+    "attestation",
+    "permissions",
+    "expiration",
+    "verified",
+];
+
+const V1_PERMISSION_FIELDS: &[&str] = &[
+    "filesystem_access",
+    "network_access",
+    "process_spawn",
+    "env_access",
+    "system_access",
+    "memory_limit_mb",
+    "cpu_limit_percent",
+    "timeout_seconds",
+];
+
+/// Protocol versions a peer supports, exchanged when connecting
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Handshake {
+    /// Newest protocol version the peer speaks
+    pub protocol_version: u32,
+    /// Oldest protocol version the peer still accepts
+    pub min_protocol_version: u32,
+}
+
+impl Default for Handshake {
+    fn default() -> Self {
+        Self {
+            protocol_version: PROTOCOL_VERSION,
+            min_protocol_version: MIN_PROTOCOL_VERSION,
+        }
+    }
+}
+
+impl Handshake {
+    /// Handshake advertising the versions supported by this crate
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pick the newest version both sides support
+    pub fn negotiate(&self, peer: &Handshake) -> Result<u32, ProtocolError> {
+        let version = self.protocol_version.min(peer.protocol_version);
+        if version < self.min_protocol_version || version < peer.min_protocol_version {
+            return Err(ProtocolError::Incompatible {
+                local: *self,
+                peer: *peer,
+            });
+        }
+        Ok(version)
+    }
+}
+
+/// Errors from protocol negotiation and conversion
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProtocolError {
+    /// The peers share no protocol version
+    Incompatible { local: Handshake, peer: Handshake },
+    /// The requested version is not known to this crate
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for ProtocolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProtocolError::Incompatible { local, peer } => write!(
+                f,
+                "no common protocol version (local {}-{}, peer {}-{})",
+                local.min_protocol_version,
+                local.protocol_version,
+                peer.min_protocol_version,
+                peer.protocol_version
+            ),
+            ProtocolError::UnsupportedVersion(version) => write!(
+                f,
+                "unsupported protocol version {} (supported {}-{})",
+                version, MIN_PROTOCOL_VERSION, PROTOCOL_VERSION
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ProtocolError {}
+
+/// Information lost when converting a document to an older protocol version
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DowngradeReport {
+    /// Version the document was converted to
+    pub target_version: u32,
+    /// Paths of dropped fields that carried non-default values
+    pub dropped: Vec<String>,
+}
+
+impl DowngradeReport {
+    /// Check if the conversion was lossless
+    pub fn is_lossless(&self) -> bool {
+        self.dropped.is_empty()
+    }
+}
+
+impl Capabilities {
+    /// Serialize this capability set for a peer speaking an older protocol version
+    ///
+    /// Fields the target version does not know are dropped; those that held
+    /// non-default values are recorded in the report.
+    pub fn to_protocol_version(
+        &self,
+        version: u32,
+    ) -> Result<(Value, DowngradeReport), ProtocolError> {
+        if !(MIN_PROTOCOL_VERSION..=PROTOCOL_VERSION).contains(&version) {
+            return Err(ProtocolError::UnsupportedVersion(version));
+        }
+
+        let mut document = serde_json::to_value(self).unwrap_or(Value::Null);
+        let mut report = DowngradeReport {
+            target_version: version,
+            dropped: Vec::new(),
+        };
+
+        if version == 1 {
+            let Value::Object(fields) = &mut document else {
+                return Ok((document, report));
+            };
+
+            let defaults =
+                serde_json::to_value(Capabilities::new(self.id.clone())).unwrap_or(Value::Null);
+            retain_fields(fields, V1_CAPABILITIES_FIELDS, &defaults, "", &mut report);
+
+            let tool_defaults =
+                serde_json::to_value(ToolCapability::new("", false)).unwrap_or(Value::Null);
+            let permission_defaults =
+                serde_json::to_value(CapabilityPermissions::default()).unwrap_or(Value::Null);
+
+            for category in CAPABILITY_TYPES {
+                let Some(Value::Array(tools)) = fields.get_mut(&format!("{}_tools", category))
+                else {
+                    continue;
+                };
+                for tool in tools.iter_mut().filter_map(Value::as_object_mut) {
+                    let path = format!(
+                        "{}.{}",
+                        category,
+                        tool.get("tool_name")
+                            .and_then(Value::as_str)
+                            .unwrap_or_default()
+                    );
+                    retain_fields(tool, V1_TOOL_FIELDS, &tool_defaults, &path, &mut report);
+                    if let Some(Value::Object(permissions)) = tool.get_mut("permissions") {
+                        retain_fields(
+                            permissions,
+                            V1_PERMISSION_FIELDS,
+                            &permission_defaults,
+                            &format!("{}.permissions", path),
+                            &mut report,
+                        );
+                    }
+                }
+            }
+        }
+
+        Ok((document, report))
+    }
+
+    /// Serialize this capability set for the version negotiated with a peer
+    pub fn to_peer(
+        &self,
+        local: &Handshake,
+        peer: &Handshake,
+    ) -> Result<(Value, DowngradeReport), ProtocolError> {
+        self.to_protocol_version(local.negotiate(peer)?)
+    }
+}
+
+fn retain_fields(
+    fields: &mut Map<String, Value>,
+    known: &[&str],
+    defaults: &Value,
+    path: &str,
+    report: &mut DowngradeReport,
+) {
+    let mut unknown: Vec<String> = fields
+        .keys()
+        .filter(|key| !known.contains(&key.as_str()))
+        .cloned()
+        .collect();
+    unknown.sort();
+
+    for key in unknown {
+        let value = fields.remove(&key);
+        if value.as_ref() != defaults.get(&key) {
+            report.dropped.push(if path.is_empty() {
+                key
+            } else {
+                format!("{}.{}", path, key)
+            });
+        }
+    }
+}
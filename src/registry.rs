@@ -1,15 +1,73 @@
 //! Registry for managing multiple capability sets
 
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
 
-use crate::constants::*;
-use crate::types::{CapabilitySecurityReport};
 use crate::capabilities::Capabilities;
+use crate::checker::ToolChecker;
+use crate::clock::{self, current_timestamp, Clock, InjectedClock, SharedClock};
+use crate::constants::{
+    MAX_AUDIT_LOG_EVENTS, MAX_REGISTERED_WORKERS, MAX_REGISTRATION_REJECTIONS,
+    SESSION_RETENTION_SECONDS,
+};
+use crate::derive::FlagRule;
+use crate::expr::CapabilityExpr;
+use crate::grants::{AuditEvent, AuditEventKind, GrantError, GrantOverlay};
+use crate::health::{WorkerHealth, WorkerHealthReport, WorkerHealthTransition};
+use crate::match_cache::{MatchCache, MatchCacheStats};
+use crate::platform::Platform;
+use crate::requirements::{MatchResult, Requirements};
+use crate::retry::{RetryBudget, RetryBudgetPolicy};
+use crate::revocation::{apply_revocations, revoked_hash, RevocationList, RevocationListError};
+use crate::revocation_checker::{
+    RevocationChecker, RevocationFailurePolicy, WorkerRevocationChecker,
+};
+use crate::session::{Session, SessionError, SessionState};
+use crate::shedding::{LoadLevel, MatchPriority, Shed, SheddingError, SheddingPolicy};
+use crate::signing::{encode_fields, AlgorithmRegistry, AttestationVerifier};
+use crate::telemetry::{DemotionPolicy, HealthTransition, JobOutcome, ToolHealth};
+use crate::trust::{
+    RevocationEntry, TrustAnchor, TrustBundle, TrustBundleError, TrustPolicy, TrustStore,
+};
+use crate::types::{CapabilityAttestation, CapabilitySecurityReport, ToolCapability};
+use crate::validation::{RegistrationRejection, ValidationError};
 
 /// Registry for managing multiple capability sets
 #[derive(Debug, Default)]
 pub struct CapabilityRegistry {
     capabilities: HashMap<String, Capabilities>,
+    sessions: HashMap<String, Session>,
+    next_session_id: u64,
+    load_level: LoadLevel,
+    shedding_policy: SheddingPolicy,
+    demotion_policy: DemotionPolicy,
+    tool_health: HashMap<(String, String), ToolHealth>,
+    trust_store: TrustStore,
+    trust_on_first_use: bool,
+    flag_rules: Vec<FlagRule>,
+    grants: HashMap<String, Vec<GrantOverlay>>,
+    next_grant_id: u64,
+    audit_log: Vec<AuditEvent>,
+    retry_policy: RetryBudgetPolicy,
+    retry_budgets: HashMap<String, RetryBudget>,
+    worker_health: HashMap<String, WorkerHealthReport>,
+    revision: u64,
+    match_cache: Option<MatchCache>,
+    validate_on_register: bool,
+    templates: HashMap<String, Capabilities>,
+    algorithms: AlgorithmRegistry,
+    trust_policy: Option<TrustPolicy>,
+    max_attestation_age: Option<u64>,
+    attestation_counters: HashMap<(String, String), u64>,
+    revocations: Vec<(String, RevocationEntry)>,
+    revocation_sequences: HashMap<String, u64>,
+    max_revocation_list_age: Option<u64>,
+    revocation_checker: Option<WorkerRevocationChecker>,
+    revocation_failure_policy: RevocationFailurePolicy,
+    rejections: VecDeque<RegistrationRejection>,
+    rejected_registrations: usize,
+    clock: InjectedClock,
 }
 
 impl CapabilityRegistry {
@@ -17,12 +75,406 @@ impl CapabilityRegistry {
     pub fn new() -> Self {
         Self {
             capabilities: HashMap::new(),
+            sessions: HashMap::new(),
+            next_session_id: 0,
+            load_level: LoadLevel::Normal,
+            shedding_policy: SheddingPolicy::default(),
+            demotion_policy: DemotionPolicy::default(),
+            tool_health: HashMap::new(),
+            trust_store: TrustStore::default(),
+            trust_on_first_use: false,
+            flag_rules: Vec::new(),
+            grants: HashMap::new(),
+            next_grant_id: 0,
+            audit_log: Vec::new(),
+            retry_policy: RetryBudgetPolicy::default(),
+            retry_budgets: HashMap::new(),
+            worker_health: HashMap::new(),
+            revision: 0,
+            match_cache: None,
+            validate_on_register: false,
+            templates: HashMap::new(),
+            algorithms: AlgorithmRegistry::new(),
+            trust_policy: None,
+            max_attestation_age: None,
+            attestation_counters: HashMap::new(),
+            revocations: Vec::new(),
+            revocation_sequences: HashMap::new(),
+            max_revocation_list_age: None,
+            revocation_checker: None,
+            revocation_failure_policy: RevocationFailurePolicy::default(),
+            rejections: VecDeque::new(),
+            rejected_registrations: 0,
+            clock: InjectedClock::default(),
         }
     }
 
+    /// Read time from a clock instead of the thread clock
+    ///
+    /// Registration, matching, verification, sessions, grants, retry budgets
+    /// and health install it for the calling thread while they run, so the
+    /// parallel and timeout-bounded checks they start read it too.
+    pub fn with_clock(mut self, clock: impl Clock + Send + Sync + 'static) -> Self {
+        self.clock = InjectedClock::new(clock);
+        self
+    }
+
+    /// Clock of the registry, or else the clock of the current thread
+    pub(crate) fn clock(&self) -> Option<SharedClock> {
+        self.clock.current()
+    }
+
+    /// Set the rules used to derive flags of registered capability sets
+    pub fn with_flag_rules(mut self, rules: Vec<FlagRule>) -> Self {
+        self.flag_rules = rules;
+        self
+    }
+
+    /// Set the algorithms accepted when verifying registered workers
+    pub fn with_algorithms(mut self, algorithms: AlgorithmRegistry) -> Self {
+        self.algorithms = algorithms;
+        self
+    }
+
+    /// Algorithms accepted when verifying registered workers
+    pub fn algorithms(&self) -> &AlgorithmRegistry {
+        &self.algorithms
+    }
+
+    /// Consult a revocation checker when verifying registered workers
+    ///
+    /// Every attestation of a worker is checked with the worker's ID, so
+    /// revoked workers and revoked capability hashes both fail verification.
+    pub fn with_revocation_checker(
+        mut self,
+        checker: impl RevocationChecker + Send + Sync + 'static,
+    ) -> Self {
+        self.revocation_checker = Some(WorkerRevocationChecker::new(checker));
+        self
+    }
+
+    /// Reject imported revocation lists issued more than a number of seconds ago
+    pub fn with_max_revocation_list_age(mut self, seconds: u64) -> Self {
+        self.max_revocation_list_age = Some(seconds);
+        self
+    }
+
+    /// Set what verification does when the revocation status cannot be determined
+    pub fn with_revocation_failure_policy(mut self, policy: RevocationFailurePolicy) -> Self {
+        self.revocation_failure_policy = policy;
+        self
+    }
+
+    /// Pin a key trusted to sign trust bundles and revocation lists
+    pub fn with_trust_anchor(mut self, anchor: TrustAnchor) -> Self {
+        self.trust_store.anchors.push(anchor);
+        self
+    }
+
+    /// Accept the first validly signed trust bundle while no anchor is pinned
+    ///
+    /// Without this, bundles are only accepted from anchors pinned with
+    /// `with_trust_anchor` or by a previously applied bundle.
+    pub fn with_trust_on_first_use(mut self) -> Self {
+        self.trust_on_first_use = true;
+        self
+    }
+
+    /// Only count attestations from attesters trusted by a policy
+    pub fn with_trust_policy(mut self, policy: TrustPolicy) -> Self {
+        self.trust_policy = Some(policy);
+        self
+    }
+
+    /// Set or clear the policy deciding which attesters count
+    pub fn set_trust_policy(&mut self, policy: Option<TrustPolicy>) {
+        self.trust_policy = policy;
+        self.record_change();
+    }
+
+    /// Policy deciding which attesters count, if any
+    pub fn trust_policy(&self) -> Option<&TrustPolicy> {
+        self.trust_policy.as_ref()
+    }
+
+    /// Reject attestations older than a number of seconds
+    pub fn with_max_attestation_age(mut self, seconds: u64) -> Self {
+        self.max_attestation_age = Some(seconds);
+        self
+    }
+
+    /// Set or clear the maximum age of accepted attestations
+    pub fn set_max_attestation_age(&mut self, seconds: Option<u64>) {
+        self.max_attestation_age = seconds;
+        self.record_change();
+    }
+
+    /// Maximum age of accepted attestations in seconds, if any
+    pub fn max_attestation_age(&self) -> Option<u64> {
+        self.max_attestation_age
+    }
+
+    /// Highest issuance counter registered for a worker's tool
+    ///
+    /// Counters are kept when the worker is removed, so a re-provisioned
+    /// worker cannot be registered with an older attestation, and only reset
+    /// by `clear_all`. The set attestation is recorded under the worker ID.
+    pub fn last_attestation_counter(&self, worker_id: &str, tool_name: &str) -> Option<u64> {
+        self.attestation_counters
+            .get(&(worker_id.to_string(), tool_name.to_string()))
+            .copied()
+    }
+
+    /// Add a template that registered capability sets can extend
+    ///
+    /// Fails with `ValidationError::UnknownTemplate` if the template extends
+    /// one the registry does not know.
+    pub fn with_template(
+        mut self,
+        name: impl Into<String>,
+        base: Capabilities,
+    ) -> Result<Self, ValidationError> {
+        self.register_template(name, base)?;
+        Ok(self)
+    }
+
+    /// Add or replace a template that registered capability sets can extend
+    ///
+    /// A template extending another template is resolved now, so later
+    /// changes to its own base do not affect it.
+    pub fn register_template(
+        &mut self,
+        name: impl Into<String>,
+        base: Capabilities,
+    ) -> Result<(), ValidationError> {
+        let base = self.resolve_template(base)?;
+        self.templates.insert(name.into(), base);
+        Ok(())
+    }
+
+    /// Get a template by name
+    pub fn template(&self, name: &str) -> Option<&Capabilities> {
+        self.templates.get(name)
+    }
+
+    /// Resolve a capability set against the template it extends (if any)
+    fn resolve_template(&self, caps: Capabilities) -> Result<Capabilities, ValidationError> {
+        match caps.template.as_deref() {
+            Some(name) => match self.templates.get(name) {
+                Some(base) => Ok(caps.extends(base)),
+                None => Err(ValidationError::UnknownTemplate {
+                    name: name.to_string(),
+                }),
+            },
+            None => Ok(caps),
+        }
+    }
+
+    /// Cache ranked match results of repeated requirement sets and load-shed capability queries
+    pub fn with_match_cache(mut self, cache: MatchCache) -> Self {
+        self.match_cache = Some(cache);
+        self
+    }
+
+    /// Revision of the registry, incremented on every change affecting matches
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Hit-rate metrics of the match cache (`None` if no cache is attached)
+    pub fn match_cache_stats(&self) -> Option<MatchCacheStats> {
+        self.match_cache.as_ref().map(MatchCache::stats)
+    }
+
+    /// Invalidate cached matches after a change
+    fn record_change(&mut self) {
+        self.revision += 1;
+        if let Some(cache) = &self.match_cache {
+            cache.invalidate();
+        }
+    }
+
+    /// Flag derivation rules applied on registration
+    pub fn flag_rules(&self) -> &[FlagRule] {
+        &self.flag_rules
+    }
+
+    /// Reject capability sets that fail `Capabilities::validate` or exceed the worker limit
+    pub fn with_validation(mut self) -> Self {
+        self.validate_on_register = true;
+        self
+    }
+
     /// Register a capability set
+    ///
+    /// Flags covered by the registry's derivation rules are recomputed from the
+    /// declared tools instead of being trusted from the manifest. Sets that
+    /// `try_register` rejects are not registered; the rejection is recorded
+    /// in `registration_rejections` and counted in the statistics. Prefer
+    /// `try_register` where the caller can handle the error.
     pub fn register(&mut self, caps: Capabilities) {
+        let _clock = self.clock.enter();
+        let worker_id = caps.id.clone();
+        if let Err(errors) = self.try_register(caps) {
+            self.rejected_registrations += 1;
+            if self.rejections.len() >= MAX_REGISTRATION_REJECTIONS {
+                self.rejections.pop_front();
+            }
+            self.rejections.push_back(RegistrationRejection {
+                worker_id,
+                rejected_at: current_timestamp(),
+                errors,
+            });
+        }
+    }
+
+    /// Most recent sets `register` refused, oldest first
+    ///
+    /// Holds at most `MAX_REGISTRATION_REJECTIONS` entries.
+    pub fn registration_rejections(&self) -> impl Iterator<Item = &RegistrationRejection> {
+        self.rejections.iter()
+    }
+
+    /// Register a capability set, returning the violations that prevented it
+    ///
+    /// Sets are only checked against the limits when the registry was created
+    /// `with_validation`. Attestations older than the maximum attestation age,
+    /// or with an issuance counter not above (or missing after) one already
+    /// registered for the worker's tool, are always rejected, so re-registering
+    /// a set needs freshly issued attestations. Only attestations that verify
+    /// advance the registered counters.
+    pub fn try_register(&mut self, caps: Capabilities) -> Result<(), Vec<ValidationError>> {
+        let _clock = self.clock.enter();
+        let mut caps = self.resolve_template(caps).map_err(|e| vec![e])?;
+        let mut errors = Vec::new();
+        if self.validate_on_register {
+            errors = caps.validate().err().unwrap_or_default();
+            if !self.capabilities.contains_key(&caps.id)
+                && self.capabilities.len() >= MAX_REGISTERED_WORKERS
+            {
+                errors.push(ValidationError::TooManyWorkers {
+                    count: self.capabilities.len(),
+                });
+            }
+        }
+        errors.extend(self.check_attestation_freshness(&caps));
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        let counters: Vec<(String, u64)> = self
+            .verified_attestations(&caps)
+            .filter_map(|(subject, attestation)| Some((subject.to_string(), attestation.counter?)))
+            .collect();
+
+        if !self.flag_rules.is_empty() {
+            caps.derive_declared_flags(&self.flag_rules);
+        }
+        apply_revocations(
+            &mut caps,
+            self.revocations
+                .iter()
+                .map(|(issuer, entry)| (issuer.as_str(), entry)),
+        );
+        for (subject, counter) in counters {
+            let last = self
+                .attestation_counters
+                .entry((caps.id.clone(), subject))
+                .or_insert(counter);
+            *last = (*last).max(counter);
+        }
+        self.reconcile_tool_health(&mut caps);
+        self.record_change();
         self.capabilities.insert(caps.id.clone(), caps);
+        Ok(())
+    }
+
+    /// Keep telemetry suspensions across re-registration
+    ///
+    /// Suspended tools stay suspended until they recover on probation, and
+    /// the health of tools the new set no longer declares is forgotten.
+    fn reconcile_tool_health(&mut self, caps: &mut Capabilities) {
+        self.tool_health.retain(|(worker_id, tool_name), health| {
+            if *worker_id != caps.id {
+                return true;
+            }
+            let Some(tool) = caps.find_tool_mut(tool_name) else {
+                return false;
+            };
+            tool.suspended = health.suspended;
+            true
+        });
+    }
+
+    /// Stale or replayed attestations of a set about to be registered
+    fn check_attestation_freshness(&self, caps: &Capabilities) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+        for (subject, attestation) in attestations(caps) {
+            if !self.within_max_age(attestation) {
+                errors.push(ValidationError::StaleAttestation {
+                    tool: subject.to_string(),
+                });
+            }
+            let Some(last_seen) = self.last_attestation_counter(&caps.id, subject) else {
+                continue;
+            };
+            match attestation.counter {
+                Some(counter) if counter <= last_seen => {
+                    errors.push(ValidationError::ReplayedAttestation {
+                        tool: subject.to_string(),
+                        counter,
+                        last_seen,
+                    });
+                }
+                Some(_) => {}
+                None => errors.push(ValidationError::MissingAttestationCounter {
+                    tool: subject.to_string(),
+                    last_seen,
+                }),
+            }
+        }
+        errors
+    }
+
+    /// Attestations of a set whose signature verifies and whose attester is trusted
+    fn verified_attestations<'a>(
+        &'a self,
+        caps: &'a Capabilities,
+    ) -> impl Iterator<Item = (&'a str, &'a CapabilityAttestation)> {
+        let tools = caps.iter_tools().filter_map(move |(category, tool)| {
+            let attestation = tool.attestation.as_ref()?;
+            let trusted = self
+                .trust_policy
+                .as_ref()
+                .is_none_or(|policy| policy.allows(attestation, category));
+            (trusted && tool.verify_attestation_with(&self.algorithms))
+                .then_some((tool.tool_name.as_str(), attestation))
+        });
+        let set = caps.attestation.as_ref().filter(|attestation| {
+            self.trust_policy
+                .as_ref()
+                .is_none_or(|policy| policy.allows_all_categories(attestation))
+                && caps.verify_set_attestation_with(&self.algorithms)
+        });
+        tools.chain(set.map(|attestation| (caps.id.as_str(), attestation)))
+    }
+
+    /// Check an attestation's age, granting the algorithms' expiration tolerance
+    fn within_max_age(&self, attestation: &CapabilityAttestation) -> bool {
+        self.issued_within(attestation.timestamp, self.max_attestation_age)
+    }
+
+    /// Check the age of a timestamp, granting the algorithms' expiration tolerance
+    fn issued_within(&self, timestamp: u64, max_age: Option<u64>) -> bool {
+        let Some(max_age) = max_age else {
+            return true;
+        };
+        let tolerance = self.algorithms.expiration_tolerance();
+        let max_age = max_age
+            .saturating_add(tolerance.skew_tolerance())
+            .saturating_add(tolerance.grace_period());
+        clock::unix_timestamp()
+            .and_then(|now| clock::age_within(timestamp, now, tolerance.skew_tolerance()))
+            .is_ok_and(|age| age <= max_age)
     }
 
     /// Get capabilities by ID
@@ -32,6 +484,7 @@ impl CapabilityRegistry {
 
     /// Get mutable capabilities by ID
     pub fn get_mut(&mut self, id: &str) -> Option<&mut Capabilities> {
+        self.record_change();
         self.capabilities.get_mut(id)
     }
 
@@ -41,22 +494,151 @@ impl CapabilityRegistry {
     }
 
     /// Find workers with a specific capability
+    ///
+    /// Tools whose concurrency slots are exhausted by active sessions do not
+    /// count, and workers with an exhausted retry budget or an unhealthy
+    /// health report are skipped.
     pub fn find_with_capability(
         &self,
         capability_type: &str,
-        tool_checker: &dyn Fn(&str) -> bool,
+        tool_checker: &(impl ToolChecker + ?Sized),
     ) -> Vec<&Capabilities> {
+        let _clock = self.clock.enter();
         self.capabilities
             .values()
-            .filter(|caps| caps.has_capability(capability_type, tool_checker))
+            .filter(|caps| self.is_selectable(&caps.id))
+            .filter(|caps| {
+                caps.tools_in_category(capability_type).iter().any(|tool| {
+                    tool.is_satisfied(tool_checker) && self.has_free_slot(&caps.id, tool)
+                })
+            })
+            .collect()
+    }
+
+    /// Find workers with a capability that are allowed to analyze a target repository
+    pub fn find_with_capability_for_target(
+        &self,
+        capability_type: &str,
+        tool_checker: &(impl ToolChecker + ?Sized),
+        repository: &str,
+    ) -> Vec<&Capabilities> {
+        let _clock = self.clock.enter();
+        self.find_with_capability(capability_type, tool_checker)
+            .into_iter()
+            .filter(|caps| {
+                caps.has_capability_for_target(capability_type, tool_checker, repository)
+            })
+            .collect()
+    }
+
+    /// Find workers with a capability whose worker and tool run on a platform
+    pub fn find_with_capability_for_platform(
+        &self,
+        capability_type: &str,
+        tool_checker: &(impl ToolChecker + ?Sized),
+        platform: &Platform,
+    ) -> Vec<&Capabilities> {
+        let _clock = self.clock.enter();
+        self.find_with_capability(capability_type, tool_checker)
+            .into_iter()
+            .filter(|caps| {
+                caps.has_capability_for_platform(capability_type, tool_checker, platform)
+            })
+            .collect()
+    }
+
+    /// Find workers whose environment meets the given requirements
+    ///
+    /// Workers with an exhausted retry budget or an unhealthy health report are skipped.
+    pub fn find_meeting_requirements(&self, requirements: &Requirements) -> Vec<&Capabilities> {
+        let _clock = self.clock.enter();
+        self.capabilities
+            .values()
+            .filter(|caps| {
+                self.is_selectable(&caps.id) && self.granted(caps).meets_requirements(requirements)
+            })
+            .collect()
+    }
+
+    /// Find workers satisfying both the capability clauses and environment requirements of a job
+    ///
+    /// Workers with an exhausted retry budget or an unhealthy health report are skipped.
+    pub fn find_satisfying(
+        &self,
+        requirements: &Requirements,
+        tool_checker: &(impl ToolChecker + ?Sized),
+    ) -> Vec<&Capabilities> {
+        let _clock = self.clock.enter();
+        self.capabilities
+            .values()
+            .filter(|caps| {
+                self.is_selectable(&caps.id)
+                    && self
+                        .granted(caps)
+                        .satisfies(requirements, tool_checker)
+                        .is_match()
+            })
+            .collect()
+    }
+
+    /// Find workers satisfying a capability expression
+    ///
+    /// Workers with an exhausted retry budget or an unhealthy health report are skipped.
+    pub fn find_matching_expr(
+        &self,
+        expr: &CapabilityExpr,
+        tool_checker: &(impl ToolChecker + ?Sized),
+    ) -> Vec<&Capabilities> {
+        let _clock = self.clock.enter();
+        self.capabilities
+            .values()
+            .filter(|caps| {
+                self.is_selectable(&caps.id) && self.granted(caps).matches_expr(expr, tool_checker)
+            })
+            .collect()
+    }
+
+    /// Rank workers meeting the requirements by preference score, best first
+    pub fn rank_workers(&self, requirements: &Requirements) -> Vec<&Capabilities> {
+        let _clock = self.clock.enter();
+        let mut ranked = self.find_meeting_requirements(requirements);
+        ranked.sort_by(|a, b| {
+            b.preference_score(requirements)
+                .cmp(&a.preference_score(requirements))
+                .then_with(|| a.id.cmp(&b.id))
+        });
+        ranked
+    }
+
+    /// IDs of the workers ranked by `rank_workers`, served from the match cache when attached
+    pub fn rank_workers_cached(&self, requirements: &Requirements) -> Vec<String> {
+        let _clock = self.clock.enter();
+        let Some(cache) = &self.match_cache else {
+            return self.rank_worker_ids(requirements);
+        };
+
+        let digest = requirements.digest();
+        if let Some(worker_ids) = cache.get(&digest, self.revision) {
+            return worker_ids;
+        }
+        let worker_ids = self.rank_worker_ids(requirements);
+        cache.insert(digest, self.revision, worker_ids.clone());
+        worker_ids
+    }
+
+    fn rank_worker_ids(&self, requirements: &Requirements) -> Vec<String> {
+        self.rank_workers(requirements)
+            .into_iter()
+            .map(|caps| caps.id.clone())
             .collect()
     }
 
     /// Find workers with verified capabilities
     pub fn find_verified_workers(&self) -> Vec<&Capabilities> {
+        let _clock = self.clock.enter();
         self.capabilities
             .values()
-            .filter(|caps| caps.verify_all_capabilities())
+            .filter(|caps| self.verifies(caps))
             .collect()
     }
 
@@ -69,31 +651,63 @@ impl CapabilityRegistry {
     ) -> bool {
         if let Some(caps) = self.capabilities.get_mut(worker_id) {
             caps.revoke_all_capabilities(reason, revoked_by);
+            self.record_change();
             true
         } else {
             false
         }
     }
 
-    /// Get security report for all workers
-    pub fn get_security_report(&self) -> HashMap<String, HashMap<String, CapabilitySecurityReport>> {
+    /// Get security report for all workers, verifying with the registry's algorithms
+    pub fn get_security_report(
+        &self,
+    ) -> HashMap<String, HashMap<String, CapabilitySecurityReport>> {
+        let _clock = self.clock.enter();
         let mut report = HashMap::new();
-        
+
         for (worker_id, capabilities) in &self.capabilities {
-            report.insert(worker_id.clone(), capabilities.get_security_report());
+            report.insert(
+                worker_id.clone(),
+                capabilities.security_report_with(&self.algorithms),
+            );
         }
-        
+
         report
     }
 
+    /// Check a worker's capabilities with the registry's algorithms and trust policy
+    ///
+    /// Attestations older than the maximum attestation age, or rejected by
+    /// the revocation checker, do not verify.
+    pub(crate) fn verifies(&self, caps: &Capabilities) -> bool {
+        if !attestations(caps).all(|(_, attestation)| self.within_max_age(attestation)) {
+            return false;
+        }
+        if let Some(checker) = &self.revocation_checker {
+            let revoked = attestations(caps).any(|(_, attestation)| {
+                checker.rejects(&caps.id, attestation, self.revocation_failure_policy)
+            });
+            if revoked {
+                return false;
+            }
+        }
+        match &self.trust_policy {
+            Some(policy) => caps
+                .try_verify_trusted_with(policy, &self.algorithms)
+                .is_ok(),
+            None => caps.verify_all_capabilities_with(&self.algorithms),
+        }
+    }
+
     /// Verify all workers have valid capabilities
     pub fn verify_all_workers(&self) -> HashMap<String, bool> {
+        let _clock = self.clock.enter();
         let mut results = HashMap::new();
-        
+
         for (worker_id, capabilities) in &self.capabilities {
-            results.insert(worker_id.clone(), capabilities.verify_all_capabilities());
+            results.insert(worker_id.clone(), self.verifies(capabilities));
         }
-        
+
         results
     }
 
@@ -103,26 +717,214 @@ impl CapabilityRegistry {
         capability_type: &str,
         required_permission: &str,
     ) -> Vec<&Capabilities> {
+        let _clock = self.clock.enter();
         self.capabilities
             .values()
-            .filter(|caps| caps.has_required_permissions(capability_type, required_permission))
+            .filter(|caps| {
+                self.granted(caps)
+                    .has_required_permissions(capability_type, required_permission)
+            })
             .collect()
     }
 
-    /// Get registry statistics
+    /// Trust configuration applied to this registry
+    pub fn trust_store(&self) -> &TrustStore {
+        &self.trust_store
+    }
+
+    /// Verify and apply a trust bundle, replacing the trust configuration
+    ///
+    /// Bundles must be signed by one of the registry's anchors, unless no
+    /// bundle has been applied yet and the registry trusts on first use.
+    /// With `require_trusted_attester`, the trust policy counts only the
+    /// bundle's attesters (keeping its thresholds); otherwise the policy is
+    /// cleared. The bundle's maximum attestation age replaces the registry's.
+    /// Revocations take effect immediately. Returns the number of tools revoked.
+    pub fn apply_trust_bundle(
+        &mut self,
+        bundle: &TrustBundle,
+        verifier: &dyn AttestationVerifier,
+    ) -> Result<usize, TrustBundleError> {
+        bundle.verify_signature(verifier)?;
+
+        let first_use = self.trust_on_first_use
+            && self.trust_store.anchors.is_empty()
+            && self.trust_store.bundle_version == 0;
+        if !first_use && !self.trust_store.is_anchor(&bundle.public_key) {
+            return Err(TrustBundleError::UntrustedSigner(bundle.signer.clone()));
+        }
+        if bundle.version <= self.trust_store.bundle_version {
+            return Err(TrustBundleError::StaleVersion {
+                current: self.trust_store.bundle_version,
+                received: bundle.version,
+            });
+        }
+
+        self.trust_store = TrustStore {
+            anchors: bundle.anchors.clone(),
+            attesters: bundle.attesters.clone(),
+            revocations: bundle.revocations.clone(),
+            settings: bundle.settings.clone(),
+            bundle_version: bundle.version,
+        };
+        let settings = &bundle.settings;
+        self.trust_policy = settings.require_trusted_attester.then(|| TrustPolicy {
+            attesters: bundle.attesters.clone(),
+            thresholds: self
+                .trust_policy
+                .take()
+                .map(|policy| policy.thresholds)
+                .unwrap_or_default(),
+        });
+        self.max_attestation_age = settings
+            .max_attestation_age_days
+            .map(|days| days.saturating_mul(24 * 60 * 60));
+        self.record_change();
+
+        let mut revoked = 0;
+        for (worker_id, caps) in self.capabilities.iter_mut() {
+            for tool in caps.tools_iter_mut() {
+                if tool.is_revoked() {
+                    continue;
+                }
+                let hash = tool
+                    .attestation
+                    .as_ref()
+                    .map(|a| a.capability_hash.as_str());
+                if let Some(entry) = self.trust_store.revocation_for(worker_id, hash) {
+                    let reason = entry.reason.clone();
+                    tool.revoke(reason, bundle.signer.clone());
+                    revoked += 1;
+                }
+            }
+        }
+
+        Ok(revoked)
+    }
+
+    /// Export the revocations known to this registry
+    ///
+    /// Lists imported revocations, those of the applied trust bundle, and the
+    /// capability hash of every revoked tool. The list is unsigned; set its
+    /// sequence and `sign` it before handing it to other registries.
+    pub fn export_revocations(&self, issuer: impl Into<String>) -> RevocationList {
+        let mut list = RevocationList::new(issuer);
+        for (_, entry) in &self.revocations {
+            list.add(entry.clone());
+        }
+        for entry in &self.trust_store.revocations {
+            list.add(entry.clone());
+        }
+
+        let mut worker_ids: Vec<&String> = self.capabilities.keys().collect();
+        worker_ids.sort();
+        for worker_id in worker_ids {
+            for tool in self.capabilities[worker_id].tools_iter() {
+                let hash = revoked_hash(tool);
+                if !tool.is_revoked() || list.revocation_for(worker_id, Some(&hash)).is_some() {
+                    continue;
+                }
+                let reason = tool
+                    .expiration
+                    .revocation_reason
+                    .clone()
+                    .unwrap_or_default();
+                list.add(RevocationEntry {
+                    revoked_at: tool.expiration.revoked_at.unwrap_or(list.issued_at),
+                    ..RevocationEntry::capability(hash, reason)
+                });
+            }
+        }
+        list
+    }
+
+    /// Verify and import the revocations of another registry
+    ///
+    /// The list must be signed by one of the registry's trust anchors, name
+    /// that anchor as its issuer, be issued within the maximum revocation
+    /// list age (if set) and not be older than a list already imported from
+    /// its issuer. Matching
+    /// tools are revoked now, by the list's issuer, and tools of sets
+    /// registered later are revoked on registration. Returns the number of
+    /// tools revoked now.
+    pub fn import_revocations(
+        &mut self,
+        list: &RevocationList,
+        verifier: &dyn AttestationVerifier,
+    ) -> Result<usize, RevocationListError> {
+        let _clock = self.clock.enter();
+        list.verify_signature(verifier)?;
+        let anchor = self
+            .trust_store
+            .anchors
+            .iter()
+            .find(|anchor| anchor.public_key == list.public_key)
+            .ok_or_else(|| RevocationListError::UntrustedSigner(list.signer.clone()))?;
+        if anchor.name != list.issuer {
+            return Err(RevocationListError::IssuerMismatch {
+                issuer: list.issuer.clone(),
+                anchor: anchor.name.clone(),
+            });
+        }
+        if !self.issued_within(list.issued_at, self.max_revocation_list_age) {
+            return Err(RevocationListError::Outdated {
+                issued_at: list.issued_at,
+            });
+        }
+        if let Some(&current) = self.revocation_sequences.get(&list.issuer) {
+            if list.sequence < current {
+                return Err(RevocationListError::StaleSequence {
+                    issuer: list.issuer.clone(),
+                    current,
+                    received: list.sequence,
+                });
+            }
+        }
+        self.revocation_sequences
+            .insert(list.issuer.clone(), list.sequence);
+
+        for entry in &list.entries {
+            if !self
+                .revocations
+                .iter()
+                .any(|(_, known)| known.same_subject(entry))
+            {
+                self.revocations.push((list.issuer.clone(), entry.clone()));
+            }
+        }
+
+        let entries = self
+            .revocations
+            .iter()
+            .map(|(issuer, entry)| (issuer.as_str(), entry));
+        let revoked: usize = self
+            .capabilities
+            .values_mut()
+            .map(|caps| apply_revocations(caps, entries.clone()))
+            .sum();
+        if revoked > 0 {
+            self.record_change();
+        }
+        Ok(revoked)
+    }
+
+    /// Get registry statistics, counting attestations that verify with the registry's algorithms
     pub fn get_statistics(&self) -> RegistryStatistics {
+        let _clock = self.clock.enter();
         let total_workers = self.capabilities.len();
         let verified_workers = self.find_verified_workers().len();
-        
+
         let mut total_tools = 0;
         let mut total_required_tools = 0;
         let mut total_verified_tools = 0;
-        
+        let mut total_deprecated_tools = 0;
+
         for capabilities in self.capabilities.values() {
-            let stats = capabilities.get_statistics();
+            let stats = capabilities.statistics_with(&self.algorithms);
             total_tools += stats.total_tools;
             total_required_tools += stats.required_tools;
             total_verified_tools += stats.verified_tools;
+            total_deprecated_tools += stats.deprecated_tools;
         }
 
         RegistryStatistics {
@@ -131,16 +933,30 @@ impl CapabilityRegistry {
             total_tools,
             total_required_tools,
             total_verified_tools,
+            total_deprecated_tools,
+            active_sessions: self.sessions.values().filter(|s| s.is_active()).count(),
+            rejected_registrations: self.rejected_registrations,
         }
     }
 
     /// Remove a worker from the registry
     pub fn remove_worker(&mut self, worker_id: &str) -> Option<Capabilities> {
+        self.record_change();
+        self.grants.remove(worker_id);
+        self.retry_budgets.remove(worker_id);
+        self.worker_health.remove(worker_id);
+        self.tool_health.retain(|(id, _), _| id != worker_id);
         self.capabilities.remove(worker_id)
     }
 
     /// Clear all workers from the registry
     pub fn clear_all(&mut self) {
+        self.record_change();
+        self.grants.clear();
+        self.retry_budgets.clear();
+        self.worker_health.clear();
+        self.tool_health.clear();
+        self.attestation_counters.clear();
         self.capabilities.clear();
     }
 
@@ -157,6 +973,14 @@ impl CapabilityRegistry {
             .collect()
     }
 
+    /// Get all workers carrying a tag
+    pub fn find_workers_with_tag(&self, tag: &str) -> Vec<&Capabilities> {
+        self.capabilities
+            .values()
+            .filter(|caps| caps.has_tag(tag))
+            .collect()
+    }
+
     /// Get workers with metadata matching a key-value pair
     pub fn find_workers_with_metadata(&self, key: &str, value: &str) -> Vec<&Capabilities> {
         self.capabilities
@@ -165,26 +989,805 @@ impl CapabilityRegistry {
             .collect()
     }
 
+    /// Get workers with a tool whose metadata matches a key-value pair
+    pub fn find_workers_with_tool_metadata(&self, key: &str, value: &str) -> Vec<&Capabilities> {
+        self.capabilities
+            .values()
+            .filter(|caps| !caps.tools_with_metadata(key, value).is_empty())
+            .collect()
+    }
+
     /// Get all unique tool names across all workers
     pub fn get_all_tool_names(&self) -> std::collections::HashSet<String> {
         let mut tool_names = std::collections::HashSet::new();
-        
+
         for capabilities in self.capabilities.values() {
             for tool_name in capabilities.all_tools() {
                 tool_names.insert(tool_name);
             }
         }
-        
+
         tool_names
     }
 
     /// Find workers that have all required tools available
-    pub fn find_workers_with_all_required_tools(&self, tool_checker: &dyn Fn(&str) -> bool) -> Vec<&Capabilities> {
+    pub fn find_workers_with_all_required_tools(
+        &self,
+        tool_checker: &(impl ToolChecker + ?Sized),
+    ) -> Vec<&Capabilities> {
+        let _clock = self.clock.enter();
         self.capabilities
             .values()
             .filter(|caps| caps.has_all_required_tools(tool_checker))
             .collect()
     }
+
+    /// Report the current load level of the embedding service
+    pub fn set_load_level(&mut self, level: LoadLevel) {
+        self.load_level = level;
+    }
+
+    /// Get the current load level
+    pub fn load_level(&self) -> LoadLevel {
+        self.load_level
+    }
+
+    /// Set the load shedding policy
+    pub fn set_shedding_policy(&mut self, policy: SheddingPolicy) {
+        self.shedding_policy = policy;
+    }
+
+    /// Find workers with a capability, shedding work according to the load level
+    ///
+    /// Results are cached in the match cache attached with `with_match_cache`,
+    /// keyed by capability type and the checker's `cache_key`. Under
+    /// `Degraded` load, cached results younger than the policy's cache TTL
+    /// are served; under `Overloaded` load, any cached result is served
+    /// (marked stale when past the TTL) and requests below the policy's
+    /// minimum priority are rejected. Without a match cache, or for checkers
+    /// without a cache key, results are always computed.
+    pub fn find_with_capability_shed(
+        &self,
+        capability_type: &str,
+        tool_checker: &(impl ToolChecker + ?Sized),
+        priority: MatchPriority,
+    ) -> Result<Shed<Vec<String>>, SheddingError> {
+        let _clock = self.clock.enter();
+        if self.load_level == LoadLevel::Overloaded
+            && priority < self.shedding_policy.min_priority_when_overloaded
+        {
+            return Err(SheddingError::Overloaded { priority });
+        }
+
+        let cache =
+            self.match_cache
+                .as_ref()
+                .zip(tool_checker.cache_key().map(|checker| {
+                    format!("capability:{}", encode_fields([capability_type, &checker]))
+                }));
+        let max_age = match self.load_level {
+            LoadLevel::Normal => None,
+            LoadLevel::Degraded => Some(Some(self.shedding_policy.cache_ttl_seconds)),
+            LoadLevel::Overloaded => Some(None),
+        };
+        if let (Some((cache, key)), Some(max_age)) = (&cache, max_age) {
+            if let Some((age_seconds, worker_ids)) = cache.get_within(key, self.revision, max_age) {
+                // Revocations and expiry are never shed
+                let value = worker_ids
+                    .into_iter()
+                    .filter(|id| self.has_current_tool(id, capability_type))
+                    .collect();
+                return Ok(Shed {
+                    value,
+                    cached: true,
+                    stale: age_seconds > self.shedding_policy.cache_ttl_seconds,
+                    age_seconds,
+                    digest_only: false,
+                });
+            }
+        }
+
+        let worker_ids: Vec<String> = self
+            .find_with_capability(capability_type, tool_checker)
+            .into_iter()
+            .map(|caps| caps.id.clone())
+            .collect();
+        if let Some((cache, key)) = cache {
+            cache.insert(key, self.revision, worker_ids.clone());
+        }
+
+        Ok(Shed::fresh(worker_ids))
+    }
+
+    /// Verify a worker, degrading to digest-only checks when not under normal load
+    ///
+    /// Digest-only checks skip signatures but still reject revoked and
+    /// expired capabilities.
+    pub fn verify_worker_shed(&self, worker_id: &str) -> Option<Shed<bool>> {
+        let _clock = self.clock.enter();
+        let caps = self.capabilities.get(worker_id)?;
+
+        Some(match self.load_level {
+            LoadLevel::Normal => Shed::fresh(self.verifies(caps)),
+            LoadLevel::Degraded | LoadLevel::Overloaded => Shed {
+                digest_only: true,
+                ..Shed::fresh(
+                    caps.tools_iter().all(|tool| self.is_current(tool))
+                        && caps.verify_all_digests(),
+                )
+            },
+        })
+    }
+
+    /// Whether a worker still has an unrevoked, unexpired tool in a category
+    fn has_current_tool(&self, worker_id: &str, capability_type: &str) -> bool {
+        self.capabilities.get(worker_id).is_some_and(|caps| {
+            caps.tools_in_category(capability_type)
+                .iter()
+                .any(|tool| self.is_current(tool))
+        })
+    }
+
+    /// Whether a tool is neither revoked nor expired, within the verifier's tolerances
+    fn is_current(&self, tool: &ToolCapability) -> bool {
+        !tool.is_revoked()
+            && tool.try_is_expired_within(self.algorithms.expiration_tolerance()) == Ok(false)
+    }
+
+    /// Open a long-running session on a worker for a capability
+    ///
+    /// The first satisfied tool with a free concurrency slot is bound to the
+    /// session, occupying one slot until the session ends, and its
+    /// `permissions.timeout_seconds` becomes the session's maximum lifetime.
+    pub fn open_session(
+        &mut self,
+        worker_id: &str,
+        capability_type: &str,
+        tool_checker: &(impl ToolChecker + ?Sized),
+    ) -> Result<String, SessionError> {
+        let _clock = self.clock.enter();
+        self.open_session_preferring(worker_id, capability_type, tool_checker, false)
+    }
+
+    /// Open a session, binding a checkpoint-capable tool first when `resumable`
+    fn open_session_preferring(
+        &mut self,
+        worker_id: &str,
+        capability_type: &str,
+        tool_checker: &(impl ToolChecker + ?Sized),
+        resumable: bool,
+    ) -> Result<String, SessionError> {
+        let caps = self
+            .capabilities
+            .get(worker_id)
+            .ok_or_else(|| SessionError::UnknownWorker(worker_id.to_string()))?;
+
+        let mut satisfied: Vec<&ToolCapability> = caps
+            .tools_in_category(capability_type)
+            .iter()
+            .filter(|tool| tool.is_satisfied(tool_checker))
+            .collect();
+        if satisfied.is_empty() {
+            return Err(SessionError::NoMatchingTool(capability_type.to_string()));
+        }
+        if resumable {
+            satisfied.sort_by_key(|tool| !tool.checkpointing);
+        }
+
+        let tool = satisfied
+            .into_iter()
+            .find(|tool| self.has_free_slot(worker_id, tool))
+            .ok_or_else(|| SessionError::SlotsExhausted(capability_type.to_string()))?;
+
+        self.next_session_id += 1;
+        let id = format!("{}-session-{}", worker_id, self.next_session_id);
+        let session = Session::start(
+            id.clone(),
+            worker_id.to_string(),
+            capability_type.to_string(),
+            tool.tool_name.clone(),
+            tool.checkpointing,
+            tool.permissions.timeout_seconds,
+        );
+        self.sessions.insert(id.clone(), session);
+        self.record_change();
+
+        Ok(id)
+    }
+
+    /// Record a heartbeat with optional progress for an active session
+    pub fn session_heartbeat(
+        &mut self,
+        session_id: &str,
+        progress_percent: Option<u8>,
+        progress_message: Option<String>,
+    ) -> Result<(), SessionError> {
+        let _clock = self.clock.enter();
+        let session = self.active_session_mut(session_id)?;
+        session.last_heartbeat = current_timestamp();
+        if let Some(percent) = progress_percent {
+            session.progress_percent = Some(percent.min(100));
+        }
+        if progress_message.is_some() {
+            session.progress_message = progress_message;
+        }
+        Ok(())
+    }
+
+    /// Cancel an active session on behalf of the orchestrator
+    pub fn cancel_session(&mut self, session_id: &str, reason: String) -> Result<(), SessionError> {
+        let _clock = self.clock.enter();
+        self.active_session_mut(session_id)?
+            .end(SessionState::Cancelled, Some(reason));
+        self.record_change();
+        Ok(())
+    }
+
+    /// Mark an active session as completed
+    pub fn complete_session(&mut self, session_id: &str) -> Result<(), SessionError> {
+        let _clock = self.clock.enter();
+        let session = self.active_session_mut(session_id)?;
+        session.end(SessionState::Completed, None);
+        session.progress_percent = Some(100);
+        self.record_change();
+        Ok(())
+    }
+
+    /// Record the location of the latest checkpoint of an active session
+    pub fn record_checkpoint(
+        &mut self,
+        session_id: &str,
+        location: impl Into<String>,
+    ) -> Result<(), SessionError> {
+        let _clock = self.clock.enter();
+        let session = self.active_session_mut(session_id)?;
+        if !session.checkpointing {
+            return Err(SessionError::CheckpointingUnsupported(
+                session.tool_name.clone(),
+            ));
+        }
+
+        session.checkpoint_location = Some(location.into());
+        session.last_heartbeat = current_timestamp();
+        Ok(())
+    }
+
+    /// Reschedule an interrupted session onto the best available worker
+    ///
+    /// Only active and expired sessions can be rescheduled, and each only
+    /// once; an active session is cancelled in favour of its replacement.
+    /// Workers whose tools can resume from checkpoints are preferred, such a
+    /// tool is bound to the new session, and the checkpoint location is
+    /// handed over when it supports it.
+    pub fn reschedule_session(
+        &mut self,
+        session_id: &str,
+        tool_checker: &(impl ToolChecker + ?Sized),
+    ) -> Result<String, SessionError> {
+        let _clock = self.clock.enter();
+        let previous = self
+            .sessions
+            .get(session_id)
+            .cloned()
+            .ok_or_else(|| SessionError::NotFound(session_id.to_string()))?;
+
+        if let Some(next) = previous.rescheduled_to {
+            return Err(SessionError::AlreadyRescheduled(next));
+        }
+        if !matches!(previous.state, SessionState::Active | SessionState::Expired) {
+            return Err(SessionError::NotReschedulable(previous.state));
+        }
+
+        let requirements = Requirements::new().prefer_resumable(previous.capability_type.clone());
+        let worker_id = self
+            .rank_workers(&requirements)
+            .into_iter()
+            .find(|caps| {
+                caps.tools_in_category(&previous.capability_type)
+                    .iter()
+                    .any(|tool| {
+                        tool.is_satisfied(tool_checker) && self.has_free_slot(&caps.id, tool)
+                    })
+            })
+            .map(|caps| caps.id.clone())
+            .ok_or_else(|| SessionError::NoMatchingTool(previous.capability_type.clone()))?;
+
+        let new_id = self.open_session_preferring(
+            &worker_id,
+            &previous.capability_type,
+            tool_checker,
+            true,
+        )?;
+
+        if let Some(session) = self.sessions.get_mut(&new_id) {
+            session.resumed_from = Some(previous.id.clone());
+            if session.checkpointing {
+                session.checkpoint_location = previous.checkpoint_location;
+            }
+        }
+        if let Some(session) = self.sessions.get_mut(&previous.id) {
+            if session.is_active() {
+                session.end(
+                    SessionState::Cancelled,
+                    Some(format!("rescheduled to {}", new_id)),
+                );
+            }
+            session.rescheduled_to = Some(new_id.clone());
+        }
+
+        Ok(new_id)
+    }
+
+    /// Expire sessions that missed their heartbeat or exceeded their lifetime
+    ///
+    /// Returns the ids of the sessions that were expired. Sessions that ended
+    /// more than `SESSION_RETENTION_SECONDS` ago are evicted.
+    pub fn expire_sessions(&mut self) -> Vec<String> {
+        let _clock = self.clock.enter();
+        let now = current_timestamp();
+        let mut expired = Vec::new();
+
+        for session in self.sessions.values_mut() {
+            if session.is_active() && session.is_overdue(now) {
+                let reason = if now > session.deadline() {
+                    "maximum lifetime exceeded"
+                } else {
+                    "heartbeat timeout"
+                };
+                session.end(SessionState::Expired, Some(reason.to_string()));
+                expired.push(session.id.clone());
+            }
+        }
+
+        let before = self.sessions.len();
+        self.sessions.retain(|_, session| {
+            session.is_active()
+                || session.ended_at.is_none_or(|ended_at| {
+                    now.saturating_sub(ended_at) <= SESSION_RETENTION_SECONDS
+                })
+        });
+
+        if !expired.is_empty() || self.sessions.len() != before {
+            self.record_change();
+        }
+        expired
+    }
+
+    /// Get a session by ID
+    pub fn get_session(&self, session_id: &str) -> Option<&Session> {
+        self.sessions.get(session_id)
+    }
+
+    /// List all sessions
+    pub fn list_sessions(&self) -> Vec<&Session> {
+        self.sessions.values().collect()
+    }
+
+    /// List active sessions of a worker
+    pub fn active_sessions_for_worker(&self, worker_id: &str) -> Vec<&Session> {
+        self.sessions
+            .values()
+            .filter(|session| session.worker_id == worker_id && session.is_active())
+            .collect()
+    }
+
+    /// Issue a temporary grant to a worker
+    ///
+    /// The grant is recorded in the audit log and applied over the worker's
+    /// base capabilities until it expires; the base declaration is unchanged.
+    pub fn grant_temporary(
+        &mut self,
+        worker_id: &str,
+        mut grant: GrantOverlay,
+    ) -> Result<String, GrantError> {
+        let _clock = self.clock.enter();
+        if !self.capabilities.contains_key(worker_id) {
+            return Err(GrantError::UnknownWorker(worker_id.to_string()));
+        }
+        if grant.is_expired() {
+            return Err(GrantError::AlreadyExpired);
+        }
+
+        self.next_grant_id += 1;
+        grant.id = format!("{}-grant-{}", worker_id, self.next_grant_id);
+        self.audit(AuditEvent::now(
+            worker_id,
+            &grant.id,
+            AuditEventKind::GrantIssued {
+                granted_by: grant.granted_by.clone(),
+                expires_at: grant.expires_at,
+                job_id: grant.job_id.clone(),
+            },
+        ));
+
+        let id = grant.id.clone();
+        self.grants
+            .entry(worker_id.to_string())
+            .or_default()
+            .push(grant);
+        self.record_change();
+        Ok(id)
+    }
+
+    /// Revoke a grant before it expires
+    pub fn revoke_grant(
+        &mut self,
+        grant_id: &str,
+        revoked_by: impl Into<String>,
+    ) -> Result<(), GrantError> {
+        let _clock = self.clock.enter();
+        let (worker_id, grants) = self
+            .grants
+            .iter_mut()
+            .find(|(_, grants)| grants.iter().any(|grant| grant.id == grant_id))
+            .ok_or_else(|| GrantError::UnknownGrant(grant_id.to_string()))?;
+
+        grants.retain(|grant| grant.id != grant_id);
+        let event = AuditEvent::now(
+            worker_id,
+            grant_id,
+            AuditEventKind::GrantRevoked {
+                revoked_by: revoked_by.into(),
+            },
+        );
+        self.audit(event);
+        self.record_change();
+        Ok(())
+    }
+
+    /// Remove expired grants, recording each in the audit log
+    ///
+    /// Returns the ids of the grants that expired.
+    pub fn expire_grants(&mut self) -> Vec<String> {
+        let _clock = self.clock.enter();
+        let mut expired = Vec::new();
+        let mut events = Vec::new();
+
+        for (worker_id, grants) in self.grants.iter_mut() {
+            grants.retain(|grant| {
+                if grant.is_expired() {
+                    events.push(AuditEvent::now(
+                        worker_id,
+                        &grant.id,
+                        AuditEventKind::GrantExpired,
+                    ));
+                    expired.push(grant.id.clone());
+                    false
+                } else {
+                    true
+                }
+            });
+        }
+        self.grants.retain(|_, grants| !grants.is_empty());
+        for event in events {
+            self.audit(event);
+        }
+
+        if !expired.is_empty() {
+            self.record_change();
+        }
+        expired
+    }
+
+    /// Active grants of a worker
+    pub fn active_grants(&self, worker_id: &str) -> Vec<&GrantOverlay> {
+        let _clock = self.clock.enter();
+        self.grants
+            .get(worker_id)
+            .map(|grants| grants.iter().filter(|grant| !grant.is_expired()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Capabilities of a worker with its active grants applied
+    pub fn effective_capabilities(&self, worker_id: &str) -> Option<Capabilities> {
+        let _clock = self.clock.enter();
+        let base = self.capabilities.get(worker_id)?;
+        Some(self.granted(base).into_owned())
+    }
+
+    /// A registered set with its worker's active grants applied, as matching sees it
+    fn granted<'a>(&self, caps: &'a Capabilities) -> Cow<'a, Capabilities> {
+        let grants = self.active_grants(&caps.id);
+        if grants.is_empty() {
+            return Cow::Borrowed(caps);
+        }
+        Cow::Owned(
+            grants
+                .into_iter()
+                .fold(caps.clone(), |caps, grant| grant.apply(&caps)),
+        )
+    }
+
+    /// Append to the audit log, dropping the oldest events beyond `MAX_AUDIT_LOG_EVENTS`
+    fn audit(&mut self, event: AuditEvent) {
+        self.audit_log.push(event);
+        let excess = self.audit_log.len().saturating_sub(MAX_AUDIT_LOG_EVENTS);
+        self.audit_log.drain(..excess);
+    }
+
+    /// Match a job's requirements against a worker with its active grants applied
+    ///
+    /// Expired grants are removed first, and every grant applied is recorded in
+    /// the audit log. The `find_*` matchers apply active grants as well, but
+    /// do not record them.
+    pub fn satisfies_with_grants(
+        &mut self,
+        worker_id: &str,
+        requirements: &Requirements,
+        tool_checker: &(impl ToolChecker + ?Sized),
+    ) -> Option<MatchResult> {
+        let _clock = self.clock.enter();
+        self.expire_grants();
+        let caps = self.effective_capabilities(worker_id)?;
+
+        let applied: Vec<String> = self
+            .active_grants(worker_id)
+            .into_iter()
+            .map(|grant| grant.id.clone())
+            .collect();
+        for grant_id in applied {
+            self.audit(AuditEvent::now(
+                worker_id,
+                &grant_id,
+                AuditEventKind::GrantApplied,
+            ));
+        }
+
+        Some(caps.satisfies(requirements, tool_checker))
+    }
+
+    /// Audit log of grant events, oldest first
+    ///
+    /// Holds at most `MAX_AUDIT_LOG_EVENTS` events.
+    pub fn audit_log(&self) -> &[AuditEvent] {
+        &self.audit_log
+    }
+
+    /// Set the policy used to demote tools with poor job outcomes
+    pub fn set_demotion_policy(&mut self, policy: DemotionPolicy) {
+        self.demotion_policy = policy;
+    }
+
+    /// Set the policy for per-worker retry budgets
+    pub fn set_retry_policy(&mut self, policy: RetryBudgetPolicy) {
+        self.retry_policy = policy;
+        self.record_change();
+    }
+
+    /// Remaining retry budget of a worker (full for workers without failures)
+    pub fn retry_budget(&self, worker_id: &str) -> f64 {
+        let _clock = self.clock.enter();
+        self.retry_budgets
+            .get(worker_id)
+            .map(|budget| budget.remaining_at(&self.retry_policy, current_timestamp()))
+            .unwrap_or(self.retry_policy.capacity)
+    }
+
+    /// Check if a worker has retry budget left and may be selected
+    pub fn has_retry_budget(&self, worker_id: &str) -> bool {
+        let _clock = self.clock.enter();
+        self.retry_budgets
+            .get(worker_id)
+            .is_none_or(|budget| !budget.is_exhausted_at(&self.retry_policy, current_timestamp()))
+    }
+
+    /// Check if the `find_*` matchers may select a worker
+    ///
+    /// The worker needs retry budget left, and its latest health report (if
+    /// any) must not be unhealthy.
+    pub(crate) fn is_selectable(&self, worker_id: &str) -> bool {
+        self.has_retry_budget(worker_id)
+            && self
+                .worker_health
+                .get(worker_id)
+                .is_none_or(|report| report.status != WorkerHealth::Unhealthy)
+    }
+
+    /// Record the outcome of a job run with a worker's tool
+    ///
+    /// Failures spend the worker's retry budget. Suspends the tool when its
+    /// success rate over the policy window drops below the threshold. Outcomes
+    /// of suspended tools are ignored; they recover through `record_health_check`.
+    pub fn record_outcome(
+        &mut self,
+        worker_id: &str,
+        tool_name: &str,
+        success: bool,
+        duration: Duration,
+    ) -> Option<HealthTransition> {
+        let _clock = self.clock.enter();
+        self.capabilities.get(worker_id)?.find_tool(tool_name)?;
+
+        if !success {
+            let now = current_timestamp();
+            let policy = &self.retry_policy;
+            let budget = self
+                .retry_budgets
+                .entry(worker_id.to_string())
+                .or_insert_with(|| RetryBudget::full(policy, now));
+            budget.spend(policy, now);
+            if budget.is_exhausted_at(policy, now) {
+                self.record_change();
+            }
+        }
+
+        let policy = &self.demotion_policy;
+        let health = self
+            .tool_health
+            .entry((worker_id.to_string(), tool_name.to_string()))
+            .or_default();
+        if health.suspended {
+            return None;
+        }
+
+        health.outcomes.push_back(JobOutcome {
+            success,
+            duration,
+            recorded_at: current_timestamp(),
+        });
+        while health.outcomes.len() > policy.window_size {
+            health.outcomes.pop_front();
+        }
+
+        if health.outcomes.len() < policy.min_samples
+            || health.success_rate() >= policy.min_success_rate
+        {
+            return None;
+        }
+
+        health.suspended = true;
+        health.probation_successes = 0;
+        self.set_tool_suspended(worker_id, tool_name, true);
+        Some(HealthTransition::Demoted)
+    }
+
+    /// Record a health check of a suspended tool
+    ///
+    /// After the policy's number of consecutive successful checks the tool is
+    /// reinstated with a fresh outcome window; a failed check restarts probation.
+    pub fn record_health_check(
+        &mut self,
+        worker_id: &str,
+        tool_name: &str,
+        success: bool,
+    ) -> Option<HealthTransition> {
+        let _clock = self.clock.enter();
+        let required = self.demotion_policy.probation_successes;
+        let health = self
+            .tool_health
+            .get_mut(&(worker_id.to_string(), tool_name.to_string()))?;
+        if !health.suspended {
+            return None;
+        }
+
+        if !success {
+            health.probation_successes = 0;
+            return None;
+        }
+
+        health.probation_successes += 1;
+        if health.probation_successes < required {
+            return None;
+        }
+
+        health.suspended = false;
+        health.probation_successes = 0;
+        health.outcomes.clear();
+        self.set_tool_suspended(worker_id, tool_name, false);
+        Some(HealthTransition::Recovered)
+    }
+
+    /// Get the telemetry health of a worker's tool
+    pub fn tool_health(&self, worker_id: &str, tool_name: &str) -> Option<&ToolHealth> {
+        self.tool_health
+            .get(&(worker_id.to_string(), tool_name.to_string()))
+    }
+
+    /// Re-verify a worker and record its health
+    ///
+    /// Returns the transition if the worker's status changed (or was not
+    /// known yet), `None` otherwise or if the worker is not registered.
+    pub fn verify_worker_health(
+        &mut self,
+        worker_id: &str,
+        tool_checker: &(impl ToolChecker + ?Sized),
+    ) -> Option<WorkerHealthTransition> {
+        let _clock = self.clock.enter();
+        let report = WorkerHealthReport::check(
+            self.capabilities.get(worker_id)?,
+            tool_checker,
+            &self.algorithms,
+        );
+        self.record_worker_health(report)
+    }
+
+    /// Record a health report produced outside the registry
+    ///
+    /// Reports for workers that are no longer registered are dropped.
+    pub fn record_worker_health(
+        &mut self,
+        report: WorkerHealthReport,
+    ) -> Option<WorkerHealthTransition> {
+        let _clock = self.clock.enter();
+        if !self.capabilities.contains_key(&report.worker_id) {
+            return None;
+        }
+
+        let from = self
+            .worker_health
+            .insert(report.worker_id.clone(), report.clone())
+            .map(|previous| previous.status);
+        if from != Some(report.status) {
+            self.record_change();
+        }
+        (from != Some(report.status)).then(|| WorkerHealthTransition {
+            worker_id: report.worker_id.clone(),
+            from,
+            to: report.status,
+            report,
+        })
+    }
+
+    /// Latest health report of a worker
+    pub fn worker_health(&self, worker_id: &str) -> Option<&WorkerHealthReport> {
+        self.worker_health.get(worker_id)
+    }
+
+    fn set_tool_suspended(&mut self, worker_id: &str, tool_name: &str, suspended: bool) {
+        if let Some(tool) = self
+            .capabilities
+            .get_mut(worker_id)
+            .and_then(|caps| caps.find_tool_mut(tool_name))
+        {
+            tool.suspended = suspended;
+            self.record_change();
+        }
+    }
+
+    /// Number of concurrency slots of a worker's tool occupied by active sessions
+    pub fn slots_in_use(&self, worker_id: &str, tool_name: &str) -> u32 {
+        self.sessions
+            .values()
+            .filter(|s| s.is_active() && s.worker_id == worker_id && s.tool_name == tool_name)
+            .count() as u32
+    }
+
+    /// All registered capability sets
+    #[cfg(feature = "parallel")]
+    pub(crate) fn workers(&self) -> Vec<&Capabilities> {
+        self.capabilities.values().collect()
+    }
+
+    pub(crate) fn has_free_slot(&self, worker_id: &str, tool: &ToolCapability) -> bool {
+        tool.max_concurrent
+            .map(|max| self.slots_in_use(worker_id, &tool.tool_name) < max)
+            .unwrap_or(true)
+    }
+
+    fn active_session_mut(&mut self, session_id: &str) -> Result<&mut Session, SessionError> {
+        let session = self
+            .sessions
+            .get_mut(session_id)
+            .ok_or_else(|| SessionError::NotFound(session_id.to_string()))?;
+
+        if !session.is_active() {
+            return Err(SessionError::NotActive(session.state));
+        }
+
+        Ok(session)
+    }
+}
+
+/// Tool attestations of a set with their tool names, then the set attestation
+/// under the worker ID
+fn attestations(caps: &Capabilities) -> impl Iterator<Item = (&str, &CapabilityAttestation)> {
+    caps.iter_tools()
+        .filter_map(|(_, tool)| Some((tool.tool_name.as_str(), tool.attestation.as_ref()?)))
+        .chain(
+            caps.attestation
+                .as_ref()
+                .map(|attestation| (caps.id.as_str(), attestation)),
+        )
 }
 
 /// Statistics about the registry
@@ -195,4 +1798,7 @@ pub struct RegistryStatistics {
     pub total_tools: usize,
     pub total_required_tools: usize,
     pub total_verified_tools: usize,
+    pub total_deprecated_tools: usize,
+    pub active_sessions: usize,
+    pub rejected_registrations: usize,
 }
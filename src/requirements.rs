@@ -0,0 +1,427 @@
+//! Job requirements matched against worker capabilities
+
+use serde::{Deserialize, Serialize};
+
+use crate::capabilities::Capabilities;
+use crate::checker::{ToolChecker, ToolStatus};
+use crate::clock::current_timestamp;
+use crate::constants::*;
+use crate::formats::{ArtifactFormat, ReportFormat};
+use crate::models::ModelRequirement;
+use crate::platform::Platform;
+use crate::priming::WarmAssetKind;
+use crate::sandbox::IsolationLevel;
+
+/// Capability clause satisfied when any of its categories has a usable tool
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RequirementClause {
+    /// Alternative capability categories
+    pub any_of: Vec<String>,
+}
+
+/// Outcome of matching a single clause
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClauseMatch {
+    /// The clause that was matched
+    pub clause: RequirementClause,
+    /// Category that satisfied the clause (if any)
+    pub category: Option<String>,
+    /// Tool (primary name or alternative) used to satisfy the clause
+    pub tool: Option<String>,
+    /// Status of the tool used (where it was found, which version)
+    #[serde(default)]
+    pub status: Option<ToolStatus>,
+}
+
+impl ClauseMatch {
+    /// Check if the clause was satisfied
+    pub fn is_matched(&self) -> bool {
+        self.tool.is_some()
+    }
+}
+
+/// Outcome of matching requirements against a capability set
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatchResult {
+    /// Worker the requirements were matched against
+    pub worker_id: String,
+    /// Per-clause outcomes, in requirement order
+    pub clauses: Vec<ClauseMatch>,
+    /// Whether the environment requirements (network, sandbox, formats, ...) are met
+    pub environment_met: bool,
+}
+
+impl MatchResult {
+    /// Check if every clause and the environment requirements are satisfied
+    pub fn is_match(&self) -> bool {
+        self.environment_met && self.clauses.iter().all(ClauseMatch::is_matched)
+    }
+
+    /// Clauses that could not be satisfied
+    pub fn unmatched_clauses(&self) -> Vec<&RequirementClause> {
+        self.clauses
+            .iter()
+            .filter(|clause| !clause.is_matched())
+            .map(|clause| &clause.clause)
+            .collect()
+    }
+
+    /// Tools used to satisfy the clauses
+    pub fn tools_used(&self) -> Vec<&str> {
+        self.clauses
+            .iter()
+            .filter_map(|clause| clause.tool.as_deref())
+            .collect()
+    }
+}
+
+/// Requirements a job places on the worker that executes it
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Requirements {
+    /// Capability clauses that must all be satisfied
+    pub clauses: Vec<RequirementClause>,
+    /// Whether the job needs general outbound internet access
+    pub internet: bool,
+    /// Named endpoints the job must be able to reach
+    pub endpoints: Vec<String>,
+    /// Minimum sandbox isolation level the job needs
+    pub min_isolation: Option<IsolationLevel>,
+    /// Minimum sandbox memory in MB the job needs
+    pub min_sandbox_memory_mb: Option<u64>,
+    /// Format of the input artifact the job provides
+    pub input_format: Option<ArtifactFormat>,
+    /// Size of the input artifact in bytes
+    pub input_size_bytes: Option<u64>,
+    /// Number of files in the repository the job analyzes
+    pub repo_file_count: Option<u64>,
+    /// Report format the job's results must be delivered in
+    pub output_format: Option<ReportFormat>,
+    /// Capability types for which checkpoint-capable tools are preferred
+    pub prefer_resumable: Vec<String>,
+    /// Language models the job needs (each must be satisfied by some model)
+    pub models: Vec<ModelRequirement>,
+    /// Repository for which primed workers are preferred
+    pub primed_for: Option<String>,
+    /// Maximum age in seconds of warm assets that count as primed
+    pub max_warm_age_seconds: Option<u64>,
+    /// Repository the job targets, checked against worker scopes
+    pub target_repository: Option<String>,
+    /// Operating system the job must run on
+    pub os: Option<String>,
+    /// CPU architecture the job must run on
+    pub arch: Option<String>,
+    /// Tags the worker must all carry
+    pub tags: Vec<String>,
+}
+
+impl Requirements {
+    /// Create an empty set of requirements
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Require a capability category
+    pub fn requires(mut self, capability_type: impl Into<String>) -> Self {
+        self.clauses.push(RequirementClause {
+            any_of: vec![capability_type.into()],
+        });
+        self
+    }
+
+    /// Require at least one of several capability categories
+    pub fn requires_any_of<I, S>(mut self, capability_types: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.clauses.push(RequirementClause {
+            any_of: capability_types.into_iter().map(Into::into).collect(),
+        });
+        self
+    }
+
+    /// Require general outbound internet access
+    pub fn requires_internet(mut self) -> Self {
+        self.internet = true;
+        self
+    }
+
+    /// Require a named endpoint to be reachable
+    pub fn requires_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoints.push(endpoint.into());
+        self
+    }
+
+    /// Require at least the given sandbox isolation level
+    pub fn requires_isolation(mut self, level: IsolationLevel) -> Self {
+        self.min_isolation = Some(level);
+        self
+    }
+
+    /// Require sandboxes with at least the given amount of memory
+    pub fn requires_sandbox_memory(mut self, memory_mb: u64) -> Self {
+        self.min_sandbox_memory_mb = Some(memory_mb);
+        self
+    }
+
+    /// Require the worker to run a given operating system (e.g., `linux`)
+    pub fn requires_os(mut self, os: impl Into<String>) -> Self {
+        self.os = Some(os.into());
+        self
+    }
+
+    /// Require the worker to run on a given CPU architecture (e.g., `x86_64`)
+    pub fn requires_arch(mut self, arch: impl Into<String>) -> Self {
+        self.arch = Some(arch.into());
+        self
+    }
+
+    /// Require the worker to carry a tag
+    pub fn requires_tag(mut self, tag: impl Into<String>) -> Self {
+        self.tags.push(tag.into());
+        self
+    }
+
+    /// Require the worker to carry every tag of a set
+    pub fn requires_tags<I, S>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.tags.extend(tags.into_iter().map(Into::into));
+        self
+    }
+
+    /// Require the worker and the tools used to run on a platform
+    pub fn for_platform(mut self, platform: Platform) -> Self {
+        self.os = Some(platform.os);
+        self.arch = Some(platform.arch);
+        self
+    }
+
+    /// Require the worker to accept the given input artifact format
+    pub fn with_input(mut self, format: ArtifactFormat) -> Self {
+        self.input_format = Some(format);
+        self
+    }
+
+    /// Set the size of the input artifact in bytes
+    pub fn with_input_size(mut self, bytes: u64) -> Self {
+        self.input_size_bytes = Some(bytes);
+        self
+    }
+
+    /// Set the number of files in the repository the job analyzes
+    pub fn with_repo_file_count(mut self, files: u64) -> Self {
+        self.repo_file_count = Some(files);
+        self
+    }
+
+    /// Require the worker to emit results in the given report format
+    pub fn with_output(mut self, format: ReportFormat) -> Self {
+        self.output_format = Some(format);
+        self
+    }
+
+    /// Require a language model matching the given requirement
+    pub fn requires_model(mut self, model: ModelRequirement) -> Self {
+        self.models.push(model);
+        self
+    }
+
+    /// Set the repository the job targets
+    pub fn for_repository(mut self, repository: impl Into<String>) -> Self {
+        self.target_repository = Some(repository.into());
+        self
+    }
+
+    /// Prefer workers already primed for a repository
+    pub fn prefer_primed_for(mut self, repository: impl Into<String>) -> Self {
+        self.primed_for = Some(repository.into());
+        self
+    }
+
+    /// Set the maximum age of warm assets that count as primed
+    pub fn with_max_warm_age(mut self, max_age_seconds: u64) -> Self {
+        self.max_warm_age_seconds = Some(max_age_seconds);
+        self
+    }
+
+    /// Prefer workers whose tools for a capability type can resume from checkpoints
+    pub fn prefer_resumable(mut self, capability_type: impl Into<String>) -> Self {
+        self.prefer_resumable.push(capability_type.into());
+        self
+    }
+}
+
+impl Capabilities {
+    /// Match a job's requirements, reporting which clauses matched and which tools were used
+    ///
+    /// When the job targets a repository, only tools scoped to it are used;
+    /// when it requires an OS or architecture, only tools running on it.
+    pub fn satisfies(
+        &self,
+        requirements: &Requirements,
+        tool_checker: &(impl ToolChecker + ?Sized),
+    ) -> MatchResult {
+        let clauses = requirements
+            .clauses
+            .iter()
+            .map(|clause| {
+                let found = clause.any_of.iter().find_map(|category| {
+                    self.tools_in_category(category)
+                        .iter()
+                        .filter(|tool| {
+                            requirements
+                                .target_repository
+                                .as_deref()
+                                .is_none_or(|repository| tool.scope.allows(repository))
+                                && tool.runs_on(
+                                    requirements.os.as_deref(),
+                                    requirements.arch.as_deref(),
+                                )
+                        })
+                        .find_map(|tool| tool.satisfied_by(tool_checker))
+                        .map(|tool| (category.clone(), tool.to_string()))
+                });
+
+                ClauseMatch {
+                    clause: clause.clone(),
+                    status: found.as_ref().map(|(_, tool)| tool_checker.status(tool)),
+                    category: found.as_ref().map(|(category, _)| category.clone()),
+                    tool: found.map(|(_, tool)| tool),
+                }
+            })
+            .collect();
+
+        MatchResult {
+            worker_id: self.id.clone(),
+            clauses,
+            environment_met: self.meets_requirements(requirements),
+        }
+    }
+
+    /// Check if this capability set meets the environment requirements of a job
+    ///
+    /// Capability clauses are not considered; use `satisfies` for those.
+    pub fn meets_requirements(&self, requirements: &Requirements) -> bool {
+        if requirements.internet && !self.network.internet {
+            return false;
+        }
+
+        if !requirements
+            .endpoints
+            .iter()
+            .all(|endpoint| self.network.can_reach(endpoint))
+        {
+            return false;
+        }
+
+        if requirements
+            .os
+            .as_deref()
+            .is_some_and(|os| self.os() != Some(os))
+        {
+            return false;
+        }
+
+        if !requirements.tags.iter().all(|tag| self.has_tag(tag)) {
+            return false;
+        }
+
+        if requirements
+            .arch
+            .as_deref()
+            .is_some_and(|arch| self.arch() != Some(arch))
+        {
+            return false;
+        }
+
+        if let Some(level) = requirements.min_isolation {
+            if !self.sandbox.provides(level) {
+                return false;
+            }
+        }
+
+        if let Some(memory_mb) = requirements.min_sandbox_memory_mb {
+            match self.sandbox.limits.memory_limit_mb {
+                Some(limit) if limit >= memory_mb => {}
+                _ => return false,
+            }
+        }
+
+        if let Some(format) = &requirements.input_format {
+            if !self.accepts_format(format) {
+                return false;
+            }
+        }
+
+        if let Some(bytes) = requirements.input_size_bytes {
+            if !self.payload.admits_input_size(bytes) {
+                return false;
+            }
+        }
+
+        if let Some(files) = requirements.repo_file_count {
+            if !self.payload.admits_repo_file_count(files) {
+                return false;
+            }
+        }
+
+        if let Some(format) = &requirements.output_format {
+            if !self.emits_format(format) {
+                return false;
+            }
+        }
+
+        if let Some(repository) = &requirements.target_repository {
+            if !self.scope.allows(repository) {
+                return false;
+            }
+        }
+
+        requirements.models.iter().all(|wanted| {
+            self.models
+                .iter()
+                .any(|model| wanted.is_satisfied_by(model))
+        })
+    }
+
+    /// Score how well this capability set matches the soft preferences of a job
+    ///
+    /// Higher scores are better; hard requirements are not considered.
+    pub fn preference_score(&self, requirements: &Requirements) -> u32 {
+        let resumable = requirements
+            .prefer_resumable
+            .iter()
+            .filter(|capability_type| {
+                self.tools_in_category(capability_type)
+                    .iter()
+                    .any(|tool| tool.checkpointing)
+            })
+            .count() as u32;
+
+        let primed = match &requirements.primed_for {
+            Some(repository) => {
+                let now = current_timestamp();
+                let max_age = requirements
+                    .max_warm_age_seconds
+                    .unwrap_or(DEFAULT_WARM_ASSET_MAX_AGE_SECONDS);
+                self.warm_assets
+                    .iter()
+                    .filter(|asset| {
+                        matches!(
+                            asset.kind,
+                            WarmAssetKind::Repository | WarmAssetKind::DependencyCache
+                        ) && asset.identifier == *repository
+                            && asset.is_fresh(now, max_age)
+                    })
+                    .count() as u32
+            }
+            None => 0,
+        };
+
+        resumable + primed
+    }
+}
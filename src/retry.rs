@@ -0,0 +1,65 @@
+//! Per-worker retry budgets that pause selection of repeatedly failing workers
+
+use serde::{Deserialize, Serialize};
+
+use crate::constants::*;
+
+/// Policy controlling per-worker retry budgets
+///
+/// Every failed job spends budget; the budget refills continuously up to its
+/// capacity. A worker whose budget drops below one unit is not selected.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetryBudgetPolicy {
+    /// Maximum budget of a worker
+    pub capacity: f64,
+    /// Budget regained per second
+    pub refill_per_second: f64,
+    /// Budget spent by each failed job
+    pub cost_per_failure: f64,
+}
+
+impl Default for RetryBudgetPolicy {
+    fn default() -> Self {
+        Self {
+            capacity: DEFAULT_RETRY_BUDGET_CAPACITY,
+            refill_per_second: DEFAULT_RETRY_BUDGET_REFILL_PER_SECOND,
+            cost_per_failure: 1.0,
+        }
+    }
+}
+
+/// Retry budget of a single worker
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetryBudget {
+    /// Remaining budget at `updated_at`
+    pub remaining: f64,
+    /// Timestamp the budget was last updated
+    pub updated_at: u64,
+}
+
+impl RetryBudget {
+    /// Create a full budget
+    pub fn full(policy: &RetryBudgetPolicy, now: u64) -> Self {
+        Self {
+            remaining: policy.capacity,
+            updated_at: now,
+        }
+    }
+
+    /// Remaining budget at `now`, including refill since the last update
+    pub fn remaining_at(&self, policy: &RetryBudgetPolicy, now: u64) -> f64 {
+        let elapsed = now.saturating_sub(self.updated_at) as f64;
+        (self.remaining + elapsed * policy.refill_per_second).min(policy.capacity)
+    }
+
+    /// Check if the budget is exhausted at `now`
+    pub fn is_exhausted_at(&self, policy: &RetryBudgetPolicy, now: u64) -> bool {
+        self.remaining_at(policy, now) < 1.0
+    }
+
+    /// Spend budget for a failed job
+    pub(crate) fn spend(&mut self, policy: &RetryBudgetPolicy, now: u64) {
+        self.remaining = (self.remaining_at(policy, now) - policy.cost_per_failure).max(0.0);
+        self.updated_at = now;
+    }
+}
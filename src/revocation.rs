@@ -0,0 +1,317 @@
+//! Revocation lists exchanged between registries
+//!
+//! A `RevocationList` captures revoked workers and capability hashes with
+//! their reasons and timestamps. A coordinator exports the revocations it
+//! knows with `CapabilityRegistry::export_revocations` and signs the list;
+//! other coordinators import it with `import_revocations`, which checks the
+//! signature against their trust anchors and revokes matching tools now and
+//! on later registration.
+//!
+//! ```rust
+//! use worker_capabilities::{
+//!     AttestationSigner, Capabilities, CapabilityRegistry, CapabilitySigner, MockVerifier,
+//!     RevocationList, RevocationListError, TrustAnchor,
+//! };
+//!
+//! let worker = Capabilities::new("worker-1").with_static_analysis("clippy", true);
+//! let signer = CapabilitySigner::new("key-a", "coordinator-a");
+//!
+//! let mut coordinator_a = CapabilityRegistry::new();
+//! coordinator_a.register(worker.clone());
+//! coordinator_a.revoke_worker_capabilities("worker-1", "compromised".into(), "ops".into());
+//!
+//! let list = coordinator_a
+//!     .export_revocations("coordinator-a")
+//!     .with_sequence(1)
+//!     .sign(&signer);
+//! let list = RevocationList::from_json(&list.to_json().unwrap()).unwrap();
+//!
+//! // Coordinator B pins coordinator A's key as a trust anchor
+//! let anchor = TrustAnchor {
+//!     name: "coordinator-a".to_string(),
+//!     public_key: signer.public_key(),
+//! };
+//! let mut coordinator_b = CapabilityRegistry::new().with_trust_anchor(anchor);
+//! coordinator_b.register(worker);
+//! assert_eq!(coordinator_b.import_revocations(&list, &MockVerifier), Ok(1));
+//! assert!(coordinator_b.get("worker-1").unwrap().iter_tools().all(|(_, tool)| tool.is_revoked()));
+//!
+//! // Unsigned lists are rejected
+//! let unsigned = RevocationList::new("coordinator-a");
+//! assert_eq!(
+//!     coordinator_b.import_revocations(&unsigned, &MockVerifier),
+//!     Err(RevocationListError::DigestMismatch)
+//! );
+//! ```
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt;
+
+use crate::capabilities::Capabilities;
+use crate::clock::current_timestamp;
+use crate::signing::{signed_message, AttestationSigner, AttestationVerifier};
+use crate::trust::RevocationEntry;
+use crate::types::ToolCapability;
+
+/// Portable list of revoked workers and capabilities
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RevocationList {
+    /// Identity of the exporting coordinator
+    pub issuer: String,
+    /// Timestamp when the list was exported
+    pub issued_at: u64,
+    /// Monotonic list sequence; lists older than one already imported from the issuer are rejected
+    #[serde(default)]
+    pub sequence: u64,
+    /// Revoked workers and capability hashes
+    #[serde(default)]
+    pub entries: Vec<RevocationEntry>,
+    /// SHA256 digest of the list contents
+    #[serde(default)]
+    pub digest: String,
+    /// Signature over the digest
+    #[serde(default)]
+    pub signature: String,
+    /// Public key of the signer
+    #[serde(default)]
+    pub public_key: String,
+    /// Signer identity
+    #[serde(default)]
+    pub signer: String,
+    /// Signature algorithm
+    #[serde(default)]
+    pub algorithm: String,
+}
+
+impl RevocationList {
+    /// Create an empty list issued now
+    pub fn new(issuer: impl Into<String>) -> Self {
+        Self {
+            issuer: issuer.into(),
+            issued_at: current_timestamp(),
+            sequence: 0,
+            entries: Vec::new(),
+            digest: String::new(),
+            signature: String::new(),
+            public_key: String::new(),
+            signer: String::new(),
+            algorithm: String::new(),
+        }
+    }
+
+    /// Set the list sequence
+    pub fn with_sequence(mut self, sequence: u64) -> Self {
+        self.sequence = sequence;
+        self
+    }
+
+    /// Add a revocation
+    pub fn with_entry(mut self, entry: RevocationEntry) -> Self {
+        self.add(entry);
+        self
+    }
+
+    /// Add a revocation unless the worker or capability is already listed
+    ///
+    /// Returns whether the entry was added.
+    pub fn add(&mut self, entry: RevocationEntry) -> bool {
+        let listed = self
+            .entries
+            .iter()
+            .any(|existing| existing.same_subject(&entry));
+        if !listed {
+            self.entries.push(entry);
+        }
+        !listed
+    }
+
+    /// Add every revocation of another list, returning the number added
+    pub fn merge(&mut self, other: &RevocationList) -> usize {
+        other
+            .entries
+            .iter()
+            .filter(|entry| self.add((*entry).clone()))
+            .count()
+    }
+
+    /// Find the revocation covering a capability of a worker
+    pub fn revocation_for(
+        &self,
+        worker_id: &str,
+        capability_hash: Option<&str>,
+    ) -> Option<&RevocationEntry> {
+        self.entries
+            .iter()
+            .find(|entry| entry.matches(worker_id, capability_hash))
+    }
+
+    /// Number of revocations
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check if the list holds no revocation
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Compute the digest of the list contents (excluding the signature fields)
+    pub fn compute_digest(&self) -> String {
+        let contents = serde_json::json!({
+            "issuer": self.issuer,
+            "issued_at": self.issued_at,
+            "sequence": self.sequence,
+            "entries": self.entries,
+        });
+        let mut hasher = Sha256::new();
+        hasher.update(contents.to_string().as_bytes());
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// Sign the list
+    pub fn sign(mut self, signer: &dyn AttestationSigner) -> Self {
+        self.digest = self.compute_digest();
+        self.public_key = signer.public_key();
+        self.signer = signer.attester().to_string();
+        self.algorithm = signer.algorithm().to_string();
+        self.signature = signer.sign(self.signed_message().as_bytes());
+        self
+    }
+
+    /// Message covered by the list signature
+    fn signed_message(&self) -> String {
+        signed_message(&self.algorithm, &self.signer, &self.digest, self.issued_at)
+    }
+
+    /// Check the digest and signature of the list
+    pub fn verify_signature(
+        &self,
+        verifier: &dyn AttestationVerifier,
+    ) -> Result<(), RevocationListError> {
+        if self.digest != self.compute_digest() {
+            return Err(RevocationListError::DigestMismatch);
+        }
+        let message = self.signed_message();
+        if self.public_key.is_empty()
+            || !verifier.verify_as(
+                &self.algorithm,
+                &self.public_key,
+                message.as_bytes(),
+                &self.signature,
+            )
+        {
+            return Err(RevocationListError::InvalidSignature);
+        }
+        Ok(())
+    }
+
+    /// Serialize the list as JSON
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Parse a list from JSON
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
+
+/// Errors from importing a revocation list
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RevocationListError {
+    /// The list contents do not match its digest
+    DigestMismatch,
+    /// The list signature is missing or invalid
+    InvalidSignature,
+    /// The list was not signed by a trust anchor of the importing registry
+    UntrustedSigner(String),
+    /// The list names an issuer other than the anchor that signed it
+    IssuerMismatch { issuer: String, anchor: String },
+    /// The list was issued longer ago than the importing registry accepts
+    Outdated { issued_at: u64 },
+    /// The list is older than one already imported from its issuer
+    StaleSequence {
+        issuer: String,
+        current: u64,
+        received: u64,
+    },
+}
+
+impl fmt::Display for RevocationListError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RevocationListError::DigestMismatch => {
+                write!(f, "revocation list contents do not match its digest")
+            }
+            RevocationListError::InvalidSignature => {
+                write!(f, "revocation list signature is invalid")
+            }
+            RevocationListError::UntrustedSigner(signer) => {
+                write!(f, "revocation list signed by untrusted signer '{}'", signer)
+            }
+            RevocationListError::IssuerMismatch { issuer, anchor } => write!(
+                f,
+                "revocation list of '{}' was signed by anchor '{}'",
+                issuer, anchor
+            ),
+            RevocationListError::Outdated { issued_at } => write!(
+                f,
+                "revocation list issued at {} is older than the registry accepts",
+                issued_at
+            ),
+            RevocationListError::StaleSequence {
+                issuer,
+                current,
+                received,
+            } => write!(
+                f,
+                "revocation list {} of '{}' is older than imported list {}",
+                received, issuer, current
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RevocationListError {}
+
+impl RevocationEntry {
+    /// Check if two entries revoke the same worker or capability
+    pub(crate) fn same_subject(&self, other: &RevocationEntry) -> bool {
+        self.worker_id == other.worker_id && self.capability_hash == other.capability_hash
+    }
+}
+
+/// Revoke the tools of a set matched by revocation entries
+///
+/// Tools are matched by worker ID or by attested (or current) capability
+/// hash. Returns the number of tools revoked.
+pub(crate) fn apply_revocations<'a>(
+    caps: &mut Capabilities,
+    entries: impl Iterator<Item = (&'a str, &'a RevocationEntry)> + Clone,
+) -> usize {
+    let worker_id = caps.id.clone();
+    let mut revoked = 0;
+    for tool in caps.tools_iter_mut() {
+        if tool.is_revoked() {
+            continue;
+        }
+        let hash = revoked_hash(tool);
+        let entry = entries
+            .clone()
+            .find(|(_, entry)| entry.matches(&worker_id, Some(&hash)));
+        if let Some((issuer, entry)) = entry {
+            tool.revoke(entry.reason.clone(), issuer.to_string());
+            revoked += 1;
+        }
+    }
+    revoked
+}
+
+/// Capability hash a revocation of a tool refers to
+pub(crate) fn revoked_hash(tool: &ToolCapability) -> String {
+    tool.attestation
+        .as_ref()
+        .map(|attestation| attestation.capability_hash.clone())
+        .unwrap_or_else(|| tool.generate_capability_hash())
+}
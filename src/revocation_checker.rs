@@ -0,0 +1,488 @@
+//! Online revocation checking
+//!
+//! A `RevocationChecker` reports whether an attestation's capability hash
+//! has been revoked, OCSP-style, so a central kill-switch takes effect
+//! without redistributing capability files. Wrapping a verifier in a
+//! `RevocationCheckingVerifier` consults the checker whenever an attestation
+//! verifies, after its signature and freshness. When the status cannot be
+//! determined, the `RevocationFailurePolicy` decides: hard-fail (the
+//! default) rejects the attestation, soft-fail accepts it.
+//!
+//! With the `http` feature, `HttpRevocationChecker` queries a revocation
+//! endpoint: `GET <base>/revocations/{capability_hash}` answers `200` with a
+//! JSON body such as `{"revoked": true, "reason": "key compromised"}`, or
+//! `404` when the capability was never revoked; revoked workers are looked
+//! up at `GET <base>/revocations/workers/{worker_id}`. A `RevocationList`
+//! works as an offline checker.
+//!
+//! Attestations carry no worker ID, so a `RevocationCheckingVerifier` only
+//! sees revoked capability hashes. `CapabilityRegistry::with_revocation_checker`
+//! consults a checker with the worker ID as well whenever it verifies
+//! registered workers.
+//!
+//! ```rust
+//! use worker_capabilities::{
+//!     AttestationSigner, Ed25519Signer, Ed25519Verifier, RevocationCheckingVerifier,
+//!     RevocationEntry, RevocationList, ToolCapability, VerificationError,
+//! };
+//!
+//! let signer = Ed25519Signer::new([7; 32], "ci");
+//! let tool = ToolCapability::new("clippy", true);
+//! let tool = tool.clone().with_attestation(signer.attest(&tool));
+//!
+//! let list = RevocationList::new("coordinator");
+//! let verifier = RevocationCheckingVerifier::new(Ed25519Verifier, list.clone());
+//! assert!(tool.verify_attestation_with(&verifier));
+//!
+//! let hash = tool.attestation.as_ref().unwrap().capability_hash.clone();
+//! let list = list.with_entry(RevocationEntry::capability(hash, "key compromised"));
+//! let verifier = RevocationCheckingVerifier::new(Ed25519Verifier, list);
+//! assert!(matches!(
+//!     tool.try_verify_attestation_with(&verifier),
+//!     Err(VerificationError::Revoked { .. })
+//! ));
+//! ```
+
+#[cfg(feature = "http")]
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+#[cfg(feature = "http")]
+use std::sync::{Mutex, MutexGuard};
+#[cfg(feature = "http")]
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "http")]
+use crate::constants::*;
+use crate::revocation::RevocationList;
+use crate::signing::AttestationVerifier;
+use crate::trust::RevocationEntry;
+use crate::types::{CapabilityAttestation, ExpirationTolerance};
+
+/// Revocation status of an attestation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RevocationStatus {
+    /// The capability has not been revoked
+    Good,
+    /// The capability was revoked
+    Revoked {
+        /// Reason given for the revocation
+        reason: String,
+    },
+}
+
+/// Errors from determining the revocation status of an attestation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RevocationCheckError {
+    /// The revocation service could not be reached
+    Transport(String),
+    /// The revocation service answered with an unexpected status code
+    Status(u16),
+    /// The response of the revocation service could not be parsed
+    Parse(String),
+    /// The capability hash cannot be looked up
+    InvalidHash(String),
+    /// The worker ID cannot be looked up
+    InvalidWorkerId(String),
+}
+
+impl fmt::Display for RevocationCheckError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RevocationCheckError::Transport(reason) => {
+                write!(f, "revocation service unreachable: {}", reason)
+            }
+            RevocationCheckError::Status(code) => {
+                write!(f, "revocation service answered with status {}", code)
+            }
+            RevocationCheckError::Parse(reason) => {
+                write!(f, "invalid revocation response: {}", reason)
+            }
+            RevocationCheckError::InvalidHash(hash) => {
+                write!(f, "invalid capability hash '{}'", hash)
+            }
+            RevocationCheckError::InvalidWorkerId(worker_id) => {
+                write!(f, "invalid worker ID '{}'", worker_id)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RevocationCheckError {}
+
+/// Source of revocation status for attestations
+pub trait RevocationChecker {
+    /// Revocation status of an attestation's capability hash
+    fn status(
+        &self,
+        attestation: &CapabilityAttestation,
+    ) -> Result<RevocationStatus, RevocationCheckError>;
+
+    /// Revocation status of a worker's attestation, covering revoked workers
+    ///
+    /// Defaults to `status`, which only knows capability hashes.
+    fn worker_status(
+        &self,
+        _worker_id: &str,
+        attestation: &CapabilityAttestation,
+    ) -> Result<RevocationStatus, RevocationCheckError> {
+        self.status(attestation)
+    }
+}
+
+impl RevocationChecker for RevocationList {
+    fn status(
+        &self,
+        attestation: &CapabilityAttestation,
+    ) -> Result<RevocationStatus, RevocationCheckError> {
+        let entry = self.entries.iter().find(|entry| {
+            entry.capability_hash.as_deref() == Some(attestation.capability_hash.as_str())
+        });
+        Ok(revocation_status(entry))
+    }
+
+    fn worker_status(
+        &self,
+        worker_id: &str,
+        attestation: &CapabilityAttestation,
+    ) -> Result<RevocationStatus, RevocationCheckError> {
+        Ok(revocation_status(self.revocation_for(
+            worker_id,
+            Some(&attestation.capability_hash),
+        )))
+    }
+}
+
+fn revocation_status(entry: Option<&RevocationEntry>) -> RevocationStatus {
+    match entry {
+        Some(entry) => RevocationStatus::Revoked {
+            reason: entry.reason.clone(),
+        },
+        None => RevocationStatus::Good,
+    }
+}
+
+/// Revocation checker consulted by a registry when verifying workers
+#[derive(Clone)]
+pub(crate) struct WorkerRevocationChecker(Arc<dyn RevocationChecker + Send + Sync>);
+
+impl WorkerRevocationChecker {
+    pub(crate) fn new(checker: impl RevocationChecker + Send + Sync + 'static) -> Self {
+        Self(Arc::new(checker))
+    }
+
+    /// Check if a worker's attestation is revoked, or its status unknown under hard-fail
+    pub(crate) fn rejects(
+        &self,
+        worker_id: &str,
+        attestation: &CapabilityAttestation,
+        failure_policy: RevocationFailurePolicy,
+    ) -> bool {
+        match self.0.worker_status(worker_id, attestation) {
+            Ok(RevocationStatus::Good) => false,
+            Ok(RevocationStatus::Revoked { .. }) => true,
+            Err(_) => failure_policy == RevocationFailurePolicy::HardFail,
+        }
+    }
+}
+
+impl fmt::Debug for WorkerRevocationChecker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WorkerRevocationChecker")
+            .finish_non_exhaustive()
+    }
+}
+
+/// What verification does when the revocation status cannot be determined
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RevocationFailurePolicy {
+    /// Reject the attestation
+    #[default]
+    HardFail,
+    /// Accept the attestation
+    SoftFail,
+}
+
+/// Verifier rejecting attestations a revocation checker reports as revoked
+///
+/// Signatures and freshness are checked by `inner`.
+#[derive(Debug)]
+pub struct RevocationCheckingVerifier<V, C> {
+    inner: V,
+    checker: C,
+    failure_policy: RevocationFailurePolicy,
+}
+
+impl<V: AttestationVerifier, C: RevocationChecker> RevocationCheckingVerifier<V, C> {
+    /// Check revocation of attestations whose signatures `inner` verifies
+    pub fn new(inner: V, checker: C) -> Self {
+        Self {
+            inner,
+            checker,
+            failure_policy: RevocationFailurePolicy::default(),
+        }
+    }
+
+    /// Set what happens when the revocation status cannot be determined
+    pub fn with_failure_policy(mut self, policy: RevocationFailurePolicy) -> Self {
+        self.failure_policy = policy;
+        self
+    }
+
+    /// The revocation checker
+    pub fn checker(&self) -> &C {
+        &self.checker
+    }
+}
+
+impl<V: AttestationVerifier, C: RevocationChecker> AttestationVerifier
+    for RevocationCheckingVerifier<V, C>
+{
+    fn supports(&self, algorithm: &str) -> bool {
+        self.inner.supports(algorithm)
+    }
+
+    fn verify(&self, public_key: &str, message: &[u8], signature: &str) -> bool {
+        self.inner.verify(public_key, message, signature)
+    }
+
+    fn verify_raw(&self, public_key: &str, message: &[u8], signature: &[u8]) -> bool {
+        self.inner.verify_raw(public_key, message, signature)
+    }
+
+    fn verify_as(
+        &self,
+        algorithm: &str,
+        public_key: &str,
+        message: &[u8],
+        signature: &str,
+    ) -> bool {
+        self.inner
+            .verify_as(algorithm, public_key, message, signature)
+    }
+
+    fn verify_raw_as(
+        &self,
+        algorithm: &str,
+        public_key: &str,
+        message: &[u8],
+        signature: &[u8],
+    ) -> bool {
+        self.inner
+            .verify_raw_as(algorithm, public_key, message, signature)
+    }
+
+    fn verify_attestation(&self, attestation: &CapabilityAttestation) -> bool {
+        self.inner.verify_attestation(attestation)
+    }
+
+    fn check_freshness(&self, attestation: &CapabilityAttestation) -> bool {
+        self.inner.check_freshness(attestation)
+    }
+
+    fn check_revocation(
+        &self,
+        attestation: &CapabilityAttestation,
+    ) -> Result<RevocationStatus, RevocationCheckError> {
+        if let RevocationStatus::Revoked { reason } = self.inner.check_revocation(attestation)? {
+            return Ok(RevocationStatus::Revoked { reason });
+        }
+        match self.checker.status(attestation) {
+            Err(_) if self.failure_policy == RevocationFailurePolicy::SoftFail => {
+                Ok(RevocationStatus::Good)
+            }
+            status => status,
+        }
+    }
+
+    fn expiration_tolerance(&self) -> ExpirationTolerance {
+        self.inner.expiration_tolerance()
+    }
+}
+
+/// Response body of the revocation endpoint
+#[cfg(feature = "http")]
+#[derive(Debug, serde::Deserialize)]
+struct RevocationResponse {
+    revoked: bool,
+    #[serde(default)]
+    reason: Option<String>,
+}
+
+/// Checker querying a revocation endpoint over HTTP (requires the `http` feature)
+///
+/// Requests time out and are retried on transport and server errors.
+/// Answers are cached per capability hash (see `with_cache_ttl`); failed
+/// lookups are not cached.
+#[cfg(feature = "http")]
+#[derive(Debug)]
+pub struct HttpRevocationChecker {
+    base_url: String,
+    timeout: Duration,
+    retries: u32,
+    retry_backoff: Duration,
+    cache_ttl: Duration,
+    cache: Mutex<HashMap<String, (Instant, RevocationStatus)>>,
+}
+
+#[cfg(feature = "http")]
+impl HttpRevocationChecker {
+    /// Create a checker for the revocation service at `base_url`
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into(),
+            timeout: Duration::from_secs(DEFAULT_REVOCATION_CHECK_TIMEOUT_SECONDS),
+            retries: DEFAULT_REVOCATION_CHECK_RETRIES,
+            retry_backoff: Duration::from_millis(DEFAULT_REVOCATION_CHECK_RETRY_BACKOFF_MILLIS),
+            cache_ttl: Duration::from_secs(DEFAULT_REVOCATION_CHECK_CACHE_TTL_SECONDS),
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Set the timeout of a single request
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Set how often a failed request is retried, and the backoff between attempts
+    pub fn with_retries(mut self, retries: u32, backoff: Duration) -> Self {
+        self.retries = retries;
+        self.retry_backoff = backoff;
+        self
+    }
+
+    /// Set how long answers are cached
+    pub fn with_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.cache_ttl = ttl;
+        self
+    }
+
+    /// URL queried for a capability hash
+    pub fn revocation_url(&self, capability_hash: &str) -> String {
+        format!(
+            "{}{}{}",
+            self.base_url.trim_end_matches('/'),
+            REVOCATION_CHECK_PATH,
+            capability_hash
+        )
+    }
+
+    /// URL queried for a worker ID
+    pub fn worker_revocation_url(&self, worker_id: &str) -> String {
+        format!(
+            "{}{}{}",
+            self.base_url.trim_end_matches('/'),
+            REVOCATION_WORKER_CHECK_PATH,
+            worker_id
+        )
+    }
+
+    /// Forget the cached answer for a capability hash
+    pub fn invalidate(&self, capability_hash: &str) {
+        self.entries().remove(capability_hash);
+    }
+
+    /// Forget the cached answer for a worker ID
+    pub fn invalidate_worker(&self, worker_id: &str) {
+        self.entries().remove(&worker_cache_key(worker_id));
+    }
+
+    /// Forget all cached answers
+    pub fn invalidate_all(&self) {
+        self.entries().clear();
+    }
+
+    fn entries(&self) -> MutexGuard<'_, HashMap<String, (Instant, RevocationStatus)>> {
+        self.cache.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Cached answer for a key, or the answer of the service at `url`
+    fn cached_status(
+        &self,
+        key: &str,
+        url: &str,
+    ) -> Result<RevocationStatus, RevocationCheckError> {
+        if let Some((fetched, status)) = self.entries().get(key) {
+            if fetched.elapsed() < self.cache_ttl {
+                return Ok(status.clone());
+            }
+        }
+        let status = self.fetch(url)?;
+        self.entries()
+            .insert(key.to_string(), (Instant::now(), status.clone()));
+        Ok(status)
+    }
+
+    /// Query the service, retrying transport errors and server errors
+    fn fetch(&self, url: &str) -> Result<RevocationStatus, RevocationCheckError> {
+        let mut error = RevocationCheckError::Transport("no attempt made".to_string());
+        for attempt in 0..=self.retries {
+            if attempt > 0 {
+                std::thread::sleep(self.retry_backoff * attempt);
+            }
+
+            match ureq::get(url).timeout(self.timeout).call() {
+                Ok(response) => {
+                    let body = response
+                        .into_string()
+                        .map_err(|e| RevocationCheckError::Transport(e.to_string()))?;
+                    let body: RevocationResponse = serde_json::from_str(&body)
+                        .map_err(|e| RevocationCheckError::Parse(e.to_string()))?;
+                    if !body.revoked {
+                        return Ok(RevocationStatus::Good);
+                    }
+                    return Ok(RevocationStatus::Revoked {
+                        reason: body.reason.unwrap_or_default(),
+                    });
+                }
+                Err(ureq::Error::Status(404, _)) => return Ok(RevocationStatus::Good),
+                Err(ureq::Error::Status(code, _)) if code < 500 => {
+                    return Err(RevocationCheckError::Status(code))
+                }
+                Err(ureq::Error::Status(code, _)) => error = RevocationCheckError::Status(code),
+                Err(e) => error = RevocationCheckError::Transport(e.to_string()),
+            }
+        }
+        Err(error)
+    }
+}
+
+#[cfg(feature = "http")]
+impl RevocationChecker for HttpRevocationChecker {
+    fn status(
+        &self,
+        attestation: &CapabilityAttestation,
+    ) -> Result<RevocationStatus, RevocationCheckError> {
+        let hash = attestation.capability_hash.as_str();
+        if hash.is_empty() || !hash.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+            return Err(RevocationCheckError::InvalidHash(hash.to_string()));
+        }
+        self.cached_status(hash, &self.revocation_url(hash))
+    }
+
+    fn worker_status(
+        &self,
+        worker_id: &str,
+        attestation: &CapabilityAttestation,
+    ) -> Result<RevocationStatus, RevocationCheckError> {
+        let valid = !worker_id.is_empty()
+            && worker_id
+                .bytes()
+                .all(|byte| byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.'));
+        if !valid || worker_id == "." || worker_id == ".." {
+            return Err(RevocationCheckError::InvalidWorkerId(worker_id.to_string()));
+        }
+        let url = self.worker_revocation_url(worker_id);
+        match self.cached_status(&worker_cache_key(worker_id), &url)? {
+            RevocationStatus::Good => self.status(attestation),
+            revoked => Ok(revoked),
+        }
+    }
+}
+
+/// Cache key of a worker ID, distinct from any capability hash
+#[cfg(feature = "http")]
+fn worker_cache_key(worker_id: &str) -> String {
+    format!("worker:{}", worker_id)
+}
@@ -0,0 +1,127 @@
+//! Sandbox technology declarations for isolated tool execution
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::well_known::{DOCKER_SUPPORT, FIRECRACKER_SUPPORT, GVISOR_SUPPORT};
+
+/// Isolation technology offered by a worker
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SandboxTechnology {
+    /// Tools run directly on the host
+    #[default]
+    None,
+    /// Docker (or another OCI runtime) containers
+    Docker,
+    /// gVisor user-space kernel
+    #[serde(rename = "gvisor")]
+    GVisor,
+    /// Firecracker micro-VMs
+    Firecracker,
+}
+
+impl SandboxTechnology {
+    /// Isolation level provided by this technology
+    pub fn isolation_level(&self) -> IsolationLevel {
+        match self {
+            SandboxTechnology::None => IsolationLevel::None,
+            SandboxTechnology::Docker => IsolationLevel::Container,
+            SandboxTechnology::GVisor => IsolationLevel::Kernel,
+            SandboxTechnology::Firecracker => IsolationLevel::VirtualMachine,
+        }
+    }
+
+    /// Flag name previously used to advertise this technology
+    pub fn legacy_flag(&self) -> Option<&'static str> {
+        match self {
+            SandboxTechnology::None => None,
+            SandboxTechnology::Docker => Some(DOCKER_SUPPORT),
+            SandboxTechnology::GVisor => Some(GVISOR_SUPPORT),
+            SandboxTechnology::Firecracker => Some(FIRECRACKER_SUPPORT),
+        }
+    }
+}
+
+/// Strength of isolation, ordered from weakest to strongest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IsolationLevel {
+    /// No isolation
+    None,
+    /// Namespace/cgroup container isolation
+    Container,
+    /// User-space kernel isolation
+    Kernel,
+    /// Hardware virtualization
+    VirtualMachine,
+}
+
+/// Configured limits of a sandbox
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SandboxLimits {
+    /// Maximum memory per sandbox in MB
+    pub memory_limit_mb: Option<u64>,
+    /// Maximum virtual CPUs per sandbox
+    pub max_vcpus: Option<u32>,
+    /// Maximum disk space per sandbox in MB
+    pub disk_limit_mb: Option<u64>,
+    /// Whether the sandbox allows network access
+    pub network_enabled: bool,
+}
+
+/// Sandbox capability of a worker
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SandboxCapability {
+    /// Isolation technology
+    pub technology: SandboxTechnology,
+    /// Sandbox limits
+    pub limits: SandboxLimits,
+}
+
+impl SandboxCapability {
+    /// Create a sandbox capability for a technology with default limits
+    pub fn new(technology: SandboxTechnology) -> Self {
+        Self {
+            technology,
+            limits: SandboxLimits::default(),
+        }
+    }
+
+    /// Set sandbox limits
+    pub fn with_limits(mut self, limits: SandboxLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Derive a sandbox capability from legacy flags such as `docker_support`
+    ///
+    /// The strongest advertised technology wins.
+    pub fn from_legacy_flags(flags: &HashMap<String, bool>) -> Self {
+        let technology = [
+            SandboxTechnology::Firecracker,
+            SandboxTechnology::GVisor,
+            SandboxTechnology::Docker,
+        ]
+        .into_iter()
+        .find(|tech| {
+            tech.legacy_flag()
+                .and_then(|flag| flags.get(flag))
+                .copied()
+                .unwrap_or(false)
+        })
+        .unwrap_or_default();
+
+        Self::new(technology)
+    }
+
+    /// Isolation level of this sandbox
+    pub fn isolation_level(&self) -> IsolationLevel {
+        self.technology.isolation_level()
+    }
+
+    /// Check if this sandbox provides at least the given isolation level
+    pub fn provides(&self, level: IsolationLevel) -> bool {
+        self.isolation_level() >= level
+    }
+}
@@ -0,0 +1,157 @@
+//! Mapping between capability tool names and SARIF `tool.driver` metadata
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::capabilities::Capabilities;
+
+/// Known analysis tools as (tool name, SARIF driver name, information URI)
+const KNOWN_SARIF_DRIVERS: &[(&str, &str, &str)] = &[
+    (
+        "clippy",
+        "clippy",
+        "https://rust-lang.github.io/rust-clippy/",
+    ),
+    ("cargo-audit", "cargo-audit", "https://rustsec.org"),
+    ("codeql", "CodeQL", "https://codeql.github.com"),
+    ("semgrep", "Semgrep OSS", "https://semgrep.dev"),
+    ("eslint", "ESLint", "https://eslint.org"),
+    ("bandit", "Bandit", "https://bandit.readthedocs.io"),
+    ("pylint", "Pylint", "https://pylint.readthedocs.io"),
+    ("gosec", "gosec", "https://github.com/securego/gosec"),
+    ("trivy", "Trivy", "https://github.com/aquasecurity/trivy"),
+    ("slither", "Slither", "https://github.com/crytic/slither"),
+    ("mythril", "Mythril", "https://github.com/Consensys/mythril"),
+];
+
+/// SARIF `tool.driver` metadata
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifDriver {
+    /// Driver name as it appears in a SARIF run
+    pub name: String,
+    /// Driver version (if reported)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Information URI of the driver
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub information_uri: Option<String>,
+}
+
+impl SarifDriver {
+    /// Build the SARIF driver metadata for a capability tool name
+    ///
+    /// Unknown tools map to a driver with the same name.
+    pub fn for_tool(tool_name: &str) -> Self {
+        match KNOWN_SARIF_DRIVERS
+            .iter()
+            .find(|(tool, _, _)| tool.eq_ignore_ascii_case(tool_name))
+        {
+            Some((_, driver, uri)) => Self {
+                name: driver.to_string(),
+                version: None,
+                information_uri: Some(uri.to_string()),
+            },
+            None => Self {
+                name: tool_name.to_string(),
+                version: None,
+                information_uri: None,
+            },
+        }
+    }
+
+    /// Capability tool name this driver corresponds to
+    ///
+    /// Unknown drivers map to their lowercased driver name.
+    pub fn tool_name(&self) -> String {
+        KNOWN_SARIF_DRIVERS
+            .iter()
+            .find(|(_, driver, _)| driver.eq_ignore_ascii_case(&self.name))
+            .map(|(tool, _, _)| tool.to_string())
+            .unwrap_or_else(|| self.name.to_ascii_lowercase())
+    }
+
+    /// Extract the driver of every run in a SARIF log
+    pub fn from_sarif_log(log: &Value) -> Vec<SarifDriver> {
+        log.get("runs")
+            .and_then(Value::as_array)
+            .map(|runs| {
+                runs.iter()
+                    .map(|run| {
+                        let driver = run.pointer("/tool/driver");
+                        let field = |key: &str| {
+                            driver
+                                .and_then(|d| d.get(key))
+                                .and_then(Value::as_str)
+                                .map(str::to_string)
+                        };
+                        SarifDriver {
+                            name: field("name").unwrap_or_default(),
+                            version: field("semanticVersion").or_else(|| field("version")),
+                            information_uri: field("informationUri"),
+                        }
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Correlation of a SARIF run with a registered tool
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SarifRunCorrelation {
+    /// Index of the run in the SARIF log
+    pub run_index: usize,
+    /// Driver name reported by the run
+    pub driver_name: String,
+    /// Registered tool that produced the run (if any)
+    pub tool_name: Option<String>,
+    /// Version declared in the capability set
+    pub declared_version: Option<String>,
+    /// Version reported by the SARIF run
+    pub reported_version: Option<String>,
+    /// Whether declared and reported versions agree (if both are known)
+    pub version_matches: Option<bool>,
+}
+
+impl Capabilities {
+    /// Correlate SARIF runs with the tools registered in this capability set
+    pub fn correlate_sarif_runs(&self, drivers: &[SarifDriver]) -> Vec<SarifRunCorrelation> {
+        drivers
+            .iter()
+            .enumerate()
+            .map(|(run_index, driver)| {
+                let wanted = driver.tool_name();
+                let tool = self.tools_iter().find(|tool| {
+                    tool.tool_name.eq_ignore_ascii_case(&wanted)
+                        || tool
+                            .alternatives
+                            .iter()
+                            .any(|alt| alt.eq_ignore_ascii_case(&wanted))
+                });
+
+                let declared_version = tool.and_then(|tool| tool.version.clone());
+                let version_matches = match (&declared_version, &driver.version) {
+                    (Some(declared), Some(reported)) => {
+                        Some(declared.trim_start_matches('v') == reported.trim_start_matches('v'))
+                    }
+                    _ => None,
+                };
+
+                SarifRunCorrelation {
+                    run_index,
+                    driver_name: driver.name.clone(),
+                    tool_name: tool.map(|tool| tool.tool_name.clone()),
+                    declared_version,
+                    reported_version: driver.version.clone(),
+                    version_matches,
+                }
+            })
+            .collect()
+    }
+
+    /// Correlate the runs of a SARIF log with the tools registered in this capability set
+    pub fn correlate_sarif_log(&self, log: &Value) -> Vec<SarifRunCorrelation> {
+        self.correlate_sarif_runs(&SarifDriver::from_sarif_log(log))
+    }
+}
@@ -0,0 +1,169 @@
+//! Background re-verification of registered workers on a tokio runtime
+//!
+//! `VerificationScheduler` periodically re-checks attestations, expirations
+//! and tool availability of every registered worker, records the resulting
+//! health in the registry and invokes callbacks on status transitions.
+//! `AttestationManager::spawn_sweeper` periodically sweeps expired
+//! attestations out of a shared manager.
+
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+use crate::attestation::AttestationManager;
+use crate::checker::ToolChecker;
+use crate::clock;
+use crate::constants::*;
+use crate::health::{WorkerHealthReport, WorkerHealthTransition};
+use crate::registry::CapabilityRegistry;
+
+type TransitionCallback = Arc<dyn Fn(&WorkerHealthTransition) + Send + Sync>;
+
+/// Scheduler re-verifying all workers of a shared registry on an interval
+pub struct VerificationScheduler<C> {
+    registry: Arc<RwLock<CapabilityRegistry>>,
+    tool_checker: C,
+    interval: Duration,
+    callbacks: Vec<TransitionCallback>,
+}
+
+impl<C: ToolChecker + Send + Sync + 'static> VerificationScheduler<C> {
+    /// Create a scheduler for a registry using a tool checker
+    pub fn new(registry: Arc<RwLock<CapabilityRegistry>>, tool_checker: C) -> Self {
+        Self {
+            registry,
+            tool_checker,
+            interval: Duration::from_secs(DEFAULT_VERIFICATION_INTERVAL_SECONDS),
+            callbacks: Vec::new(),
+        }
+    }
+
+    /// Set how often workers are re-verified
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Call `callback` whenever a worker's health status changes
+    pub fn on_transition(
+        mut self,
+        callback: impl Fn(&WorkerHealthTransition) + Send + Sync + 'static,
+    ) -> Self {
+        self.callbacks.push(Arc::new(callback));
+        self
+    }
+
+    /// Re-verify every registered worker once and return the transitions
+    ///
+    /// Checks run without holding the registry lock; callbacks run after the
+    /// results are recorded. This blocks while tools are checked.
+    pub fn run_once(&self) -> Vec<WorkerHealthTransition> {
+        let (workers, algorithms): (Vec<_>, _) = {
+            let registry = self.registry.read().unwrap_or_else(|e| e.into_inner());
+            let workers = registry
+                .list_ids()
+                .into_iter()
+                .filter_map(|id| registry.get(&id).cloned())
+                .collect();
+            (workers, registry.algorithms().clone())
+        };
+
+        let reports: Vec<_> = workers
+            .iter()
+            .map(|caps| WorkerHealthReport::check(caps, &self.tool_checker, &algorithms))
+            .collect();
+
+        let transitions: Vec<_> = {
+            let mut registry = self.registry.write().unwrap_or_else(|e| e.into_inner());
+            reports
+                .into_iter()
+                .filter_map(|report| registry.record_worker_health(report))
+                .collect()
+        };
+
+        for transition in &transitions {
+            for callback in &self.callbacks {
+                callback(transition);
+            }
+        }
+        transitions
+    }
+
+    /// Start re-verifying now and then every interval on the current tokio runtime
+    ///
+    /// Checks run on the blocking thread pool, with the clock of the calling
+    /// thread. Panics if called outside a tokio runtime.
+    pub fn spawn(self) -> VerificationHandle {
+        let interval = self.interval;
+        let scheduler = Arc::new(self);
+        let clock = clock::current();
+        let (stop, mut stopped) = watch::channel(false);
+
+        let task = tokio::spawn(async move {
+            loop {
+                let tick = Arc::clone(&scheduler);
+                let clock = clock.clone();
+                let _ = tokio::task::spawn_blocking(move || {
+                    clock::with_inherited(clock, || tick.run_once())
+                })
+                .await;
+
+                match tokio::time::timeout(interval, stopped.changed()).await {
+                    Err(_) => continue,
+                    Ok(_) => break,
+                }
+            }
+        });
+
+        VerificationHandle { stop, task }
+    }
+}
+
+impl AttestationManager {
+    /// Start sweeping a shared manager now and then every interval on the current tokio runtime
+    ///
+    /// Subscribers of the manager receive the events of each sweep. Sweeps use
+    /// the clock of the calling thread. Panics if called outside a tokio
+    /// runtime.
+    pub fn spawn_sweeper(
+        manager: Arc<RwLock<AttestationManager>>,
+        interval: Duration,
+    ) -> VerificationHandle {
+        let clock = clock::current();
+        let (stop, mut stopped) = watch::channel(false);
+
+        let task = tokio::spawn(async move {
+            loop {
+                clock::with_inherited(clock.clone(), || {
+                    manager.write().unwrap_or_else(|e| e.into_inner()).sweep()
+                });
+
+                match tokio::time::timeout(interval, stopped.changed()).await {
+                    Err(_) => continue,
+                    Ok(_) => break,
+                }
+            }
+        });
+
+        VerificationHandle { stop, task }
+    }
+}
+
+/// Handle to a running verification scheduler or attestation sweeper
+///
+/// Dropping the handle also stops the task after its current round.
+#[derive(Debug)]
+pub struct VerificationHandle {
+    stop: watch::Sender<bool>,
+    task: JoinHandle<()>,
+}
+
+impl VerificationHandle {
+    /// Stop the scheduler and wait for the current round to finish
+    pub async fn stop(self) {
+        let _ = self.stop.send(true);
+        let _ = self.task.await;
+    }
+}
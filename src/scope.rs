@@ -0,0 +1,120 @@
+//! Repository and organization scoping of capabilities
+
+use serde::{Deserialize, Serialize};
+
+use crate::signing::encode_fields;
+
+/// Restricts which repositories a capability may be used on
+///
+/// An empty scope is unrestricted. Repository patterns support `*` as a
+/// wildcard (e.g., `github.com/acme/*`); organizations match the owner
+/// segment of a repository path (`acme` in `github.com/acme/widgets`).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CapabilityScope {
+    /// Allowed repository patterns
+    pub repositories: Vec<String>,
+    /// Allowed organizations
+    pub organizations: Vec<String>,
+}
+
+impl CapabilityScope {
+    /// Create an unrestricted scope
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allow repositories matching a pattern
+    pub fn with_repository(mut self, pattern: impl Into<String>) -> Self {
+        self.repositories.push(pattern.into());
+        self
+    }
+
+    /// Allow every repository of an organization
+    pub fn with_organization(mut self, organization: impl Into<String>) -> Self {
+        self.organizations.push(organization.into());
+        self
+    }
+
+    /// Check if the scope places no restriction
+    pub fn is_unrestricted(&self) -> bool {
+        self.repositories.is_empty() && self.organizations.is_empty()
+    }
+
+    /// Check if a target repository is within scope
+    pub fn allows(&self, repository: &str) -> bool {
+        if self.is_unrestricted() {
+            return true;
+        }
+
+        let repository = normalize(repository);
+
+        if self
+            .repositories
+            .iter()
+            .any(|pattern| glob_match(&normalize(pattern), &repository))
+        {
+            return true;
+        }
+
+        let segments: Vec<&str> = repository.split('/').collect();
+        let owner = if segments.len() >= 2 {
+            Some(segments[segments.len() - 2])
+        } else {
+            None
+        };
+
+        owner.is_some_and(|owner| {
+            self.organizations
+                .iter()
+                .any(|org| org.eq_ignore_ascii_case(owner))
+        })
+    }
+
+    /// Canonical string of the scope for hashing
+    pub fn canonical(&self) -> String {
+        let mut repositories: Vec<String> =
+            self.repositories.iter().map(|r| normalize(r)).collect();
+        repositories.sort();
+        let mut organizations: Vec<String> = self
+            .organizations
+            .iter()
+            .map(|o| o.to_ascii_lowercase())
+            .collect();
+        organizations.sort();
+        encode_fields([encode_fields(repositories), encode_fields(organizations)])
+    }
+}
+
+fn normalize(repository: &str) -> String {
+    let repository = repository
+        .trim()
+        .trim_start_matches("https://")
+        .trim_start_matches("http://")
+        .trim_end_matches('/');
+    repository
+        .strip_suffix(".git")
+        .unwrap_or(repository)
+        .to_ascii_lowercase()
+}
+
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    if parts.len() == 1 {
+        return pattern == text;
+    }
+
+    let mut rest = match text.strip_prefix(parts[0]) {
+        Some(rest) => rest,
+        None => return false,
+    };
+
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+
+    rest.ends_with(parts[parts.len() - 1])
+}
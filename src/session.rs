@@ -0,0 +1,177 @@
+//! Long-running capability sessions with heartbeats and progress
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::clock::current_timestamp;
+use crate::constants::*;
+
+/// Lifecycle state of a session
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionState {
+    /// Session is running
+    Active,
+    /// Session finished normally
+    Completed,
+    /// Session was cancelled by the orchestrator
+    Cancelled,
+    /// Session missed its heartbeat or exceeded its maximum lifetime
+    Expired,
+}
+
+/// A long-running job bound to a matched capability
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    /// Session identifier
+    pub id: String,
+    /// Worker running the session
+    pub worker_id: String,
+    /// Capability type the session was matched on
+    pub capability_type: String,
+    /// Tool that satisfied the capability
+    pub tool_name: String,
+    /// Whether the bound tool can checkpoint and resume
+    #[serde(default)]
+    pub checkpointing: bool,
+    /// Timestamp when the session started
+    pub started_at: u64,
+    /// Timestamp of the last heartbeat
+    pub last_heartbeat: u64,
+    /// Maximum lifetime in seconds (from the tool's `permissions.timeout_seconds`)
+    pub max_lifetime_seconds: u64,
+    /// Seconds without a heartbeat after which the session expires
+    pub heartbeat_timeout_seconds: u64,
+    /// Last reported progress percentage
+    pub progress_percent: Option<u8>,
+    /// Last reported progress message
+    pub progress_message: Option<String>,
+    /// Current state
+    pub state: SessionState,
+    /// Reason the session ended (if cancelled or expired)
+    pub end_reason: Option<String>,
+    /// Location of the latest checkpoint (if the tool checkpoints)
+    pub checkpoint_location: Option<String>,
+    /// Session this one resumes (if rescheduled)
+    pub resumed_from: Option<String>,
+    /// Session that resumes this one (once rescheduled)
+    #[serde(default)]
+    pub rescheduled_to: Option<String>,
+    /// Timestamp when the session left the active state
+    #[serde(default)]
+    pub ended_at: Option<u64>,
+}
+
+impl Session {
+    /// Start a session at the current time
+    pub(crate) fn start(
+        id: String,
+        worker_id: String,
+        capability_type: String,
+        tool_name: String,
+        checkpointing: bool,
+        max_lifetime_seconds: u64,
+    ) -> Self {
+        let now = current_timestamp();
+        Self {
+            id,
+            worker_id,
+            capability_type,
+            tool_name,
+            checkpointing,
+            started_at: now,
+            last_heartbeat: now,
+            max_lifetime_seconds,
+            heartbeat_timeout_seconds: DEFAULT_SESSION_HEARTBEAT_TIMEOUT_SECONDS,
+            progress_percent: None,
+            progress_message: None,
+            state: SessionState::Active,
+            end_reason: None,
+            checkpoint_location: None,
+            resumed_from: None,
+            rescheduled_to: None,
+            ended_at: None,
+        }
+    }
+
+    /// Timestamp after which the session exceeds its maximum lifetime
+    pub fn deadline(&self) -> u64 {
+        self.started_at.saturating_add(self.max_lifetime_seconds)
+    }
+
+    /// Timestamp after which the session expires unless a heartbeat arrives
+    pub fn heartbeat_deadline(&self) -> u64 {
+        self.last_heartbeat
+            .saturating_add(self.heartbeat_timeout_seconds)
+            .min(self.deadline())
+    }
+
+    /// Check if the session is still active
+    pub fn is_active(&self) -> bool {
+        self.state == SessionState::Active
+    }
+
+    /// Check if the session has outlived its heartbeat or lifetime at `now`
+    pub fn is_overdue(&self, now: u64) -> bool {
+        now > self.heartbeat_deadline()
+    }
+
+    /// Leave the active state
+    pub(crate) fn end(&mut self, state: SessionState, reason: Option<String>) {
+        self.state = state;
+        self.end_reason = reason;
+        self.ended_at = Some(current_timestamp());
+    }
+}
+
+/// Errors returned by session operations
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionError {
+    /// No session with the given id exists
+    NotFound(String),
+    /// The session is no longer active
+    NotActive(SessionState),
+    /// The worker is not registered
+    UnknownWorker(String),
+    /// No tool of the worker satisfies the capability
+    NoMatchingTool(String),
+    /// Every satisfying tool has exhausted its concurrency slots
+    SlotsExhausted(String),
+    /// The session completed or was cancelled and cannot be rescheduled
+    NotReschedulable(SessionState),
+    /// The session was already rescheduled (to the given session)
+    AlreadyRescheduled(String),
+    /// The session's tool does not support checkpointing
+    CheckpointingUnsupported(String),
+}
+
+impl fmt::Display for SessionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SessionError::NotFound(id) => write!(f, "session {} not found", id),
+            SessionError::NotActive(state) => write!(f, "session is not active ({:?})", state),
+            SessionError::UnknownWorker(id) => write!(f, "worker {} is not registered", id),
+            SessionError::NoMatchingTool(capability) => {
+                write!(f, "no available tool satisfies {}", capability)
+            }
+            SessionError::SlotsExhausted(capability) => {
+                write!(
+                    f,
+                    "all tools satisfying {} are at their concurrency limit",
+                    capability
+                )
+            }
+            SessionError::NotReschedulable(state) => {
+                write!(f, "session cannot be rescheduled ({:?})", state)
+            }
+            SessionError::AlreadyRescheduled(id) => {
+                write!(f, "session was already rescheduled to {}", id)
+            }
+            SessionError::CheckpointingUnsupported(tool) => {
+                write!(f, "tool {} does not support checkpointing", tool)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SessionError {}
@@ -0,0 +1,106 @@
+//! Load shedding hooks for registries embedded in overloaded services
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::constants::*;
+
+/// Load level reported by the embedding service
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LoadLevel {
+    /// Serve every query with full verification
+    #[default]
+    Normal,
+    /// Degrade to digest-only verification and serve fresh cached matches
+    Degraded,
+    /// Additionally serve stale cached matches and reject low-priority queries
+    Overloaded,
+}
+
+/// Priority of a match request
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchPriority {
+    /// Background or speculative queries
+    Low,
+    /// Regular scheduling queries
+    #[default]
+    Normal,
+    /// Queries that must be answered even under overload
+    High,
+}
+
+/// Policy controlling how the registry sheds load
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SheddingPolicy {
+    /// Age in seconds up to which cached match results are considered fresh
+    pub cache_ttl_seconds: u64,
+    /// Lowest priority still answered while overloaded
+    pub min_priority_when_overloaded: MatchPriority,
+}
+
+impl Default for SheddingPolicy {
+    fn default() -> Self {
+        Self {
+            cache_ttl_seconds: DEFAULT_MATCH_CACHE_TTL_SECONDS,
+            min_priority_when_overloaded: MatchPriority::Normal,
+        }
+    }
+}
+
+/// A query result annotated with how it was produced under load shedding
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Shed<T> {
+    /// The result value
+    pub value: T,
+    /// Whether the value was served from cache instead of computed
+    pub cached: bool,
+    /// Whether the cached value is older than the cache TTL
+    pub stale: bool,
+    /// Age of the value in seconds
+    pub age_seconds: u64,
+    /// Whether verification was reduced to digest checks only
+    pub digest_only: bool,
+}
+
+impl<T> Shed<T> {
+    /// Wrap a freshly computed value
+    pub fn fresh(value: T) -> Self {
+        Self {
+            value,
+            cached: false,
+            stale: false,
+            age_seconds: 0,
+            digest_only: false,
+        }
+    }
+}
+
+/// Errors returned when the registry sheds a query
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SheddingError {
+    /// The registry is overloaded and the request priority is too low
+    Overloaded {
+        /// Priority of the rejected request
+        priority: MatchPriority,
+    },
+}
+
+impl fmt::Display for SheddingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SheddingError::Overloaded { priority } => {
+                write!(
+                    f,
+                    "registry overloaded, rejected {:?} priority request",
+                    priority
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for SheddingError {}
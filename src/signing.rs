@@ -0,0 +1,630 @@
+//! Pluggable signing backends for attestations
+//!
+//! Attestations, capability bundles and trust bundles are signed through an
+//! `AttestationSigner` and checked through an `AttestationVerifier`, so
+//! callers can plug in ring, ed25519-dalek, OpenSSL or an HSM. A signer signs
+//! opaque messages; the attestation layer decides what the message covers.
+//!
+//! An `AlgorithmRegistry` maps algorithm identifiers (`Ed25519`, `ES256`,
+//! `RS256`, ...) to verifiers, so a fleet whose workers attest with different
+//! key types can be verified in one pass.
+//!
+//! Attestations can answer a verifier's challenge (`attest_challenge`) and
+//! carry an issuance counter (`CountingSigner`), both covered by the
+//! signature. `AttestationVerifier::check_freshness` decides whether a validly
+//! signed attestation is fresh; `ChallengeVerifier` accepts each issued
+//! challenge once. `AttestationVerifier::check_revocation` asks whether it
+//! was revoked since (see `RevocationCheckingVerifier`).
+//!
+//! With the `test-signing` feature, `CapabilitySigner` and `MockVerifier`
+//! provide a mock scheme whose "signatures" are derived from the message
+//! alone. They offer no security and are meant for tests only.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use crate::clock::{current_timestamp, Clock, InjectedClock, SharedClock};
+#[cfg(feature = "test-signing")]
+use crate::constants::DEFAULT_ATTESTATION_ALGORITHM;
+use crate::revocation_checker::{RevocationCheckError, RevocationStatus};
+use crate::types::{CapabilityAttestation, ExpirationTolerance, ToolCapability};
+
+/// Backend producing signatures for attestations
+pub trait AttestationSigner {
+    /// Attester identity recorded in attestations
+    fn attester(&self) -> &str;
+
+    /// Algorithm identifier recorded in attestations
+    fn algorithm(&self) -> &str;
+
+    /// Encoded public key matching the signing key
+    fn public_key(&self) -> String;
+
+    /// Sign a message, returning the encoded signature
+    fn sign(&self, message: &[u8]) -> String;
+
+    /// Sign a message, returning the raw signature bytes (used by JWS)
+    ///
+    /// Override when `sign` returns an encoding of the raw signature.
+    fn sign_raw(&self, message: &[u8]) -> Vec<u8> {
+        self.sign(message).into_bytes()
+    }
+
+    /// Attest a capability with this signer
+    fn attest(&self, capability: &ToolCapability) -> CapabilityAttestation {
+        self.attest_hash(&capability.generate_capability_hash())
+    }
+
+    /// Attest a capability in answer to a verifier's challenge
+    ///
+    /// The nonce is covered by the signature, so the attestation cannot be
+    /// replayed against a different challenge.
+    fn attest_challenge(&self, capability: &ToolCapability, nonce: &str) -> CapabilityAttestation {
+        self.attest_hash_with(&capability.generate_capability_hash(), Some(nonce), None)
+    }
+
+    /// Attest an arbitrary hash (e.g., of a whole document) with this signer
+    ///
+    /// The signature covers the hash and the attestation timestamp.
+    fn attest_hash(&self, hash: &str) -> CapabilityAttestation {
+        self.attest_hash_with(hash, None, None)
+    }
+
+    /// Attest a hash with an optional nonce and issuance counter
+    ///
+    /// The signature covers the hash, timestamp, counter and nonce.
+    fn attest_hash_with(
+        &self,
+        hash: &str,
+        nonce: Option<&str>,
+        counter: Option<u64>,
+    ) -> CapabilityAttestation {
+        let mut attestation = CapabilityAttestation {
+            capability_hash: hash.to_string(),
+            signature: String::new(),
+            public_key: self.public_key(),
+            timestamp: current_timestamp(),
+            algorithm: self.algorithm().to_string(),
+            attester: self.attester().to_string(),
+            nonce: nonce.map(str::to_string),
+            counter,
+        };
+        attestation.signature = self.sign(attestation.signed_message().as_bytes());
+        attestation
+    }
+}
+
+/// Backend checking signatures made by an `AttestationSigner`
+pub trait AttestationVerifier {
+    /// Check if this verifier handles an algorithm
+    fn supports(&self, algorithm: &str) -> bool;
+
+    /// Check a signature over a message against an encoded public key
+    fn verify(&self, public_key: &str, message: &[u8], signature: &str) -> bool;
+
+    /// Check raw signature bytes produced by `AttestationSigner::sign_raw`
+    fn verify_raw(&self, public_key: &str, message: &[u8], signature: &[u8]) -> bool {
+        std::str::from_utf8(signature)
+            .is_ok_and(|signature| self.verify(public_key, message, signature))
+    }
+
+    /// Check a signature made with a declared algorithm
+    ///
+    /// Fails unless this verifier handles `algorithm`.
+    fn verify_as(
+        &self,
+        algorithm: &str,
+        public_key: &str,
+        message: &[u8],
+        signature: &str,
+    ) -> bool {
+        self.supports(algorithm) && self.verify(public_key, message, signature)
+    }
+
+    /// Check raw signature bytes made with a declared algorithm
+    ///
+    /// Fails unless this verifier handles `algorithm`.
+    fn verify_raw_as(
+        &self,
+        algorithm: &str,
+        public_key: &str,
+        message: &[u8],
+        signature: &[u8],
+    ) -> bool {
+        self.supports(algorithm) && self.verify_raw(public_key, message, signature)
+    }
+
+    /// Check the signature of an attestation
+    fn verify_attestation(&self, attestation: &CapabilityAttestation) -> bool {
+        self.verify_as(
+            &attestation.algorithm,
+            &attestation.public_key,
+            attestation.signed_message().as_bytes(),
+            &attestation.signature,
+        )
+    }
+
+    /// Check that an attestation with a valid signature is not replayed
+    ///
+    /// Called after the signature verifies. Override to require an issued
+    /// challenge, an increasing counter or a recent timestamp; the default
+    /// accepts every attestation.
+    fn check_freshness(&self, _attestation: &CapabilityAttestation) -> bool {
+        true
+    }
+
+    /// Check that a fresh attestation has not been revoked
+    ///
+    /// Called after the freshness check. Override to consult a revocation
+    /// service; the default reports every attestation as good.
+    fn check_revocation(
+        &self,
+        _attestation: &CapabilityAttestation,
+    ) -> Result<RevocationStatus, RevocationCheckError> {
+        Ok(RevocationStatus::Good)
+    }
+
+    /// Clock tolerances granted when checking expiry
+    ///
+    /// The default grants none.
+    fn expiration_tolerance(&self) -> ExpirationTolerance {
+        ExpirationTolerance::default()
+    }
+
+    /// Clock expiry is checked against
+    ///
+    /// The default is the clock of the current thread.
+    fn clock(&self) -> Option<SharedClock> {
+        None
+    }
+}
+
+/// Verifiers by algorithm identifier
+///
+/// Attestations and bundles are checked with the verifier registered for
+/// their declared algorithm. Signatures checked without an algorithm
+/// (`verify`, `verify_raw`) are rejected; use `verify_as` instead.
+#[derive(Clone)]
+pub struct AlgorithmRegistry {
+    verifiers: BTreeMap<String, Arc<dyn AttestationVerifier + Send + Sync>>,
+    tolerance: ExpirationTolerance,
+    clock: InjectedClock,
+}
+
+impl AlgorithmRegistry {
+    /// Registry with the built-in algorithms
+    ///
+    /// With the `ed25519` feature, `Ed25519` signatures are verified, and with
+    /// the `jws` feature as well, `JWS/EdDSA` tokens. No other algorithm is
+    /// accepted until a verifier is registered for it.
+    pub fn new() -> Self {
+        let registry = Self::empty();
+        #[cfg(feature = "ed25519")]
+        let registry = registry.with_algorithm(
+            crate::constants::ED25519_ATTESTATION_ALGORITHM,
+            crate::ed25519::Ed25519Verifier,
+        );
+        #[cfg(all(feature = "ed25519", feature = "jws"))]
+        let registry = registry.with_algorithm(
+            format!("{}EdDSA", crate::constants::JWS_ALGORITHM_PREFIX),
+            crate::jws::JwsVerifier::new(crate::ed25519::Ed25519Verifier),
+        );
+        registry
+    }
+
+    /// Registry without any algorithm
+    pub fn empty() -> Self {
+        Self {
+            verifiers: BTreeMap::new(),
+            tolerance: ExpirationTolerance::default(),
+            clock: InjectedClock::default(),
+        }
+    }
+
+    /// Grant clock skew and a grace period when checking expiry
+    pub fn with_expiration_tolerance(mut self, tolerance: ExpirationTolerance) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Check expiry against a clock instead of the thread clock
+    pub fn with_clock(mut self, clock: impl Clock + Send + Sync + 'static) -> Self {
+        self.clock = InjectedClock::new(clock);
+        self
+    }
+
+    /// Register the verifier for an algorithm, replacing any previous one
+    pub fn with_algorithm(
+        mut self,
+        algorithm: impl Into<String>,
+        verifier: impl AttestationVerifier + Send + Sync + 'static,
+    ) -> Self {
+        self.register(algorithm, verifier);
+        self
+    }
+
+    /// Register the verifier for an algorithm, replacing any previous one
+    pub fn register(
+        &mut self,
+        algorithm: impl Into<String>,
+        verifier: impl AttestationVerifier + Send + Sync + 'static,
+    ) {
+        self.verifiers.insert(algorithm.into(), Arc::new(verifier));
+    }
+
+    /// Stop accepting an algorithm
+    pub fn remove(&mut self, algorithm: &str) -> bool {
+        self.verifiers.remove(algorithm).is_some()
+    }
+
+    /// Verifier registered for an algorithm
+    pub fn verifier(&self, algorithm: &str) -> Option<&(dyn AttestationVerifier + Send + Sync)> {
+        self.verifiers
+            .get(algorithm)
+            .map(|verifier| verifier.as_ref())
+    }
+
+    /// Registered algorithm identifiers, sorted
+    pub fn algorithms(&self) -> Vec<&str> {
+        self.verifiers.keys().map(String::as_str).collect()
+    }
+
+    /// Shared registry of the built-in algorithms
+    pub(crate) fn builtin() -> &'static AlgorithmRegistry {
+        static BUILTIN: OnceLock<AlgorithmRegistry> = OnceLock::new();
+        BUILTIN.get_or_init(AlgorithmRegistry::new)
+    }
+}
+
+impl Default for AlgorithmRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for AlgorithmRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AlgorithmRegistry")
+            .field("algorithms", &self.algorithms())
+            .field("tolerance", &self.tolerance)
+            .field("clock", &self.clock)
+            .finish()
+    }
+}
+
+impl AttestationVerifier for AlgorithmRegistry {
+    fn supports(&self, algorithm: &str) -> bool {
+        self.verifiers.contains_key(algorithm)
+    }
+
+    fn verify(&self, _public_key: &str, _message: &[u8], _signature: &str) -> bool {
+        false
+    }
+
+    fn verify_raw(&self, _public_key: &str, _message: &[u8], _signature: &[u8]) -> bool {
+        false
+    }
+
+    fn verify_as(
+        &self,
+        algorithm: &str,
+        public_key: &str,
+        message: &[u8],
+        signature: &str,
+    ) -> bool {
+        self.verifier(algorithm)
+            .is_some_and(|verifier| verifier.verify_as(algorithm, public_key, message, signature))
+    }
+
+    fn verify_raw_as(
+        &self,
+        algorithm: &str,
+        public_key: &str,
+        message: &[u8],
+        signature: &[u8],
+    ) -> bool {
+        self.verifier(algorithm).is_some_and(|verifier| {
+            verifier.verify_raw_as(algorithm, public_key, message, signature)
+        })
+    }
+
+    fn verify_attestation(&self, attestation: &CapabilityAttestation) -> bool {
+        self.verifier(&attestation.algorithm)
+            .is_some_and(|verifier| verifier.verify_attestation(attestation))
+    }
+
+    fn check_freshness(&self, attestation: &CapabilityAttestation) -> bool {
+        self.verifier(&attestation.algorithm)
+            .is_some_and(|verifier| verifier.check_freshness(attestation))
+    }
+
+    fn check_revocation(
+        &self,
+        attestation: &CapabilityAttestation,
+    ) -> Result<RevocationStatus, RevocationCheckError> {
+        self.verifier(&attestation.algorithm)
+            .map_or(Ok(RevocationStatus::Good), |verifier| {
+                verifier.check_revocation(attestation)
+            })
+    }
+
+    fn expiration_tolerance(&self) -> ExpirationTolerance {
+        self.tolerance
+    }
+
+    fn clock(&self) -> Option<SharedClock> {
+        self.clock.current()
+    }
+}
+
+/// Signer stamping attestations with an increasing issuance counter
+#[derive(Debug)]
+pub struct CountingSigner<S> {
+    inner: S,
+    last: AtomicU64,
+}
+
+impl<S: AttestationSigner> CountingSigner<S> {
+    /// Count issuances of `inner`, starting after `last_issued`
+    ///
+    /// Persist `last_issued` across restarts so counters never repeat. Once the
+    /// counter reaches `u64::MAX` it stays there instead of wrapping to zero.
+    pub fn new(inner: S, last_issued: u64) -> Self {
+        Self {
+            inner,
+            last: AtomicU64::new(last_issued),
+        }
+    }
+
+    /// Counter of the most recent attestation
+    pub fn last_issued(&self) -> u64 {
+        self.last.load(Ordering::SeqCst)
+    }
+}
+
+impl<S: AttestationSigner> AttestationSigner for CountingSigner<S> {
+    fn attester(&self) -> &str {
+        self.inner.attester()
+    }
+
+    fn algorithm(&self) -> &str {
+        self.inner.algorithm()
+    }
+
+    fn public_key(&self) -> String {
+        self.inner.public_key()
+    }
+
+    fn sign(&self, message: &[u8]) -> String {
+        self.inner.sign(message)
+    }
+
+    fn sign_raw(&self, message: &[u8]) -> Vec<u8> {
+        self.inner.sign_raw(message)
+    }
+
+    fn attest_hash_with(
+        &self,
+        hash: &str,
+        nonce: Option<&str>,
+        counter: Option<u64>,
+    ) -> CapabilityAttestation {
+        let counter = counter.unwrap_or_else(|| {
+            // Saturate at `u64::MAX`; verifiers then reject the repeated counter.
+            let previous = self
+                .last
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |last| {
+                    Some(last.saturating_add(1))
+                })
+                .unwrap_or(u64::MAX);
+            previous.saturating_add(1)
+        });
+        self.inner.attest_hash_with(hash, nonce, Some(counter))
+    }
+}
+
+/// Verifier rejecting attestations that answer no outstanding challenge
+///
+/// Each challenge is accepted once; attestations without a nonce, or older
+/// than the optional maximum age, are rejected.
+#[derive(Debug)]
+pub struct ChallengeVerifier<V> {
+    inner: V,
+    challenges: Mutex<HashSet<String>>,
+    max_age_seconds: Option<u64>,
+}
+
+impl<V: AttestationVerifier> ChallengeVerifier<V> {
+    /// Check challenges of attestations whose signatures `inner` verifies
+    pub fn new(inner: V) -> Self {
+        Self {
+            inner,
+            challenges: Mutex::new(HashSet::new()),
+            max_age_seconds: None,
+        }
+    }
+
+    /// Also reject attestations older than a number of seconds
+    pub fn with_max_age(mut self, seconds: u64) -> Self {
+        self.max_age_seconds = Some(seconds);
+        self
+    }
+
+    /// Issue a challenge for a worker to attest with
+    pub fn issue(&self, nonce: impl Into<String>) {
+        if let Ok(mut challenges) = self.challenges.lock() {
+            challenges.insert(nonce.into());
+        }
+    }
+}
+
+impl<V: AttestationVerifier> AttestationVerifier for ChallengeVerifier<V> {
+    fn supports(&self, algorithm: &str) -> bool {
+        self.inner.supports(algorithm)
+    }
+
+    fn verify(&self, public_key: &str, message: &[u8], signature: &str) -> bool {
+        self.inner.verify(public_key, message, signature)
+    }
+
+    fn verify_raw(&self, public_key: &str, message: &[u8], signature: &[u8]) -> bool {
+        self.inner.verify_raw(public_key, message, signature)
+    }
+
+    fn verify_as(
+        &self,
+        algorithm: &str,
+        public_key: &str,
+        message: &[u8],
+        signature: &str,
+    ) -> bool {
+        self.inner
+            .verify_as(algorithm, public_key, message, signature)
+    }
+
+    fn verify_raw_as(
+        &self,
+        algorithm: &str,
+        public_key: &str,
+        message: &[u8],
+        signature: &[u8],
+    ) -> bool {
+        self.inner
+            .verify_raw_as(algorithm, public_key, message, signature)
+    }
+
+    fn verify_attestation(&self, attestation: &CapabilityAttestation) -> bool {
+        self.inner.verify_attestation(attestation)
+    }
+
+    fn check_freshness(&self, attestation: &CapabilityAttestation) -> bool {
+        if let Some(max_age) = self.max_age_seconds {
+            if !attestation.age_seconds().is_ok_and(|age| age <= max_age) {
+                return false;
+            }
+        }
+        let Some(nonce) = &attestation.nonce else {
+            return false;
+        };
+        self.inner.check_freshness(attestation)
+            && self
+                .challenges
+                .lock()
+                .is_ok_and(|mut challenges| challenges.remove(nonce))
+    }
+
+    fn check_revocation(
+        &self,
+        attestation: &CapabilityAttestation,
+    ) -> Result<RevocationStatus, RevocationCheckError> {
+        self.inner.check_revocation(attestation)
+    }
+
+    fn expiration_tolerance(&self) -> ExpirationTolerance {
+        self.inner.expiration_tolerance()
+    }
+}
+
+/// Message signed for a digest and timestamp by an attester with an algorithm
+pub(crate) fn signed_message(
+    algorithm: &str,
+    attester: &str,
+    digest: &str,
+    timestamp: u64,
+) -> String {
+    encode_fields([algorithm, attester, digest, &timestamp.to_string()])
+}
+
+/// Unambiguous encoding of a sequence of fields
+///
+/// Each field is prefixed with its length in bytes (`<len>:<field>`), so
+/// separators inside values cannot shift field boundaries.
+pub(crate) fn encode_fields<I, S>(fields: I) -> String
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    fields
+        .into_iter()
+        .map(|field| format!("{}:{}", field.as_ref().len(), field.as_ref()))
+        .collect()
+}
+
+/// Lowercase hex encoding of raw keys and signatures
+#[cfg(any(feature = "ed25519", feature = "x509"))]
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Decode hex written by `to_hex`
+#[cfg(any(feature = "ed25519", feature = "x509"))]
+pub(crate) fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 || !hex.is_ascii() {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
+/// Mock signer for tests
+///
+/// Signatures are derived from the message and do not depend on the key.
+#[cfg(feature = "test-signing")]
+#[derive(Debug, Clone)]
+pub struct CapabilitySigner {
+    /// Private key used for signing
+    pub private_key: String,
+    /// Attester identity recorded in attestations
+    pub attester: String,
+}
+
+#[cfg(feature = "test-signing")]
+impl CapabilitySigner {
+    /// Create a signer from a private key and attester identity
+    pub fn new(private_key: impl Into<String>, attester: impl Into<String>) -> Self {
+        Self {
+            private_key: private_key.into(),
+            attester: attester.into(),
+        }
+    }
+}
+
+#[cfg(feature = "test-signing")]
+impl AttestationSigner for CapabilitySigner {
+    fn attester(&self) -> &str {
+        &self.attester
+    }
+
+    fn algorithm(&self) -> &str {
+        DEFAULT_ATTESTATION_ALGORITHM
+    }
+
+    fn public_key(&self) -> String {
+        format!("pubkey_{}", self.private_key)
+    }
+
+    fn sign(&self, message: &[u8]) -> String {
+        format!("signature_{}", String::from_utf8_lossy(message))
+    }
+}
+
+/// Mock verifier accepting signatures made by `CapabilitySigner`
+#[cfg(feature = "test-signing")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MockVerifier;
+
+#[cfg(feature = "test-signing")]
+impl AttestationVerifier for MockVerifier {
+    fn supports(&self, algorithm: &str) -> bool {
+        algorithm == DEFAULT_ATTESTATION_ALGORITHM
+    }
+
+    fn verify(&self, public_key: &str, message: &[u8], signature: &str) -> bool {
+        !public_key.is_empty()
+            && signature == format!("signature_{}", String::from_utf8_lossy(message))
+    }
+}
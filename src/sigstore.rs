@@ -0,0 +1,572 @@
+//! Sigstore attestation backend (requires the `sigstore` feature)
+//!
+//! `SigstoreClient` signs capability hashes with the cosign keyless flow: an
+//! ephemeral ECDSA P-256 key is certified by Fulcio for the holder of an OIDC
+//! identity token, and the signature is recorded in the Rekor transparency
+//! log. The attestation's public key is the Fulcio certificate chain, its
+//! algorithm is `Sigstore`, and its signature field holds a `SigstoreBundle`
+//! (the signature and the Rekor entry with its inclusion proof).
+//!
+//! `SigstoreVerifier` checks bundles offline: the certificate chain against
+//! the Fulcio roots at the time the entry was logged, the signature against
+//! the certificate, the entry against the signature, the inclusion proof
+//! against the logged root hash, and the signed entry timestamp against the
+//! Rekor public key. Distribute the Fulcio roots and Rekor key from the
+//! Sigstore trust root.
+//!
+//! ```rust,no_run
+//! use worker_capabilities::{
+//!     AlgorithmRegistry, SigstoreClient, SigstoreVerifier, ToolCapability, X509RootStore,
+//!     SIGSTORE_ATTESTATION_ALGORITHM,
+//! };
+//!
+//! let token = std::env::var("SIGSTORE_ID_TOKEN").unwrap();
+//! let tool = ToolCapability::new("cargo", true);
+//! let attestation = SigstoreClient::new().attest(&token, &tool).unwrap();
+//! let tool = tool.with_attestation(attestation);
+//!
+//! let fulcio_roots = X509RootStore::from_pem(&std::fs::read_to_string("fulcio.pem").unwrap()).unwrap();
+//! let verifier = SigstoreVerifier::new(fulcio_roots)
+//!     .with_rekor_key(&std::fs::read_to_string("rekor.pub").unwrap())
+//!     .unwrap()
+//!     .with_identity("https://github.com/acme/workers/.github/workflows/release.yml@refs/heads/main");
+//! let algorithms =
+//!     AlgorithmRegistry::new().with_algorithm(SIGSTORE_ATTESTATION_ALGORITHM, verifier);
+//! assert!(tool.verify_attestation_with(&algorithms));
+//! ```
+
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use base64::Engine;
+use ring::rand::SystemRandom;
+use ring::signature::{EcdsaKeyPair, KeyPair, ECDSA_P256_SHA256_ASN1_SIGNING};
+use rustls_pki_types::pem::PemObject;
+use rustls_pki_types::SubjectPublicKeyInfoDer;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::time::Duration;
+use webpki::RawPublicKeyEntity;
+
+use crate::clock::current_timestamp;
+use crate::constants::*;
+use crate::descriptor::canonicalize;
+use crate::signing::{from_hex, AttestationVerifier};
+use crate::types::{CapabilityAttestation, ToolCapability};
+use crate::x509::{
+    leaf_uri_names, parse_pem, verify_leaf_signature, X509RootStore, SIGNATURE_ALGORITHMS,
+};
+
+/// DER prefix of a P-256 SubjectPublicKeyInfo, followed by the public point
+const P256_SPKI_PREFIX: &[u8] = &[
+    0x30, 0x59, 0x30, 0x13, 0x06, 0x07, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01, 0x06, 0x08, 0x2a,
+    0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07, 0x03, 0x42, 0x00,
+];
+
+/// Signature and transparency log entry of a Sigstore attestation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SigstoreBundle {
+    /// Base64 DER signature over the attestation message
+    pub signature: String,
+    /// Rekor entry recording the signature
+    pub tlog_entry: RekorEntry,
+}
+
+/// Rekor log entry, as returned by the Rekor API
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RekorEntry {
+    /// Base64 canonical entry body
+    pub body: String,
+    /// Timestamp when the entry was logged
+    pub integrated_time: u64,
+    /// Hex identifier of the log
+    #[serde(rename = "logID")]
+    pub log_id: String,
+    /// Global index of the entry
+    pub log_index: u64,
+    /// Proofs issued by the log
+    pub verification: RekorVerification,
+}
+
+/// Proofs issued by Rekor for an entry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RekorVerification {
+    /// Inclusion of the entry in the log's Merkle tree
+    pub inclusion_proof: InclusionProof,
+    /// Base64 signature of the log over the entry (SET)
+    pub signed_entry_timestamp: String,
+}
+
+/// Merkle inclusion proof of a log entry (RFC 9162)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InclusionProof {
+    /// Index of the entry in the tree
+    pub log_index: u64,
+    /// Size of the tree the proof is for
+    pub tree_size: u64,
+    /// Hex root hash of the tree
+    pub root_hash: String,
+    /// Hex sibling hashes from the leaf to the root
+    pub hashes: Vec<String>,
+}
+
+impl InclusionProof {
+    /// Check that an entry body is a leaf of the tree with the root hash
+    pub fn verify(&self, body: &[u8]) -> bool {
+        if self.log_index >= self.tree_size {
+            return false;
+        }
+        let mut hashes = Vec::with_capacity(self.hashes.len());
+        for hash in &self.hashes {
+            match decode_hash(hash) {
+                Some(hash) => hashes.push(hash),
+                None => return false,
+            }
+        }
+
+        let mut index = self.log_index;
+        let mut last = self.tree_size - 1;
+        let mut root = merkle_hash(&[&[0x00], body]);
+        for sibling in &hashes {
+            if last == 0 {
+                return false;
+            }
+            if index & 1 == 1 || index == last {
+                root = merkle_hash(&[&[0x01], sibling, &root]);
+                while index & 1 == 0 && index != 0 {
+                    index >>= 1;
+                    last >>= 1;
+                }
+            } else {
+                root = merkle_hash(&[&[0x01], &root, sibling]);
+            }
+            index >>= 1;
+            last >>= 1;
+        }
+        last == 0 && decode_hash(&self.root_hash) == Some(root)
+    }
+}
+
+/// Errors from Sigstore signing and verification
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SigstoreError {
+    /// The OIDC identity token could not be read
+    InvalidToken(String),
+    /// A key could not be generated or parsed
+    InvalidKey(String),
+    /// Fulcio or Rekor could not be reached
+    Transport(String),
+    /// Fulcio or Rekor returned an unexpected response
+    InvalidResponse(String),
+    /// The certificate chain does not lead to a Fulcio root
+    UntrustedCertificate(String),
+    /// The certificate is not issued to a configured identity
+    UnexpectedIdentity,
+    /// The signature does not verify against the certificate
+    InvalidSignature,
+    /// The transparency log entry does not prove the signature was logged
+    NotLogged(String),
+}
+
+impl fmt::Display for SigstoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SigstoreError::InvalidToken(reason) => write!(f, "invalid identity token: {}", reason),
+            SigstoreError::InvalidKey(reason) => write!(f, "invalid key: {}", reason),
+            SigstoreError::Transport(reason) => write!(f, "sigstore request failed: {}", reason),
+            SigstoreError::InvalidResponse(reason) => {
+                write!(f, "unexpected sigstore response: {}", reason)
+            }
+            SigstoreError::UntrustedCertificate(reason) => {
+                write!(f, "untrusted signing certificate: {}", reason)
+            }
+            SigstoreError::UnexpectedIdentity => {
+                write!(f, "signing certificate is not issued to a trusted identity")
+            }
+            SigstoreError::InvalidSignature => write!(f, "signature does not match certificate"),
+            SigstoreError::NotLogged(reason) => {
+                write!(f, "signature is not proven to be logged: {}", reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SigstoreError {}
+
+/// Client for keyless signing with Fulcio and Rekor
+#[derive(Debug, Clone)]
+pub struct SigstoreClient {
+    fulcio_url: String,
+    rekor_url: String,
+    timeout: Duration,
+}
+
+impl SigstoreClient {
+    /// Client for the public Sigstore instance
+    pub fn new() -> Self {
+        Self {
+            fulcio_url: DEFAULT_FULCIO_URL.to_string(),
+            rekor_url: DEFAULT_REKOR_URL.to_string(),
+            timeout: Duration::from_secs(DEFAULT_SIGSTORE_TIMEOUT_SECONDS),
+        }
+    }
+
+    /// Use a private Fulcio instance
+    pub fn with_fulcio_url(mut self, url: impl Into<String>) -> Self {
+        self.fulcio_url = url.into();
+        self
+    }
+
+    /// Use a private Rekor instance
+    pub fn with_rekor_url(mut self, url: impl Into<String>) -> Self {
+        self.rekor_url = url.into();
+        self
+    }
+
+    /// Set the timeout of each request
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Attest a capability as the holder of an OIDC identity token
+    pub fn attest(
+        &self,
+        identity_token: &str,
+        capability: &ToolCapability,
+    ) -> Result<CapabilityAttestation, SigstoreError> {
+        self.attest_hash(identity_token, &capability.generate_capability_hash())
+    }
+
+    /// Attest an arbitrary hash as the holder of an OIDC identity token
+    ///
+    /// The attester is the certificate's URI identity (e.g., a CI workflow),
+    /// or the token's email or subject.
+    pub fn attest_hash(
+        &self,
+        identity_token: &str,
+        hash: &str,
+    ) -> Result<CapabilityAttestation, SigstoreError> {
+        let subject = token_subject(identity_token)?;
+
+        let rng = SystemRandom::new();
+        let pkcs8 = EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, &rng)
+            .map_err(|e| SigstoreError::InvalidKey(e.to_string()))?;
+        let key = EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, pkcs8.as_ref(), &rng)
+            .map_err(|e| SigstoreError::InvalidKey(e.to_string()))?;
+        let sign = |message: &[u8]| {
+            key.sign(&rng, message)
+                .map(|signature| signature.as_ref().to_vec())
+                .map_err(|e| SigstoreError::InvalidKey(e.to_string()))
+        };
+
+        let chain = self.request_certificate(identity_token, &key, &sign(subject.as_bytes())?)?;
+
+        let mut attestation = CapabilityAttestation {
+            capability_hash: hash.to_string(),
+            signature: String::new(),
+            attester: leaf_uri_names(&chain).into_iter().next().unwrap_or(subject),
+            public_key: chain,
+            timestamp: current_timestamp(),
+            algorithm: SIGSTORE_ATTESTATION_ALGORITHM.to_string(),
+            nonce: None,
+            counter: None,
+        };
+        let message = attestation.signed_message();
+        let signature = sign(message.as_bytes())?;
+        let tlog_entry =
+            self.log_signature(&attestation.public_key, message.as_bytes(), &signature)?;
+
+        let bundle = SigstoreBundle {
+            signature: STANDARD.encode(&signature),
+            tlog_entry,
+        };
+        attestation.signature = serde_json::to_string(&bundle)
+            .map_err(|e| SigstoreError::InvalidResponse(e.to_string()))?;
+        Ok(attestation)
+    }
+
+    /// Certify an ephemeral key with Fulcio, returning the PEM chain
+    fn request_certificate(
+        &self,
+        identity_token: &str,
+        key: &EcdsaKeyPair,
+        proof_of_possession: &[u8],
+    ) -> Result<String, SigstoreError> {
+        let public_key = [P256_SPKI_PREFIX, key.public_key().as_ref()].concat();
+        let request = json!({
+            "credentials": { "oidcIdentityToken": identity_token },
+            "publicKeyRequest": {
+                "publicKey": {
+                    "algorithm": "ECDSA",
+                    "content": pem_encode("PUBLIC KEY", &public_key),
+                },
+                "proofOfPossession": STANDARD.encode(proof_of_possession),
+            },
+        });
+        let response = self.post(&format!("{}/api/v2/signingCert", self.fulcio_url), &request)?;
+
+        let certificates = [
+            "signedCertificateEmbeddedSct",
+            "signedCertificateDetachedSct",
+        ]
+        .iter()
+        .find_map(|field| response[field]["chain"]["certificates"].as_array())
+        .ok_or_else(|| SigstoreError::InvalidResponse("missing certificate chain".into()))?;
+        let chain: String = certificates
+            .iter()
+            .filter_map(Value::as_str)
+            .map(|pem| format!("{}\n", pem.trim_end()))
+            .collect();
+        if parse_pem(&chain).map_or(true, |chain| chain.is_empty()) {
+            return Err(SigstoreError::InvalidResponse(
+                "invalid certificate chain".into(),
+            ));
+        }
+        Ok(chain)
+    }
+
+    /// Record a signature in Rekor as a `hashedrekord` entry
+    fn log_signature(
+        &self,
+        chain: &str,
+        message: &[u8],
+        signature: &[u8],
+    ) -> Result<RekorEntry, SigstoreError> {
+        let leaf = leaf_pem(chain)
+            .ok_or_else(|| SigstoreError::InvalidResponse("invalid certificate chain".into()))?;
+        let request = json!({
+            "apiVersion": "0.0.1",
+            "kind": "hashedrekord",
+            "spec": {
+                "data": { "hash": { "algorithm": "sha256", "value": sha256_hex(message) } },
+                "signature": {
+                    "content": STANDARD.encode(signature),
+                    "publicKey": { "content": STANDARD.encode(leaf) },
+                },
+            },
+        });
+        let response = self.post(&format!("{}/api/v1/log/entries", self.rekor_url), &request)?;
+
+        let entry = response
+            .as_object()
+            .and_then(|entries| entries.values().next())
+            .ok_or_else(|| SigstoreError::InvalidResponse("missing log entry".into()))?;
+        serde_json::from_value(entry.clone())
+            .map_err(|e| SigstoreError::InvalidResponse(e.to_string()))
+    }
+
+    fn post(&self, url: &str, request: &Value) -> Result<Value, SigstoreError> {
+        let body = ureq::post(url)
+            .timeout(self.timeout)
+            .set("Content-Type", "application/json")
+            .send_string(&request.to_string())
+            .map_err(|e| SigstoreError::Transport(e.to_string()))?
+            .into_string()
+            .map_err(|e| SigstoreError::Transport(e.to_string()))?;
+        serde_json::from_str(&body).map_err(|e| SigstoreError::InvalidResponse(e.to_string()))
+    }
+}
+
+impl Default for SigstoreClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Offline verifier for Sigstore attestations
+///
+/// Without a Rekor key no bundle verifies. Without identities, certificates
+/// issued to any identity are accepted.
+#[derive(Debug, Clone)]
+pub struct SigstoreVerifier {
+    fulcio_roots: X509RootStore,
+    rekor_keys: Vec<SubjectPublicKeyInfoDer<'static>>,
+    identities: Vec<String>,
+}
+
+impl SigstoreVerifier {
+    /// Verify certificates against the Fulcio roots
+    pub fn new(fulcio_roots: X509RootStore) -> Self {
+        Self {
+            fulcio_roots,
+            rekor_keys: Vec::new(),
+            identities: Vec::new(),
+        }
+    }
+
+    /// Accept log entries signed by a Rekor public key (PEM)
+    pub fn with_rekor_key(mut self, pem: &str) -> Result<Self, SigstoreError> {
+        let key = SubjectPublicKeyInfoDer::from_pem_slice(pem.as_bytes())
+            .map_err(|e| SigstoreError::InvalidKey(e.to_string()))?;
+        self.rekor_keys.push(key);
+        Ok(self)
+    }
+
+    /// Only accept certificates issued to a URI identity (e.g., a CI workflow)
+    pub fn with_identity(mut self, identity: impl Into<String>) -> Self {
+        self.identities.push(identity.into());
+        self
+    }
+
+    /// Check a bundle over a message signed by the leaf of a PEM chain
+    pub fn verify_bundle(
+        &self,
+        chain_pem: &str,
+        message: &[u8],
+        bundle: &SigstoreBundle,
+    ) -> Result<(), SigstoreError> {
+        let entry = &bundle.tlog_entry;
+        self.fulcio_roots
+            .verify_chain_at(chain_pem, entry.integrated_time)
+            .map_err(|e| SigstoreError::UntrustedCertificate(e.to_string()))?;
+
+        if !self.identities.is_empty()
+            && !leaf_uri_names(chain_pem)
+                .iter()
+                .any(|name| self.identities.contains(name))
+        {
+            return Err(SigstoreError::UnexpectedIdentity);
+        }
+
+        let algorithms: Vec<_> = SIGNATURE_ALGORITHMS
+            .iter()
+            .map(|(_, algorithm)| *algorithm)
+            .collect();
+        let signature = STANDARD
+            .decode(&bundle.signature)
+            .map_err(|_| SigstoreError::InvalidSignature)?;
+        if !verify_leaf_signature(chain_pem, message, &signature, &algorithms) {
+            return Err(SigstoreError::InvalidSignature);
+        }
+
+        let body = STANDARD
+            .decode(&entry.body)
+            .map_err(|e| SigstoreError::NotLogged(e.to_string()))?;
+        if !entry_matches(&body, chain_pem, message, &signature) {
+            return Err(SigstoreError::NotLogged(
+                "log entry does not match the signature".into(),
+            ));
+        }
+        if !entry.verification.inclusion_proof.verify(&body) {
+            return Err(SigstoreError::NotLogged("invalid inclusion proof".into()));
+        }
+
+        let payload = canonicalize(json!({
+            "body": entry.body,
+            "integratedTime": entry.integrated_time,
+            "logID": entry.log_id,
+            "logIndex": entry.log_index,
+        }))
+        .to_string();
+        let timestamp = STANDARD
+            .decode(&entry.verification.signed_entry_timestamp)
+            .unwrap_or_default();
+        let signed_by_log = self.rekor_keys.iter().any(|key| {
+            RawPublicKeyEntity::try_from(key).is_ok_and(|key| {
+                algorithms.iter().any(|algorithm| {
+                    key.verify_signature(*algorithm, payload.as_bytes(), &timestamp)
+                        .is_ok()
+                })
+            })
+        });
+        if !signed_by_log {
+            return Err(SigstoreError::NotLogged(
+                "signed entry timestamp does not verify".into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl AttestationVerifier for SigstoreVerifier {
+    fn supports(&self, algorithm: &str) -> bool {
+        algorithm == SIGSTORE_ATTESTATION_ALGORITHM
+    }
+
+    fn verify(&self, public_key: &str, message: &[u8], signature: &str) -> bool {
+        serde_json::from_str::<SigstoreBundle>(signature)
+            .is_ok_and(|bundle| self.verify_bundle(public_key, message, &bundle).is_ok())
+    }
+}
+
+/// Check that a `hashedrekord` entry records a signature by the leaf certificate
+fn entry_matches(body: &[u8], chain_pem: &str, message: &[u8], signature: &[u8]) -> bool {
+    let Ok(entry) = serde_json::from_slice::<Value>(body) else {
+        return false;
+    };
+    let spec = &entry["spec"];
+    let logged_signature = spec["signature"]["content"]
+        .as_str()
+        .and_then(|content| STANDARD.decode(content).ok());
+    let logged_certificate = spec["signature"]["publicKey"]["content"]
+        .as_str()
+        .and_then(|content| STANDARD.decode(content).ok())
+        .and_then(|pem| parse_pem(&String::from_utf8_lossy(&pem)).ok())
+        .and_then(|chain| chain.into_iter().next());
+    let leaf = parse_pem(chain_pem)
+        .ok()
+        .and_then(|chain| chain.into_iter().next());
+
+    entry["kind"] == "hashedrekord"
+        && spec["data"]["hash"]["algorithm"] == "sha256"
+        && spec["data"]["hash"]["value"] == sha256_hex(message)
+        && logged_signature.as_deref() == Some(signature)
+        && logged_certificate.is_some()
+        && logged_certificate == leaf
+}
+
+/// Identity the proof of possession is made for: the token's email or subject
+fn token_subject(identity_token: &str) -> Result<String, SigstoreError> {
+    let claims = identity_token
+        .split('.')
+        .nth(1)
+        .ok_or_else(|| SigstoreError::InvalidToken("not a JWT".into()))?;
+    let claims = URL_SAFE_NO_PAD
+        .decode(claims.trim_end_matches('='))
+        .map_err(|e| SigstoreError::InvalidToken(e.to_string()))?;
+    let claims: Value =
+        serde_json::from_slice(&claims).map_err(|e| SigstoreError::InvalidToken(e.to_string()))?;
+
+    claims["email"]
+        .as_str()
+        .or_else(|| claims["sub"].as_str())
+        .map(str::to_string)
+        .ok_or_else(|| SigstoreError::InvalidToken("missing subject".into()))
+}
+
+fn leaf_pem(chain_pem: &str) -> Option<String> {
+    let leaf = parse_pem(chain_pem).ok()?.into_iter().next()?;
+    Some(pem_encode("CERTIFICATE", &leaf))
+}
+
+fn pem_encode(label: &str, der: &[u8]) -> String {
+    let encoded = STANDARD.encode(der);
+    let mut pem = format!("-----BEGIN {}-----\n", label);
+    for line in encoded.as_bytes().chunks(64) {
+        pem.push_str(&String::from_utf8_lossy(line));
+        pem.push('\n');
+    }
+    pem.push_str(&format!("-----END {}-----\n", label));
+    pem
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+fn merkle_hash(parts: &[&[u8]]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher.finalize().into()
+}
+
+fn decode_hash(hex: &str) -> Option<[u8; 32]> {
+    from_hex(hex)?.try_into().ok()
+}
@@ -0,0 +1,203 @@
+//! Partial order over capability sets
+//!
+//! `a.is_subset_of(&b)` holds when `b` can do everything `a` can: every tool
+//! of `a` is declared by `b` in the same category with at least the same
+//! candidates and permissions, and every flag enabled in `a` is enabled in
+//! `b`. Use it to check that a worker's new registration is not weaker than
+//! its attested baseline:
+//!
+//! ```rust
+//! use worker_capabilities::Capabilities;
+//!
+//! let baseline = Capabilities::new("worker-1").with_static_analysis("clippy", true);
+//! let registration = Capabilities::new("worker-1")
+//!     .with_static_analysis("clippy", true)
+//!     .with_fuzzing_tool("cargo-fuzz", false);
+//!
+//! assert!(baseline.is_subset_of(&registration));
+//! assert!(!registration.is_subset_of(&baseline));
+//! ```
+
+use std::path::Path;
+
+use crate::capabilities::Capabilities;
+use crate::requirements::Requirements;
+use crate::scope::CapabilityScope;
+use crate::types::{AccessMode, CapabilityPermissions, EgressRule, ToolCapability};
+
+impl Capabilities {
+    /// Check if another set can do everything this set can
+    pub fn is_subset_of(&self, other: &Capabilities) -> bool {
+        let flags = self
+            .flags
+            .iter()
+            .filter(|(_, enabled)| **enabled)
+            .all(|(flag, _)| other.has_flag(flag));
+
+        flags
+            && self.iter_tools().all(|(category, tool)| {
+                other
+                    .tools_in_category(category)
+                    .iter()
+                    .any(|candidate| tool.is_subset_of(candidate))
+            })
+    }
+
+    /// Check if another set can do at least everything this set can
+    pub fn is_superset_of(&self, other: &Capabilities) -> bool {
+        other.is_subset_of(self)
+    }
+
+    /// Check if the declared tools and environment cover a set of requirements
+    ///
+    /// Unlike `satisfies`, no tool checker is consulted: a clause is covered
+    /// by any usable declared tool (not expired, revoked or suspended, on the
+    /// required platform and in scope of the target repository).
+    pub fn covers(&self, requirements: &Requirements) -> bool {
+        self.meets_requirements(requirements)
+            && requirements.clauses.iter().all(|clause| {
+                clause.any_of.iter().any(|category| {
+                    self.tools_in_category(category).iter().any(|tool| {
+                        !tool.is_expired()
+                            && !tool.is_revoked()
+                            && !tool.suspended
+                            && tool
+                                .runs_on(requirements.os.as_deref(), requirements.arch.as_deref())
+                            && requirements
+                                .target_repository
+                                .as_deref()
+                                .is_none_or(|repository| tool.scope.allows(repository))
+                    })
+                })
+            })
+    }
+}
+
+impl ToolCapability {
+    /// Check if another declaration of the tool can do everything this one can
+    ///
+    /// The other declaration must have the same name, offer every candidate
+    /// of this one, grant at least the same permissions, not expire earlier,
+    /// and not be revoked or suspended unless this one is. It must declare
+    /// the same version (if this one does), and may only pin a version
+    /// constraint or binary digest this one pins too. Its platforms and
+    /// scope must cover those of this one.
+    pub fn is_subset_of(&self, other: &ToolCapability) -> bool {
+        self.tool_name == other.tool_name
+            && self
+                .alternatives
+                .iter()
+                .all(|alternative| other.alternatives.contains(alternative))
+            && self.permissions.is_subset_of(&other.permissions)
+            && self.expiration.expires_at <= other.expiration.expires_at
+            && (self.is_revoked() || !other.is_revoked())
+            && (self.suspended || !other.suspended)
+            && (self.version.is_none() || self.version == other.version)
+            && (other.version_req.is_none() || self.version_req == other.version_req)
+            && (other.binary_sha256.is_none() || self.binary_sha256 == other.binary_sha256)
+            && (other.platforms.is_empty()
+                || (!self.platforms.is_empty()
+                    && self
+                        .platforms
+                        .iter()
+                        .all(|platform| other.platforms.contains(platform))))
+            && self.scope.is_subset_of(&other.scope)
+    }
+}
+
+impl CapabilityScope {
+    /// Check if another scope allows every repository this one allows
+    ///
+    /// An unrestricted scope is only covered by another unrestricted one.
+    /// Repository patterns are covered by the same pattern, or (without
+    /// wildcards) by a pattern or organization the other scope allows.
+    pub fn is_subset_of(&self, other: &CapabilityScope) -> bool {
+        if other.is_unrestricted() {
+            return true;
+        }
+        if self.is_unrestricted() {
+            return false;
+        }
+
+        let repositories = self.repositories.iter().all(|pattern| {
+            other
+                .repositories
+                .iter()
+                .any(|allowed| allowed.eq_ignore_ascii_case(pattern))
+                || (!pattern.contains('*') && other.allows(pattern))
+        });
+        let organizations = self.organizations.iter().all(|organization| {
+            other
+                .organizations
+                .iter()
+                .any(|allowed| allowed.eq_ignore_ascii_case(organization))
+        });
+        repositories && organizations
+    }
+}
+
+impl CapabilityPermissions {
+    /// Check if another permission set allows everything this one allows
+    ///
+    /// An empty path or egress policy allows everything, so it is only
+    /// covered by another empty policy.
+    pub fn is_subset_of(&self, other: &CapabilityPermissions) -> bool {
+        let flags = (!self.filesystem_access || other.filesystem_access)
+            && (!self.network_access || other.network_access)
+            && (!self.process_spawn || other.process_spawn)
+            && (!self.env_access || other.env_access)
+            && (!self.system_access || other.system_access);
+        let limits = self.memory_limit_mb <= other.memory_limit_mb
+            && self.cpu_limit_percent <= other.cpu_limit_percent
+            && self.timeout_seconds <= other.timeout_seconds;
+
+        let paths = other.filesystem_paths.is_empty()
+            || (!self.filesystem_paths.is_empty()
+                && self.filesystem_paths.iter().all(|rule| {
+                    let prefix = Path::new(&rule.prefix);
+                    let mode = if rule.writable {
+                        AccessMode::Write
+                    } else {
+                        AccessMode::Read
+                    };
+                    other
+                        .filesystem_paths
+                        .iter()
+                        .any(|allowed| allowed.covers(prefix, mode))
+                }));
+        let egress = other.egress_allowlist.is_empty()
+            || (!self.egress_allowlist.is_empty()
+                && self.egress_allowlist.iter().all(|rule| {
+                    other
+                        .egress_allowlist
+                        .iter()
+                        .any(|allowed| egress_covers(allowed, rule))
+                }));
+
+        flags && limits && paths && egress
+    }
+}
+
+/// Check if an egress rule allows every destination another rule allows
+fn egress_covers(allowed: &EgressRule, rule: &EgressRule) -> bool {
+    let host = match rule.host.strip_prefix("*.") {
+        // A wildcard is only covered by the same or a broader wildcard
+        Some(domain) => {
+            allowed.host.eq_ignore_ascii_case(&rule.host)
+                || (allowed.host.starts_with("*.")
+                    && allowed.matches_host(&format!("x.{}", domain)))
+        }
+        None => allowed.matches_host(&rule.host),
+    };
+    let ports = allowed.ports.is_empty()
+        || (!rule.ports.is_empty() && rule.ports.iter().all(|port| allowed.ports.contains(port)));
+    let protocols = allowed.protocols.is_empty()
+        || (!rule.protocols.is_empty()
+            && rule.protocols.iter().all(|protocol| {
+                allowed
+                    .protocols
+                    .iter()
+                    .any(|p| p.eq_ignore_ascii_case(protocol))
+            }));
+    host && ports && protocols
+}
@@ -0,0 +1,83 @@
+//! Free-form tags on workers and tools
+//!
+//! Tags label workers and tools beyond boolean flags and exact metadata
+//! values. Requirements can demand a set of worker tags:
+//!
+//! ```rust
+//! use worker_capabilities::{Capabilities, CapabilityRegistry, Requirements, ToolCapability};
+//!
+//! let mut registry = CapabilityRegistry::new();
+//! registry.register(
+//!     Capabilities::builder("worker-1")
+//!         .with_tag("gpu")
+//!         .with_tag("eu-west")
+//!         .with_tool_capability(
+//!             "static_analysis",
+//!             ToolCapability::new("codeql", true).with_tags(["licensed"]),
+//!         )
+//!         .build()
+//!         .unwrap(),
+//! );
+//!
+//! assert_eq!(registry.find_workers_with_tag("gpu").len(), 1);
+//! assert_eq!(registry.get("worker-1").unwrap().tools_with_tag("licensed").len(), 1);
+//!
+//! let requirements = Requirements::new().requires_tags(["gpu", "eu-west"]);
+//! assert!(registry.get("worker-1").unwrap().meets_requirements(&requirements));
+//! ```
+
+use crate::capabilities::Capabilities;
+use crate::types::ToolCapability;
+
+impl Capabilities {
+    /// Add a tag to the worker
+    pub fn with_tag(mut self, tag: impl Into<String>) -> Self {
+        add_tag(&mut self.tags, tag.into());
+        self
+    }
+
+    /// Check if the worker carries a tag
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+
+    /// Tools carrying a tag, in any category
+    pub fn tools_with_tag(&self, tag: &str) -> Vec<&ToolCapability> {
+        self.iter_tools()
+            .map(|(_, tool)| tool)
+            .filter(|tool| tool.has_tag(tag))
+            .collect()
+    }
+}
+
+impl ToolCapability {
+    /// Add a tag to the tool
+    pub fn with_tag(mut self, tag: impl Into<String>) -> Self {
+        add_tag(&mut self.tags, tag.into());
+        self
+    }
+
+    /// Add several tags to the tool
+    pub fn with_tags<I, S>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        for tag in tags {
+            add_tag(&mut self.tags, tag.into());
+        }
+        self
+    }
+
+    /// Check if the tool carries a tag
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+}
+
+/// Add a tag unless it is already present
+pub(crate) fn add_tag(tags: &mut Vec<String>, tag: String) {
+    if !tags.contains(&tag) {
+        tags.push(tag);
+    }
+}
@@ -0,0 +1,81 @@
+//! Job outcome telemetry and automatic capability demotion
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// Policy for demoting tools with a poor success rate
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DemotionPolicy {
+    /// Success rate (0.0-1.0) below which a tool is suspended
+    pub min_success_rate: f64,
+    /// Number of most recent outcomes considered
+    pub window_size: usize,
+    /// Minimum number of outcomes in the window before demotion applies
+    pub min_samples: usize,
+    /// Consecutive successful health checks needed to recover a suspended tool
+    pub probation_successes: u32,
+}
+
+impl Default for DemotionPolicy {
+    fn default() -> Self {
+        Self {
+            min_success_rate: 0.8,
+            window_size: 20,
+            min_samples: 5,
+            probation_successes: 3,
+        }
+    }
+}
+
+/// Outcome of a single job run with a tool
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobOutcome {
+    /// Whether the job succeeded
+    pub success: bool,
+    /// How long the job ran
+    pub duration: Duration,
+    /// Timestamp when the outcome was recorded
+    pub recorded_at: u64,
+}
+
+/// Rolling health state of a tool on a worker
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ToolHealth {
+    /// Most recent job outcomes, oldest first
+    pub outcomes: VecDeque<JobOutcome>,
+    /// Whether the tool is currently suspended
+    pub suspended: bool,
+    /// Consecutive successful health checks while suspended
+    pub probation_successes: u32,
+}
+
+impl ToolHealth {
+    /// Success rate over the recorded window (1.0 when empty)
+    pub fn success_rate(&self) -> f64 {
+        if self.outcomes.is_empty() {
+            return 1.0;
+        }
+        let successes = self.outcomes.iter().filter(|o| o.success).count();
+        successes as f64 / self.outcomes.len() as f64
+    }
+
+    /// Average job duration over the recorded window
+    pub fn average_duration(&self) -> Option<Duration> {
+        if self.outcomes.is_empty() {
+            return None;
+        }
+        let total: Duration = self.outcomes.iter().map(|o| o.duration).sum();
+        Some(total / self.outcomes.len() as u32)
+    }
+}
+
+/// Health state change caused by telemetry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthTransition {
+    /// The tool was suspended
+    Demoted,
+    /// The tool finished probation and was reinstated
+    Recovered,
+}
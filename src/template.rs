@@ -0,0 +1,91 @@
+//! Base templates that capability sets extend
+//!
+//! A fleet defines a base set once (e.g., `base-linux-worker`) and each
+//! worker declares only its deltas. `Capabilities::extends` resolves a set
+//! against a base directly; registries resolve sets declaring a template by
+//! name when they are registered:
+//!
+//! ```rust
+//! use worker_capabilities::{Capabilities, CapabilityRegistry};
+//!
+//! let base = Capabilities::new("base-linux-worker")
+//!     .with_static_analysis("clippy", true)
+//!     .with_flag("docker_support");
+//!
+//! let mut registry = CapabilityRegistry::new()
+//!     .with_template("base-linux-worker", base)
+//!     .unwrap();
+//! registry.register(
+//!     Capabilities::new("worker-1")
+//!         .with_template("base-linux-worker")
+//!         .with_fuzzing_tool("cargo-fuzz", false),
+//! );
+//!
+//! let worker = registry.get("worker-1").unwrap();
+//! assert!(worker.find_tool("clippy").is_some());
+//! assert!(worker.find_tool("cargo-fuzz").is_some());
+//! assert!(worker.has_flag("docker_support"));
+//!
+//! // Sets extending an unknown template are refused
+//! registry.register(Capabilities::new("worker-2").with_template("base-macos-worker"));
+//! assert!(registry.get("worker-2").is_none());
+//! assert_eq!(registry.registration_rejections().count(), 1);
+//! ```
+
+use crate::capabilities::Capabilities;
+use crate::network::NetworkReachability;
+use crate::payload::PayloadLimits;
+use crate::sandbox::SandboxCapability;
+use crate::scope::CapabilityScope;
+
+impl Capabilities {
+    /// Declare the registry template this set extends
+    pub fn with_template(mut self, name: impl Into<String>) -> Self {
+        self.template = Some(name.into());
+        self
+    }
+
+    /// Resolve this set against a base set
+    ///
+    /// The deltas are merged into the base as by `merge`: tools are unioned
+    /// (a tool in both gets the stricter permissions), and flags and metadata
+    /// override the base. Network, sandbox, payload, scope and environment
+    /// sections declared by this set replace the base's; models and warm
+    /// assets are added to the base's. The result keeps this set's ID and
+    /// carries no set attestation.
+    pub fn extends(&self, base: &Capabilities) -> Capabilities {
+        let mut resolved = base.clone();
+        resolved.id = self.id.clone();
+        resolved.template = None;
+        resolved.attestation = None;
+        resolved.merge(self);
+
+        if self.network != NetworkReachability::default() {
+            resolved.network = self.network.clone();
+        }
+        if self.sandbox != SandboxCapability::default() {
+            resolved.sandbox = self.sandbox.clone();
+        }
+        if self.payload != PayloadLimits::default() {
+            resolved.payload = self.payload.clone();
+        }
+        if self.scope != CapabilityScope::default() {
+            resolved.scope = self.scope.clone();
+        }
+        if self.environment.is_some() {
+            resolved.environment = self.environment.clone();
+        }
+        for model in &self.models {
+            if !resolved.models.contains(model) {
+                resolved.models.push(model.clone());
+            }
+        }
+        for asset in &self.warm_assets {
+            resolved
+                .warm_assets
+                .retain(|a| a.kind != asset.kind || a.identifier != asset.identifier);
+            resolved.warm_assets.push(asset.clone());
+        }
+        resolved
+    }
+}
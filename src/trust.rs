@@ -0,0 +1,733 @@
+//! Trust configuration: anchors, attesters, revocations and policy, distributed as one bundle
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::capabilities::Capabilities;
+use crate::clock::current_timestamp;
+use crate::constants::*;
+use crate::error::VerificationError;
+use crate::signing::{signed_message, AttestationSigner, AttestationVerifier};
+use crate::types::{AttestationThreshold, CapabilityAttestation, ToolCapability};
+
+/// Key trusted to sign trust bundles
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrustAnchor {
+    /// Human-readable name of the anchor
+    pub name: String,
+    /// Public key of the anchor
+    pub public_key: String,
+}
+
+/// Attester whose capability attestations are trusted
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AttesterEntry {
+    /// Attester identity recorded in attestations
+    pub attester: String,
+    /// Public key the attester signs with
+    pub public_key: String,
+    /// Categories the attester may vouch for (any category if empty)
+    #[serde(default)]
+    pub categories: Vec<String>,
+    /// Earliest attestation timestamp the key covers (unbounded if absent)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valid_from: Option<u64>,
+    /// Latest attestation timestamp the key covers (unbounded if absent)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valid_until: Option<u64>,
+}
+
+impl AttesterEntry {
+    /// Trust an attester signing with a public key for any category
+    ///
+    /// An empty attester identity matches any identity using the key.
+    pub fn new(attester: impl Into<String>, public_key: impl Into<String>) -> Self {
+        Self {
+            attester: attester.into(),
+            public_key: public_key.into(),
+            categories: Vec::new(),
+            valid_from: None,
+            valid_until: None,
+        }
+    }
+
+    /// Restrict the attester to a category (in addition to any previous ones)
+    pub fn with_category(mut self, category: impl Into<String>) -> Self {
+        self.categories.push(category.into());
+        self
+    }
+
+    /// Only cover attestations made at or after a timestamp
+    pub fn with_valid_from(mut self, timestamp: u64) -> Self {
+        self.valid_from = Some(timestamp);
+        self
+    }
+
+    /// Only cover attestations made at or before a timestamp
+    pub fn with_valid_until(mut self, timestamp: u64) -> Self {
+        self.valid_until = Some(timestamp);
+        self
+    }
+
+    /// Check if the key covers attestations made at a timestamp
+    pub fn covers(&self, timestamp: u64) -> bool {
+        self.valid_from.is_none_or(|from| timestamp >= from)
+            && self.valid_until.is_none_or(|until| timestamp <= until)
+    }
+
+    /// Check if every attestation the key covers has expired by a timestamp
+    pub fn is_retired_at(&self, timestamp: u64) -> bool {
+        self.valid_until.is_some_and(|until| {
+            timestamp > until.saturating_add(ATTESTATION_EXPIRY_DAYS * 24 * 60 * 60)
+        })
+    }
+
+    /// Check if this entry vouches for an attestation of a tool in a category
+    pub fn allows(&self, attestation: &CapabilityAttestation, category: &str) -> bool {
+        self.signed(attestation)
+            && (self.categories.is_empty() || self.categories.iter().any(|c| c == category))
+    }
+
+    /// Check if an attestation was made by this attester, whatever the category
+    ///
+    /// The attestation must be dated within the validity window of the key.
+    pub fn signed(&self, attestation: &CapabilityAttestation) -> bool {
+        (self.attester.is_empty() || self.attester == attestation.attester)
+            && self.public_key == attestation.public_key
+            && self.covers(attestation.timestamp)
+    }
+}
+
+/// Attesters whose attestations count during verification
+///
+/// Every entry pins the public key the attester signs with. An attester may
+/// be listed with several keys, each covering the attestations dated within
+/// its validity window, so keys can be rotated without invalidating
+/// attestations made with the previous key.
+///
+/// Thresholds require sign-off from several pinned attesters on a tool; they
+/// belong to the verifier's policy, not to the worker's manifest.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrustPolicy {
+    /// Trusted attesters
+    #[serde(default)]
+    pub attesters: Vec<AttesterEntry>,
+    /// Attesters that must sign off, by tool name
+    #[serde(default)]
+    pub thresholds: BTreeMap<String, AttestationThreshold>,
+}
+
+impl TrustPolicy {
+    /// Create a policy trusting no attester
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Trust an attester
+    pub fn with_attester(mut self, attester: AttesterEntry) -> Self {
+        self.attesters.push(attester);
+        self
+    }
+
+    /// Require sign-off on a tool from `required` of the given attesters
+    ///
+    /// Only chain links whose attester and key match a pinned entry of the
+    /// policy count towards the threshold.
+    pub fn with_threshold<I, S>(
+        mut self,
+        tool_name: impl Into<String>,
+        required: usize,
+        attesters: I,
+    ) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.thresholds.insert(
+            tool_name.into(),
+            AttestationThreshold::new(required, attesters),
+        );
+        self
+    }
+
+    /// Threshold configured for a tool
+    pub fn threshold(&self, tool_name: &str) -> Option<&AttestationThreshold> {
+        self.thresholds.get(tool_name)
+    }
+
+    /// Check if a link was signed by a pinned key of a named attester
+    pub(crate) fn pins(&self, attestation: &CapabilityAttestation) -> bool {
+        self.attesters.iter().any(|entry| {
+            !entry.attester.is_empty()
+                && entry.attester == attestation.attester
+                && entry.signed(attestation)
+        })
+    }
+
+    /// Rotate an attester to a new key
+    ///
+    /// Open-ended keys of the attester stop covering attestations made after
+    /// now but keep verifying earlier ones. The new key covers attestations
+    /// from now on, for the categories of the attester's previous key.
+    pub fn rotate_key(&mut self, attester: &str, public_key: impl Into<String>) {
+        let now = current_timestamp();
+        let mut categories = Vec::new();
+        for entry in &mut self.attesters {
+            if entry.attester == attester && entry.valid_until.is_none() {
+                entry.valid_until = Some(now);
+                categories = entry.categories.clone();
+            }
+        }
+        self.attesters.push(AttesterEntry {
+            categories,
+            ..AttesterEntry::new(attester, public_key).with_valid_from(now)
+        });
+    }
+
+    /// Drop rotated-out keys whose attestations have all expired
+    ///
+    /// Returns the number of keys removed.
+    pub fn prune_retired_keys(&mut self) -> usize {
+        let now = current_timestamp();
+        let before = self.attesters.len();
+        self.attesters.retain(|entry| !entry.is_retired_at(now));
+        before - self.attesters.len()
+    }
+
+    /// Check if an attestation of a tool in a category comes from a trusted attester
+    pub fn allows(&self, attestation: &CapabilityAttestation, category: &str) -> bool {
+        self.attesters
+            .iter()
+            .any(|entry| entry.allows(attestation, category))
+    }
+
+    /// Check if an attestation comes from a trusted attester, ignoring category restrictions
+    pub fn trusts(&self, attestation: &CapabilityAttestation) -> bool {
+        self.attesters.iter().any(|entry| entry.signed(attestation))
+    }
+
+    /// Check if an attestation comes from an attester trusted for all categories
+    pub fn allows_all_categories(&self, attestation: &CapabilityAttestation) -> bool {
+        self.attesters
+            .iter()
+            .any(|entry| entry.categories.is_empty() && entry.allows(attestation, ""))
+    }
+}
+
+/// Signer whose key can be rotated without invalidating earlier attestations
+///
+/// Attestations are signed with the active key. Every key the signer has
+/// used is kept as an `AttesterEntry` with its validity window, to publish to
+/// verifiers (e.g., in a `TrustBundle`) until its attestations expire.
+#[derive(Debug)]
+pub struct RotatingSigner<S> {
+    active: S,
+    keys: Vec<AttesterEntry>,
+}
+
+impl<S: AttestationSigner> RotatingSigner<S> {
+    /// Sign with `signer` until the first rotation
+    pub fn new(signer: S) -> Self {
+        let keys = vec![AttesterEntry::new(signer.attester(), signer.public_key())];
+        Self {
+            active: signer,
+            keys,
+        }
+    }
+
+    /// Make `next` the active signer, returning the previous one
+    ///
+    /// The previous key keeps covering the attestations it made until now.
+    pub fn rotate(&mut self, next: S) -> S {
+        let now = current_timestamp();
+        let retired = self.active.public_key();
+        for entry in &mut self.keys {
+            if entry.public_key == retired && entry.valid_until.is_none() {
+                entry.valid_until = Some(now);
+            }
+        }
+        self.keys
+            .push(AttesterEntry::new(next.attester(), next.public_key()).with_valid_from(now));
+        std::mem::replace(&mut self.active, next)
+    }
+
+    /// Signer currently producing attestations
+    pub fn active(&self) -> &S {
+        &self.active
+    }
+
+    /// Keys used by this signer, oldest first
+    pub fn keys(&self) -> &[AttesterEntry] {
+        &self.keys
+    }
+
+    /// Policy trusting every key of this signer within its validity window
+    pub fn policy(&self) -> TrustPolicy {
+        TrustPolicy {
+            attesters: self.keys.clone(),
+            ..TrustPolicy::default()
+        }
+    }
+
+    /// Forget rotated-out keys whose attestations have all expired
+    ///
+    /// Returns the number of keys removed.
+    pub fn prune_retired_keys(&mut self) -> usize {
+        let now = current_timestamp();
+        let before = self.keys.len();
+        self.keys.retain(|entry| !entry.is_retired_at(now));
+        before - self.keys.len()
+    }
+}
+
+impl<S: AttestationSigner> AttestationSigner for RotatingSigner<S> {
+    fn attester(&self) -> &str {
+        self.active.attester()
+    }
+
+    fn algorithm(&self) -> &str {
+        self.active.algorithm()
+    }
+
+    fn public_key(&self) -> String {
+        self.active.public_key()
+    }
+
+    fn sign(&self, message: &[u8]) -> String {
+        self.active.sign(message)
+    }
+
+    fn sign_raw(&self, message: &[u8]) -> Vec<u8> {
+        self.active.sign_raw(message)
+    }
+
+    fn attest_hash_with(
+        &self,
+        hash: &str,
+        nonce: Option<&str>,
+        counter: Option<u64>,
+    ) -> CapabilityAttestation {
+        self.active.attest_hash_with(hash, nonce, counter)
+    }
+}
+
+/// Revoked worker or capability
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RevocationEntry {
+    /// Revoked worker (all of its capabilities)
+    pub worker_id: Option<String>,
+    /// Revoked capability hash
+    pub capability_hash: Option<String>,
+    /// Reason for revocation
+    pub reason: String,
+    /// Timestamp of revocation
+    pub revoked_at: u64,
+}
+
+impl RevocationEntry {
+    /// Revoke every capability of a worker
+    pub fn worker(worker_id: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self {
+            worker_id: Some(worker_id.into()),
+            capability_hash: None,
+            reason: reason.into(),
+            revoked_at: current_timestamp(),
+        }
+    }
+
+    /// Revoke a single capability by hash
+    pub fn capability(capability_hash: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self {
+            worker_id: None,
+            capability_hash: Some(capability_hash.into()),
+            reason: reason.into(),
+            revoked_at: current_timestamp(),
+        }
+    }
+
+    /// Check if this entry revokes a capability of a worker
+    pub fn matches(&self, worker_id: &str, capability_hash: Option<&str>) -> bool {
+        self.worker_id.as_deref() == Some(worker_id)
+            || (self.capability_hash.is_some()
+                && self.capability_hash.as_deref() == capability_hash)
+    }
+}
+
+/// Policy settings distributed with the trust configuration
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TrustSettings {
+    /// Only count attestations from registered attesters
+    pub require_trusted_attester: bool,
+    /// Maximum attestation age in days (crate default if `None`)
+    pub max_attestation_age_days: Option<u64>,
+}
+
+/// Trust configuration held by a registry
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TrustStore {
+    /// Keys trusted to sign trust bundles
+    pub anchors: Vec<TrustAnchor>,
+    /// Trusted attesters
+    pub attesters: Vec<AttesterEntry>,
+    /// Revoked workers and capabilities
+    pub revocations: Vec<RevocationEntry>,
+    /// Policy settings
+    pub settings: TrustSettings,
+    /// Version of the last applied bundle (0 if none)
+    pub bundle_version: u64,
+}
+
+impl TrustStore {
+    /// Check if an attestation comes from an attester trusted for a category
+    pub fn is_trusted_attestation(
+        &self,
+        attestation: &CapabilityAttestation,
+        category: &str,
+    ) -> bool {
+        self.attesters
+            .iter()
+            .any(|entry| entry.allows(attestation, category))
+    }
+
+    /// Policy trusting the attesters of this store
+    pub fn policy(&self) -> TrustPolicy {
+        TrustPolicy {
+            attesters: self.attesters.clone(),
+            ..TrustPolicy::default()
+        }
+    }
+
+    /// Find the revocation entry covering a capability of a worker
+    pub fn revocation_for(
+        &self,
+        worker_id: &str,
+        capability_hash: Option<&str>,
+    ) -> Option<&RevocationEntry> {
+        self.revocations
+            .iter()
+            .find(|entry| entry.matches(worker_id, capability_hash))
+    }
+
+    /// Verify every capability of a worker against this trust configuration
+    ///
+    /// On top of `Capabilities::try_verify_all_capabilities_with`, each
+    /// attestation must cover its tool's current hash and come from an
+    /// attester trusted for the tool's category, and neither the worker nor
+    /// the capability may be revoked here.
+    pub fn try_verify(
+        &self,
+        caps: &Capabilities,
+        verifier: &dyn AttestationVerifier,
+    ) -> Result<(), VerificationError> {
+        caps.try_verify_all_capabilities_with(verifier)?;
+
+        for category in caps.category_names() {
+            for tool in caps.tools_in_category(&category) {
+                tool.try_verify_attestation_with(verifier)?;
+                let Some(attestation) = &tool.attestation else {
+                    continue;
+                };
+                if let Some(entry) =
+                    self.revocation_for(&caps.id, Some(&attestation.capability_hash))
+                {
+                    return Err(VerificationError::Revoked {
+                        tool: tool.tool_name.clone(),
+                        reason: Some(entry.reason.clone()),
+                    });
+                }
+                if !self.is_trusted_attestation(attestation, &category) {
+                    return Err(VerificationError::UntrustedAttester {
+                        tool: tool.tool_name.clone(),
+                        attester: attestation.attester.clone(),
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check if a public key belongs to a trust anchor
+    pub fn is_anchor(&self, public_key: &str) -> bool {
+        self.anchors
+            .iter()
+            .any(|anchor| anchor.public_key == public_key)
+    }
+
+    /// Check if a signer is a trust anchor or an attester trusted for all categories
+    pub fn is_trusted_signer(&self, signer: &str, public_key: &str) -> bool {
+        self.is_anchor(public_key)
+            || self.attesters.iter().any(|entry| {
+                entry.attester == signer
+                    && entry.public_key == public_key
+                    && entry.categories.is_empty()
+            })
+    }
+}
+
+impl ToolCapability {
+    /// Check if the tool is attested by an attester the policy trusts for a category
+    pub fn verify_trusted(&self, category: &str, policy: &TrustPolicy) -> bool {
+        self.try_verify_trusted(category, policy).is_ok()
+    }
+
+    /// Check the attester of the tool against a policy, reporting why it failed
+    ///
+    /// Only the attester is checked; combine with `try_verify_attestation_with`
+    /// to also check the attestation itself.
+    pub fn try_verify_trusted(
+        &self,
+        category: &str,
+        policy: &TrustPolicy,
+    ) -> Result<(), VerificationError> {
+        let attestation =
+            self.attestation
+                .as_ref()
+                .ok_or_else(|| VerificationError::MissingAttestation {
+                    tool: self.tool_name.clone(),
+                })?;
+        if !policy.allows(attestation, category) {
+            return Err(VerificationError::UntrustedAttester {
+                tool: self.tool_name.clone(),
+                attester: attestation.attester.clone(),
+            });
+        }
+        Ok(())
+    }
+}
+
+impl Capabilities {
+    /// Verify all capabilities, counting only attestations trusted by a policy
+    pub fn verify_trusted_with(
+        &self,
+        policy: &TrustPolicy,
+        verifier: &dyn AttestationVerifier,
+    ) -> bool {
+        self.try_verify_trusted_with(policy, verifier).is_ok()
+    }
+
+    /// Verify all capabilities against a policy, reporting the first failure
+    ///
+    /// On top of `try_verify_all_capabilities_with`, every tool must be
+    /// attested by an attester trusted for its category and meet the
+    /// policy's threshold, and a set attestation (if present) must verify and
+    /// come from one trusted for all categories.
+    pub fn try_verify_trusted_with(
+        &self,
+        policy: &TrustPolicy,
+        verifier: &dyn AttestationVerifier,
+    ) -> Result<(), VerificationError> {
+        self.try_verify_all_capabilities_with(verifier)?;
+        for (category, tool) in self.iter_tools() {
+            tool.try_verify_trusted(category, policy)?;
+            tool.check_threshold(policy, verifier)?;
+        }
+        if let Some(attestation) = &self.attestation {
+            self.try_verify_set_attestation_with(verifier)?;
+            if !policy.allows_all_categories(attestation) {
+                return Err(VerificationError::UntrustedAttester {
+                    tool: self.id.clone(),
+                    attester: attestation.attester.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Signed document packaging a complete trust configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrustBundle {
+    /// Document format version
+    pub format_version: u32,
+    /// Monotonic bundle version; older bundles are rejected
+    pub version: u64,
+    /// Timestamp when the bundle was issued
+    pub issued_at: u64,
+    /// Keys trusted to sign later bundles
+    pub anchors: Vec<TrustAnchor>,
+    /// Trusted attesters
+    pub attesters: Vec<AttesterEntry>,
+    /// Revoked workers and capabilities
+    pub revocations: Vec<RevocationEntry>,
+    /// Policy settings
+    pub settings: TrustSettings,
+    /// SHA256 digest of the bundle contents
+    #[serde(default)]
+    pub digest: String,
+    /// Signature over the digest
+    #[serde(default)]
+    pub signature: String,
+    /// Public key of the signer
+    #[serde(default)]
+    pub public_key: String,
+    /// Signer identity
+    #[serde(default)]
+    pub signer: String,
+    /// Signature algorithm
+    #[serde(default)]
+    pub algorithm: String,
+}
+
+impl TrustBundle {
+    /// Create an empty, unsigned bundle
+    pub fn new(version: u64) -> Self {
+        Self {
+            format_version: TRUST_BUNDLE_FORMAT_VERSION,
+            version,
+            issued_at: current_timestamp(),
+            anchors: Vec::new(),
+            attesters: Vec::new(),
+            revocations: Vec::new(),
+            settings: TrustSettings::default(),
+            digest: String::new(),
+            signature: String::new(),
+            public_key: String::new(),
+            signer: String::new(),
+            algorithm: String::new(),
+        }
+    }
+
+    /// Package the current contents of a trust store
+    pub fn from_store(store: &TrustStore, version: u64) -> Self {
+        Self {
+            anchors: store.anchors.clone(),
+            attesters: store.attesters.clone(),
+            revocations: store.revocations.clone(),
+            settings: store.settings.clone(),
+            ..Self::new(version)
+        }
+    }
+
+    /// Add a trust anchor
+    pub fn with_anchor(mut self, anchor: TrustAnchor) -> Self {
+        self.anchors.push(anchor);
+        self
+    }
+
+    /// Add a trusted attester
+    pub fn with_attester(mut self, attester: AttesterEntry) -> Self {
+        self.attesters.push(attester);
+        self
+    }
+
+    /// Add a revocation
+    pub fn with_revocation(mut self, revocation: RevocationEntry) -> Self {
+        self.revocations.push(revocation);
+        self
+    }
+
+    /// Set policy settings
+    pub fn with_settings(mut self, settings: TrustSettings) -> Self {
+        self.settings = settings;
+        self
+    }
+
+    /// Compute the digest of the bundle contents (excluding the signature fields)
+    pub fn compute_digest(&self) -> String {
+        let contents = serde_json::json!({
+            "format_version": self.format_version,
+            "version": self.version,
+            "issued_at": self.issued_at,
+            "anchors": self.anchors,
+            "attesters": self.attesters,
+            "revocations": self.revocations,
+            "settings": self.settings,
+        });
+        let mut hasher = Sha256::new();
+        hasher.update(contents.to_string().as_bytes());
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// Sign the bundle
+    pub fn sign(mut self, signer: &dyn AttestationSigner) -> Self {
+        self.digest = self.compute_digest();
+        self.public_key = signer.public_key();
+        self.signer = signer.attester().to_string();
+        self.algorithm = signer.algorithm().to_string();
+        self.signature = signer.sign(self.signed_message().as_bytes());
+        self
+    }
+
+    /// Message covered by the bundle signature
+    fn signed_message(&self) -> String {
+        signed_message(&self.algorithm, &self.signer, &self.digest, self.issued_at)
+    }
+
+    /// Check the digest and signature of the bundle
+    pub fn verify_signature(
+        &self,
+        verifier: &dyn AttestationVerifier,
+    ) -> Result<(), TrustBundleError> {
+        if self.format_version != TRUST_BUNDLE_FORMAT_VERSION {
+            return Err(TrustBundleError::UnsupportedFormat(self.format_version));
+        }
+        if self.digest != self.compute_digest() {
+            return Err(TrustBundleError::DigestMismatch);
+        }
+        let message = self.signed_message();
+        if self.public_key.is_empty()
+            || !verifier.verify_as(
+                &self.algorithm,
+                &self.public_key,
+                message.as_bytes(),
+                &self.signature,
+            )
+        {
+            return Err(TrustBundleError::InvalidSignature);
+        }
+        Ok(())
+    }
+
+    /// Serialize the bundle as JSON
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Parse a bundle from JSON
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
+
+/// Errors from applying a trust bundle
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TrustBundleError {
+    /// The bundle uses an unknown document format
+    UnsupportedFormat(u32),
+    /// The bundle contents do not match its digest
+    DigestMismatch,
+    /// The bundle signature is missing or invalid
+    InvalidSignature,
+    /// The bundle was not signed by a trust anchor of the current store
+    UntrustedSigner(String),
+    /// The bundle is not newer than the applied one
+    StaleVersion { current: u64, received: u64 },
+}
+
+impl fmt::Display for TrustBundleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrustBundleError::UnsupportedFormat(version) => {
+                write!(f, "unsupported trust bundle format version {}", version)
+            }
+            TrustBundleError::DigestMismatch => {
+                write!(f, "trust bundle contents do not match its digest")
+            }
+            TrustBundleError::InvalidSignature => write!(f, "trust bundle signature is invalid"),
+            TrustBundleError::UntrustedSigner(signer) => {
+                write!(f, "trust bundle signed by untrusted signer '{}'", signer)
+            }
+            TrustBundleError::StaleVersion { current, received } => write!(
+                f,
+                "trust bundle version {} is not newer than applied version {}",
+                received, current
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TrustBundleError {}
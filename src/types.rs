@@ -1,13 +1,22 @@
 //! Type definitions for worker capabilities
 
+use semver::VersionReq;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Component, Path};
 
+use crate::checker::ToolChecker;
+use crate::clock;
+use crate::clock::current_timestamp;
 use crate::constants::*;
+use crate::error::CapabilityError;
+use crate::platform::Platform;
+use crate::scope::CapabilityScope;
+use crate::signing::{encode_fields, AlgorithmRegistry};
+use crate::wasi::WasiModule;
 
 /// Capability attestation for cryptographic verification
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CapabilityAttestation {
     /// Hash of the capability claims
     pub capability_hash: String,
@@ -21,10 +30,120 @@ pub struct CapabilityAttestation {
     pub algorithm: String,
     /// Attester identity
     pub attester: String,
+    /// Challenge answered by the attestation, binding it to one issuance
+    #[serde(default)]
+    pub nonce: Option<String>,
+    /// Issuance counter of the attester, increasing with each attestation
+    #[serde(default)]
+    pub counter: Option<u64>,
+}
+
+/// Kind of filesystem access requested by an executor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccessMode {
+    /// Read files
+    Read,
+    /// Create, modify or delete files
+    Write,
+}
+
+/// Filesystem access granted below a path prefix
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PathRule {
+    /// Absolute path prefix
+    pub prefix: String,
+    /// Whether writes are allowed below the prefix (reads always are)
+    pub writable: bool,
+}
+
+impl PathRule {
+    /// Grant read-only access below a prefix
+    pub fn read_only(prefix: impl Into<String>) -> Self {
+        Self {
+            prefix: prefix.into(),
+            writable: false,
+        }
+    }
+
+    /// Grant read-write access below a prefix
+    pub fn read_write(prefix: impl Into<String>) -> Self {
+        Self {
+            prefix: prefix.into(),
+            writable: true,
+        }
+    }
+
+    /// Check if the rule covers a path for the given access mode
+    pub fn covers(&self, path: &Path, mode: AccessMode) -> bool {
+        (mode == AccessMode::Read || self.writable) && path.starts_with(Path::new(&self.prefix))
+    }
+}
+
+/// Outbound network destination allowed by a capability
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct EgressRule {
+    /// Host name or `*.` wildcard pattern (e.g., `crates.io`, `*.github.com`)
+    pub host: String,
+    /// Allowed ports (any port if empty)
+    #[serde(default)]
+    pub ports: Vec<u16>,
+    /// Allowed protocols such as `tcp` or `udp` (any protocol if empty)
+    #[serde(default)]
+    pub protocols: Vec<String>,
+}
+
+impl EgressRule {
+    /// Allow a host on any port and protocol
+    pub fn host(host: impl Into<String>) -> Self {
+        Self {
+            host: host.into(),
+            ports: Vec::new(),
+            protocols: Vec::new(),
+        }
+    }
+
+    /// Restrict the rule to a port
+    pub fn with_port(mut self, port: u16) -> Self {
+        self.ports.push(port);
+        self
+    }
+
+    /// Restrict the rule to a protocol
+    pub fn with_protocol(mut self, protocol: impl Into<String>) -> Self {
+        self.protocols.push(protocol.into().to_ascii_lowercase());
+        self
+    }
+
+    /// Check if the rule matches a host name
+    pub fn matches_host(&self, host: &str) -> bool {
+        let host = host.trim_end_matches('.').to_ascii_lowercase();
+        let pattern = self.host.to_ascii_lowercase();
+        match pattern.strip_prefix("*.") {
+            Some(domain) => host.len() > domain.len() && host.ends_with(&format!(".{}", domain)),
+            None => host == pattern,
+        }
+    }
+
+    /// Check if the rule allows a destination
+    pub fn allows(&self, host: &str, port: u16, protocol: Option<&str>) -> bool {
+        self.matches_host(host)
+            && (self.ports.is_empty() || self.ports.contains(&port))
+            && match protocol {
+                Some(protocol) => {
+                    self.protocols.is_empty()
+                        || self
+                            .protocols
+                            .iter()
+                            .any(|p| p.eq_ignore_ascii_case(protocol))
+                }
+                None => true,
+            }
+    }
 }
 
 /// Capability permissions and boundaries
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct CapabilityPermissions {
     /// Can access filesystem
     pub filesystem_access: bool,
@@ -42,6 +161,12 @@ pub struct CapabilityPermissions {
     pub cpu_limit_percent: u8,
     /// Maximum execution time in seconds
     pub timeout_seconds: u64,
+    /// Filesystem path policy (unrestricted paths if empty)
+    #[serde(default)]
+    pub filesystem_paths: Vec<PathRule>,
+    /// Egress allowlist (unrestricted destinations if empty)
+    #[serde(default)]
+    pub egress_allowlist: Vec<EgressRule>,
 }
 
 impl Default for CapabilityPermissions {
@@ -55,12 +180,112 @@ impl Default for CapabilityPermissions {
             memory_limit_mb: DEFAULT_MEMORY_LIMIT_MB,
             cpu_limit_percent: DEFAULT_CPU_LIMIT_PERCENT,
             timeout_seconds: DEFAULT_TIMEOUT_SECONDS,
+            filesystem_paths: Vec::new(),
+            egress_allowlist: Vec::new(),
         }
     }
 }
 
+impl CapabilityPermissions {
+    /// Allow filesystem access below a path prefix
+    pub fn with_path(mut self, rule: PathRule) -> Self {
+        self.filesystem_access = true;
+        self.filesystem_paths.push(rule);
+        self
+    }
+
+    /// Check if a path may be accessed with the given mode
+    ///
+    /// Relative paths and paths containing `..` are always denied. Without a
+    /// path policy, `filesystem_access` alone decides.
+    pub fn check_path_access(&self, path: impl AsRef<Path>, mode: AccessMode) -> bool {
+        let path = path.as_ref();
+        if !self.filesystem_access {
+            return false;
+        }
+
+        if !path.is_absolute() || path.components().any(|c| c == Component::ParentDir) {
+            return false;
+        }
+
+        if self.filesystem_paths.is_empty() {
+            return true;
+        }
+
+        self.filesystem_paths
+            .iter()
+            .any(|rule| rule.covers(path, mode))
+    }
+
+    /// Allow outbound connections to a destination
+    pub fn with_egress(mut self, rule: EgressRule) -> Self {
+        self.network_access = true;
+        self.egress_allowlist.push(rule);
+        self
+    }
+
+    /// Check if an outbound connection to `host:port` is allowed
+    ///
+    /// Without an allowlist, `network_access` alone decides.
+    pub fn check_egress(&self, host: &str, port: u16) -> bool {
+        self.check_egress_protocol(host, port, None)
+    }
+
+    /// Check if an outbound connection to `host:port` over a protocol is allowed
+    pub fn check_egress_protocol(&self, host: &str, port: u16, protocol: Option<&str>) -> bool {
+        if !self.network_access {
+            return false;
+        }
+
+        if self.egress_allowlist.is_empty() {
+            return true;
+        }
+
+        self.egress_allowlist
+            .iter()
+            .any(|rule| rule.allows(host, port, protocol))
+    }
+
+    /// Canonical string of the egress policy for hashing
+    pub fn canonical_egress_policy(&self) -> String {
+        let mut rules: Vec<String> = self
+            .egress_allowlist
+            .iter()
+            .map(|rule| {
+                let mut ports = rule.ports.clone();
+                ports.sort_unstable();
+                let mut protocols = rule.protocols.clone();
+                protocols.sort();
+                encode_fields([
+                    rule.host.to_ascii_lowercase(),
+                    encode_fields(ports.iter().map(|p| p.to_string())),
+                    encode_fields(protocols),
+                ])
+            })
+            .collect();
+        rules.sort();
+        encode_fields(rules)
+    }
+
+    /// Canonical string of the path policy for hashing
+    pub fn canonical_path_policy(&self) -> String {
+        let mut rules: Vec<String> = self
+            .filesystem_paths
+            .iter()
+            .map(|rule| {
+                encode_fields([
+                    rule.prefix.as_str(),
+                    if rule.writable { "rw" } else { "ro" },
+                ])
+            })
+            .collect();
+        rules.sort();
+        encode_fields(rules)
+    }
+}
+
 /// Capability expiration and revocation
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CapabilityExpiration {
     /// Expiration timestamp
     pub expires_at: u64,
@@ -77,10 +302,7 @@ pub struct CapabilityExpiration {
 impl Default for CapabilityExpiration {
     fn default() -> Self {
         Self {
-            expires_at: SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap()
-                .as_secs() + DEFAULT_EXPIRATION_HOURS * 60 * 60, // 24 hours from now
+            expires_at: current_timestamp().saturating_add(DEFAULT_EXPIRATION_HOURS * 60 * 60), // 24 hours from now
             revoked: false,
             revocation_reason: None,
             revoked_at: None,
@@ -89,6 +311,115 @@ impl Default for CapabilityExpiration {
     }
 }
 
+impl CapabilityExpiration {
+    /// Check if a timestamp lies past expiry, the skew tolerance and the grace period
+    pub fn is_expired_at(&self, now: u64, tolerance: ExpirationTolerance) -> bool {
+        now > self
+            .expires_at
+            .saturating_add(tolerance.skew_tolerance())
+            .saturating_add(tolerance.grace_period())
+    }
+}
+
+/// Clock tolerances a verifier grants when checking expiry
+///
+/// Configured by the coordinator (see
+/// `AlgorithmRegistry::with_expiration_tolerance`), never by the worker's
+/// manifest. Both tolerances are capped at `MAX_EXPIRATION_TOLERANCE_SECONDS`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExpirationTolerance {
+    skew_tolerance: u64,
+    grace_period: u64,
+}
+
+impl ExpirationTolerance {
+    /// Tolerate clocks disagreeing by `skew_tolerance` seconds, and keep
+    /// capabilities and attestations usable `grace_period` seconds past expiry
+    pub fn new(skew_tolerance: u64, grace_period: u64) -> Self {
+        Self {
+            skew_tolerance: skew_tolerance.min(MAX_EXPIRATION_TOLERANCE_SECONDS),
+            grace_period: grace_period.min(MAX_EXPIRATION_TOLERANCE_SECONDS),
+        }
+    }
+
+    /// Seconds the coordinator and worker clocks may disagree by
+    pub fn skew_tolerance(&self) -> u64 {
+        self.skew_tolerance
+    }
+
+    /// Seconds capabilities and attestations stay usable past expiry
+    pub fn grace_period(&self) -> u64 {
+        self.grace_period
+    }
+}
+
+/// Number of pinned attesters that must sign off on a tool (see `TrustPolicy::with_threshold`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AttestationThreshold {
+    /// Distinct attesters required
+    pub required: usize,
+    /// Attesters whose sign-off counts
+    pub attesters: Vec<String>,
+}
+
+impl AttestationThreshold {
+    /// Require `required` of the given attesters
+    pub fn new<I, S>(required: usize, attesters: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            required,
+            attesters: attesters.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// Deprecation of a tool that workers should migrate off
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeprecationInfo {
+    /// Why the tool is deprecated
+    pub reason: String,
+    /// Tool to migrate to (if any)
+    #[serde(default)]
+    pub replacement: Option<String>,
+    /// Timestamp after which the tool is no longer supported (if set)
+    #[serde(default)]
+    pub sunset_at: Option<u64>,
+}
+
+impl DeprecationInfo {
+    /// Deprecate a tool for a reason
+    pub fn new(reason: impl Into<String>) -> Self {
+        Self {
+            reason: reason.into(),
+            replacement: None,
+            sunset_at: None,
+        }
+    }
+
+    /// Name the tool to migrate to
+    pub fn with_replacement(mut self, replacement: impl Into<String>) -> Self {
+        self.replacement = Some(replacement.into());
+        self
+    }
+
+    /// Set the timestamp after which the tool is no longer supported
+    pub fn with_sunset(mut self, sunset_at: u64) -> Self {
+        self.sunset_at = Some(sunset_at);
+        self
+    }
+
+    /// Check if the sunset date has passed
+    ///
+    /// A clock set before the Unix epoch counts as not yet sunset.
+    pub fn is_sunset(&self) -> bool {
+        self.sunset_at
+            .is_some_and(|sunset_at| clock::unix_timestamp().is_ok_and(|now| now > sunset_at))
+    }
+}
+
 /// Tool capability definition with security features
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolCapability {
@@ -106,6 +437,48 @@ pub struct ToolCapability {
     pub expiration: CapabilityExpiration,
     /// Whether capability is verified
     pub verified: bool,
+    /// Declared version of the tool
+    #[serde(default)]
+    pub version: Option<String>,
+    /// Whether the tool can checkpoint and resume its work
+    #[serde(default)]
+    pub checkpointing: bool,
+    /// Maximum number of concurrent jobs using this tool (unlimited if `None`)
+    #[serde(default)]
+    pub max_concurrent: Option<u32>,
+    /// Whether the tool is suspended due to poor job outcomes
+    #[serde(default)]
+    pub suspended: bool,
+    /// Repositories this tool may be used on
+    #[serde(default)]
+    pub scope: CapabilityScope,
+    /// WASI module the tool runs as (native process if `None`)
+    #[serde(default)]
+    pub wasi: Option<WasiModule>,
+    /// Version constraint the installed tool must satisfy
+    #[serde(default)]
+    pub version_req: Option<VersionReq>,
+    /// Expected SHA256 of the tool executable (lowercase hex)
+    #[serde(default)]
+    pub binary_sha256: Option<String>,
+    /// Platforms the tool runs on (any platform if empty)
+    #[serde(default)]
+    pub platforms: Vec<Platform>,
+    /// Free-form labels (e.g., `gpu`, `licensed`)
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Custom metadata of the tool (e.g., install path, license)
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
+    /// Preference weights of the primary tool and alternatives (higher is tried first, unweighted is 0)
+    #[serde(default)]
+    pub preferences: BTreeMap<String, i32>,
+    /// Deprecation of the tool (if deprecated)
+    #[serde(default)]
+    pub deprecated: Option<DeprecationInfo>,
+    /// Counter-signatures endorsing the attestation, each covering the previous link
+    #[serde(default)]
+    pub countersignatures: Vec<CapabilityAttestation>,
 }
 
 impl ToolCapability {
@@ -119,6 +492,20 @@ impl ToolCapability {
             permissions: CapabilityPermissions::default(),
             expiration: CapabilityExpiration::default(),
             verified: false,
+            version: None,
+            checkpointing: false,
+            max_concurrent: None,
+            suspended: false,
+            scope: CapabilityScope::default(),
+            wasi: None,
+            version_req: None,
+            binary_sha256: None,
+            platforms: Vec::new(),
+            tags: Vec::new(),
+            metadata: HashMap::new(),
+            preferences: BTreeMap::new(),
+            deprecated: None,
+            countersignatures: Vec::new(),
         }
     }
 
@@ -137,6 +524,20 @@ impl ToolCapability {
             permissions,
             expiration,
             verified: false,
+            version: None,
+            checkpointing: false,
+            max_concurrent: None,
+            suspended: false,
+            scope: CapabilityScope::default(),
+            wasi: None,
+            version_req: None,
+            binary_sha256: None,
+            platforms: Vec::new(),
+            tags: Vec::new(),
+            metadata: HashMap::new(),
+            preferences: BTreeMap::new(),
+            deprecated: None,
+            countersignatures: Vec::new(),
         }
     }
 
@@ -146,9 +547,82 @@ impl ToolCapability {
         self
     }
 
+    /// Set the preference weight of the primary tool or an alternative
+    ///
+    /// Candidates are tried by descending weight; equal weights keep the
+    /// declaration order (primary tool first, then alternatives).
+    pub fn with_preference(mut self, tool: impl Into<String>, weight: i32) -> Self {
+        self.preferences.insert(tool.into(), weight);
+        self
+    }
+
+    /// Primary tool and alternatives in the order they are tried
+    pub fn candidates(&self) -> Vec<&str> {
+        let mut candidates: Vec<&str> = std::iter::once(&self.tool_name)
+            .chain(&self.alternatives)
+            .map(String::as_str)
+            .collect();
+        candidates.sort_by_key(|name| {
+            std::cmp::Reverse(self.preferences.get(*name).copied().unwrap_or(0))
+        });
+        candidates
+    }
+
+    /// Declare the installed tool version
+    pub fn with_version(mut self, version: impl Into<String>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    /// Add metadata to the tool
+    pub fn with_metadata(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.metadata.insert(key.into(), value.into());
+        self
+    }
+
+    /// Get a metadata value of the tool
+    pub fn get_metadata(&self, key: &str) -> Option<&String> {
+        self.metadata.get(key)
+    }
+
+    /// Mark the tool as deprecated
+    ///
+    /// Deprecation is reported in security reports and statistics; it does
+    /// not affect whether the tool satisfies a capability.
+    pub fn with_deprecation(mut self, deprecation: DeprecationInfo) -> Self {
+        self.deprecated = Some(deprecation);
+        self
+    }
+
+    /// Check if the tool is deprecated
+    pub fn is_deprecated(&self) -> bool {
+        self.deprecated.is_some()
+    }
+
+    /// Declare that the tool can checkpoint and resume its work
+    pub fn with_checkpointing(mut self) -> Self {
+        self.checkpointing = true;
+        self
+    }
+
+    /// Limit the number of concurrent jobs using this tool
+    pub fn with_max_concurrent(mut self, max_concurrent: u32) -> Self {
+        self.max_concurrent = Some(max_concurrent);
+        self
+    }
+
+    /// Restrict the repositories this tool may be used on
+    pub fn with_scope(mut self, scope: CapabilityScope) -> Self {
+        self.scope = scope;
+        self
+    }
+
     /// Add attestation to capability
+    ///
+    /// Counter-signatures of a previous attestation are dropped.
     pub fn with_attestation(mut self, attestation: CapabilityAttestation) -> Self {
         self.attestation = Some(attestation);
+        self.countersignatures.clear();
         self.verified = true;
         self
     }
@@ -166,33 +640,55 @@ impl ToolCapability {
     }
 
     /// Check if this capability is satisfied
-    pub fn is_satisfied(&self, tool_checker: &dyn Fn(&str) -> bool) -> bool {
+    pub fn is_satisfied(&self, tool_checker: &(impl ToolChecker + ?Sized)) -> bool {
+        self.satisfied_by(tool_checker).is_some()
+    }
+
+    /// Name of the tool (primary or alternative) that satisfies this capability
+    pub fn satisfied_by(&self, tool_checker: &(impl ToolChecker + ?Sized)) -> Option<&str> {
         // Check if capability is expired
         if self.is_expired() {
-            return false;
+            return None;
         }
 
         // Check if capability is revoked
         if self.is_revoked() {
-            return false;
+            return None;
         }
 
-        // Check primary tool
-        if tool_checker(&self.tool_name) {
-            return true;
+        // Check if capability is suspended
+        if self.suspended {
+            return None;
         }
 
-        // Check alternatives
-        self.alternatives.iter().any(|alt| tool_checker(alt))
+        // Check candidates in preference order, including their versions
+        self.candidates().into_iter().find(|name| {
+            tool_checker.is_available(name)
+                && self.checked_version_matches(tool_checker, name)
+                && self.checked_binary_matches(tool_checker, name)
+        })
     }
 
     /// Check if capability is expired
+    ///
+    /// A clock set before the Unix epoch counts as expired.
     pub fn is_expired(&self) -> bool {
-        let current_time = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
-        current_time > self.expiration.expires_at
+        self.try_is_expired().unwrap_or(true)
+    }
+
+    /// Check if capability is expired, reporting an unreadable clock
+    pub fn try_is_expired(&self) -> Result<bool, CapabilityError> {
+        self.try_is_expired_within(ExpirationTolerance::default())
+    }
+
+    /// Check if capability is expired, granting a verifier's tolerances
+    pub fn try_is_expired_within(
+        &self,
+        tolerance: ExpirationTolerance,
+    ) -> Result<bool, CapabilityError> {
+        Ok(self
+            .expiration
+            .is_expired_at(clock::unix_timestamp()?, tolerance))
     }
 
     /// Check if capability is revoked
@@ -200,31 +696,10 @@ impl ToolCapability {
         self.expiration.revoked
     }
 
-    /// Verify capability attestation
+    /// Verify capability attestation with the built-in algorithms
+    #[deprecated(note = "use `verify_attestation_with` and an explicit verifier")]
     pub fn verify_attestation(&self) -> bool {
-        match &self.attestation {
-            Some(attestation) => {
-                // Check if attestation is not expired
-                let current_time = SystemTime::now()
-                    .duration_since(UNIX_EPOCH)
-                    .unwrap()
-                    .as_secs();
-                
-                if current_time - attestation.timestamp > ATTESTATION_EXPIRY_DAYS * 24 * 60 * 60 {
-                    return false; // Attestation expired
-                }
-
-                // Verify attestation algorithm
-                if attestation.algorithm != DEFAULT_ATTESTATION_ALGORITHM {
-                    return false;
-                }
-
-                // In a real implementation, verify the actual signature
-                // For now, just check that attestation exists and is not empty
-                !attestation.signature.is_empty() && !attestation.public_key.is_empty()
-            }
-            None => false, // No attestation means not verified
-        }
+        self.verify_attestation_with(AlgorithmRegistry::builtin())
     }
 
     /// Check if capability has required permissions
@@ -239,16 +714,21 @@ impl ToolCapability {
         }
     }
 
+    /// Check if the tool may access a path with the given mode
+    pub fn check_path_access(&self, path: impl AsRef<Path>, mode: AccessMode) -> bool {
+        self.permissions.check_path_access(path, mode)
+    }
+
+    /// Check if the tool may connect to `host:port`
+    pub fn check_egress(&self, host: &str, port: u16) -> bool {
+        self.permissions.check_egress(host, port)
+    }
+
     /// Revoke capability
     pub fn revoke(&mut self, reason: String, revoked_by: String) {
         self.expiration.revoked = true;
         self.expiration.revocation_reason = Some(reason);
-        self.expiration.revoked_at = Some(
-            SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap()
-                .as_secs()
-        );
+        self.expiration.revoked_at = Some(current_timestamp());
         self.expiration.revoked_by = Some(revoked_by);
     }
 }
@@ -263,4 +743,6 @@ pub struct CapabilitySecurityReport {
     pub is_revoked: bool,
     pub permissions: CapabilityPermissions,
     pub expiration: CapabilityExpiration,
+    #[serde(default)]
+    pub deprecated: Option<DeprecationInfo>,
 }
@@ -0,0 +1,129 @@
+//! Compatibility types for the flat 0.1 capability model
+//!
+//! Version 0.1 declared tools with their attestation, permissions, expiration
+//! and revocation state, but without the later extensions. These types keep
+//! that shape, so 0.1 manifests still parse, and convert into the current
+//! model: the 0.1 security fields are carried across unchanged, and each
+//! later field takes its default. A revoked or expired 0.1 tool stays revoked
+//! or expired after conversion.
+//!
+//! ```rust
+//! use worker_capabilities::{v1, Capabilities};
+//!
+//! let manifest = r#"{
+//!     "id": "worker-1",
+//!     "static_analysis_tools": [
+//!         { "tool_name": "clippy", "required": true, "alternatives": [] }
+//!     ],
+//!     "security_scanning_tools": [
+//!         { "tool_name": "semgrep", "required": false,
+//!           "expiration": { "expires_at": 4102444800, "revoked": true,
+//!                           "revocation_reason": "compromised",
+//!                           "revoked_at": 1700000000, "revoked_by": "security" } }
+//!     ],
+//!     "dynamic_analysis_tools": [],
+//!     "fuzzing_tools": [],
+//!     "test_framework_tools": [],
+//!     "flags": { "ast_support": true },
+//!     "metadata": {}
+//! }"#;
+//!
+//! let legacy: v1::Capabilities = serde_json::from_str(manifest).unwrap();
+//! let caps = Capabilities::from(legacy);
+//! assert!(caps.has_flag("ast_support"));
+//! assert!(!caps.static_analysis_tools[0].is_expired());
+//! assert!(caps.security_scanning_tools[0].is_revoked());
+//! ```
+//!
+//! Attestations made by 0.1 signers covered a different capability hash, so
+//! they fail verification until the tool is re-attested.
+//!
+//! Code that built 0.1 types directly migrates by switching to the builders
+//! of `worker_capabilities::Capabilities`, which produce the same sets.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::types::{CapabilityAttestation, CapabilityExpiration, CapabilityPermissions};
+
+/// Tool declaration of the 0.1 model
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolCapability {
+    /// Name of the tool
+    pub tool_name: String,
+    /// Whether this tool is required (vs optional)
+    pub required: bool,
+    /// Alternative tools that can substitute
+    #[serde(default)]
+    pub alternatives: Vec<String>,
+    /// Capability attestation
+    #[serde(default)]
+    pub attestation: Option<CapabilityAttestation>,
+    /// Capability permissions
+    #[serde(default)]
+    pub permissions: CapabilityPermissions,
+    /// Capability expiration
+    #[serde(default)]
+    pub expiration: CapabilityExpiration,
+    /// Whether capability is verified
+    #[serde(default)]
+    pub verified: bool,
+}
+
+/// Capability set of the 0.1 model
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Capabilities {
+    /// Identifier for this capability set
+    pub id: String,
+    /// Static analysis tools
+    #[serde(default)]
+    pub static_analysis_tools: Vec<ToolCapability>,
+    /// Security scanning tools
+    #[serde(default)]
+    pub security_scanning_tools: Vec<ToolCapability>,
+    /// Dynamic analysis tools
+    #[serde(default)]
+    pub dynamic_analysis_tools: Vec<ToolCapability>,
+    /// Fuzzing tools
+    #[serde(default)]
+    pub fuzzing_tools: Vec<ToolCapability>,
+    /// Test framework tools
+    #[serde(default)]
+    pub test_framework_tools: Vec<ToolCapability>,
+    /// Additional capability flags
+    #[serde(default)]
+    pub flags: HashMap<String, bool>,
+    /// Custom metadata
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
+}
+
+impl From<ToolCapability> for crate::types::ToolCapability {
+    fn from(tool: ToolCapability) -> Self {
+        let mut upgraded =
+            Self::new(tool.tool_name, tool.required).with_alternatives(tool.alternatives);
+        upgraded.attestation = tool.attestation;
+        upgraded.permissions = tool.permissions;
+        upgraded.expiration = tool.expiration;
+        upgraded.verified = tool.verified;
+        upgraded
+    }
+}
+
+impl From<Capabilities> for crate::capabilities::Capabilities {
+    fn from(caps: Capabilities) -> Self {
+        fn upgrade(tools: Vec<ToolCapability>) -> Vec<crate::types::ToolCapability> {
+            tools.into_iter().map(Into::into).collect()
+        }
+
+        let mut upgraded = Self::new(caps.id);
+        upgraded.static_analysis_tools = upgrade(caps.static_analysis_tools);
+        upgraded.security_scanning_tools = upgrade(caps.security_scanning_tools);
+        upgraded.dynamic_analysis_tools = upgrade(caps.dynamic_analysis_tools);
+        upgraded.fuzzing_tools = upgrade(caps.fuzzing_tools);
+        upgraded.test_framework_tools = upgrade(caps.test_framework_tools);
+        upgraded.flags = caps.flags;
+        upgraded.metadata = caps.metadata;
+        upgraded
+    }
+}
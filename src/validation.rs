@@ -0,0 +1,200 @@
+//! Validation of capability sets against the security limits
+//!
+//! `Capabilities::validate` checks a set against the limits in `constants`
+//! (tool name length, alternatives, tools, flags, metadata entries and the
+//! furthest allowed expiration). Registries created `with_validation` reject
+//! sets that fail.
+
+use std::fmt;
+
+use crate::capabilities::Capabilities;
+use crate::clock::current_timestamp;
+use crate::constants::*;
+
+/// Violation of a capability limit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationError {
+    /// The capability set has an empty ID
+    EmptyId,
+    /// A tool in a category has an empty name
+    EmptyToolName { category: String },
+    /// A tool or alternative name is longer than `MAX_TOOL_NAME_LENGTH`
+    ToolNameTooLong { tool: String, length: usize },
+    /// A tool has more than `MAX_ALTERNATIVE_TOOLS` alternatives
+    TooManyAlternatives { tool: String, count: usize },
+    /// The set declares more than `MAX_TOOLS_PER_WORKER` tools
+    TooManyTools { count: usize },
+    /// The set has more than `MAX_CAPABILITY_FLAGS` flags
+    TooManyFlags { count: usize },
+    /// The set or one of its tools has more than `MAX_METADATA_ENTRIES` metadata entries
+    TooManyMetadataEntries { count: usize },
+    /// A tool expires more than `MAX_EXPIRATION_DAYS` in the future
+    ExpirationTooFar { tool: String, expires_at: u64 },
+    /// The registry already holds `MAX_REGISTERED_WORKERS` workers
+    TooManyWorkers { count: usize },
+    /// The set extends a template the registry does not know
+    UnknownTemplate { name: String },
+    /// An attestation is older than the registry's maximum attestation age
+    StaleAttestation { tool: String },
+    /// An attestation's issuance counter is not above one already registered
+    ReplayedAttestation {
+        tool: String,
+        counter: u64,
+        last_seen: u64,
+    },
+    /// An attestation omits its issuance counter after one was registered
+    MissingAttestationCounter { tool: String, last_seen: u64 },
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationError::EmptyId => write!(f, "capability set has an empty ID"),
+            ValidationError::EmptyToolName { category } => {
+                write!(f, "tool with an empty name in '{}'", category)
+            }
+            ValidationError::ToolNameTooLong { tool, length } => write!(
+                f,
+                "tool name '{}...' is {} bytes long (limit {})",
+                tool.chars().take(32).collect::<String>(),
+                length,
+                MAX_TOOL_NAME_LENGTH
+            ),
+            ValidationError::TooManyAlternatives { tool, count } => write!(
+                f,
+                "tool '{}' has {} alternatives (limit {})",
+                tool, count, MAX_ALTERNATIVE_TOOLS
+            ),
+            ValidationError::TooManyTools { count } => {
+                write!(
+                    f,
+                    "{} tools declared (limit {})",
+                    count, MAX_TOOLS_PER_WORKER
+                )
+            }
+            ValidationError::TooManyFlags { count } => {
+                write!(f, "{} flags set (limit {})", count, MAX_CAPABILITY_FLAGS)
+            }
+            ValidationError::TooManyMetadataEntries { count } => write!(
+                f,
+                "{} metadata entries (limit {})",
+                count, MAX_METADATA_ENTRIES
+            ),
+            ValidationError::ExpirationTooFar { tool, expires_at } => write!(
+                f,
+                "tool '{}' expires at {}, more than {} days ahead",
+                tool, expires_at, MAX_EXPIRATION_DAYS
+            ),
+            ValidationError::TooManyWorkers { count } => write!(
+                f,
+                "registry holds {} workers (limit {})",
+                count, MAX_REGISTERED_WORKERS
+            ),
+            ValidationError::UnknownTemplate { name } => write!(f, "unknown template '{}'", name),
+            ValidationError::StaleAttestation { tool } => {
+                write!(
+                    f,
+                    "attestation of '{}' is older than the registry accepts",
+                    tool
+                )
+            }
+            ValidationError::ReplayedAttestation {
+                tool,
+                counter,
+                last_seen,
+            } => write!(
+                f,
+                "attestation of '{}' has counter {}, not above the registered {}",
+                tool, counter, last_seen
+            ),
+            ValidationError::MissingAttestationCounter { tool, last_seen } => write!(
+                f,
+                "attestation of '{}' has no counter, but counter {} is registered",
+                tool, last_seen
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// Capability set a registry refused to register
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegistrationRejection {
+    /// ID of the rejected set
+    pub worker_id: String,
+    /// Unix timestamp of the attempt
+    pub rejected_at: u64,
+    /// Violations that prevented registration
+    pub errors: Vec<ValidationError>,
+}
+
+impl Capabilities {
+    /// Check the set against the security limits, returning every violation
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+
+        if self.id.trim().is_empty() {
+            errors.push(ValidationError::EmptyId);
+        }
+
+        let latest_expiration =
+            current_timestamp().saturating_add(MAX_EXPIRATION_DAYS * 24 * 60 * 60);
+        let mut tools = 0;
+        for category in self.category_names() {
+            for tool in self.tools_in_category(&category) {
+                tools += 1;
+                if tool.tool_name.trim().is_empty() {
+                    errors.push(ValidationError::EmptyToolName {
+                        category: category.clone(),
+                    });
+                }
+                for name in std::iter::once(&tool.tool_name).chain(&tool.alternatives) {
+                    if name.len() > MAX_TOOL_NAME_LENGTH {
+                        errors.push(ValidationError::ToolNameTooLong {
+                            tool: name.clone(),
+                            length: name.len(),
+                        });
+                    }
+                }
+                if tool.alternatives.len() > MAX_ALTERNATIVE_TOOLS {
+                    errors.push(ValidationError::TooManyAlternatives {
+                        tool: tool.tool_name.clone(),
+                        count: tool.alternatives.len(),
+                    });
+                }
+                if tool.metadata.len() > MAX_METADATA_ENTRIES {
+                    errors.push(ValidationError::TooManyMetadataEntries {
+                        count: tool.metadata.len(),
+                    });
+                }
+                if tool.expiration.expires_at > latest_expiration {
+                    errors.push(ValidationError::ExpirationTooFar {
+                        tool: tool.tool_name.clone(),
+                        expires_at: tool.expiration.expires_at,
+                    });
+                }
+            }
+        }
+
+        if tools > MAX_TOOLS_PER_WORKER {
+            errors.push(ValidationError::TooManyTools { count: tools });
+        }
+        if self.flags.len() > MAX_CAPABILITY_FLAGS {
+            errors.push(ValidationError::TooManyFlags {
+                count: self.flags.len(),
+            });
+        }
+        if self.metadata.len() > MAX_METADATA_ENTRIES {
+            errors.push(ValidationError::TooManyMetadataEntries {
+                count: self.metadata.len(),
+            });
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
@@ -0,0 +1,121 @@
+//! Semver version constraints on tools
+
+use semver::{Version, VersionReq};
+
+use crate::capabilities::Capabilities;
+use crate::checker::ToolChecker;
+use crate::types::ToolCapability;
+
+/// Parse a version reported by a tool, tolerating common deviations from semver
+///
+/// Accepts a leading `v`, surrounding text (`clippy 0.1.72 (5680fa1 2023-08-23)`)
+/// and missing minor/patch components (`1.60` parses as `1.60.0`).
+pub fn parse_tool_version(text: &str) -> Option<Version> {
+    text.split_whitespace()
+        .map(|token| token.trim_start_matches('v'))
+        .find(|token| token.starts_with(|c: char| c.is_ascii_digit()))
+        .and_then(|token| {
+            if let Ok(version) = Version::parse(token) {
+                return Some(version);
+            }
+            let core: String = token
+                .chars()
+                .take_while(|c| c.is_ascii_digit() || *c == '.')
+                .collect();
+            let mut parts = core.split('.').filter(|part| !part.is_empty());
+            let major = parts.next()?.parse().ok()?;
+            let minor = parts.next().map_or(Some(0), |part| part.parse().ok())?;
+            let patch = parts.next().map_or(Some(0), |part| part.parse().ok())?;
+            Some(Version::new(major, minor, patch))
+        })
+}
+
+impl ToolCapability {
+    /// Require the installed tool version to satisfy a semver constraint (e.g., `>=1.60, <2.0`)
+    ///
+    /// An invalid constraint is rejected rather than silently ignored.
+    pub fn with_version_req(mut self, requirement: &str) -> Result<Self, semver::Error> {
+        self.version_req = Some(VersionReq::parse(requirement)?);
+        Ok(self)
+    }
+
+    /// Check if a detected version satisfies the version constraint (always true without one)
+    pub fn version_matches(&self, version: &str) -> bool {
+        match &self.version_req {
+            Some(requirement) => {
+                parse_tool_version(version).is_some_and(|v| requirement.matches(&v))
+            }
+            None => true,
+        }
+    }
+
+    /// Check the declared `version` against the version constraint
+    ///
+    /// Tools with a constraint but no declared version do not match.
+    pub fn declared_version_matches(&self) -> bool {
+        match (&self.version_req, &self.version) {
+            (None, _) => true,
+            (Some(_), Some(version)) => self.version_matches(version),
+            (Some(_), None) => false,
+        }
+    }
+
+    /// Check the version detected by a checker (or else the declared version) against the constraint
+    ///
+    /// With a constraint, the declared version is covered by the capability
+    /// hash, so an attested tool cannot edit it to pass.
+    pub fn checked_version_matches(
+        &self,
+        tool_checker: &(impl ToolChecker + ?Sized),
+        tool: &str,
+    ) -> bool {
+        match tool_checker.detected_version(tool) {
+            Some(version) => self.version_matches(&version),
+            None => self.declared_version_matches(),
+        }
+    }
+
+    /// Check if this capability is satisfied by a checker reporting detected versions
+    ///
+    /// The checker returns the version of an installed tool, or `None` if it
+    /// is not installed. The constraint is evaluated against the detected
+    /// version rather than the declared one.
+    pub fn is_satisfied_with_versions(
+        &self,
+        version_checker: &dyn Fn(&str) -> Option<String>,
+    ) -> bool {
+        self.satisfied_with_versions_by(version_checker).is_some()
+    }
+
+    /// Name and detected version of the tool (primary or alternative) that satisfies this capability
+    pub fn satisfied_with_versions_by(
+        &self,
+        version_checker: &dyn Fn(&str) -> Option<String>,
+    ) -> Option<(&str, String)> {
+        if self.is_expired() || self.is_revoked() || self.suspended {
+            return None;
+        }
+
+        std::iter::once(&self.tool_name)
+            .chain(&self.alternatives)
+            .find_map(|name| {
+                version_checker(name)
+                    .filter(|version| self.version_matches(version))
+                    .filter(|_| self.binary_matches(None, name))
+                    .map(|version| (name.as_str(), version))
+            })
+    }
+}
+
+impl Capabilities {
+    /// Check if a capability is available using a checker reporting detected versions
+    pub fn has_capability_with_versions(
+        &self,
+        capability_type: &str,
+        version_checker: &dyn Fn(&str) -> Option<String>,
+    ) -> bool {
+        self.tools_in_category(capability_type)
+            .iter()
+            .any(|tool| tool.is_satisfied_with_versions(version_checker))
+    }
+}
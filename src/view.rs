@@ -0,0 +1,247 @@
+//! Read-only registry views for untrusted consumers
+//!
+//! A `RegistryView` borrows a registry and exposes only its query APIs.
+//! Results are copies filtered through a `VisibilityProfile`, so plugins can
+//! match workers without being able to mutate the registry or read
+//! attestations, signing keys or sensitive metadata.
+
+use serde::{Deserialize, Serialize};
+
+use crate::capabilities::Capabilities;
+use crate::checker::ToolChecker;
+use crate::expr::CapabilityExpr;
+use crate::health::WorkerHealthReport;
+use crate::registry::{CapabilityRegistry, RegistryStatistics};
+use crate::requirements::Requirements;
+
+/// Fields hidden from the consumers of a registry view
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VisibilityProfile {
+    /// Remove set and tool attestations entirely
+    #[serde(default)]
+    pub hide_attestations: bool,
+    /// Blank the signatures and public keys of set and tool attestations
+    #[serde(default)]
+    pub hide_keys: bool,
+    /// Remove all metadata of the worker and its tools
+    #[serde(default)]
+    pub hide_metadata: bool,
+    /// Remove metadata whose key starts with one of these prefixes
+    #[serde(default)]
+    pub hidden_metadata_prefixes: Vec<String>,
+}
+
+impl VisibilityProfile {
+    /// Profile hiding nothing
+    pub fn full() -> Self {
+        Self::default()
+    }
+
+    /// Profile hiding attestations, keys and all metadata
+    pub fn restricted() -> Self {
+        Self {
+            hide_attestations: true,
+            hide_keys: true,
+            hide_metadata: true,
+            hidden_metadata_prefixes: Vec::new(),
+        }
+    }
+
+    /// Remove tool attestations
+    pub fn with_hidden_attestations(mut self) -> Self {
+        self.hide_attestations = true;
+        self
+    }
+
+    /// Blank attestation signatures and public keys
+    pub fn with_hidden_keys(mut self) -> Self {
+        self.hide_keys = true;
+        self
+    }
+
+    /// Remove all metadata
+    pub fn with_hidden_metadata(mut self) -> Self {
+        self.hide_metadata = true;
+        self
+    }
+
+    /// Remove metadata whose key starts with `prefix`
+    pub fn with_hidden_metadata_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.hidden_metadata_prefixes.push(prefix.into());
+        self
+    }
+
+    /// Whether a metadata key is visible under this profile
+    pub fn shows_metadata(&self, key: &str) -> bool {
+        !self.hide_metadata
+            && !self
+                .hidden_metadata_prefixes
+                .iter()
+                .any(|prefix| key.starts_with(prefix.as_str()))
+    }
+
+    /// Copy of a capability set with the hidden fields removed
+    pub fn apply(&self, caps: &Capabilities) -> Capabilities {
+        let mut caps = caps.clone();
+        caps.metadata.retain(|key, _| self.shows_metadata(key));
+        if self.hide_attestations {
+            caps.attestation = None;
+        } else if let Some(attestation) = caps.attestation.as_mut().filter(|_| self.hide_keys) {
+            attestation.signature.clear();
+            attestation.public_key.clear();
+        }
+        for tool in caps.tools_iter_mut() {
+            tool.metadata.retain(|key, _| self.shows_metadata(key));
+            if self.hide_attestations {
+                tool.attestation = None;
+                tool.countersignatures.clear();
+            } else if self.hide_keys {
+                for attestation in tool
+                    .attestation
+                    .iter_mut()
+                    .chain(&mut tool.countersignatures)
+                {
+                    attestation.signature.clear();
+                    attestation.public_key.clear();
+                }
+            }
+        }
+        caps
+    }
+}
+
+/// Read-only view of a registry filtered through a visibility profile
+///
+/// Matching runs against the full capability sets; only the returned copies
+/// are filtered.
+#[derive(Debug, Clone)]
+pub struct RegistryView<'a> {
+    registry: &'a CapabilityRegistry,
+    profile: VisibilityProfile,
+}
+
+impl<'a> RegistryView<'a> {
+    /// Create a view of a registry
+    pub fn new(registry: &'a CapabilityRegistry, profile: VisibilityProfile) -> Self {
+        Self { registry, profile }
+    }
+
+    /// The profile applied to results
+    pub fn profile(&self) -> &VisibilityProfile {
+        &self.profile
+    }
+
+    /// Get capabilities by ID
+    pub fn get(&self, id: &str) -> Option<Capabilities> {
+        self.registry.get(id).map(|caps| self.profile.apply(caps))
+    }
+
+    /// List all registered capability IDs
+    pub fn list_ids(&self) -> Vec<String> {
+        self.registry.list_ids()
+    }
+
+    /// Check if a worker is registered
+    pub fn contains_worker(&self, worker_id: &str) -> bool {
+        self.registry.contains_worker(worker_id)
+    }
+
+    /// Find workers with a specific capability
+    pub fn find_with_capability(
+        &self,
+        capability_type: &str,
+        tool_checker: &(impl ToolChecker + ?Sized),
+    ) -> Vec<Capabilities> {
+        self.filtered(
+            self.registry
+                .find_with_capability(capability_type, tool_checker),
+        )
+    }
+
+    /// Find workers whose environment meets the given requirements
+    pub fn find_meeting_requirements(&self, requirements: &Requirements) -> Vec<Capabilities> {
+        self.filtered(self.registry.find_meeting_requirements(requirements))
+    }
+
+    /// Find workers satisfying both the capability clauses and environment requirements of a job
+    pub fn find_satisfying(
+        &self,
+        requirements: &Requirements,
+        tool_checker: &(impl ToolChecker + ?Sized),
+    ) -> Vec<Capabilities> {
+        self.filtered(self.registry.find_satisfying(requirements, tool_checker))
+    }
+
+    /// Find workers satisfying a capability expression
+    pub fn find_matching_expr(
+        &self,
+        expr: &CapabilityExpr,
+        tool_checker: &(impl ToolChecker + ?Sized),
+    ) -> Vec<Capabilities> {
+        self.filtered(self.registry.find_matching_expr(expr, tool_checker))
+    }
+
+    /// Rank workers meeting the requirements by preference score, best first
+    pub fn rank_workers(&self, requirements: &Requirements) -> Vec<Capabilities> {
+        self.filtered(self.registry.rank_workers(requirements))
+    }
+
+    /// Find workers with verified capabilities
+    pub fn find_verified_workers(&self) -> Vec<Capabilities> {
+        self.filtered(self.registry.find_verified_workers())
+    }
+
+    /// Find workers with a flag enabled
+    pub fn find_workers_with_flag(&self, flag: &str) -> Vec<Capabilities> {
+        self.filtered(self.registry.find_workers_with_flag(flag))
+    }
+
+    /// Find workers carrying a tag
+    pub fn find_workers_with_tag(&self, tag: &str) -> Vec<Capabilities> {
+        self.filtered(self.registry.find_workers_with_tag(tag))
+    }
+
+    /// Find workers with a visible metadata value
+    ///
+    /// Hidden keys never match, so their values cannot be probed.
+    pub fn find_workers_with_metadata(&self, key: &str, value: &str) -> Vec<Capabilities> {
+        if !self.profile.shows_metadata(key) {
+            return Vec::new();
+        }
+        self.filtered(self.registry.find_workers_with_metadata(key, value))
+    }
+
+    /// Find workers with a tool whose visible metadata matches a value
+    ///
+    /// Hidden keys never match, so their values cannot be probed.
+    pub fn find_workers_with_tool_metadata(&self, key: &str, value: &str) -> Vec<Capabilities> {
+        if !self.profile.shows_metadata(key) {
+            return Vec::new();
+        }
+        self.filtered(self.registry.find_workers_with_tool_metadata(key, value))
+    }
+
+    /// Get registry statistics
+    pub fn get_statistics(&self) -> RegistryStatistics {
+        self.registry.get_statistics()
+    }
+
+    /// Latest health report of a worker
+    pub fn worker_health(&self, worker_id: &str) -> Option<&WorkerHealthReport> {
+        self.registry.worker_health(worker_id)
+    }
+
+    fn filtered(&self, workers: Vec<&Capabilities>) -> Vec<Capabilities> {
+        workers
+            .into_iter()
+            .map(|caps| self.profile.apply(caps))
+            .collect()
+    }
+}
+
+impl CapabilityRegistry {
+    /// Read-only view of the registry filtered through a visibility profile
+    pub fn view(&self, profile: VisibilityProfile) -> RegistryView<'_> {
+        RegistryView::new(self, profile)
+    }
+}
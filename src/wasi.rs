@@ -0,0 +1,270 @@
+//! WASI-sandboxed execution of tools compiled to WebAssembly
+//!
+//! A tool declares a `WasiModule` to run inside a WASI runtime instead of as a
+//! native process. Its `CapabilityPermissions` map onto the WASI context:
+//! filesystem path rules become preopened directories, `env_access` inherits
+//! the host environment, and the memory and timeout limits bound the store.
+//! WASI preview 1 has no sockets or process spawning, so network, process and
+//! system permissions are always denied. The executor is behind the `wasi`
+//! feature.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::types::{CapabilityPermissions, ToolCapability};
+
+/// WebAssembly module a tool is compiled to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WasiModule {
+    /// Path of the `.wasm` module on the worker
+    pub module_path: String,
+}
+
+impl WasiModule {
+    /// Declare a WASI module
+    pub fn new(module_path: impl Into<String>) -> Self {
+        Self {
+            module_path: module_path.into(),
+        }
+    }
+}
+
+/// Directory made available to a WASI module
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WasiPreopen {
+    /// Directory on the host
+    pub host_path: String,
+    /// Path the module sees the directory under
+    pub guest_path: String,
+    /// Whether the module may create and modify files in the directory
+    pub writable: bool,
+}
+
+/// WASI context derived from capability permissions
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WasiPolicy {
+    /// Preopened directories
+    pub preopens: Vec<WasiPreopen>,
+    /// Whether the host environment is passed to the module
+    pub inherit_env: bool,
+    /// Maximum linear memory in MB
+    pub memory_limit_mb: u64,
+    /// Maximum execution time in seconds
+    pub timeout_seconds: u64,
+}
+
+impl CapabilityPermissions {
+    /// Map these permissions to a WASI context
+    ///
+    /// Each path rule becomes a preopen at the same guest path. Unrestricted
+    /// filesystem access cannot be expressed in WASI, so nothing is preopened
+    /// unless path rules are declared.
+    pub fn to_wasi_policy(&self) -> WasiPolicy {
+        let preopens = if self.filesystem_access {
+            self.filesystem_paths
+                .iter()
+                .map(|rule| WasiPreopen {
+                    host_path: rule.prefix.clone(),
+                    guest_path: rule.prefix.clone(),
+                    writable: rule.writable,
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        WasiPolicy {
+            preopens,
+            inherit_env: self.env_access,
+            memory_limit_mb: self.memory_limit_mb,
+            timeout_seconds: self.timeout_seconds,
+        }
+    }
+}
+
+impl ToolCapability {
+    /// Declare that this tool runs as a WASI-sandboxed module
+    pub fn with_wasi_module(mut self, module: WasiModule) -> Self {
+        self.wasi = Some(module);
+        self
+    }
+
+    /// Check if this tool runs inside the WASI sandbox
+    pub fn is_wasi_sandboxed(&self) -> bool {
+        self.wasi.is_some()
+    }
+
+    /// Run the tool's WASI module with its permissions applied, returning the exit code
+    #[cfg(feature = "wasi")]
+    pub fn run_wasi(&self, args: &[String]) -> Result<i32, WasiError> {
+        let module = self
+            .wasi
+            .as_ref()
+            .ok_or_else(|| WasiError::NotWasiTool(self.tool_name.clone()))?;
+        executor::run_module(module, args, &self.permissions.to_wasi_policy())
+    }
+}
+
+/// Errors from WASI execution
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WasiError {
+    /// The tool does not declare a WASI module
+    NotWasiTool(String),
+    /// The module could not be loaded or instantiated
+    Module(String),
+    /// A preopened directory could not be opened
+    Preopen { path: String, reason: String },
+    /// The module trapped
+    Trap(String),
+    /// The module ran past its timeout
+    TimedOut(u64),
+    /// The memory limit (in MB) does not fit the host's address space
+    MemoryLimitTooLarge(u64),
+}
+
+impl fmt::Display for WasiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WasiError::NotWasiTool(tool) => {
+                write!(f, "tool '{}' does not declare a WASI module", tool)
+            }
+            WasiError::Module(reason) => write!(f, "failed to load WASI module: {}", reason),
+            WasiError::Preopen { path, reason } => {
+                write!(f, "failed to preopen '{}': {}", path, reason)
+            }
+            WasiError::Trap(reason) => write!(f, "WASI module trapped: {}", reason),
+            WasiError::TimedOut(seconds) => {
+                write!(f, "WASI module exceeded its {}s timeout", seconds)
+            }
+            WasiError::MemoryLimitTooLarge(limit_mb) => {
+                write!(f, "memory limit of {} MB is too large", limit_mb)
+            }
+        }
+    }
+}
+
+impl std::error::Error for WasiError {}
+
+#[cfg(feature = "wasi")]
+mod executor {
+    use std::sync::OnceLock;
+    use std::time::Duration;
+
+    use wasmtime::{Config, Engine, Linker, Module, Store, StoreLimits, StoreLimitsBuilder, Trap};
+    use wasmtime_wasi::preview1::{self, WasiP1Ctx};
+    use wasmtime_wasi::{DirPerms, FilePerms, I32Exit, WasiCtxBuilder};
+
+    use super::{WasiError, WasiModule, WasiPolicy};
+    use crate::constants::WASI_EPOCH_TICK_MILLIS;
+
+    struct State {
+        wasi: WasiP1Ctx,
+        limits: StoreLimits,
+    }
+
+    /// Engine shared by all runs, whose epoch advances every `WASI_EPOCH_TICK_MILLIS`
+    ///
+    /// A single ticker thread drives the epoch; each run bounds its store by
+    /// a number of ticks instead of starting a timer of its own.
+    fn engine() -> Result<&'static Engine, WasiError> {
+        static ENGINE: OnceLock<Result<Engine, String>> = OnceLock::new();
+        ENGINE
+            .get_or_init(|| {
+                let mut config = Config::new();
+                config.epoch_interruption(true);
+                let engine = Engine::new(&config).map_err(|e| e.to_string())?;
+                let ticker = engine.clone();
+                std::thread::spawn(move || loop {
+                    std::thread::sleep(Duration::from_millis(WASI_EPOCH_TICK_MILLIS));
+                    ticker.increment_epoch();
+                });
+                Ok(engine)
+            })
+            .as_ref()
+            .map_err(|e| WasiError::Module(e.clone()))
+    }
+
+    /// Epoch ticks covering a timeout, rounded up so a run gets at least its full time
+    fn deadline_ticks(timeout_seconds: u64) -> u64 {
+        timeout_seconds
+            .saturating_mul(1000)
+            .div_ceil(WASI_EPOCH_TICK_MILLIS)
+            .saturating_add(1)
+    }
+
+    pub(super) fn run_module(
+        module: &WasiModule,
+        args: &[String],
+        policy: &WasiPolicy,
+    ) -> Result<i32, WasiError> {
+        let memory_size = policy
+            .memory_limit_mb
+            .checked_mul(1024 * 1024)
+            .and_then(|bytes| usize::try_from(bytes).ok())
+            .ok_or(WasiError::MemoryLimitTooLarge(policy.memory_limit_mb))?;
+        let engine = engine()?;
+        let compiled = Module::from_file(engine, &module.module_path)
+            .map_err(|e| WasiError::Module(e.to_string()))?;
+
+        let mut builder = WasiCtxBuilder::new();
+        builder.inherit_stdio();
+        builder.arg(&module.module_path);
+        builder.args(args);
+        if policy.inherit_env {
+            builder.inherit_env();
+        }
+        for preopen in &policy.preopens {
+            let (dir_perms, file_perms) = if preopen.writable {
+                (DirPerms::all(), FilePerms::all())
+            } else {
+                (DirPerms::READ, FilePerms::READ)
+            };
+            builder
+                .preopened_dir(
+                    &preopen.host_path,
+                    &preopen.guest_path,
+                    dir_perms,
+                    file_perms,
+                )
+                .map_err(|e| WasiError::Preopen {
+                    path: preopen.host_path.clone(),
+                    reason: e.to_string(),
+                })?;
+        }
+
+        let limits = StoreLimitsBuilder::new().memory_size(memory_size).build();
+        let mut store = Store::new(
+            engine,
+            State {
+                wasi: builder.build_p1(),
+                limits,
+            },
+        );
+        store.limiter(|state| &mut state.limits);
+        store.set_epoch_deadline(deadline_ticks(policy.timeout_seconds));
+
+        let mut linker: Linker<State> = Linker::new(engine);
+        preview1::add_to_linker_sync(&mut linker, |state| &mut state.wasi)
+            .map_err(|e| WasiError::Module(e.to_string()))?;
+        let instance = linker
+            .instantiate(&mut store, &compiled)
+            .map_err(|e| WasiError::Module(e.to_string()))?;
+        let start = instance
+            .get_typed_func::<(), ()>(&mut store, "_start")
+            .map_err(|e| WasiError::Module(e.to_string()))?;
+
+        let timeout = policy.timeout_seconds;
+        match start.call(&mut store, ()) {
+            Ok(()) => Ok(0),
+            Err(err) => {
+                if let Some(exit) = err.downcast_ref::<I32Exit>() {
+                    Ok(exit.0)
+                } else if err.downcast_ref::<Trap>() == Some(&Trap::Interrupt) {
+                    Err(WasiError::TimedOut(timeout))
+                } else {
+                    Err(WasiError::Trap(format!("{:#}", err)))
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,89 @@
+//! Well-known flags and metadata keys with typed accessors
+//!
+//! ```rust
+//! use worker_capabilities::prelude::*;
+//! use worker_capabilities::well_known;
+//!
+//! let caps = Capabilities::new("worker-1")
+//!     .with_flag(well_known::AST_SUPPORT)
+//!     .with_max_concurrent_jobs(8);
+//!
+//! assert!(caps.ast_support());
+//! assert_eq!(caps.max_concurrent_jobs(), Some(8));
+//! ```
+
+use crate::capabilities::Capabilities;
+
+/// Flag: the worker can parse and analyze ASTs
+pub const AST_SUPPORT: &str = "ast_support";
+/// Flag: the worker has access to a language model
+pub const LLM_SUPPORT: &str = "llm_support";
+/// Flag: the worker can analyze EVM contracts
+pub const EVM_SUPPORT: &str = "evm_support";
+/// Flag: the worker can run Docker sandboxes
+pub const DOCKER_SUPPORT: &str = "docker_support";
+/// Flag: the worker can run gVisor sandboxes
+pub const GVISOR_SUPPORT: &str = "gvisor_support";
+/// Flag: the worker can run Firecracker microVMs
+pub const FIRECRACKER_SUPPORT: &str = "firecracker_support";
+
+/// Metadata: version of the worker software
+pub const VERSION: &str = "version";
+/// Metadata: platform the worker runs on (e.g., `linux-x86_64`)
+pub const PLATFORM: &str = "platform";
+/// Metadata: maximum number of jobs the worker runs at once
+pub const MAX_CONCURRENT_JOBS: &str = "max_concurrent_jobs";
+/// Metadata: memory available to the worker in MB
+pub const MEMORY_LIMIT_MB: &str = "memory_limit_mb";
+
+impl Capabilities {
+    /// Check the `ast_support` flag
+    pub fn ast_support(&self) -> bool {
+        self.has_flag(AST_SUPPORT)
+    }
+
+    /// Check the `llm_support` flag
+    pub fn llm_support(&self) -> bool {
+        self.has_flag(LLM_SUPPORT)
+    }
+
+    /// Check the `evm_support` flag
+    pub fn evm_support(&self) -> bool {
+        self.has_flag(EVM_SUPPORT)
+    }
+
+    /// Worker software version from the `version` metadata key
+    pub fn worker_version(&self) -> Option<&str> {
+        self.get_metadata(VERSION).map(String::as_str)
+    }
+
+    /// Set the `version` metadata key
+    pub fn with_worker_version(self, version: impl Into<String>) -> Self {
+        self.with_metadata(VERSION, version)
+    }
+
+    /// Platform from the `platform` metadata key (set by `with_platform`)
+    pub fn platform(&self) -> Option<&str> {
+        self.get_metadata(PLATFORM).map(String::as_str)
+    }
+
+    /// Parsed `max_concurrent_jobs` metadata (`None` if missing or not a number)
+    pub fn max_concurrent_jobs(&self) -> Option<u32> {
+        self.get_metadata(MAX_CONCURRENT_JOBS)?.trim().parse().ok()
+    }
+
+    /// Set the `max_concurrent_jobs` metadata key
+    pub fn with_max_concurrent_jobs(self, jobs: u32) -> Self {
+        self.with_metadata(MAX_CONCURRENT_JOBS, jobs.to_string())
+    }
+
+    /// Parsed `memory_limit_mb` metadata (`None` if missing or not a number)
+    pub fn memory_limit_mb(&self) -> Option<u64> {
+        self.get_metadata(MEMORY_LIMIT_MB)?.trim().parse().ok()
+    }
+
+    /// Set the `memory_limit_mb` metadata key
+    pub fn with_memory_limit_mb(self, memory_mb: u64) -> Self {
+        self.with_metadata(MEMORY_LIMIT_MB, memory_mb.to_string())
+    }
+}
@@ -0,0 +1,374 @@
+//! X.509 certificate attestation (requires the `x509` feature)
+//!
+//! `X509Signer` wraps another signer and records the signer's certificate
+//! chain (PEM, leaf first) as the attestation's public key, with the algorithm
+//! `X509/<alg>` (e.g., `X509/Ed25519`, `X509/ES256`). `X509Verifier` checks the
+//! signature against the leaf certificate and the chain against an
+//! `X509RootStore`, so attestations are anchored in an existing PKI. Leaf
+//! certificates with an extended key usage must allow code signing.
+//!
+//! ```rust
+//! use rcgen::{BasicConstraints, CertificateParams, IsCa, KeyPair, PKCS_ED25519};
+//! use worker_capabilities::{
+//!     AlgorithmRegistry, AttestationSigner, Ed25519Signer, ToolCapability, X509RootStore,
+//!     X509Signer,
+//! };
+//!
+//! // A root CA and a leaf certificate for the Ed25519 attestation key
+//! let secret = [7; 32];
+//! let root_key = KeyPair::generate_for(&PKCS_ED25519).unwrap();
+//! let mut root_params = CertificateParams::new(Vec::new()).unwrap();
+//! root_params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+//! let root = root_params.self_signed(&root_key).unwrap();
+//!
+//! let pkcs8 = [&[0x30, 0x2e, 0x02, 0x01, 0x00, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70,
+//!     0x04, 0x22, 0x04, 0x20][..], &secret].concat();
+//! let leaf_key = KeyPair::try_from(pkcs8.as_slice()).unwrap();
+//! let leaf = CertificateParams::new(vec!["ci.example.com".to_string()]).unwrap()
+//!     .signed_by(&leaf_key, &root, &root_key).unwrap();
+//!
+//! let signer = X509Signer::new(Ed25519Signer::new(secret, "ci"), leaf.pem()).unwrap();
+//! let tool = ToolCapability::new("clippy", true);
+//! let tool = tool.clone().with_attestation(signer.attest(&tool));
+//!
+//! let roots = X509RootStore::from_pem(&root.pem()).unwrap();
+//! let algorithms = AlgorithmRegistry::new().with_x509_roots(roots);
+//! assert!(tool.verify_attestation_with(&algorithms));
+//!
+//! // Certificates that do not chain to a configured root are rejected
+//! let algorithms = AlgorithmRegistry::new().with_x509_roots(X509RootStore::new());
+//! assert!(!tool.verify_attestation_with(&algorithms));
+//! ```
+
+use rustls_pki_types::pem::PemObject;
+use rustls_pki_types::{CertificateDer, SignatureVerificationAlgorithm, TrustAnchor, UnixTime};
+use std::fmt;
+use std::time::Duration;
+use webpki::{EndEntityCert, KeyUsage};
+
+use crate::clock;
+use crate::constants::X509_ALGORITHM_PREFIX;
+use crate::signing::{from_hex, to_hex, AlgorithmRegistry, AttestationSigner, AttestationVerifier};
+use crate::types::CapabilityAttestation;
+
+/// id-kp-codeSigning (1.3.6.1.5.5.7.3.3)
+const EKU_CODE_SIGNING: &[u8] = &[0x2b, 0x06, 0x01, 0x05, 0x05, 0x07, 0x03, 0x03];
+
+/// Signature algorithms of certificate keys, by the crate's algorithm name
+pub(crate) const SIGNATURE_ALGORITHMS: &[(&str, &dyn SignatureVerificationAlgorithm)] = &[
+    ("Ed25519", webpki::ring::ED25519),
+    ("ES256", webpki::ring::ECDSA_P256_SHA256),
+    ("ES384", webpki::ring::ECDSA_P384_SHA384),
+    ("RS256", webpki::ring::RSA_PKCS1_2048_8192_SHA256),
+    ("RS384", webpki::ring::RSA_PKCS1_2048_8192_SHA384),
+    ("RS512", webpki::ring::RSA_PKCS1_2048_8192_SHA512),
+    ("PS256", webpki::ring::RSA_PSS_2048_8192_SHA256_LEGACY_KEY),
+];
+
+/// Errors from loading or validating certificates
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum X509Error {
+    /// The PEM input could not be parsed
+    InvalidPem(String),
+    /// The PEM input contains no certificate
+    EmptyChain,
+    /// A certificate could not be parsed
+    InvalidCertificate(String),
+    /// The chain does not lead to a configured root
+    Untrusted(String),
+}
+
+impl fmt::Display for X509Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            X509Error::InvalidPem(reason) => write!(f, "invalid PEM: {}", reason),
+            X509Error::EmptyChain => write!(f, "no certificate found"),
+            X509Error::InvalidCertificate(reason) => write!(f, "invalid certificate: {}", reason),
+            X509Error::Untrusted(reason) => write!(f, "untrusted certificate chain: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for X509Error {}
+
+/// Root certificates that attester chains must lead to
+#[derive(Debug, Clone, Default)]
+pub struct X509RootStore {
+    roots: Vec<TrustAnchor<'static>>,
+}
+
+impl X509RootStore {
+    /// Empty root store (trusts no certificate)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Root store with all certificates of a PEM bundle
+    pub fn from_pem(pem: &str) -> Result<Self, X509Error> {
+        let mut store = Self::new();
+        store.add_pem(pem)?;
+        Ok(store)
+    }
+
+    /// Add all certificates of a PEM bundle, returning how many were added
+    pub fn add_pem(&mut self, pem: &str) -> Result<usize, X509Error> {
+        let certs = parse_pem(pem)?;
+        for cert in &certs {
+            self.add_der(cert)?;
+        }
+        Ok(certs.len())
+    }
+
+    /// Add a DER-encoded root certificate
+    pub fn add_der(&mut self, der: &[u8]) -> Result<(), X509Error> {
+        let cert = CertificateDer::from(der);
+        let anchor = webpki::anchor_from_trusted_cert(&cert)
+            .map_err(|e| X509Error::InvalidCertificate(e.to_string()))?;
+        self.roots.push(anchor.to_owned());
+        Ok(())
+    }
+
+    /// Number of root certificates
+    pub fn len(&self) -> usize {
+        self.roots.len()
+    }
+
+    /// Check if the store has no root certificate
+    pub fn is_empty(&self) -> bool {
+        self.roots.is_empty()
+    }
+
+    /// Validate a PEM chain (leaf first) against the roots at the current time
+    pub fn verify_chain(&self, chain_pem: &str) -> Result<(), X509Error> {
+        let now = clock::unix_timestamp().map_err(|e| X509Error::Untrusted(e.to_string()))?;
+        self.verify_chain_at(chain_pem, now)
+    }
+
+    /// Validate a PEM chain (leaf first) against the roots at a Unix timestamp
+    ///
+    /// Use the signing time for short-lived certificates.
+    pub fn verify_chain_at(&self, chain_pem: &str, timestamp: u64) -> Result<(), X509Error> {
+        let chain = parse_pem(chain_pem)?;
+        let (leaf, intermediates) = chain.split_first().ok_or(X509Error::EmptyChain)?;
+        let leaf = EndEntityCert::try_from(leaf)
+            .map_err(|e| X509Error::InvalidCertificate(e.to_string()))?;
+
+        leaf.verify_for_usage(
+            webpki::ALL_VERIFICATION_ALGS,
+            &self.roots,
+            intermediates,
+            UnixTime::since_unix_epoch(Duration::from_secs(timestamp)),
+            KeyUsage::required_if_present(EKU_CODE_SIGNING),
+            None,
+            None,
+        )
+        .map(|_| ())
+        .map_err(|e| X509Error::Untrusted(e.to_string()))
+    }
+}
+
+/// Signer recording its certificate chain in attestations
+///
+/// Signatures are the hex-encoded raw signatures of the wrapped signer,
+/// whose key must match the leaf certificate.
+#[derive(Debug, Clone)]
+pub struct X509Signer<S> {
+    inner: S,
+    chain_pem: String,
+    algorithm: String,
+}
+
+impl<S: AttestationSigner> X509Signer<S> {
+    /// Sign with `inner` as the holder of a certificate chain (PEM, leaf first)
+    pub fn new(inner: S, chain_pem: impl Into<String>) -> Result<Self, X509Error> {
+        let chain_pem = chain_pem.into();
+        if parse_pem(&chain_pem)?.is_empty() {
+            return Err(X509Error::EmptyChain);
+        }
+        let algorithm = format!("{}{}", X509_ALGORITHM_PREFIX, inner.algorithm());
+        Ok(Self {
+            inner,
+            chain_pem,
+            algorithm,
+        })
+    }
+}
+
+impl<S: AttestationSigner> AttestationSigner for X509Signer<S> {
+    fn attester(&self) -> &str {
+        self.inner.attester()
+    }
+
+    fn algorithm(&self) -> &str {
+        &self.algorithm
+    }
+
+    fn public_key(&self) -> String {
+        self.chain_pem.clone()
+    }
+
+    fn sign(&self, message: &[u8]) -> String {
+        to_hex(&self.inner.sign_raw(message))
+    }
+
+    fn sign_raw(&self, message: &[u8]) -> Vec<u8> {
+        self.inner.sign_raw(message)
+    }
+}
+
+/// Verifier for attestations signed by a certificate holder
+///
+/// The public key of an attestation is its PEM certificate chain. The chain
+/// must lead to one of the roots and be valid at the attestation's signing
+/// time, and the signature must verify against the leaf certificate.
+#[derive(Debug, Clone)]
+pub struct X509Verifier {
+    roots: X509RootStore,
+}
+
+impl X509Verifier {
+    /// Verify chains against a root store
+    pub fn new(roots: X509RootStore) -> Self {
+        Self { roots }
+    }
+
+    /// Check a signature by the leaf of a chain valid at `signed_at`
+    ///
+    /// Without a signing time, the chain must be valid now.
+    fn check(
+        &self,
+        chain_pem: &str,
+        message: &[u8],
+        signature: &[u8],
+        algorithms: &[&dyn SignatureVerificationAlgorithm],
+        signed_at: Option<u64>,
+    ) -> bool {
+        let chain = match signed_at {
+            Some(timestamp) => self.roots.verify_chain_at(chain_pem, timestamp),
+            None => self.roots.verify_chain(chain_pem),
+        };
+        chain.is_ok() && verify_leaf_signature(chain_pem, message, signature, algorithms)
+    }
+}
+
+impl AttestationVerifier for X509Verifier {
+    fn supports(&self, algorithm: &str) -> bool {
+        signature_algorithm(algorithm).is_some()
+    }
+
+    fn verify(&self, public_key: &str, message: &[u8], signature: &str) -> bool {
+        from_hex(signature)
+            .is_some_and(|signature| self.verify_raw(public_key, message, &signature))
+    }
+
+    fn verify_raw(&self, public_key: &str, message: &[u8], signature: &[u8]) -> bool {
+        let algorithms: Vec<_> = SIGNATURE_ALGORITHMS
+            .iter()
+            .map(|(_, algorithm)| *algorithm)
+            .collect();
+        self.check(public_key, message, signature, &algorithms, None)
+    }
+
+    fn verify_as(
+        &self,
+        algorithm: &str,
+        public_key: &str,
+        message: &[u8],
+        signature: &str,
+    ) -> bool {
+        from_hex(signature)
+            .is_some_and(|signature| self.verify_raw_as(algorithm, public_key, message, &signature))
+    }
+
+    fn verify_raw_as(
+        &self,
+        algorithm: &str,
+        public_key: &str,
+        message: &[u8],
+        signature: &[u8],
+    ) -> bool {
+        signature_algorithm(algorithm)
+            .is_some_and(|algorithm| self.check(public_key, message, signature, &[algorithm], None))
+    }
+
+    fn verify_attestation(&self, attestation: &CapabilityAttestation) -> bool {
+        let Some(algorithm) = signature_algorithm(&attestation.algorithm) else {
+            return false;
+        };
+        from_hex(&attestation.signature).is_some_and(|signature| {
+            self.check(
+                &attestation.public_key,
+                attestation.signed_message().as_bytes(),
+                &signature,
+                &[algorithm],
+                Some(attestation.timestamp),
+            )
+        })
+    }
+}
+
+impl AlgorithmRegistry {
+    /// Verify `X509/<alg>` attestations against a root store
+    pub fn with_x509_roots(self, roots: X509RootStore) -> Self {
+        let verifier = X509Verifier::new(roots);
+        SIGNATURE_ALGORITHMS
+            .iter()
+            .fold(self, |registry, (name, _)| {
+                registry.with_algorithm(
+                    format!("{}{}", X509_ALGORITHM_PREFIX, name),
+                    verifier.clone(),
+                )
+            })
+    }
+}
+
+/// Certificate key algorithm of an `X509/<alg>` attestation algorithm
+fn signature_algorithm(algorithm: &str) -> Option<&'static dyn SignatureVerificationAlgorithm> {
+    let name = algorithm.strip_prefix(X509_ALGORITHM_PREFIX)?;
+    SIGNATURE_ALGORITHMS
+        .iter()
+        .find(|(known, _)| *known == name)
+        .map(|(_, algorithm)| *algorithm)
+}
+
+/// Check a signature against the leaf certificate of a PEM chain
+pub(crate) fn verify_leaf_signature(
+    chain_pem: &str,
+    message: &[u8],
+    signature: &[u8],
+    algorithms: &[&dyn SignatureVerificationAlgorithm],
+) -> bool {
+    let Some(leaf) = parse_pem(chain_pem)
+        .ok()
+        .and_then(|chain| chain.into_iter().next())
+    else {
+        return false;
+    };
+    let Ok(leaf) = EndEntityCert::try_from(&leaf) else {
+        return false;
+    };
+    algorithms.iter().any(|algorithm| {
+        leaf.verify_signature(*algorithm, message, signature)
+            .is_ok()
+    })
+}
+
+/// URI subject alternative names of the leaf certificate of a PEM chain
+#[cfg(feature = "sigstore")]
+pub(crate) fn leaf_uri_names(chain_pem: &str) -> Vec<String> {
+    let Some(leaf) = parse_pem(chain_pem)
+        .ok()
+        .and_then(|chain| chain.into_iter().next())
+    else {
+        return Vec::new();
+    };
+    EndEntityCert::try_from(&leaf)
+        .map(|leaf| leaf.valid_uri_names().map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+pub(crate) fn parse_pem(pem: &str) -> Result<Vec<CertificateDer<'static>>, X509Error> {
+    CertificateDer::pem_slice_iter(pem.as_bytes())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| X509Error::InvalidPem(e.to_string()))
+}
@@ -0,0 +1,41 @@
+//! Preference weights decide which of several installed alternatives is used
+
+use worker_capabilities::*;
+
+fn rustfmt() -> ToolCapability {
+    ToolCapability::new("rustfmt-nightly", true).with_alternatives(vec!["rustfmt".to_string()])
+}
+
+#[test]
+fn unweighted_candidates_keep_declaration_order() {
+    assert_eq!(rustfmt().candidates(), vec!["rustfmt-nightly", "rustfmt"]);
+    let both = |_: &str| true;
+    assert_eq!(rustfmt().satisfied_by(&both), Some("rustfmt-nightly"));
+}
+
+#[test]
+fn higher_weights_are_tried_first() {
+    let tool = rustfmt().with_preference("rustfmt", 10);
+    assert_eq!(tool.candidates(), vec!["rustfmt", "rustfmt-nightly"]);
+
+    let both = |_: &str| true;
+    let nightly_only = |name: &str| name == "rustfmt-nightly";
+    assert_eq!(tool.satisfied_by(&both), Some("rustfmt"));
+    assert_eq!(tool.satisfied_by(&nightly_only), Some("rustfmt-nightly"));
+    assert_eq!(tool.satisfied_by(&|_: &str| false), None);
+}
+
+#[test]
+fn workers_report_the_satisfying_tool() {
+    let mut caps = Capabilities::new("worker");
+    caps.add_tools_from(
+        CAPABILITY_STATIC_ANALYSIS,
+        &[rustfmt().with_preference("rustfmt-nightly", -1)],
+    );
+    let both = |_: &str| true;
+    assert_eq!(
+        caps.satisfied_by(CAPABILITY_STATIC_ANALYSIS, &both),
+        Some("rustfmt")
+    );
+    assert_eq!(caps.satisfied_by(CAPABILITY_FUZZING, &both), None);
+}
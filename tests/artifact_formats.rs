@@ -0,0 +1,54 @@
+//! Workers are only sent artifacts in formats they accept
+
+use worker_capabilities::*;
+
+fn source_analyzer() -> Capabilities {
+    Capabilities::new("source-analyzer")
+        .accepting(ArtifactFormat::SourceTarball)
+        .accepting(ArtifactFormat::GitUrl)
+        .accepting(ArtifactFormat::GitUrl)
+}
+
+#[test]
+fn container_images_skip_source_only_analyzers() {
+    let image = Requirements::new().with_input(ArtifactFormat::ContainerImage);
+    let repository = Requirements::new().with_input(ArtifactFormat::GitUrl);
+    let scanner = Capabilities::new("image-scanner").accepting(ArtifactFormat::ContainerImage);
+
+    assert!(!source_analyzer().meets_requirements(&image));
+    assert!(source_analyzer().meets_requirements(&repository));
+    assert!(scanner.meets_requirements(&image));
+
+    let mut registry = CapabilityRegistry::new();
+    registry.register(source_analyzer());
+    registry.register(scanner);
+    let matched: Vec<_> = registry
+        .find_meeting_requirements(&image)
+        .into_iter()
+        .map(|caps| caps.id.as_str())
+        .collect();
+    assert_eq!(matched, vec!["image-scanner"]);
+}
+
+#[test]
+fn formats_are_declared_once() {
+    assert_eq!(source_analyzer().accepts.len(), 2);
+}
+
+#[test]
+fn identifiers_round_trip_and_keep_unknown_formats() {
+    for format in [
+        ArtifactFormat::SourceTarball,
+        ArtifactFormat::GitUrl,
+        ArtifactFormat::ContainerImage,
+        ArtifactFormat::CompiledBinary,
+        ArtifactFormat::SolidityJson,
+    ] {
+        assert_eq!(ArtifactFormat::parse(format.as_str()), format);
+    }
+    assert_eq!(ArtifactFormat::parse("Git_URL"), ArtifactFormat::GitUrl);
+    assert_eq!(
+        ArtifactFormat::parse("wasm_module"),
+        ArtifactFormat::Other("wasm_module".to_string())
+    );
+}
@@ -0,0 +1,87 @@
+//! Threshold sign-off counts only attesters pinned by the verifier's policy
+
+use worker_capabilities::*;
+
+fn security() -> Ed25519Signer {
+    Ed25519Signer::new([1; 32], "security")
+}
+
+fn platform() -> Ed25519Signer {
+    Ed25519Signer::new([2; 32], "platform")
+}
+
+fn policy() -> TrustPolicy {
+    TrustPolicy::new()
+        .with_attester(AttesterEntry::new("security", security().public_key()))
+        .with_attester(AttesterEntry::new("platform", platform().public_key()))
+        .with_threshold("docker", 2, ["security", "platform"])
+}
+
+fn not_met(attested: usize) -> Result<(), VerificationError> {
+    Err(VerificationError::ThresholdNotMet {
+        tool: "docker".to_string(),
+        required: 2,
+        attested,
+    })
+}
+
+#[test]
+fn threshold_is_met_by_pinned_attesters() {
+    let tool = ToolCapability::new("docker", true)
+        .countersign(&security())
+        .countersign(&platform());
+    assert_eq!(
+        tool.try_verify_chain_with(&policy(), &Ed25519Verifier),
+        Ok(())
+    );
+
+    let mut caps = Capabilities::new("worker-1");
+    caps.add_tools_from("security_scanning", &[tool]);
+    assert_eq!(
+        caps.try_verify_trusted_with(&policy(), &Ed25519Verifier),
+        Ok(())
+    );
+}
+
+#[test]
+fn impersonated_attester_does_not_count() {
+    // A key the policy does not pin, signing under a pinned attester's name
+    let impostor = Ed25519Signer::new([9; 32], "platform");
+    let tool = ToolCapability::new("docker", true)
+        .countersign(&security())
+        .countersign(&impostor);
+
+    let policy = policy().with_attester(AttesterEntry::new("", impostor.public_key()));
+    assert_eq!(
+        tool.try_verify_chain_with(&policy, &Ed25519Verifier),
+        not_met(1)
+    );
+}
+
+#[test]
+fn one_key_counts_once_whatever_the_name() {
+    let policy = policy().with_attester(AttesterEntry::new("platform", security().public_key()));
+    let renamed = Ed25519Signer::new([1; 32], "platform");
+    let tool = ToolCapability::new("docker", true)
+        .countersign(&security())
+        .countersign(&renamed);
+    assert_eq!(
+        tool.try_verify_chain_with(&policy, &Ed25519Verifier),
+        not_met(1)
+    );
+}
+
+#[test]
+fn registry_enforces_policy_threshold() {
+    let tool = ToolCapability::new("docker", true).countersign(&security());
+    let mut caps = Capabilities::new("worker-1");
+    caps.add_tools_from("security_scanning", &[tool]);
+    assert_eq!(
+        caps.try_verify_trusted_with(&policy(), &Ed25519Verifier),
+        not_met(1)
+    );
+
+    let mut registry = CapabilityRegistry::new().with_trust_policy(policy());
+    registry.register(caps);
+    assert!(registry.find_verified_workers().is_empty());
+}
@@ -0,0 +1,86 @@
+//! Attestations are re-signed before expiry, never when invalid or withdrawn
+
+use std::time::Duration;
+
+use worker_capabilities::clock::{self, MockClock};
+use worker_capabilities::*;
+
+const START: u64 = 1_700_000_000;
+const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+fn signer() -> CapabilitySigner {
+    CapabilitySigner::new("key", "ci")
+}
+
+fn attested(name: &str) -> ToolCapability {
+    let tool = ToolCapability::new(name, true);
+    tool.clone().with_attestation(signer().attest(&tool))
+}
+
+#[test]
+fn renewal_re_signs_the_current_hash() {
+    let clock = MockClock::at_unix(START);
+    clock::with_clock(clock.clone(), || {
+        let mut tool = attested("clippy").countersign(&CapabilitySigner::new("key", "platform"));
+        clock.advance(DAY);
+
+        tool.renew_attestation(&signer(), &MockVerifier).unwrap();
+        let attestation = tool.attestation.as_ref().unwrap();
+        assert_eq!(attestation.timestamp, START + DAY.as_secs());
+        assert_eq!(attestation.capability_hash, tool.generate_capability_hash());
+        assert!(tool.countersignatures.is_empty());
+        assert!(tool.verify_attestation_with(&MockVerifier));
+    });
+}
+
+#[test]
+fn tampered_or_revoked_tools_are_not_renewed() {
+    clock::with_clock(MockClock::at_unix(START), || {
+        let mut tampered = attested("clippy");
+        tampered.required = false;
+        assert_eq!(
+            tampered.renew_attestation(&signer(), &MockVerifier),
+            Err(VerificationError::HashMismatch {
+                tool: "clippy".to_string(),
+            })
+        );
+
+        let mut revoked = attested("clippy");
+        revoked.revoke("compromised".to_string(), "security".to_string());
+        assert!(matches!(
+            revoked.renew_attestation(&signer(), &MockVerifier),
+            Err(VerificationError::Revoked { .. })
+        ));
+    });
+}
+
+#[test]
+fn managers_renew_only_attestations_close_to_expiry() {
+    let clock = MockClock::at_unix(START);
+    clock::with_clock(clock.clone(), || {
+        let mut manager = AttestationManager::new();
+        manager.add_attestation("expired".to_string(), signer().attest_hash("a"));
+        clock.advance(DAY * 2);
+        manager.add_attestation("expiring".to_string(), signer().attest_hash("b"));
+        clock.advance(DAY * (ATTESTATION_EXPIRY_DAYS as u32 - 1));
+        manager.add_attestation("fresh".to_string(), signer().attest_hash("c"));
+
+        let events = manager.renew_expiring(DAY * 2, &signer(), &MockVerifier);
+        assert_eq!(events.len(), 2);
+        assert!(matches!(&events[0], RenewalEvent::Failed { tool, .. } if tool == "expired"));
+        assert_eq!(
+            events[1],
+            RenewalEvent::Renewed {
+                tool: "expiring".to_string(),
+            }
+        );
+
+        let renewed = manager.get_attestation("expiring").unwrap();
+        assert_eq!(renewed.capability_hash, "b");
+        assert_eq!(renewed.timestamp, clock::unix_timestamp().unwrap());
+        assert_eq!(
+            manager.get_attestation("fresh").unwrap().timestamp,
+            renewed.timestamp
+        );
+    });
+}
@@ -0,0 +1,73 @@
+//! Sweeps prune or flag expired attestations and notify subscribers
+
+use std::time::Duration;
+
+use worker_capabilities::clock::{self, MockClock};
+use worker_capabilities::*;
+
+const START: u64 = 1_700_000_000;
+const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+fn signer() -> CapabilitySigner {
+    CapabilitySigner::new("key", "ci")
+}
+
+/// Manager holding an attestation that expires and one made later that does not
+fn manager(action: SweepAction, clock: &MockClock) -> AttestationManager {
+    let mut manager = AttestationManager::new().with_sweep_action(action);
+    manager.add_attestation("old".to_string(), signer().attest_hash("a"));
+    clock.advance(DAY * 2);
+    manager.add_attestation("new".to_string(), signer().attest_hash("b"));
+    clock.advance(DAY * (ATTESTATION_EXPIRY_DAYS as u32 - 1));
+    manager
+}
+
+#[test]
+fn pruning_removes_expired_attestations() {
+    let clock = MockClock::at_unix(START);
+    clock::with_clock(clock.clone(), || {
+        let mut manager = manager(SweepAction::Prune, &clock);
+        let events = manager.sweep();
+        assert!(matches!(&events[..], [SweepEvent::Pruned { tool, .. }] if tool == "old"));
+        assert_eq!(manager.count(), 1);
+        assert!(manager.sweep().is_empty());
+    });
+}
+
+#[test]
+fn flags_are_reported_once_until_replaced() {
+    let clock = MockClock::at_unix(START);
+    clock::with_clock(clock.clone(), || {
+        let mut manager = manager(SweepAction::Flag, &clock);
+        let flagged = vec![SweepEvent::Flagged {
+            tool: "old".to_string(),
+        }];
+        assert_eq!(manager.sweep(), flagged);
+        assert!(manager.sweep().is_empty());
+        assert_eq!(manager.flagged_attestations(), vec!["old"]);
+        assert_eq!(manager.count(), 2);
+
+        manager.add_attestation("old".to_string(), signer().attest_hash("a"));
+        assert!(manager.flagged_attestations().is_empty());
+        assert!(manager.sweep().is_empty());
+    });
+}
+
+#[test]
+fn subscribers_receive_later_sweeps() {
+    let clock = MockClock::at_unix(START);
+    clock::with_clock(clock.clone(), || {
+        let mut manager = manager(SweepAction::Prune, &clock);
+        let events = manager.subscribe();
+        let dropped = manager.subscribe();
+        drop(dropped);
+
+        manager.sweep();
+        assert!(matches!(events.try_recv(), Ok(SweepEvent::Pruned { tool, .. }) if tool == "old"));
+
+        clock.advance(DAY * 2);
+        manager.sweep();
+        assert!(matches!(events.try_recv(), Ok(SweepEvent::Pruned { tool, .. }) if tool == "new"));
+        assert!(events.try_recv().is_err());
+    });
+}
@@ -0,0 +1,102 @@
+//! Batch attestation re-signs only attestations that still verify, and registers the result
+
+use worker_capabilities::*;
+
+fn signer() -> CapabilitySigner {
+    CapabilitySigner::new("key", "ci")
+}
+
+fn worker(id: &str) -> Capabilities {
+    Capabilities::new(id)
+        .with_attested_tool(CAPABILITY_STATIC_ANALYSIS, "clippy", true, &signer())
+        .attest(&signer())
+        .unwrap()
+}
+
+fn attest_all(
+    registry: &mut CapabilityRegistry,
+    signer: &dyn AttestationSigner,
+) -> BatchAttestationSummary {
+    AttestationManager::new().attest_all(
+        registry,
+        signer,
+        &MockVerifier,
+        &|_| true,
+        &BatchAttestationOptions::default(),
+        &|_| {},
+    )
+}
+
+#[test]
+fn forged_attestations_are_reported_instead_of_renewed() {
+    let mut forged = Capabilities::new("forged").with_attested_tool(
+        CAPABILITY_STATIC_ANALYSIS,
+        "clippy",
+        true,
+        &signer(),
+    );
+    forged.static_analysis_tools[0]
+        .attestation
+        .as_mut()
+        .unwrap()
+        .signature = "forged".to_string();
+    let mut registry = CapabilityRegistry::new();
+    registry.register(forged);
+
+    let summary = attest_all(&mut registry, &signer());
+    assert_eq!(summary.tools_attested, 0);
+    assert_eq!(summary.failures.len(), 1);
+    assert_eq!(summary.failures[0].tool_name, "clippy");
+    let tool = &registry.get("forged").unwrap().static_analysis_tools[0];
+    assert_eq!(tool.attestation.as_ref().unwrap().signature, "forged");
+}
+
+#[test]
+fn set_attestations_are_verified_and_re_signed() {
+    let mut registry = CapabilityRegistry::new();
+    registry.register(worker("worker-1"));
+    let mut tampered = worker("worker-2");
+    tampered.flags.insert("gpu".to_string(), true);
+    registry.register(tampered);
+
+    let summary = attest_all(&mut registry, &CapabilitySigner::new("new-key", "ci"));
+    assert_eq!(summary.tools_attested, 1);
+    assert_eq!(summary.failures.len(), 1);
+    assert_eq!(summary.failures[0].tool_name, "worker-2");
+
+    let renewed = registry.get("worker-1").unwrap();
+    assert!(renewed.verify_set_attestation_with(&MockVerifier));
+    assert!(renewed.verify_all_capabilities_with(&MockVerifier));
+}
+
+#[test]
+fn renewed_sets_go_through_registration() {
+    let mut registry = CapabilityRegistry::new().with_algorithms(
+        AlgorithmRegistry::empty().with_algorithm(DEFAULT_ATTESTATION_ALGORITHM, MockVerifier),
+    );
+    let counting = CountingSigner::new(signer(), 4);
+    registry.register(Capabilities::new("worker-1").with_attested_tool(
+        CAPABILITY_STATIC_ANALYSIS,
+        "clippy",
+        true,
+        &counting,
+    ));
+    assert_eq!(
+        registry.last_attestation_counter("worker-1", "clippy"),
+        Some(5)
+    );
+
+    let summary = attest_all(&mut registry, &counting);
+    assert!(summary.failures.is_empty());
+    assert_eq!(
+        registry.last_attestation_counter("worker-1", "clippy"),
+        Some(6)
+    );
+
+    // Counters once seen are required, so an uncounted renewal is refused
+    let summary = attest_all(&mut registry, &signer());
+    assert_eq!(summary.tools_attested, 0);
+    assert_eq!(summary.failures[0].tool_name, "worker-1");
+    let tool = &registry.get("worker-1").unwrap().static_analysis_tools[0];
+    assert_eq!(tool.attestation.as_ref().unwrap().counter, Some(6));
+}
@@ -0,0 +1,44 @@
+//! Pinned tools are only satisfied by executables with the expected SHA256
+
+use std::fs;
+
+use worker_capabilities::*;
+
+const SEMGREP_SHA256: &str = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+
+fn reported(sha256: &'static str) -> StatusChecker<impl Fn(&str) -> ToolStatus> {
+    StatusChecker::new(move |tool: &str| match tool {
+        "semgrep" => ToolStatus::found().with_sha256(sha256),
+        "semgrep-unpinned" => ToolStatus::found(),
+        _ => ToolStatus::missing(),
+    })
+}
+
+#[test]
+fn checker_reported_hashes_must_match_the_pin() {
+    let pinned =
+        ToolCapability::new("semgrep", true).with_binary_sha256(SEMGREP_SHA256.to_uppercase());
+    assert!(pinned.is_satisfied(&reported(SEMGREP_SHA256)));
+    assert!(!pinned.is_satisfied(&reported(
+        "0000000000000000000000000000000000000000000000000000000000000000"
+    )));
+
+    let unhashed = ToolCapability::new("semgrep-unpinned", true).with_binary_sha256(SEMGREP_SHA256);
+    assert!(!unhashed.is_satisfied(&reported(SEMGREP_SHA256)));
+    assert!(ToolCapability::new("semgrep-unpinned", true).is_satisfied(&reported(SEMGREP_SHA256)));
+}
+
+#[test]
+fn local_executables_are_hashed() {
+    let path = std::env::temp_dir().join(format!("{}-pinned-tool", std::process::id()));
+    fs::write(&path, "test").unwrap();
+
+    assert_eq!(sha256_file(&path).unwrap(), SEMGREP_SHA256);
+    let pinned = ToolCapability::new("tool", true).with_binary_sha256(SEMGREP_SHA256);
+    assert!(pinned.binary_matches(Some(&path), "tool"));
+
+    fs::write(&path, "tampered").unwrap();
+    assert!(!pinned.binary_matches(Some(&path), "tool"));
+    fs::remove_file(&path).unwrap();
+    assert!(!pinned.binary_matches(Some(&path), "tool"));
+}
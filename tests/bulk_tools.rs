@@ -0,0 +1,49 @@
+//! Generated tool lists are added to a category in one call
+
+use worker_capabilities::*;
+
+fn names(tools: &[ToolCapability]) -> Vec<&str> {
+    tools.iter().map(|tool| tool.tool_name.as_str()).collect()
+}
+
+#[test]
+fn named_tools_are_added_in_order() {
+    let discovered = vec![("clippy".to_string(), true), ("rustfmt".to_string(), false)];
+    let caps = Capabilities::new("worker").with_tools(CAPABILITY_STATIC_ANALYSIS, discovered);
+    assert_eq!(
+        names(&caps.static_analysis_tools),
+        vec!["clippy", "rustfmt"]
+    );
+    assert!(caps.static_analysis_tools[0].required);
+    assert!(!caps.static_analysis_tools[1].required);
+
+    let built = CapabilitiesBuilder::new("worker")
+        .with_tools(CAPABILITY_FUZZING, [("cargo-fuzz", true)])
+        .build()
+        .unwrap();
+    assert_eq!(
+        names(built.tools_in_category(CAPABILITY_FUZZING)),
+        vec!["cargo-fuzz"]
+    );
+}
+
+#[test]
+fn configured_tools_are_copied_into_any_category() {
+    let configured = [
+        ToolCapability::new("semgrep", true).with_version("1.50.0"),
+        ToolCapability::new("trivy", false),
+    ];
+    let mut caps = Capabilities::new("worker").with_security_tool("bandit", false);
+    caps.add_tools_from(CAPABILITY_SECURITY_SCANNING, &configured);
+    caps.add_tools_from("licensing", &configured[1..]);
+
+    assert_eq!(
+        names(&caps.security_scanning_tools),
+        vec!["bandit", "semgrep", "trivy"]
+    );
+    assert_eq!(
+        caps.find_tool("semgrep").unwrap().version.as_deref(),
+        Some("1.50.0")
+    );
+    assert_eq!(names(caps.tools_in_category("licensing")), vec!["trivy"]);
+}
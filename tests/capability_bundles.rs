@@ -0,0 +1,98 @@
+//! Signed capability bundles carry a worker's capabilities from image build to registration
+
+use worker_capabilities::*;
+
+fn provisioner() -> CapabilitySigner {
+    CapabilitySigner::new("provisioner-key", "provisioner")
+}
+
+fn trust_store() -> TrustStore {
+    TrustStore {
+        anchors: vec![TrustAnchor {
+            name: "provisioner".to_string(),
+            public_key: provisioner().public_key(),
+        }],
+        ..TrustStore::default()
+    }
+}
+
+fn bundle() -> CapabilityBundle {
+    let ci = CapabilitySigner::new("ci-key", "ci");
+    let caps = Capabilities::new("air-gapped-1")
+        .with_static_analysis("clippy", true)
+        .with_security_tool("semgrep", false);
+    let mut attestations = AttestationManager::new();
+    attestations.add_attestation(
+        "clippy".to_string(),
+        ci.attest(caps.find_tool("clippy").unwrap()),
+    );
+    attestations.add_attestation(
+        "bandit".to_string(),
+        ci.attest(&ToolCapability::new("bandit", true)),
+    );
+    CapabilityBundle::create(caps, &attestations, &provisioner())
+}
+
+#[test]
+fn trusted_bundles_extract_attested_capabilities() {
+    let bundle = bundle();
+    assert_eq!(bundle.attestations.len(), 1);
+
+    let caps = bundle
+        .verify_and_extract(&trust_store(), &MockVerifier)
+        .unwrap();
+    let clippy = caps.find_tool("clippy").unwrap();
+    assert!(clippy.verified);
+    assert_eq!(clippy.attestation.as_ref().unwrap().attester, "ci");
+    assert!(caps.find_tool("semgrep").unwrap().attestation.is_none());
+}
+
+#[test]
+fn tampered_bundles_are_rejected() {
+    let mut bundle = bundle();
+    bundle.capabilities = bundle.capabilities.with_dynamic_tool("frida", true);
+    assert_eq!(
+        bundle.verify_signature(&MockVerifier),
+        Err(BundleError::DigestMismatch)
+    );
+
+    bundle.digest = bundle.compute_digest();
+    assert_eq!(
+        bundle.verify_and_extract(&trust_store(), &MockVerifier),
+        Err(BundleError::InvalidSignature)
+    );
+}
+
+#[test]
+fn untrusted_signers_and_revoked_workers_are_rejected() {
+    let bundle = bundle();
+    assert_eq!(
+        bundle.verify_and_extract(&TrustStore::default(), &MockVerifier),
+        Err(BundleError::UntrustedSigner("provisioner".to_string()))
+    );
+
+    let mut revoked = trust_store();
+    revoked
+        .revocations
+        .push(RevocationEntry::worker("air-gapped-1", "decommissioned"));
+    assert_eq!(
+        bundle.verify_and_extract(&revoked, &MockVerifier),
+        Err(BundleError::Revoked("air-gapped-1".to_string()))
+    );
+}
+
+#[test]
+fn bundles_round_trip_through_files() {
+    let path = std::env::temp_dir().join(format!("{}-bundle.json", std::process::id()));
+    bundle().write_to(&path).unwrap();
+    let read = CapabilityBundle::read_from(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(read
+        .verify_and_extract(&trust_store(), &MockVerifier)
+        .is_ok());
+    assert!(matches!(
+        CapabilityBundle::read_from(&path),
+        Err(BundleError::Io(_))
+    ));
+}
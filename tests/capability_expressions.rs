@@ -0,0 +1,72 @@
+//! Boolean capability expressions combine flags, tools and categories
+
+use worker_capabilities::*;
+
+fn worker() -> Capabilities {
+    Capabilities::new("worker-1")
+        .with_static_analysis("clippy", true)
+        .with_alternative("rustfmt", vec!["cargo-fmt"])
+        .with_fuzzing_tool("cargo-fuzz", true)
+        .with_flag("ast_support")
+}
+
+fn installed(tool: &str) -> bool {
+    matches!(tool, "clippy" | "cargo-fmt" | "cargo-fuzz")
+}
+
+#[test]
+fn nested_expressions_evaluate_against_the_set() {
+    let expr = CapabilityExpr::all([
+        CapabilityExpr::category(CAPABILITY_STATIC_ANALYSIS),
+        CapabilityExpr::any([
+            CapabilityExpr::category(CAPABILITY_FUZZING),
+            CapabilityExpr::category(CAPABILITY_DYNAMIC_ANALYSIS),
+        ]),
+        CapabilityExpr::not(CapabilityExpr::flag("llm_support")),
+    ]);
+    assert!(worker().matches_expr(&expr, &installed));
+    assert!(!worker().matches_expr(&expr, &|tool: &str| tool == "clippy"));
+    assert!(!worker()
+        .with_flag("llm_support")
+        .matches_expr(&expr, &installed));
+}
+
+#[test]
+fn tools_match_declared_names_and_alternatives_that_are_available() {
+    let caps = worker();
+    assert!(caps.matches_expr(&CapabilityExpr::tool("cargo-fmt"), &installed));
+    assert!(!caps.matches_expr(&CapabilityExpr::tool("rustfmt"), &installed));
+    assert!(!caps.matches_expr(&CapabilityExpr::tool("semgrep"), &|_: &str| true));
+}
+
+#[test]
+fn empty_combinators_are_identities() {
+    let caps = Capabilities::new("empty");
+    assert!(caps.matches_expr(&CapabilityExpr::all([]), &installed));
+    assert!(!caps.matches_expr(&CapabilityExpr::any([]), &installed));
+}
+
+#[test]
+fn registry_filters_on_expressions_and_expressions_print() {
+    let mut registry = CapabilityRegistry::new();
+    registry.register(worker());
+    registry.register(Capabilities::new("worker-2").with_static_analysis("clippy", true));
+    let expr = CapabilityExpr::all([
+        CapabilityExpr::tool("clippy"),
+        CapabilityExpr::any([
+            CapabilityExpr::flag("ast_support"),
+            CapabilityExpr::flag("evm_support"),
+        ]),
+    ]);
+
+    let matched: Vec<_> = registry
+        .find_matching_expr(&expr, &installed)
+        .into_iter()
+        .map(|caps| caps.id.as_str())
+        .collect();
+    assert_eq!(matched, vec!["worker-1"]);
+    assert_eq!(
+        expr.to_string(),
+        "tool:clippy AND (flag:ast_support OR flag:evm_support)"
+    );
+}
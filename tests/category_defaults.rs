@@ -0,0 +1,62 @@
+//! Per-category permission baselines are applied by the builder
+
+use worker_capabilities::*;
+
+fn build(defaults: CategoryDefaults) -> Capabilities {
+    Capabilities::builder("worker-1")
+        .with_category_defaults(defaults)
+        .with_fuzzing_tool("afl", true)
+        .with_static_analysis("semgrep", true)
+        .with_security_tool("trivy", true)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn recommended_baselines_confine_fuzzers_and_isolate_analyzers() {
+    let caps = build(CategoryDefaults::recommended());
+
+    let afl = caps.find_tool("afl").unwrap();
+    assert!(afl.permissions.process_spawn);
+    assert!(afl.check_path_access("/corpus/queue/id-0001", AccessMode::Write));
+    assert!(!afl.check_path_access("/etc/passwd", AccessMode::Read));
+
+    let semgrep = caps.find_tool("semgrep").unwrap();
+    assert!(!semgrep.permissions.network_access);
+    assert!(!semgrep.check_egress("semgrep.dev", 443));
+
+    let trivy = caps.find_tool("trivy").unwrap();
+    assert_eq!(trivy.permissions, CapabilityPermissions::default());
+}
+
+#[test]
+fn custom_baselines_apply_to_custom_categories() {
+    let restricted = CapabilityPermissions {
+        memory_limit_mb: 256,
+        ..CapabilityPermissions::default()
+    };
+    let caps = Capabilities::builder("worker-1")
+        .with_category_defaults(
+            CategoryDefaults::new().with_category("linters", restricted.clone()),
+        )
+        .with_tool_in("linters", "ruff", true)
+        .build()
+        .unwrap();
+    assert_eq!(caps.find_tool("ruff").unwrap().permissions, restricted);
+}
+
+#[test]
+fn baselines_only_apply_to_tools_added_afterwards() {
+    let caps = Capabilities::builder("worker-1")
+        .with_fuzzing_tool("honggfuzz", true)
+        .with_category_defaults(CategoryDefaults::recommended())
+        .build()
+        .unwrap();
+    assert!(
+        !caps
+            .find_tool("honggfuzz")
+            .unwrap()
+            .permissions
+            .process_spawn
+    );
+}
@@ -0,0 +1,67 @@
+//! Injected faults are reproducible and degrade checks instead of breaking them
+
+#![cfg(feature = "chaos")]
+
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+use worker_capabilities::*;
+
+#[test]
+fn same_seed_injects_the_same_faults() {
+    let config = ChaosConfig::new().with_errors(0.5).with_seed(42);
+    let outcomes = |injector: &ChaosInjector| -> Vec<bool> {
+        (0..64).map(|_| injector.call(|| ()).is_ok()).collect()
+    };
+
+    let first = ChaosInjector::new(config.clone());
+    let second = ChaosInjector::new(config);
+    let runs = outcomes(&first);
+    assert_eq!(runs, outcomes(&second));
+    assert!(runs.contains(&true) && runs.contains(&false));
+    assert_eq!(
+        first.stats().failed,
+        runs.iter().filter(|ok| !**ok).count() as u64
+    );
+}
+
+#[test]
+fn failed_checks_report_tools_as_unavailable() {
+    let checker = ChaosChecker::new(|_: &str| true, ChaosConfig::new().with_errors(1.0));
+    assert!(!checker.is_available("clippy"));
+    assert_eq!(checker.stats().failed, 1);
+
+    let healthy = ChaosChecker::new(|_: &str| true, ChaosConfig::new());
+    assert!(healthy.is_available("clippy"));
+    assert_eq!(healthy.stats(), ChaosStats::default());
+}
+
+#[test]
+fn stale_data_replays_the_previous_answer() {
+    let installed = Cell::new(true);
+    let injector = ChaosInjector::new(ChaosConfig::new().with_stale_data(1.0));
+
+    assert_eq!(injector.call_cached("clippy", || installed.get()), Ok(true));
+    installed.set(false);
+    assert_eq!(injector.call_cached("clippy", || installed.get()), Ok(true));
+    assert_eq!(
+        injector.call_cached("semgrep", || installed.get()),
+        Ok(false)
+    );
+    assert_eq!(injector.stats().stale, 1);
+}
+
+#[test]
+fn latency_delays_registry_lookups() {
+    let mut registry = CapabilityRegistry::new();
+    registry.register(Capabilities::new("worker-1"));
+    let flapping = ChaosRegistry::new(
+        &registry,
+        ChaosConfig::new().with_latency(Duration::from_millis(20), 1.0),
+    );
+
+    let started = Instant::now();
+    assert_eq!(flapping.list_ids(), Ok(vec!["worker-1".to_string()]));
+    assert!(started.elapsed() >= Duration::from_millis(20));
+    assert_eq!(flapping.stats().delayed, 1);
+}
@@ -0,0 +1,72 @@
+//! Checker combinators compose local, PATH and remote lookups
+
+use std::time::{Duration, Instant};
+
+use worker_capabilities::*;
+
+fn cache(tool: &str) -> ToolStatus {
+    match tool {
+        "clippy" => ToolStatus::found().with_version("0.1.75"),
+        _ => ToolStatus::missing(),
+    }
+}
+
+fn inventory(tool: &str) -> ToolStatus {
+    match tool {
+        "clippy" | "semgrep" => ToolStatus::found().with_path(format!("/opt/{}", tool)),
+        _ => ToolStatus::missing(),
+    }
+}
+
+fn slow(tool: &str) -> bool {
+    std::thread::sleep(Duration::from_millis(500));
+    tool == "semgrep"
+}
+
+#[test]
+fn or_takes_the_first_checker_that_finds_the_tool() {
+    let checker = StatusChecker::new(cache).or(StatusChecker::new(inventory));
+    assert_eq!(
+        checker.detected_version("clippy").as_deref(),
+        Some("0.1.75")
+    );
+    assert!(checker.status("clippy").path.is_none());
+    assert!(checker.is_available("semgrep"));
+    assert!(!checker.is_available("bandit"));
+}
+
+#[test]
+fn and_requires_both_and_merges_their_details() {
+    let checker = StatusChecker::new(cache).and(StatusChecker::new(inventory));
+    let status = checker.status("clippy");
+    assert_eq!(status.version.as_deref(), Some("0.1.75"));
+    assert_eq!(status.path.unwrap().to_str(), Some("/opt/clippy"));
+    assert!(!checker.is_available("semgrep"));
+}
+
+#[test]
+fn timeouts_bound_slow_checkers_and_fall_back() {
+    let bounded = slow.with_timeout(Duration::from_millis(50));
+    let started = Instant::now();
+    assert!(!bounded.is_available("semgrep"));
+    assert!(bounded.try_status("semgrep").is_none());
+    assert!(started.elapsed() < Duration::from_millis(400));
+
+    // The fallback answers unanswered checks, but not definite misses
+    let with_fallback = slow
+        .with_timeout(Duration::from_millis(50))
+        .with_fallback(StatusChecker::new(inventory));
+    assert!(with_fallback.is_available("semgrep"));
+    let definite = StatusChecker::new(cache).with_fallback(StatusChecker::new(inventory));
+    assert!(!definite.is_available("semgrep"));
+}
+
+#[test]
+fn first_success_skips_checkers_that_time_out() {
+    let checker = slow
+        .first_success_with_timeout(StatusChecker::new(cache), Duration::from_millis(50))
+        .with_checker(StatusChecker::new(inventory));
+    assert!(checker.is_available("clippy"));
+    assert!(checker.is_available("semgrep"));
+    assert!(!checker.is_available("bandit"));
+}
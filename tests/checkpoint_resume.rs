@@ -0,0 +1,80 @@
+//! Interrupted sessions resume from their checkpoint on workers that can use it
+
+use worker_capabilities::*;
+
+fn worker(id: &str, checkpointing: bool) -> Capabilities {
+    let fuzzer = ToolCapability::new("cargo-fuzz", true);
+    let fuzzer = if checkpointing {
+        fuzzer.with_checkpointing()
+    } else {
+        fuzzer
+    };
+    let mut caps = Capabilities::new(id);
+    caps.fuzzing_tools.push(fuzzer);
+    caps
+}
+
+fn any_tool(_: &str) -> bool {
+    true
+}
+
+#[test]
+fn checkpoints_require_a_checkpointing_tool() {
+    let mut registry = CapabilityRegistry::new();
+    registry.register(worker("plain", false));
+    let id = registry
+        .open_session("plain", CAPABILITY_FUZZING, &any_tool)
+        .unwrap();
+
+    assert_eq!(
+        registry.record_checkpoint(&id, "s3://corpus/1"),
+        Err(SessionError::CheckpointingUnsupported(
+            "cargo-fuzz".to_string()
+        ))
+    );
+}
+
+#[test]
+fn rescheduling_prefers_resumable_workers_and_hands_over_the_checkpoint() {
+    let mut registry = CapabilityRegistry::new();
+    registry.register(worker("origin", true));
+    registry.register(worker("plain", false));
+    registry.register(worker("resumable", true));
+    let id = registry
+        .open_session("origin", CAPABILITY_FUZZING, &any_tool)
+        .unwrap();
+    registry.record_checkpoint(&id, "s3://corpus/1").unwrap();
+
+    let resumed = registry.reschedule_session(&id, &any_tool).unwrap();
+    let session = registry.get_session(&resumed).unwrap();
+    assert_ne!(session.worker_id, "plain");
+    assert!(session.checkpointing);
+    assert_eq!(
+        session.checkpoint_location.as_deref(),
+        Some("s3://corpus/1")
+    );
+    assert_eq!(session.resumed_from.as_deref(), Some(id.as_str()));
+
+    let previous = registry.get_session(&id).unwrap();
+    assert_eq!(previous.state, SessionState::Cancelled);
+    assert_eq!(previous.rescheduled_to.as_deref(), Some(resumed.as_str()));
+    assert_eq!(
+        registry.reschedule_session(&id, &any_tool),
+        Err(SessionError::AlreadyRescheduled(resumed))
+    );
+}
+
+#[test]
+fn completed_sessions_are_not_rescheduled() {
+    let mut registry = CapabilityRegistry::new();
+    registry.register(worker("origin", true));
+    let id = registry
+        .open_session("origin", CAPABILITY_FUZZING, &any_tool)
+        .unwrap();
+    registry.complete_session(&id).unwrap();
+
+    assert_eq!(
+        registry.reschedule_session(&id, &any_tool),
+        Err(SessionError::NotReschedulable(SessionState::Completed))
+    );
+}
@@ -0,0 +1,77 @@
+//! Jenkins labels and Buildkite tags map to capability sets and back
+
+use worker_capabilities::*;
+
+fn tags(tags: &[&str]) -> Vec<String> {
+    tags.iter().map(|tag| tag.to_string()).collect()
+}
+
+#[test]
+fn jenkins_labels_become_tools_metadata_and_flags() {
+    let caps = Capabilities::from_ci_agent(
+        CiSystem::Jenkins,
+        "jenkins-1",
+        &tags(&[
+            "static_analysis:clippy",
+            "os=linux",
+            "docker",
+            " ",
+            "nope:tool",
+        ]),
+    );
+
+    assert_eq!(caps.static_analysis_tools[0].tool_name, "clippy");
+    assert!(!caps.static_analysis_tools[0].required);
+    assert_eq!(caps.get_metadata("os"), Some(&"linux".to_string()));
+    assert!(caps.has_flag("docker"));
+    assert!(caps.has_flag("nope:tool"));
+    assert_eq!(caps.flags.len(), 2);
+}
+
+#[test]
+fn buildkite_tags_become_tools_flags_and_metadata() {
+    let caps = Capabilities::from_ci_agent(
+        CiSystem::Buildkite,
+        "buildkite-1",
+        &tags(&[
+            "tool.fuzzing.cargo-fuzz=true",
+            "flag.ast_support=true",
+            "flag.llm_support=false",
+            "queue=security",
+        ]),
+    );
+
+    assert_eq!(caps.fuzzing_tools[0].tool_name, "cargo-fuzz");
+    assert!(caps.has_flag("ast_support"));
+    assert!(!caps.has_flag("llm_support"));
+    assert_eq!(caps.get_metadata("queue"), Some(&"security".to_string()));
+}
+
+#[test]
+fn exported_tags_round_trip_through_the_registry() {
+    let caps = Capabilities::new("agent-1")
+        .with_security_tool("trivy", true)
+        .with_flag("ast_support")
+        .with_metadata("queue", "security");
+
+    for system in [CiSystem::Jenkins, CiSystem::Buildkite] {
+        let mut registry = CapabilityRegistry::new();
+        registry.register(caps.clone());
+        let exported = registry.export_ci_agent_tags(system);
+
+        let mut imported = CapabilityRegistry::new();
+        imported.import_ci_agents(system, &exported);
+        let agent = imported.get("agent-1").unwrap();
+        assert_eq!(agent.security_scanning_tools[0].tool_name, "trivy");
+        assert!(agent.has_flag("ast_support"));
+        assert_eq!(agent.get_metadata("queue"), Some(&"security".to_string()));
+    }
+
+    assert_eq!(
+        caps.to_ci_agent_tags(CiSystem::Jenkins),
+        tags(&["security_scanning:trivy", "ast_support", "queue=security"])
+    );
+    assert!(CapabilityRegistry::new()
+        .export_ci_agent_tags(CiSystem::Buildkite)
+        .is_empty());
+}
@@ -0,0 +1,178 @@
+//! Regression tests for clocks going backwards (e.g., NTP corrections)
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use worker_capabilities::clock::{self, MockClock};
+use worker_capabilities::*;
+
+const HOUR: Duration = Duration::from_secs(60 * 60);
+
+fn signer() -> CapabilitySigner {
+    CapabilitySigner::new("key", "ci")
+}
+
+#[test]
+fn clock_before_epoch_reports_skew_instead_of_panicking() {
+    let clock = MockClock::new(UNIX_EPOCH - HOUR);
+    clock::with_clock(clock, || {
+        assert_eq!(
+            clock::unix_timestamp(),
+            Err(CapabilityError::ClockSkew { skew_seconds: 3600 })
+        );
+
+        let tool = ToolCapability::new("clippy", true);
+        assert!(matches!(
+            tool.try_is_expired(),
+            Err(CapabilityError::ClockSkew { .. })
+        ));
+        assert!(tool.is_expired());
+        assert!(!tool.is_satisfied(&|_: &str| true));
+    });
+}
+
+#[test]
+fn attestation_survives_small_backward_correction() {
+    let clock = MockClock::at_unix(1_700_000_000);
+    clock::with_clock(clock.clone(), || {
+        let tool = ToolCapability::new("clippy", true);
+        let tool = tool.clone().with_attestation(signer().attest(&tool));
+
+        clock.rewind(Duration::from_secs(MAX_CLOCK_SKEW_SECONDS));
+        assert_eq!(tool.attestation.as_ref().unwrap().age_seconds(), Ok(0));
+        assert!(tool.verify_attestation_with(&MockVerifier));
+    });
+}
+
+#[test]
+fn expiration_tolerances_absorb_configured_skew_and_grace() {
+    let clock = MockClock::at_unix(1_700_000_000);
+    clock::with_clock(clock.clone(), || {
+        let tolerance = ExpirationTolerance::new(HOUR.as_secs(), HOUR.as_secs());
+        let verifier = AlgorithmRegistry::empty()
+            .with_algorithm(DEFAULT_ATTESTATION_ALGORITHM, MockVerifier)
+            .with_expiration_tolerance(tolerance);
+        let tool = ToolCapability::new("clippy", true);
+        let tool = tool.clone().with_attestation(signer().attest(&tool));
+
+        clock.rewind(HOUR);
+        assert!(tool.verify_attestation_with(&verifier));
+        assert!(!tool.verify_attestation_with(&MockVerifier));
+
+        clock.advance(HOUR + Duration::from_secs(DEFAULT_EXPIRATION_HOURS * 60 * 60) + 2 * HOUR);
+        assert!(tool.is_expired());
+        assert_eq!(tool.try_is_expired_within(tolerance), Ok(false));
+
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(tool.try_is_expired_within(tolerance), Ok(true));
+    });
+}
+
+#[test]
+fn expiration_tolerances_are_capped() {
+    let tolerance = ExpirationTolerance::new(u64::MAX, u64::MAX);
+    assert_eq!(tolerance.skew_tolerance(), MAX_EXPIRATION_TOLERANCE_SECONDS);
+    assert_eq!(tolerance.grace_period(), MAX_EXPIRATION_TOLERANCE_SECONDS);
+}
+
+#[test]
+fn manifest_cannot_extend_its_own_expiry() {
+    let clock = MockClock::at_unix(1_700_000_000);
+    clock::with_clock(clock, || {
+        let mut tool = ToolCapability::new("clippy", true);
+        tool.expiration.expires_at = 1;
+        let tool = tool.clone().with_attestation(signer().attest(&tool));
+
+        // Tolerances in the worker's manifest are ignored
+        let mut manifest = serde_json::to_value(&tool).unwrap();
+        manifest["expiration"]["grace_period"] = u64::MAX.into();
+        manifest["expiration"]["skew_tolerance"] = u64::MAX.into();
+        let tool: ToolCapability = serde_json::from_value(manifest).unwrap();
+        assert!(tool.is_expired());
+
+        let mut caps = Capabilities::new("worker-1");
+        caps.add_tools_from("static_analysis", std::slice::from_ref(&tool));
+        assert_eq!(
+            caps.try_verify_all_capabilities_with(&MockVerifier),
+            Err(VerificationError::Expired {
+                tool: "clippy".to_string()
+            })
+        );
+
+        // Tampering with the expiry is caught before any tolerance applies
+        let mut tampered = tool;
+        tampered.expiration.expires_at = u64::MAX;
+        assert_eq!(
+            tampered.try_verify_attestation_with(&MockVerifier),
+            Err(VerificationError::HashMismatch {
+                tool: "clippy".to_string()
+            })
+        );
+    });
+}
+
+#[test]
+fn attestation_from_the_future_is_rejected() {
+    let clock = MockClock::at_unix(1_700_000_000);
+    clock::with_clock(clock.clone(), || {
+        let tool = ToolCapability::new("clippy", true);
+        let tool = tool.clone().with_attestation(signer().attest(&tool));
+        let mut manager = AttestationManager::new();
+        manager.add_attestation("clippy".to_string(), signer().attest(&tool));
+
+        clock.rewind(HOUR);
+        assert_eq!(
+            tool.attestation.as_ref().unwrap().age_seconds(),
+            Err(CapabilityError::ClockSkew { skew_seconds: 3600 })
+        );
+        assert!(!tool.verify_attestation_with(&MockVerifier));
+        assert_eq!(
+            tool.try_verify_attestation_with(&MockVerifier),
+            Err(VerificationError::ClockSkew { skew_seconds: 3600 })
+        );
+        assert!(!manager.verify_all_attestations_with(&MockVerifier));
+        assert_eq!(
+            manager.try_verify_all_attestations_with(&MockVerifier),
+            Err(VerificationError::ClockSkew { skew_seconds: 3600 })
+        );
+        assert_eq!(
+            manager.get_expired_attestations(),
+            vec!["clippy".to_string()]
+        );
+    });
+}
+
+#[test]
+fn descriptor_signed_before_regression_is_rejected() {
+    let clock = MockClock::at_unix(1_700_000_000);
+    clock::with_clock(clock.clone(), || {
+        let descriptor = Capabilities::new("worker-1")
+            .with_static_analysis("clippy", true)
+            .describe()
+            .unwrap()
+            .signed(&signer());
+        assert!(descriptor.verify(&MockVerifier).is_ok());
+
+        clock.rewind(HOUR);
+        assert_eq!(
+            descriptor.verify(&MockVerifier),
+            Err(DescriptorError::InvalidAttestation)
+        );
+    });
+}
+
+#[test]
+fn registry_operations_tolerate_regression() {
+    let clock = MockClock::new(SystemTime::now());
+    clock::with_clock(clock.clone(), || {
+        let mut registry = CapabilityRegistry::new();
+        registry.register(Capabilities::new("worker-1").with_static_analysis("clippy", true));
+
+        clock.rewind(HOUR);
+        assert!(registry.expire_sessions().is_empty());
+        assert_eq!(registry.metrics_snapshot().workers, 1);
+
+        clock.set(UNIX_EPOCH - HOUR);
+        assert!(registry.expire_sessions().is_empty());
+        assert_eq!(registry.metrics_snapshot().expired_tools, 1);
+    });
+}
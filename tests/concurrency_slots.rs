@@ -0,0 +1,59 @@
+//! Sessions occupy tool slots, and matching skips tools whose slots are exhausted
+
+use worker_capabilities::*;
+
+fn any_tool(_: &str) -> bool {
+    true
+}
+
+fn registry() -> CapabilityRegistry {
+    let mut caps = Capabilities::new("worker-1").with_static_analysis("clippy", true);
+    caps.fuzzing_tools
+        .push(ToolCapability::new("cargo-fuzz", true).with_max_concurrent(1));
+    let mut registry = CapabilityRegistry::new();
+    registry.register(caps);
+    registry
+}
+
+#[test]
+fn exhausted_tools_are_not_oversubscribed() {
+    let mut registry = registry();
+    let first = registry
+        .open_session("worker-1", CAPABILITY_FUZZING, &any_tool)
+        .unwrap();
+    assert_eq!(registry.slots_in_use("worker-1", "cargo-fuzz"), 1);
+
+    assert_eq!(
+        registry.open_session("worker-1", CAPABILITY_FUZZING, &any_tool),
+        Err(SessionError::SlotsExhausted(CAPABILITY_FUZZING.to_string()))
+    );
+    assert!(registry
+        .find_with_capability(CAPABILITY_FUZZING, &any_tool)
+        .is_empty());
+
+    registry.complete_session(&first).unwrap();
+    assert_eq!(registry.slots_in_use("worker-1", "cargo-fuzz"), 0);
+    assert_eq!(
+        registry
+            .find_with_capability(CAPABILITY_FUZZING, &any_tool)
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn unlimited_tools_accept_any_number_of_sessions() {
+    let mut registry = registry();
+    for _ in 0..8 {
+        registry
+            .open_session("worker-1", CAPABILITY_STATIC_ANALYSIS, &any_tool)
+            .unwrap();
+    }
+    assert_eq!(registry.slots_in_use("worker-1", "clippy"), 8);
+    assert_eq!(
+        registry
+            .find_with_capability(CAPABILITY_STATIC_ANALYSIS, &any_tool)
+            .len(),
+        1
+    );
+}
@@ -0,0 +1,74 @@
+//! Container checkers find tools by running a probe script through the runtime
+
+#![cfg(unix)]
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+use worker_capabilities::*;
+
+/// A fake runtime that runs the probe script on the host with `bin` on PATH
+/// and records the arguments it was given
+fn fake_runtime(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("{}-{}", std::process::id(), name));
+    let bin = dir.join("bin");
+    fs::create_dir_all(&bin).unwrap();
+    write_executable(&bin.join("fakelint"), "#!/bin/sh\necho 'fakelint 2.3.1'\n");
+    write_executable(
+        &dir.join("runtime"),
+        &format!(
+            "#!/bin/sh\necho \"$@\" > {args}\nwhile [ \"$1\" != -c ]; do shift; done\nPATH={bin}:$PATH exec sh \"$@\"\n",
+            args = dir.join("args").display(),
+            bin = bin.display(),
+        ),
+    );
+    dir
+}
+
+fn write_executable(path: &Path, contents: &str) {
+    fs::write(path, contents).unwrap();
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+#[test]
+fn tools_in_images_report_path_and_version() {
+    let dir = fake_runtime("container-image");
+    let checker = ContainerToolChecker::image("scanner:1")
+        .with_runtime(dir.join("runtime").display().to_string());
+
+    let status = checker.status("fakelint");
+    let args = fs::read_to_string(dir.join("args")).unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert!(status.available);
+    assert_eq!(status.version.as_deref(), Some("2.3.1"));
+    assert!(status.path.unwrap().ends_with("bin/fakelint"));
+    assert!(args.starts_with("run --rm --name=worker-capabilities-probe-"));
+    assert!(args.contains("--network=none --entrypoint sh scanner:1 -c"));
+}
+
+#[test]
+fn running_containers_are_probed_with_exec() {
+    let dir = fake_runtime("container-exec");
+    let checker = ContainerToolChecker::container("analysis")
+        .with_runtime(dir.join("runtime").display().to_string());
+
+    assert!(checker.is_available("fakelint"));
+    let args = fs::read_to_string(dir.join("args")).unwrap();
+    assert!(!checker.is_available("missing-tool"));
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert!(args.starts_with("exec analysis sh -c"));
+}
+
+#[test]
+fn unusable_runtimes_report_tools_missing() {
+    let checker = ContainerToolChecker::image("scanner:1").with_runtime("/nonexistent/docker");
+    assert!(!checker.is_available("sh"));
+    assert!(!checker.is_available(""));
+    assert_ne!(
+        checker.cache_key(),
+        ContainerToolChecker::container("scanner:1").cache_key()
+    );
+}
@@ -0,0 +1,71 @@
+//! Attestation chains delegate trust from a vendor through to a deployment
+
+use worker_capabilities::*;
+
+fn vendor() -> Ed25519Signer {
+    Ed25519Signer::new([1; 32], "vendor")
+}
+
+fn platform() -> Ed25519Signer {
+    Ed25519Signer::new([2; 32], "platform-team")
+}
+
+fn policy() -> TrustPolicy {
+    TrustPolicy::new()
+        .with_attester(AttesterEntry::new("vendor", vendor().public_key()))
+        .with_attester(
+            AttesterEntry::new("platform-team", platform().public_key())
+                .with_category(CAPABILITY_SECURITY_SCANNING),
+        )
+}
+
+fn semgrep() -> ToolCapability {
+    ToolCapability::new("semgrep", true)
+        .countersign(&vendor())
+        .countersign(&platform())
+}
+
+#[test]
+fn every_link_covers_the_one_before() {
+    let tool = semgrep();
+    let chain = tool.chain();
+    assert_eq!(chain.len(), 2);
+    assert_eq!(chain[0].attester, "vendor");
+    assert_eq!(chain[1].capability_hash, chain[0].chain_hash());
+    assert_eq!(
+        tool.try_verify_chain_with(&policy(), &Ed25519Verifier),
+        Ok(())
+    );
+}
+
+#[test]
+fn reordered_or_unknown_links_fail() {
+    let mut tool = semgrep().countersign(&Ed25519Signer::new([3; 32], "deploy"));
+    assert_eq!(
+        tool.try_verify_chain_with(&policy(), &Ed25519Verifier),
+        Err(VerificationError::UntrustedAttester {
+            tool: "semgrep".to_string(),
+            attester: "deploy".to_string(),
+        })
+    );
+
+    tool.countersignatures.swap(0, 1);
+    assert_eq!(
+        tool.try_verify_chain_with(&policy(), &Ed25519Verifier),
+        Err(VerificationError::BrokenChain {
+            tool: "semgrep".to_string(),
+            link: 1,
+        })
+    );
+}
+
+#[test]
+fn worker_chains_respect_attester_categories() {
+    let mut scanning = Capabilities::new("worker");
+    scanning.add_tools_from(CAPABILITY_SECURITY_SCANNING, &[semgrep()]);
+    assert!(scanning.verify_chains_with(&policy(), &Ed25519Verifier));
+
+    let mut analysis = Capabilities::new("worker");
+    analysis.add_tools_from(CAPABILITY_STATIC_ANALYSIS, &[semgrep()]);
+    assert!(!analysis.verify_chains_with(&policy(), &Ed25519Verifier));
+}
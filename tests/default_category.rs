@@ -0,0 +1,35 @@
+//! Tools added without a category land in the configured default category
+
+use worker_capabilities::*;
+
+#[test]
+fn builder_adds_to_the_default_category() {
+    let caps = CapabilitiesBuilder::new("worker")
+        .with_default_category(CAPABILITY_GENERIC)
+        .with_tool("jq", true)
+        .with_tool("yq", false)
+        .build()
+        .unwrap();
+    let generic: Vec<&str> = caps
+        .tools_in_category(CAPABILITY_GENERIC)
+        .iter()
+        .map(|tool| tool.tool_name.as_str())
+        .collect();
+    assert_eq!(generic, vec!["jq", "yq"]);
+    assert!(caps.static_analysis_tools.is_empty());
+}
+
+#[test]
+fn unset_default_keeps_static_analysis() {
+    let built = CapabilitiesBuilder::new("worker")
+        .with_tool("jq", true)
+        .build()
+        .unwrap();
+    #[allow(deprecated)]
+    let direct = Capabilities::new("worker").with_tool("jq", true);
+
+    for caps in [built, direct] {
+        assert_eq!(caps.tools_in_category(CAPABILITY_STATIC_ANALYSIS).len(), 1);
+        assert!(caps.tools_in_category(CAPABILITY_GENERIC).is_empty());
+    }
+}
@@ -0,0 +1,58 @@
+//! Deprecated tools keep working but show up in reports and statistics
+
+use worker_capabilities::clock::{self, MockClock};
+use worker_capabilities::*;
+
+const SUNSET: u64 = 1_800_000_000;
+
+fn bandit() -> ToolCapability {
+    ToolCapability::new("bandit", true).with_deprecation(
+        DeprecationInfo::new("superseded by semgrep rules")
+            .with_replacement("semgrep")
+            .with_sunset(SUNSET),
+    )
+}
+
+fn worker() -> Capabilities {
+    let mut caps = Capabilities::new("worker").with_security_tool("semgrep", true);
+    caps.add_tools_from(CAPABILITY_SECURITY_SCANNING, &[bandit()]);
+    caps
+}
+
+#[test]
+fn sunset_follows_the_clock() {
+    let deprecation = bandit().deprecated.unwrap();
+    assert!(!clock::with_clock(MockClock::at_unix(SUNSET), || {
+        deprecation.is_sunset()
+    }));
+    assert!(clock::with_clock(MockClock::at_unix(SUNSET + 1), || {
+        deprecation.is_sunset()
+    }));
+    assert!(!DeprecationInfo::new("old").is_sunset());
+}
+
+#[test]
+fn deprecated_tools_still_satisfy_capabilities() {
+    assert!(bandit().is_deprecated());
+    assert!(!ToolCapability::new("semgrep", true).is_deprecated());
+    assert!(bandit().is_satisfied(&|name: &str| name == "bandit"));
+}
+
+#[test]
+fn reports_and_statistics_count_deprecations() {
+    let caps = worker();
+    let report = &caps.security_report_with(&MockVerifier)["bandit"];
+    let summary = report.summary();
+    assert!(summary.contains("Deprecated:  superseded by semgrep rules"));
+    assert!(summary.contains("Replace by:  semgrep"));
+    assert!(caps.security_report_with(&MockVerifier)["semgrep"]
+        .deprecated
+        .is_none());
+    assert_eq!(caps.statistics_with(&MockVerifier).deprecated_tools, 1);
+
+    let mut registry = CapabilityRegistry::new();
+    registry.register(caps);
+    let stats = registry.get_statistics();
+    assert_eq!(stats.total_deprecated_tools, 1);
+    assert!(stats.summary().contains("Deprecated tools"));
+}
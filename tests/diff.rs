@@ -0,0 +1,89 @@
+//! Diffs between heartbeats list what changed and whether to re-attest
+
+use worker_capabilities::*;
+
+fn heartbeat(clippy_version: &str) -> Capabilities {
+    let mut caps = Capabilities::new("worker-1")
+        .with_security_tool("semgrep", false)
+        .with_flag("gpu")
+        .with_metadata("region", "eu-west-1");
+    caps.add_tools_from(
+        CAPABILITY_STATIC_ANALYSIS,
+        &[ToolCapability::new("clippy", true).with_version(clippy_version)],
+    );
+    caps
+}
+
+#[test]
+fn identical_heartbeats_have_an_empty_diff() {
+    let diff = heartbeat("0.1.75").diff(&heartbeat("0.1.75"));
+    assert!(diff.is_empty());
+    assert!(!diff.requires_reattestation());
+}
+
+#[test]
+fn tool_changes_list_their_fields() {
+    let mut later = heartbeat("0.1.76");
+    later
+        .find_tool_mut("clippy")
+        .unwrap()
+        .permissions
+        .network_access = true;
+    let diff = heartbeat("0.1.75").diff(&later);
+
+    assert_eq!(diff.changed_tools.len(), 1);
+    let change = &diff.changed_tools[0];
+    assert_eq!(change.tool, "clippy");
+    assert_eq!(change.fields, vec!["permissions", "version"]);
+    assert!(change.hash_changed);
+    assert!(matches!(
+        &change.permissions,
+        Some(ValueChange::Changed { previous, current })
+            if !previous.network_access && current.network_access
+    ));
+    assert!(diff.requires_reattestation());
+}
+
+#[test]
+fn added_and_removed_tools_flags_and_metadata_are_listed() {
+    let mut later = heartbeat("0.1.75").with_fuzzing_tool("cargo-fuzz", false);
+    later.security_scanning_tools.clear();
+    later.flags.remove("gpu");
+    later
+        .metadata
+        .insert("region".to_string(), "us-east-1".to_string());
+    let diff = heartbeat("0.1.75").diff(&later);
+
+    assert_eq!(
+        diff.added_tools,
+        vec![(CAPABILITY_FUZZING.to_string(), "cargo-fuzz".to_string())]
+    );
+    assert_eq!(
+        diff.removed_tools,
+        vec![(
+            CAPABILITY_SECURITY_SCANNING.to_string(),
+            "semgrep".to_string()
+        )]
+    );
+    assert_eq!(
+        diff.flags.get("gpu"),
+        Some(&ValueChange::Removed { previous: true })
+    );
+    assert_eq!(
+        diff.metadata.get("region"),
+        Some(&ValueChange::Changed {
+            previous: "eu-west-1".to_string(),
+            current: "us-east-1".to_string(),
+        })
+    );
+    assert!(diff.requires_reattestation());
+}
+
+#[test]
+fn removing_tools_alone_needs_no_reattestation() {
+    let mut later = heartbeat("0.1.75");
+    later.security_scanning_tools.clear();
+    let diff = heartbeat("0.1.75").diff(&later);
+    assert!(!diff.is_empty());
+    assert!(!diff.requires_reattestation());
+}
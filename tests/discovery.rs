@@ -0,0 +1,61 @@
+//! Discovery turns the tools a checker finds into a populated capability set
+
+use worker_capabilities::well_known::AST_SUPPORT;
+use worker_capabilities::*;
+
+fn host(tool: &str) -> ToolStatus {
+    match tool {
+        "clippy-driver" => ToolStatus::found().with_version("0.1.75"),
+        "tree-sitter" | "trivy" | "cargo" => ToolStatus::found(),
+        "rustc" => ToolStatus::found().with_version("1.75.0"),
+        _ => ToolStatus::missing(),
+    }
+}
+
+#[test]
+fn found_tools_are_declared_optional_with_their_versions() {
+    let caps = scan_with("builder-7", &StatusChecker::new(host));
+
+    assert_eq!(caps.id, "builder-7");
+    let clippy = caps.find_tool("clippy").unwrap();
+    assert!(!clippy.required);
+    assert_eq!(clippy.version.as_deref(), Some("0.1.75"));
+    assert!(clippy.alternatives.contains(&"clippy-driver".to_string()));
+    assert_eq!(
+        caps.get_metadata("tool.clippy.version").map(String::as_str),
+        Some("0.1.75")
+    );
+    assert_eq!(caps.security_scanning_tools[0].tool_name, "trivy");
+    assert!(caps.find_tool("semgrep").is_none());
+}
+
+#[test]
+fn toolchains_and_platform_are_recorded() {
+    let caps = scan_with("builder-7", &StatusChecker::new(host));
+    assert_eq!(
+        caps.get_metadata("toolchain.rust.version")
+            .map(String::as_str),
+        Some("1.75.0")
+    );
+    assert_eq!(
+        caps.get_metadata("toolchain.cargo.version")
+            .map(String::as_str),
+        Some("")
+    );
+    assert!(caps.get_metadata("toolchain.go.version").is_none());
+    assert_eq!(
+        caps.get_metadata(DISCOVERY_META_OS).map(String::as_str),
+        Some(std::env::consts::OS)
+    );
+}
+
+#[test]
+fn flags_are_derived_from_the_tools_found() {
+    let caps = scan_with("builder-7", &StatusChecker::new(host));
+    assert!(caps.has_flag(AST_SUPPORT));
+    assert!(caps.is_computed_flag(AST_SUPPORT));
+
+    let bare = scan_with("bare", &|_: &str| false);
+    assert_eq!(bare.all_tools().len(), 0);
+    assert!(!bare.has_flag(AST_SUPPORT));
+}
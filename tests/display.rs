@@ -0,0 +1,77 @@
+//! Capabilities, reports, matches and statistics render as readable text
+
+use worker_capabilities::*;
+
+fn worker() -> Capabilities {
+    Capabilities::new("worker-1")
+        .with_static_analysis("clippy", true)
+        .with_alternative("rustfmt", vec!["cargo-fmt"])
+        .with_security_tool("trivy", false)
+        .with_flag("ast_support")
+        .with_metadata("os", "linux")
+}
+
+#[test]
+fn capabilities_render_a_line_and_a_table() {
+    let caps = worker();
+    assert_eq!(
+        caps.to_string(),
+        "worker-1: 3 tools (1 required, 0 verified), 1 flags"
+    );
+
+    let summary = caps.summary();
+    assert!(summary.starts_with("Capabilities: worker-1\n"));
+    assert!(summary.contains("CATEGORY"));
+    assert!(summary.contains("cargo-fmt"));
+    assert!(summary.contains("Flags: ast_support"));
+    assert!(summary.contains("  os = linux"));
+    assert!(!summary.contains("EXPIRES"));
+
+    let lines: Vec<&str> = summary.lines().collect();
+    let header = lines
+        .iter()
+        .position(|line| line.starts_with("CATEGORY"))
+        .unwrap();
+    let tool_column = lines[header].find("TOOL").unwrap();
+    assert_eq!(&lines[header + 2][tool_column..tool_column + 6], "clippy");
+}
+
+#[test]
+fn empty_sets_say_so() {
+    assert!(Capabilities::new("empty")
+        .summary()
+        .contains("No tools declared"));
+}
+
+#[test]
+fn match_results_name_the_tools_used() {
+    let requirements = Requirements::new()
+        .requires(CAPABILITY_STATIC_ANALYSIS)
+        .requires(CAPABILITY_FUZZING);
+    let result = worker().satisfies(&requirements, &|_: &str| true);
+    assert_eq!(
+        result.to_string(),
+        "worker-1: no match (static_analysis: clippy; fuzzing: unmatched)"
+    );
+}
+
+#[test]
+fn statistics_and_security_reports_render_tables() {
+    let mut registry = CapabilityRegistry::new();
+    registry.register(worker());
+    let statistics = registry.get_statistics();
+    assert_eq!(
+        statistics.to_string(),
+        "1 workers (0 verified), 3 tools (1 required, 0 verified), 0 active sessions"
+    );
+    assert!(statistics
+        .summary()
+        .lines()
+        .any(|line| line.starts_with("Workers ") && line.ends_with(" 1")));
+
+    let reports = worker().security_report_with(&MockVerifier);
+    let table = CapabilitySecurityReport::table(&reports);
+    assert!(table.starts_with("TOOL"));
+    assert_eq!(table.lines().count(), 2 + reports.len());
+    assert!(reports["clippy"].summary().contains("Attested:    no"));
+}
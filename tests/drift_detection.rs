@@ -0,0 +1,133 @@
+//! Drift detection reports each change between declared and observed tools once
+
+use std::collections::HashMap;
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+use worker_capabilities::*;
+
+type Environment = Arc<Mutex<HashMap<&'static str, ToolStatus>>>;
+
+fn environment(tools: &[(&'static str, ToolStatus)]) -> Environment {
+    Arc::new(Mutex::new(tools.iter().cloned().collect()))
+}
+
+fn checker(environment: &Environment) -> StatusChecker<impl Fn(&str) -> ToolStatus> {
+    let environment = environment.clone();
+    StatusChecker::new(move |tool: &str| {
+        environment
+            .lock()
+            .unwrap()
+            .get(tool)
+            .cloned()
+            .unwrap_or_else(ToolStatus::missing)
+    })
+}
+
+fn worker() -> Capabilities {
+    let mut caps = Capabilities::new("worker-1").with_security_tool("semgrep", false);
+    caps.add_tools_from(
+        CAPABILITY_STATIC_ANALYSIS,
+        &[ToolCapability::new("clippy", true).with_version("0.1.75")],
+    );
+    caps
+}
+
+#[test]
+fn disappearing_and_reappearing_tools_are_reported_once() {
+    let env = environment(&[
+        ("clippy", ToolStatus::found().with_version("0.1.75")),
+        ("semgrep", ToolStatus::found()),
+    ]);
+    let mut detector = DriftDetector::new(checker(&env));
+    assert!(detector.check(&worker()).is_empty());
+
+    env.lock().unwrap().remove("semgrep");
+    let disappeared = DriftEvent::ToolDisappeared {
+        worker_id: "worker-1".to_string(),
+        category: CAPABILITY_SECURITY_SCANNING.to_string(),
+        tool: "semgrep".to_string(),
+    };
+    assert_eq!(detector.check(&worker()), vec![disappeared]);
+    assert!(detector.check(&worker()).is_empty());
+
+    env.lock().unwrap().insert("semgrep", ToolStatus::found());
+    assert!(matches!(
+        detector.check(&worker())[..],
+        [DriftEvent::ToolReappeared { .. }]
+    ));
+}
+
+#[test]
+fn version_changes_compare_against_the_declared_version() {
+    let env = environment(&[
+        ("clippy", ToolStatus::found().with_version("0.1.76")),
+        ("semgrep", ToolStatus::found()),
+    ]);
+    let mut detector = DriftDetector::new(checker(&env));
+
+    assert_eq!(
+        detector.check(&worker()),
+        vec![DriftEvent::VersionChanged {
+            worker_id: "worker-1".to_string(),
+            tool: "clippy".to_string(),
+            previous: "0.1.75".to_string(),
+            current: "0.1.76".to_string(),
+        }]
+    );
+    assert!(detector.check(&worker()).is_empty());
+}
+
+#[test]
+fn undeclared_candidates_are_found() {
+    let env = environment(&[
+        ("clippy", ToolStatus::found().with_version("0.1.75")),
+        ("semgrep", ToolStatus::found()),
+        ("bandit", ToolStatus::found().with_version("1.7.5")),
+    ]);
+    let mut detector = DriftDetector::new(checker(&env))
+        .with_candidate(
+            CAPABILITY_SECURITY_SCANNING,
+            ToolCapability::new("bandit", false),
+        )
+        .with_candidate(
+            CAPABILITY_STATIC_ANALYSIS,
+            ToolCapability::new("clippy", false),
+        );
+
+    assert_eq!(
+        detector.check(&worker()),
+        vec![DriftEvent::NewToolFound {
+            worker_id: "worker-1".to_string(),
+            category: CAPABILITY_SECURITY_SCANNING.to_string(),
+            tool: "bandit".to_string(),
+            version: Some("1.7.5".to_string()),
+        }]
+    );
+    assert!(detector.check(&worker()).is_empty());
+}
+
+#[test]
+fn watchers_check_registered_workers() {
+    let env = environment(&[("clippy", ToolStatus::found().with_version("0.1.75"))]);
+    let mut registry = CapabilityRegistry::new();
+    registry.register(worker());
+    let registry = Arc::new(RwLock::new(registry));
+
+    let (events, received) = mpsc::channel();
+    let watcher = DriftWatcher::spawn(
+        registry,
+        "worker-1",
+        DriftDetector::new(checker(&env)),
+        Duration::from_secs(60),
+        move |event| events.send(event).unwrap(),
+    );
+    let event = received.recv_timeout(Duration::from_secs(5)).unwrap();
+    watcher.stop();
+
+    assert!(matches!(
+        event,
+        DriftEvent::ToolDisappeared { ref tool, .. } if tool == "semgrep"
+    ));
+}
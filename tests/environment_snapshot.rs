@@ -0,0 +1,58 @@
+//! Environment snapshots travel with capabilities and drive platform matching
+
+use worker_capabilities::*;
+
+fn arm_worker() -> Capabilities {
+    Capabilities::new("arm").with_environment(
+        EnvironmentSnapshot::new("linux", "aarch64")
+            .with_kernel("6.1.0")
+            .with_container_image_digest("sha256:abc")
+            .with_env_var("RUSTUP_TOOLCHAIN", "stable"),
+    )
+}
+
+#[test]
+fn capture_records_the_local_platform_and_allowlisted_variables() {
+    let snapshot = EnvironmentSnapshot::capture_with_env_vars(&["PATH", "WORKER_CAPS_UNSET_VAR"]);
+    assert_eq!(snapshot.os, std::env::consts::OS);
+    assert_eq!(snapshot.arch, std::env::consts::ARCH);
+    assert!(snapshot.captured_at > 0);
+    assert_eq!(snapshot.env_vars.len(), 1);
+    assert_eq!(
+        snapshot.env_var("PATH"),
+        std::env::var("PATH").ok().as_deref()
+    );
+}
+
+#[test]
+fn platform_comes_from_the_snapshot_or_discovery_metadata() {
+    assert_eq!(arm_worker().os(), Some("linux"));
+    assert_eq!(arm_worker().arch(), Some("aarch64"));
+
+    let discovered = Capabilities::new("discovered").with_metadata(DISCOVERY_META_ARCH, "x86_64");
+    assert_eq!(discovered.arch(), Some("x86_64"));
+    assert_eq!(discovered.os(), None);
+}
+
+#[test]
+fn schedulers_match_on_platform() {
+    let arm64 = Requirements::new()
+        .requires_os("linux")
+        .requires_arch("aarch64");
+    assert!(arm_worker().meets_requirements(&arm64));
+    assert!(!Capabilities::new("bare").meets_requirements(&arm64));
+    assert!(!arm_worker().meets_requirements(&Requirements::new().requires_arch("x86_64")));
+}
+
+#[test]
+fn snapshots_survive_serialization() {
+    let json = serde_json::to_string(&arm_worker()).unwrap();
+    let caps: Capabilities = serde_json::from_str(&json).unwrap();
+    let environment = caps.environment.unwrap();
+    assert_eq!(environment.kernel.as_deref(), Some("6.1.0"));
+    assert_eq!(
+        environment.container_image_digest.as_deref(),
+        Some("sha256:abc")
+    );
+    assert_eq!(environment.env_var("RUSTUP_TOOLCHAIN"), Some("stable"));
+}
@@ -0,0 +1,83 @@
+//! Capability sets compare by meaning, not by layout
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+use std::time::Duration;
+
+use worker_capabilities::clock::{self, MockClock};
+use worker_capabilities::*;
+
+fn hash_of(value: &impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// The same declaration made at a given time
+fn declared_at(clock: &MockClock) -> Capabilities {
+    clock::with_clock(clock.clone(), || {
+        Capabilities::new("worker-1").with_static_analysis("clippy", true)
+    })
+}
+
+/// A tool attested at a given time
+fn attested_at(tool: &ToolCapability, clock: &MockClock) -> ToolCapability {
+    let attestation = clock::with_clock(clock.clone(), || {
+        CapabilitySigner::new("ci-key", "ci").attest(tool)
+    });
+    tool.clone().with_attestation(attestation)
+}
+
+#[test]
+fn tool_order_and_empty_categories_do_not_matter() {
+    let a = Capabilities::new("worker-1")
+        .with_static_analysis("clippy", true)
+        .with_static_analysis("semgrep", false);
+    let mut b = Capabilities::new("worker-1")
+        .with_static_analysis("semgrep", false)
+        .with_static_analysis("clippy", true);
+    b.custom_tools.insert("gpu".to_string(), Vec::new());
+
+    assert_eq!(a, b);
+    assert_eq!(hash_of(&a), hash_of(&b));
+    let deduplicated: HashSet<_> = [a, b].into_iter().collect();
+    assert_eq!(deduplicated.len(), 1);
+}
+
+#[test]
+fn declarations_differ_by_their_content() {
+    let a = Capabilities::new("worker-1").with_static_analysis("clippy", true);
+    assert_ne!(
+        a,
+        Capabilities::new("worker-1").with_static_analysis("clippy", false)
+    );
+    assert_ne!(
+        a,
+        Capabilities::new("worker-2").with_static_analysis("clippy", true)
+    );
+
+    let mut limited = ToolCapability::new("clippy", true);
+    limited.permissions.memory_limit_mb = 1;
+    assert_ne!(limited.permissions, CapabilityPermissions::default());
+}
+
+#[test]
+fn volatile_fields_can_be_ignored() {
+    let clock = MockClock::at_unix(1_700_000_000);
+    let earlier = declared_at(&clock);
+    clock.advance(Duration::from_secs(3600));
+    let later = declared_at(&clock);
+
+    assert_ne!(earlier, later);
+    assert!(!earlier.semantic_eq(&later, EqualityOptions::strict()));
+    assert!(earlier.semantic_eq(&later, EqualityOptions::ignoring_volatile()));
+
+    let tool = &earlier.static_analysis_tools[0];
+    let first = attested_at(tool, &clock);
+    clock.advance(Duration::from_secs(3600));
+    let renewed = attested_at(tool, &clock);
+    assert_ne!(first, renewed);
+    assert!(first.semantic_eq(&renewed, EqualityOptions::ignoring_volatile()));
+    assert!(!first.semantic_eq(tool, EqualityOptions::ignoring_volatile()));
+}
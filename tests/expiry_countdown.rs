@@ -0,0 +1,58 @@
+//! Rendered summaries count down to each tool's expiry
+
+use worker_capabilities::clock::{self, MockClock};
+use worker_capabilities::*;
+
+const NOW: u64 = 1_700_000_000;
+
+fn tool(name: &str, expires_in: i64) -> ToolCapability {
+    let mut tool = ToolCapability::new(name, true);
+    tool.expiration.expires_at = NOW.saturating_add_signed(expires_in);
+    tool
+}
+
+fn row<'a>(summary: &'a str, tool: &str) -> &'a str {
+    summary
+        .lines()
+        .find(|line| line.split_whitespace().nth(1) == Some(tool))
+        .unwrap()
+}
+
+#[test]
+fn tools_show_the_time_left_or_why_they_are_unusable() {
+    let mut revoked = tool("bandit", 3600);
+    revoked.revoke("compromised".to_string(), "security".to_string());
+    let mut caps = Capabilities::new("worker-1");
+    caps.add_tools_from(
+        CAPABILITY_STATIC_ANALYSIS,
+        &[
+            tool("clippy", 2 * 86_400 + 3 * 3600 + 59),
+            tool("semgrep", 310),
+            tool("mypy", -1),
+        ],
+    );
+    caps.add_tools_from(CAPABILITY_SECURITY_SCANNING, &[revoked]);
+
+    let summary = clock::with_clock(MockClock::at_unix(NOW), || caps.render_summary());
+    assert!(summary.contains("EXPIRES"));
+    assert!(row(&summary, "clippy").ends_with("in 2d 3h"));
+    assert!(row(&summary, "semgrep").ends_with("in 5m 10s"));
+    assert!(row(&summary, "mypy").ends_with("expired"));
+    assert!(row(&summary, "bandit").ends_with("revoked"));
+    assert!(!caps.summary().contains("EXPIRES"));
+}
+
+#[test]
+fn tools_display_on_one_line() {
+    let tool = ToolCapability::new("clippy", true)
+        .with_version("0.1.75")
+        .with_alternatives(vec!["cargo-clippy".to_string()]);
+    assert_eq!(
+        tool.to_string(),
+        "clippy 0.1.75 (required, alternatives: cargo-clippy)"
+    );
+
+    let mut revoked = ToolCapability::new("semgrep", false);
+    revoked.revoke("compromised".to_string(), "security".to_string());
+    assert_eq!(revoked.to_string(), "semgrep (optional, revoked)");
+}
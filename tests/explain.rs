@@ -0,0 +1,86 @@
+//! Explanations say which alternative matched and why the other tools failed
+
+use worker_capabilities::*;
+
+fn worker() -> Capabilities {
+    let mut caps = Capabilities::new("worker-1")
+        .with_required_alternative("rustfmt", vec!["cargo-fmt"])
+        .with_static_analysis("semgrep", true)
+        .with_security_tool("trivy", false);
+    caps.static_analysis_tools.push(
+        ToolCapability::new("clippy", true)
+            .with_version("0.1.70")
+            .with_version_req(">=0.1.75")
+            .unwrap(),
+    );
+    caps.security_scanning_tools
+        .push(ToolCapability::new("bandit", false));
+    caps.security_scanning_tools[1].revoke("compromised".to_string(), "ops".to_string());
+    caps
+}
+
+fn installed(tool: &str) -> bool {
+    matches!(tool, "cargo-fmt" | "clippy" | "bandit")
+}
+
+fn explained<'a>(report: &'a CapabilityReport, tool: &str) -> &'a ToolExplanation {
+    report
+        .tools
+        .iter()
+        .find(|explanation| explanation.tool_name == tool)
+        .unwrap()
+}
+
+#[test]
+fn each_tool_is_explained() {
+    let report = worker().explain(&installed);
+    assert_eq!(report.worker_id, "worker-1");
+    assert_eq!(report.tools.len(), 5);
+
+    let rustfmt = explained(&report, "rustfmt");
+    assert_eq!(rustfmt.matched.as_deref(), Some("cargo-fmt"));
+    assert!(rustfmt.matched_alternative());
+    assert_eq!(rustfmt.failure, None);
+
+    assert_eq!(
+        explained(&report, "semgrep").failure,
+        Some(ToolCheckFailure::NotInstalled {
+            checked: vec!["semgrep".to_string()]
+        })
+    );
+    assert_eq!(
+        explained(&report, "clippy").failure,
+        Some(ToolCheckFailure::VersionMismatch {
+            required: ">=0.1.75".to_string(),
+            found: Some("0.1.70".to_string()),
+        })
+    );
+    assert_eq!(
+        explained(&report, "bandit").failure,
+        Some(ToolCheckFailure::Revoked {
+            reason: Some("compromised".to_string()),
+            revoked_by: Some("ops".to_string()),
+        })
+    );
+}
+
+#[test]
+fn missing_required_tools_are_listed() {
+    let report = worker().explain(&installed);
+    assert!(!report.all_required_satisfied());
+    let missing: Vec<&str> = report
+        .missing_required()
+        .iter()
+        .map(|explanation| explanation.tool_name.as_str())
+        .collect();
+    assert_eq!(missing, vec!["semgrep", "clippy"]);
+    assert!(!worker().has_all_required_tools(&installed));
+}
+
+#[test]
+fn reports_render_the_failure_reasons() {
+    let rendered = worker().explain(&installed).to_string();
+    assert!(rendered.contains("not installed (checked semgrep)"));
+    assert!(rendered.contains("version 0.1.70 does not satisfy >=0.1.75"));
+    assert!(rendered.contains("revoked: compromised"));
+}
@@ -0,0 +1,67 @@
+//! The fallible builder rejects inconsistent capability sets
+
+use worker_capabilities::*;
+
+#[test]
+fn consistent_declarations_build() {
+    let caps = Capabilities::builder("worker-1")
+        .with_static_analysis("clippy", true)
+        .with_security_tool("clippy", false)
+        .with_flag("ast_support")
+        .with_flag("ast_support")
+        .with_metadata("region", "eu")
+        .build()
+        .unwrap();
+    assert_eq!(caps.all_tools().len(), 2);
+    assert!(caps.has_flag("ast_support"));
+}
+
+#[test]
+fn duplicate_tools_in_a_category_are_rejected() {
+    let result = Capabilities::builder("worker-1")
+        .with_fuzzing_tool("cargo-fuzz", true)
+        .with_fuzzing_tool("cargo-fuzz", false)
+        .build();
+    assert_eq!(
+        result.unwrap_err(),
+        CapabilityError::DuplicateTool {
+            category: CAPABILITY_FUZZING.to_string(),
+            tool: "cargo-fuzz".to_string(),
+        }
+    );
+}
+
+#[test]
+fn conflicting_flags_are_rejected() {
+    let result = Capabilities::builder("worker-1")
+        .with_flag("gpu")
+        .without_flag("gpu")
+        .build();
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "flag 'gpu' set both enabled and disabled"
+    );
+}
+
+#[test]
+fn limit_violations_are_rejected() {
+    assert_eq!(
+        Capabilities::builder("").build().unwrap_err(),
+        CapabilityError::Invalid(vec![ValidationError::EmptyId])
+    );
+
+    let alternatives: Vec<String> = (0..=MAX_ALTERNATIVE_TOOLS)
+        .map(|i| format!("alt-{}", i))
+        .collect();
+    let result = Capabilities::builder("worker-1")
+        .with_tool_capability(
+            CAPABILITY_STATIC_ANALYSIS,
+            ToolCapability::new("semgrep", true).with_alternatives(alternatives),
+        )
+        .build();
+    assert!(matches!(
+        result,
+        Err(CapabilityError::Invalid(errors))
+            if matches!(errors[..], [ValidationError::TooManyAlternatives { .. }])
+    ));
+}
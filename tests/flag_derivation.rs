@@ -0,0 +1,49 @@
+//! Derived flags follow the tools, not the manifest
+
+use worker_capabilities::well_known::AST_SUPPORT;
+use worker_capabilities::*;
+
+fn ast_rule() -> Vec<FlagRule> {
+    vec![FlagRule::new(AST_SUPPORT).when_any_of(["rust-analyzer", "tree-sitter"])]
+}
+
+#[test]
+fn rules_set_flags_from_available_tools() {
+    let mut caps = Capabilities::new("worker-1");
+    caps.derive_flags(&ast_rule(), &|tool: &str| tool == "tree-sitter");
+    assert!(caps.has_flag(AST_SUPPORT));
+    assert!(caps.is_computed_flag(AST_SUPPORT));
+
+    caps.derive_flags(&ast_rule(), &|_: &str| false);
+    assert!(!caps.has_flag(AST_SUPPORT));
+}
+
+#[test]
+fn computed_flags_override_declared_ones_and_are_dropped_with_their_rule() {
+    let mut caps = Capabilities::new("worker-1").with_flag(AST_SUPPORT);
+    assert!(!caps.is_computed_flag(AST_SUPPORT));
+
+    caps.derive_flags(&ast_rule(), &|_: &str| false);
+    assert!(!caps.has_flag(AST_SUPPORT));
+    assert!(caps.is_computed_flag(AST_SUPPORT));
+
+    caps.derive_flags(&[], &|_: &str| true);
+    assert!(!caps.flags.contains_key(AST_SUPPORT));
+    assert!(!caps.is_computed_flag(AST_SUPPORT));
+}
+
+#[test]
+fn registration_recomputes_flags_from_declared_tools() {
+    let mut registry = CapabilityRegistry::new().with_flag_rules(ast_rule());
+    registry.register(Capabilities::new("claims").with_flag(AST_SUPPORT));
+    registry.register(Capabilities::new("declares").with_static_analysis("rust-analyzer", false));
+
+    assert!(!registry.get("claims").unwrap().has_flag(AST_SUPPORT));
+    assert!(registry.get("declares").unwrap().has_flag(AST_SUPPORT));
+    let flagged: Vec<_> = registry
+        .find_workers_with_flag(AST_SUPPORT)
+        .into_iter()
+        .map(|caps| caps.id.as_str())
+        .collect();
+    assert_eq!(flagged, vec!["declares"]);
+}
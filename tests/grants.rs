@@ -0,0 +1,54 @@
+//! Temporary grants apply wherever workers are matched
+
+use std::time::Duration;
+
+use worker_capabilities::*;
+
+fn registry() -> CapabilityRegistry {
+    let mut registry = CapabilityRegistry::new();
+    registry.register(Capabilities::new("worker-1").with_security_tool("trivy", true));
+    registry
+}
+
+fn internet_grant() -> GrantOverlay {
+    GrantOverlay::temporary(
+        PermissionDelta::new().with_internet(),
+        Duration::from_secs(3600),
+        "ops",
+    )
+}
+
+#[test]
+fn matchers_apply_active_grants() {
+    let mut registry = registry();
+    let requirements = Requirements::new().requires_internet();
+    assert!(registry.find_meeting_requirements(&requirements).is_empty());
+    assert!(registry
+        .find_workers_with_permissions(CAPABILITY_SECURITY_SCANNING, PERMISSION_NETWORK_ACCESS)
+        .is_empty());
+
+    registry
+        .grant_temporary("worker-1", internet_grant())
+        .unwrap();
+    assert_eq!(registry.find_meeting_requirements(&requirements).len(), 1);
+    assert_eq!(
+        registry
+            .find_workers_with_permissions(CAPABILITY_SECURITY_SCANNING, PERMISSION_NETWORK_ACCESS)
+            .len(),
+        1
+    );
+
+    // The base declaration is unchanged
+    assert!(!registry.get("worker-1").unwrap().network.internet);
+}
+
+#[test]
+fn audit_log_is_bounded() {
+    let mut registry = registry();
+    for _ in 0..MAX_AUDIT_LOG_EVENTS + 10 {
+        registry
+            .grant_temporary("worker-1", internet_grant())
+            .unwrap();
+    }
+    assert_eq!(registry.audit_log().len(), MAX_AUDIT_LOG_EVENTS);
+}
@@ -0,0 +1,89 @@
+//! HTTP checkers ask a remote agent's tool endpoint, retrying and caching
+
+#![cfg(feature = "http-checker")]
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use worker_capabilities::*;
+
+/// Serve the tool endpoint; `flaky` fails once with a server error
+fn agent() -> (String, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let requests = Arc::new(AtomicUsize::new(0));
+    let counted = requests.clone();
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let mut header = String::new();
+            while reader.read_line(&mut header).unwrap() > 2 {
+                header.clear();
+            }
+
+            let seen = counted.fetch_add(1, Ordering::SeqCst);
+            let path = request_line.split_whitespace().nth(1).unwrap_or_default();
+            let (status, body) = match path {
+                "/tools/semgrep" => (
+                    "200 OK",
+                    r#"{"available": true, "version": "1.50.0", "path": "/usr/bin/semgrep", "sha256": "ABCD"}"#,
+                ),
+                "/tools/flaky" if seen == 0 => ("500 Internal Server Error", ""),
+                "/tools/flaky" => ("200 OK", r#"{"available": true}"#),
+                _ => ("404 Not Found", ""),
+            };
+            let _ = write!(
+                stream,
+                "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+        }
+    });
+    (base_url, requests)
+}
+
+#[test]
+fn agent_responses_become_tool_status() {
+    let (base_url, _) = agent();
+    let checker = HttpToolChecker::new(base_url);
+
+    let status = checker.status("semgrep");
+    assert!(status.available);
+    assert_eq!(status.version.as_deref(), Some("1.50.0"));
+    assert_eq!(status.sha256.as_deref(), Some("abcd"));
+    assert!(!checker.is_available("bandit"));
+}
+
+#[test]
+fn server_errors_are_retried_and_responses_cached() {
+    let (base_url, requests) = agent();
+    let checker = HttpToolChecker::new(base_url).with_retries(2, Duration::from_millis(1));
+
+    assert!(checker.is_available("flaky"));
+    assert!(checker.is_available("flaky"));
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+    checker.invalidate("flaky");
+    assert!(checker.is_available("flaky"));
+    assert_eq!(requests.load(Ordering::SeqCst), 3);
+}
+
+#[test]
+fn unreachable_agents_report_tools_missing() {
+    let checker = HttpToolChecker::new("http://127.0.0.1:1/")
+        .with_timeout(Duration::from_millis(200))
+        .with_retries(0, Duration::ZERO);
+    assert!(!checker.is_available("semgrep"));
+    assert_eq!(
+        checker.tool_url("clang tidy"),
+        "http://127.0.0.1:1/tools/clang%20tidy"
+    );
+}
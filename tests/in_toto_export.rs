@@ -0,0 +1,88 @@
+//! Capabilities export to in-toto statements in signed DSSE envelopes
+
+#![cfg(all(feature = "in-toto", feature = "ed25519"))]
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use worker_capabilities::*;
+
+fn signer() -> Ed25519Signer {
+    Ed25519Signer::new([7; 32], "ci")
+}
+
+fn tool() -> ToolCapability {
+    ToolCapability::new("cargo", true)
+        .with_version("1.80.0")
+        .countersign(&signer())
+        .countersign(&Ed25519Signer::new([8; 32], "platform"))
+}
+
+#[test]
+fn attested_tools_round_trip_through_envelopes() {
+    let envelope = tool().to_in_toto_statement().sign(&signer());
+    let envelope = DsseEnvelope::from_json(&envelope.to_json().unwrap()).unwrap();
+    let statement = envelope
+        .verify(&Ed25519Verifier, &signer().public_key())
+        .unwrap();
+
+    assert_eq!(statement.subject[0].name, "cargo");
+    assert_eq!(
+        statement.subject[0].digest["sha256"],
+        tool().generate_capability_hash()
+    );
+    let imported = ToolCapability::from_in_toto_statement(&statement).unwrap();
+    assert_eq!(imported.chain().len(), 2);
+    assert!(imported.verify_chain_with(
+        &TrustPolicy::new()
+            .with_attester(AttesterEntry::new("ci", signer().public_key()))
+            .with_attester(AttesterEntry::new(
+                "platform",
+                Ed25519Signer::new([8; 32], "platform").public_key()
+            )),
+        &Ed25519Verifier
+    ));
+}
+
+#[test]
+fn envelopes_sign_the_dsse_pre_authentication_encoding() {
+    let envelope = tool().to_in_toto_statement().sign(&signer());
+    let payload = STANDARD.decode(&envelope.payload).unwrap();
+    let mut message = format!(
+        "DSSEv1 {} {} {} ",
+        envelope.payload_type.len(),
+        envelope.payload_type,
+        payload.len()
+    )
+    .into_bytes();
+    message.extend_from_slice(&payload);
+    assert_eq!(envelope.payload_type, "application/vnd.in-toto+json");
+    assert_eq!(
+        STANDARD.encode(signer().sign_raw(&message)),
+        envelope.signatures[0].sig
+    );
+
+    let other = Ed25519Signer::new([9; 32], "ci");
+    assert_eq!(
+        envelope.verify(&Ed25519Verifier, &other.public_key()),
+        Err(InTotoError::InvalidSignature)
+    );
+}
+
+#[test]
+fn statements_must_cover_the_predicate() {
+    let mut statement = tool().to_in_toto_statement();
+    statement.predicate["required"] = false.into();
+    assert_eq!(
+        ToolCapability::from_in_toto_statement(&statement),
+        Err(InTotoError::SubjectMismatch {
+            tool: "cargo".to_string(),
+        })
+    );
+
+    let mut statement = tool().to_in_toto_statement();
+    statement.predicate_type = "https://slsa.dev/provenance/v1".to_string();
+    assert!(matches!(
+        ToolCapability::from_in_toto_statement(&statement),
+        Err(InTotoError::UnsupportedType(_))
+    ));
+}
@@ -0,0 +1,74 @@
+//! Clocks given to registries and verifiers replace the thread clock
+
+use std::time::{Duration, SystemTime};
+
+use worker_capabilities::clock::{self, MockClock};
+use worker_capabilities::*;
+
+const SIGNED_AT: u64 = 1_700_000_000;
+const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+fn signer() -> CapabilitySigner {
+    CapabilitySigner::new("key", "ci")
+}
+
+fn verifier() -> AlgorithmRegistry {
+    AlgorithmRegistry::empty().with_algorithm(DEFAULT_ATTESTATION_ALGORITHM, MockVerifier)
+}
+
+fn worker() -> Capabilities {
+    clock::with_clock(MockClock::at_unix(SIGNED_AT), || {
+        Capabilities::new("worker-1")
+            .with_attested_tool(CAPABILITY_STATIC_ANALYSIS, "clippy", true, &signer())
+            .attest(&signer())
+            .unwrap()
+    })
+}
+
+#[test]
+fn verifier_clock_decides_attestation_expiry() {
+    let tool = worker().static_analysis_tools[0].clone();
+    let clock = MockClock::at_unix(SIGNED_AT);
+    let injected = verifier().with_clock(clock.clone());
+
+    // The attestation is years old by the system clock
+    assert!(!tool.verify_attestation_with(&verifier()));
+    assert!(tool.verify_attestation_with(&injected));
+
+    clock.advance(DAY * (ATTESTATION_EXPIRY_DAYS as u32 + 1));
+    assert!(!tool.verify_attestation_with(&injected));
+}
+
+#[test]
+fn registry_clock_reaches_its_verifier() {
+    let clock = MockClock::at_unix(SIGNED_AT);
+    let mut registry = CapabilityRegistry::new()
+        .with_algorithms(verifier())
+        .with_clock(clock.clone());
+    registry.try_register(worker()).unwrap();
+    assert_eq!(registry.find_verified_workers().len(), 1);
+
+    clock.advance(DAY * (ATTESTATION_EXPIRY_DAYS as u32 + 1));
+    assert!(registry.find_verified_workers().is_empty());
+}
+
+#[test]
+fn timeout_bounded_checks_read_the_registry_clock() {
+    let registry = {
+        let mut registry = CapabilityRegistry::new()
+            .with_algorithms(verifier())
+            .with_clock(MockClock::at_unix(SIGNED_AT));
+        registry.try_register(worker()).unwrap();
+        registry
+    };
+    let signed_at = SystemTime::UNIX_EPOCH + Duration::from_secs(SIGNED_AT);
+    let checker = (move |_: &str| clock::now() == signed_at).with_timeout(Duration::from_secs(5));
+
+    assert_eq!(
+        registry
+            .find_workers_with_all_required_tools(&checker)
+            .len(),
+        1
+    );
+    assert!(!checker.is_available("clippy"));
+}
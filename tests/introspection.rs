@@ -0,0 +1,68 @@
+//! Field descriptors expose every section and value with readable labels
+
+use serde_json::json;
+use worker_capabilities::*;
+
+fn worker() -> Capabilities {
+    Capabilities::new("worker-1")
+        .with_static_analysis("clippy", true)
+        .with_metadata("team/owner", "security")
+}
+
+fn root(caps: &Capabilities) -> FieldDescriptor {
+    FieldDescriptor {
+        path: String::new(),
+        key: String::new(),
+        label: String::new(),
+        kind: FieldKind::Section,
+        value: None,
+        children: caps.fields(),
+    }
+}
+
+#[test]
+fn sections_lists_and_scalars_are_typed() {
+    let caps = worker();
+    let fields = root(&caps);
+
+    let id = fields.find("/id").unwrap();
+    assert_eq!((id.label.as_str(), id.kind), ("ID", FieldKind::Text));
+    assert_eq!(id.display_value(), "worker-1");
+
+    let tools = fields.find("/static_analysis_tools").unwrap();
+    assert_eq!(tools.kind, FieldKind::List);
+    assert_eq!(tools.label, "Static analysis tools");
+    assert_eq!(tools.children[0].label, "clippy");
+
+    let required = fields.find("/static_analysis_tools/0/required").unwrap();
+    assert_eq!(required.kind, FieldKind::Bool);
+    assert_eq!(required.value, Some(json!(true)));
+}
+
+#[test]
+fn keys_are_escaped_in_pointers() {
+    let caps = worker();
+    let owner = root(&caps).find("/metadata/team~1owner").cloned().unwrap();
+    assert_eq!(owner.key, "team/owner");
+    assert_eq!(owner.display_value(), "security");
+}
+
+#[test]
+fn tools_describe_themselves() {
+    let tool = ToolCapability::new("semgrep", false).with_version("1.50.0");
+    let described = tool.describe();
+    assert_eq!(described.label, "semgrep");
+
+    let limit = described.find("/permissions/memory_limit_mb").unwrap();
+    assert_eq!(limit.label, "Memory limit (MB)");
+    assert_eq!(limit.kind, FieldKind::Number);
+    assert_eq!(
+        described.find("/version").unwrap().display_value(),
+        "1.50.0"
+    );
+    assert!(described
+        .leaves()
+        .iter()
+        .all(|leaf| leaf.children.is_empty() && leaf.value.is_some()));
+    assert_eq!(field_label("egress_allowlist"), "Egress allowlist");
+}
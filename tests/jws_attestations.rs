@@ -0,0 +1,100 @@
+//! Attestations travel as JWS tokens and externally issued tokens verify
+
+#![cfg(all(feature = "jws", feature = "ed25519"))]
+
+use std::time::Duration;
+
+use worker_capabilities::clock::{self, MockClock};
+use worker_capabilities::*;
+
+const ISSUED_AT: u64 = 1_700_000_000;
+
+fn signer() -> Ed25519Signer {
+    Ed25519Signer::new([7; 32], "identity-provider")
+}
+
+fn token() -> String {
+    token_for("clippy")
+}
+
+fn token_for(tool: &str) -> String {
+    let hash = ToolCapability::new(tool, true).generate_capability_hash();
+    clock::with_clock(MockClock::at_unix(ISSUED_AT), || {
+        CapabilityAttestation::issue_jws(&signer(), &hash)
+    })
+    .to_jws()
+    .unwrap()
+    .to_string()
+}
+
+fn verify(token: &str, public_key: &str) -> Result<JwsClaims, JwsError> {
+    clock::with_clock(MockClock::at_unix(ISSUED_AT + 60), || {
+        verify_jws(token, public_key, &Ed25519Verifier)
+    })
+}
+
+#[test]
+fn issued_tokens_carry_the_attestation_claims() {
+    let claims = verify(&token(), &signer().public_key()).unwrap();
+    assert_eq!(claims.iss, "identity-provider");
+    assert_eq!(claims.iat, ISSUED_AT);
+    assert_eq!(
+        claims.exp,
+        ISSUED_AT + ATTESTATION_EXPIRY_DAYS * 24 * 60 * 60
+    );
+    assert_eq!(
+        claims.cap_hash,
+        ToolCapability::new("clippy", true).generate_capability_hash()
+    );
+}
+
+#[test]
+fn forged_or_expired_tokens_are_rejected() {
+    let other = Ed25519Signer::new([8; 32], "identity-provider");
+    assert_eq!(
+        verify(&token(), &other.public_key()),
+        Err(JwsError::InvalidSignature)
+    );
+
+    // Claims of a token for another tool under this token's signature
+    let token = token();
+    let other_claims = token_for("rustfmt");
+    let (header, rest) = token.split_once('.').unwrap();
+    let (_, signature) = rest.split_once('.').unwrap();
+    let claims = other_claims.split('.').nth(1).unwrap();
+    let forged = format!("{}.{}.{}", header, claims, signature);
+    assert_eq!(
+        verify(&forged, &signer().public_key()),
+        Err(JwsError::InvalidSignature)
+    );
+
+    assert!(matches!(
+        verify("not-a-token", &signer().public_key()),
+        Err(JwsError::Malformed(_))
+    ));
+
+    let later = MockClock::at_unix(ISSUED_AT);
+    later.advance(Duration::from_secs(ATTESTATION_EXPIRY_DAYS * 24 * 60 * 60));
+    assert_eq!(
+        clock::with_clock(later, || verify_jws(
+            &token,
+            &signer().public_key(),
+            &Ed25519Verifier
+        )),
+        Err(JwsError::Expired)
+    );
+}
+
+#[test]
+fn imported_tokens_verify_as_tool_attestations() {
+    let attestation = CapabilityAttestation::from_jws(&token(), signer().public_key()).unwrap();
+    assert_eq!(attestation.algorithm, "JWS/EdDSA");
+    let tool = ToolCapability::new("clippy", true).with_attestation(attestation);
+    let verifier = AlgorithmRegistry::empty()
+        .with_algorithm("JWS/EdDSA", JwsVerifier::new(Ed25519Verifier))
+        .with_clock(MockClock::at_unix(ISSUED_AT + 60));
+    assert!(tool.verify_attestation_with(&verifier));
+    assert!(!ToolCapability::new("rustfmt", true)
+        .with_attestation(tool.attestation.clone().unwrap())
+        .verify_attestation_with(&verifier));
+}
@@ -0,0 +1,84 @@
+//! Rotated keys keep verifying the attestations they made before rotation
+
+#![cfg(feature = "ed25519")]
+
+use std::time::Duration;
+
+use worker_capabilities::clock::{self, MockClock};
+use worker_capabilities::*;
+
+const START: u64 = 1_700_000_000;
+const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+fn old_key() -> Ed25519Signer {
+    Ed25519Signer::new([1; 32], "ci")
+}
+
+fn new_key() -> Ed25519Signer {
+    Ed25519Signer::new([2; 32], "ci")
+}
+
+fn attest(signer: &dyn AttestationSigner) -> CapabilityAttestation {
+    signer.attest(&ToolCapability::new("clippy", true))
+}
+
+#[test]
+fn rotation_keeps_earlier_attestations_trusted() {
+    let clock = MockClock::at_unix(START);
+    clock::with_clock(clock.clone(), || {
+        let mut signer = RotatingSigner::new(old_key());
+        let before = attest(&signer);
+
+        clock.advance(DAY);
+        let retired = signer.rotate(new_key());
+        clock.advance(DAY);
+        let after = attest(&signer);
+        let forged_after_rotation = attest(&retired);
+
+        let policy = signer.policy();
+        assert_eq!(signer.keys().len(), 2);
+        assert_eq!(signer.keys()[0].valid_until, Some(START + DAY.as_secs()));
+        assert!(policy.trusts(&before));
+        assert!(policy.trusts(&after));
+        assert!(!policy.trusts(&forged_after_rotation));
+    });
+}
+
+#[test]
+fn retired_keys_are_pruned_once_their_attestations_expired() {
+    let clock = MockClock::at_unix(START);
+    clock::with_clock(clock.clone(), || {
+        let mut signer = RotatingSigner::new(old_key());
+        signer.rotate(new_key());
+
+        clock.advance(DAY * ATTESTATION_EXPIRY_DAYS as u32);
+        assert_eq!(signer.prune_retired_keys(), 0);
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(signer.prune_retired_keys(), 1);
+        assert_eq!(signer.keys()[0].public_key, new_key().public_key());
+    });
+}
+
+#[test]
+fn policies_rotate_attesters_with_their_categories() {
+    let clock = MockClock::at_unix(START);
+    clock::with_clock(clock.clone(), || {
+        let mut policy = TrustPolicy::new().with_attester(
+            AttesterEntry::new("ci", old_key().public_key())
+                .with_category(CAPABILITY_STATIC_ANALYSIS),
+        );
+        let before = attest(&old_key());
+        clock.advance(DAY);
+        policy.rotate_key("ci", new_key().public_key());
+        clock.advance(DAY);
+
+        assert!(policy.allows(&before, CAPABILITY_STATIC_ANALYSIS));
+        assert!(policy.allows(&attest(&new_key()), CAPABILITY_STATIC_ANALYSIS));
+        assert!(!policy.allows(&attest(&new_key()), CAPABILITY_FUZZING));
+        assert!(!policy.trusts(&attest(&old_key())));
+
+        clock.advance(DAY * ATTESTATION_EXPIRY_DAYS as u32);
+        assert_eq!(policy.prune_retired_keys(), 1);
+        assert!(!policy.trusts(&before));
+    });
+}
@@ -0,0 +1,90 @@
+//! Load shedding serves cached matches only for checkers that identify their answers
+
+use worker_capabilities::*;
+
+/// Checker with a fixed answer, keyed by its inventory name
+struct Inventory {
+    name: &'static str,
+    installed: bool,
+}
+
+impl ToolChecker for Inventory {
+    fn is_available(&self, _tool: &str) -> bool {
+        self.installed
+    }
+
+    fn cache_key(&self) -> Option<String> {
+        Some(format!("inventory:{}", self.name))
+    }
+}
+
+fn registry() -> CapabilityRegistry {
+    let mut registry = CapabilityRegistry::new().with_match_cache(MatchCache::default());
+    registry.register(Capabilities::new("worker-1").with_tool_in(
+        CAPABILITY_STATIC_ANALYSIS,
+        "clippy",
+        true,
+    ));
+    registry
+}
+
+fn shed(registry: &CapabilityRegistry, checker: &dyn ToolChecker) -> Shed<Vec<String>> {
+    registry
+        .find_with_capability_shed(CAPABILITY_STATIC_ANALYSIS, checker, MatchPriority::High)
+        .unwrap()
+}
+
+#[test]
+fn keyed_checkers_are_served_from_the_match_cache() {
+    let mut registry = registry();
+    let installed = Inventory {
+        name: "fleet",
+        installed: true,
+    };
+    assert!(!shed(&registry, &installed).cached);
+
+    registry.set_load_level(LoadLevel::Degraded);
+    let served = shed(&registry, &installed);
+    assert!(served.cached);
+    assert_eq!(served.value, vec!["worker-1".to_string()]);
+    assert_eq!(registry.match_cache_stats().unwrap().hits, 1);
+
+    // Another inventory has its own entry
+    let missing = Inventory {
+        name: "empty",
+        installed: false,
+    };
+    let computed = shed(&registry, &missing);
+    assert!(!computed.cached);
+    assert!(computed.value.is_empty());
+}
+
+#[test]
+fn closures_are_never_served_from_cache() {
+    let mut registry = registry();
+    let installed = |_: &str| true;
+    let missing = |_: &str| false;
+    assert_eq!(shed(&registry, &installed).value.len(), 1);
+
+    registry.set_load_level(LoadLevel::Overloaded);
+    let computed = shed(&registry, &missing);
+    assert!(!computed.cached);
+    assert!(computed.value.is_empty());
+}
+
+#[test]
+fn shedding_without_a_match_cache_computes_results() {
+    let mut registry = CapabilityRegistry::new();
+    registry.register(Capabilities::new("worker-1").with_tool_in(
+        CAPABILITY_STATIC_ANALYSIS,
+        "clippy",
+        true,
+    ));
+    let installed = Inventory {
+        name: "fleet",
+        installed: true,
+    };
+    shed(&registry, &installed);
+    registry.set_load_level(LoadLevel::Overloaded);
+    assert!(!shed(&registry, &installed).cached);
+}
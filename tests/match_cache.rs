@@ -0,0 +1,79 @@
+//! Repeated requirement sets are served from the match cache until the registry changes
+
+use worker_capabilities::clock::MockClock;
+use worker_capabilities::*;
+
+fn needs_static_analysis() -> Requirements {
+    Requirements::new().requires(CAPABILITY_STATIC_ANALYSIS)
+}
+
+fn registry(clock: &MockClock) -> CapabilityRegistry {
+    let mut registry = CapabilityRegistry::new()
+        .with_match_cache(MatchCache::new(16).with_ttl(60))
+        .with_clock(clock.clone());
+    registry.register(Capabilities::new("worker-1").with_static_analysis("clippy", true));
+    registry
+}
+
+#[test]
+fn repeated_requirements_hit_the_cache() {
+    let registry = registry(&MockClock::at_unix(1_700_000_000));
+    assert_eq!(
+        registry.rank_workers_cached(&needs_static_analysis()),
+        vec!["worker-1"]
+    );
+    assert_eq!(
+        registry.rank_workers_cached(&needs_static_analysis()),
+        vec!["worker-1"]
+    );
+    assert!(registry
+        .rank_workers_cached(&Requirements::new().requires_internet())
+        .is_empty());
+
+    let stats = registry.match_cache_stats().unwrap();
+    assert_eq!((stats.hits, stats.misses, stats.entries), (1, 2, 2));
+    assert!((stats.hit_rate() - 1.0 / 3.0).abs() < f64::EPSILON);
+}
+
+#[test]
+fn registry_changes_invalidate_cached_matches() {
+    let mut registry = registry(&MockClock::at_unix(1_700_000_000));
+    registry.rank_workers_cached(&needs_static_analysis());
+    let revision = registry.revision();
+
+    registry.register(Capabilities::new("worker-2").with_static_analysis("clippy", true));
+    assert!(registry.revision() > revision);
+    assert_eq!(
+        registry.rank_workers_cached(&needs_static_analysis()),
+        vec!["worker-1", "worker-2"]
+    );
+
+    let stats = registry.match_cache_stats().unwrap();
+    assert_eq!(stats.hits, 0);
+    assert!(stats.invalidations >= 1);
+}
+
+#[test]
+fn entries_expire_after_the_ttl() {
+    let clock = MockClock::at_unix(1_700_000_000);
+    let registry = registry(&clock);
+    registry.rank_workers_cached(&needs_static_analysis());
+
+    clock.advance(std::time::Duration::from_secs(61));
+    registry.rank_workers_cached(&needs_static_analysis());
+    assert_eq!(registry.match_cache_stats().unwrap().hits, 0);
+}
+
+#[test]
+fn digests_identify_requirement_sets() {
+    assert_eq!(
+        needs_static_analysis().digest(),
+        needs_static_analysis().digest()
+    );
+    assert_ne!(
+        needs_static_analysis().digest(),
+        needs_static_analysis()
+            .requires(CAPABILITY_FUZZING)
+            .digest()
+    );
+}
@@ -0,0 +1,116 @@
+//! Overlays merge into a base capability set, reporting every conflict
+
+use worker_capabilities::*;
+
+fn base() -> Capabilities {
+    let mut clippy = ToolCapability::new("clippy", false).with_version("0.1.75");
+    clippy.permissions.memory_limit_mb = 4096;
+    clippy.permissions.network_access = true;
+    let mut caps = Capabilities::new("worker-1")
+        .with_flag("gpu")
+        .with_metadata("region", "eu-west-1");
+    caps.add_tools_from(CAPABILITY_STATIC_ANALYSIS, &[clippy]);
+    caps
+}
+
+fn overlay() -> Capabilities {
+    let mut clippy = ToolCapability::new("clippy", true)
+        .with_version("0.1.76")
+        .with_alternatives(vec!["clippy-driver".to_string()]);
+    clippy.permissions.memory_limit_mb = 1024;
+    clippy.permissions.network_access = true;
+    clippy.permissions.filesystem_access = true;
+    let mut caps = Capabilities::new("overlay")
+        .with_security_tool("semgrep", false)
+        .with_metadata("region", "us-east-1")
+        .with_metadata("image", "rust:1.76");
+    caps.flags.insert("gpu".to_string(), false);
+    caps.add_tools_from(CAPABILITY_STATIC_ANALYSIS, &[clippy]);
+    caps
+}
+
+#[test]
+fn tools_are_unioned_and_shared_tools_combined() {
+    let mut caps = base();
+    let report = caps.merge(&overlay());
+
+    assert_eq!(
+        report.added_tools,
+        vec![(
+            CAPABILITY_SECURITY_SCANNING.to_string(),
+            "semgrep".to_string()
+        )]
+    );
+    let clippy = caps.find_tool("clippy").unwrap();
+    assert!(clippy.required);
+    assert_eq!(clippy.version.as_deref(), Some("0.1.76"));
+    assert_eq!(clippy.alternatives, vec!["clippy-driver"]);
+}
+
+#[test]
+fn stricter_permissions_win() {
+    let mut caps = base();
+    caps.merge(&overlay());
+    let permissions = &caps.find_tool("clippy").unwrap().permissions;
+    assert_eq!(permissions.memory_limit_mb, 1024);
+    assert!(permissions.network_access);
+    assert!(!permissions.filesystem_access);
+}
+
+#[test]
+fn overlay_flags_and_metadata_override_and_are_reported() {
+    let mut caps = base();
+    let report = caps.merge(&overlay());
+
+    assert!(!caps.has_flag("gpu"));
+    assert_eq!(
+        caps.get_metadata("region").map(String::as_str),
+        Some("us-east-1")
+    );
+    assert_eq!(
+        caps.get_metadata("image").map(String::as_str),
+        Some("rust:1.76")
+    );
+    assert!(!report.is_clean());
+    for conflict in [
+        MergeConflict::PermissionsNarrowed {
+            category: CAPABILITY_STATIC_ANALYSIS.to_string(),
+            tool: "clippy".to_string(),
+        },
+        MergeConflict::FlagOverridden {
+            flag: "gpu".to_string(),
+            previous: true,
+            current: false,
+        },
+        MergeConflict::MetadataOverridden {
+            key: "region".to_string(),
+            previous: "eu-west-1".to_string(),
+            current: "us-east-1".to_string(),
+        },
+    ] {
+        assert!(report.conflicts.contains(&conflict), "{:?}", conflict);
+    }
+}
+
+#[test]
+fn stale_attestations_are_dropped() {
+    let ci = CapabilitySigner::new("ci-key", "ci");
+    let mut caps = Capabilities::new("worker-1").with_attested_tool(
+        CAPABILITY_STATIC_ANALYSIS,
+        "clippy",
+        false,
+        &ci,
+    );
+    let report = caps.merge(&overlay());
+
+    assert!(caps.find_tool("clippy").unwrap().attestation.is_none());
+    assert!(report
+        .conflicts
+        .contains(&MergeConflict::AttestationDropped {
+            category: CAPABILITY_STATIC_ANALYSIS.to_string(),
+            tool: "clippy".to_string(),
+        }));
+
+    let mut identical = base();
+    assert!(identical.merge(&base()).is_clean());
+}
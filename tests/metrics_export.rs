@@ -0,0 +1,80 @@
+//! Registry metrics export as node_exporter textfiles and JSON
+
+use std::fs;
+use std::path::PathBuf;
+
+use worker_capabilities::clock::{self, MockClock};
+use worker_capabilities::*;
+
+const NOW: u64 = 1_700_000_000;
+
+fn signer() -> CapabilitySigner {
+    CapabilitySigner::new("key", "ci")
+}
+
+fn registry() -> CapabilityRegistry {
+    let verified = clock::with_clock(MockClock::at_unix(NOW), || {
+        Capabilities::new("verified")
+            .with_attested_tool(CAPABILITY_STATIC_ANALYSIS, "clippy", true, &signer())
+            .attest(&signer())
+            .unwrap()
+    });
+    let mut registry = CapabilityRegistry::new()
+        .with_algorithms(
+            AlgorithmRegistry::empty().with_algorithm(DEFAULT_ATTESTATION_ALGORITHM, MockVerifier),
+        )
+        .with_clock(MockClock::at_unix(NOW));
+    registry.register(verified);
+    registry.register(Capabilities::new("unsigned").with_static_analysis("semgrep", false));
+    registry
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("{}-{}", std::process::id(), name))
+}
+
+#[test]
+fn snapshot_counts_workers_and_verification_failures() {
+    let snapshot = registry().metrics_snapshot();
+    assert_eq!(snapshot.timestamp, NOW);
+    assert_eq!(snapshot.workers, 2);
+    assert_eq!(snapshot.verification_failures, 1);
+    assert_eq!(snapshot.tools, 2);
+    assert_eq!(
+        snapshot.worker_verified,
+        vec![
+            ("unsigned".to_string(), false),
+            ("verified".to_string(), true)
+        ]
+    );
+}
+
+#[test]
+fn textfile_uses_the_exposition_format() {
+    let path = temp_path("worker_capabilities.prom");
+    registry().write_metrics_textfile(&path).unwrap();
+    let text = fs::read_to_string(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert!(text.contains("# TYPE worker_capabilities_workers gauge\n"));
+    assert!(text.contains("\nworker_capabilities_workers 2\n"));
+    assert!(text.contains("worker_capabilities_verification_failures 1\n"));
+    assert!(text.contains("worker_capabilities_worker_verified{worker=\"verified\"} 1\n"));
+    assert!(text.contains(&format!(
+        "worker_capabilities_snapshot_timestamp_seconds {}\n",
+        NOW
+    )));
+    assert!(!PathBuf::from(format!("{}.tmp", path.display())).exists());
+}
+
+#[test]
+fn json_export_round_trips() {
+    let path = temp_path("worker_capabilities.json");
+    registry().write_metrics_json(&path).unwrap();
+    let json = fs::read_to_string(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    let snapshot: MetricsSnapshot = serde_json::from_str(&json).unwrap();
+    assert_eq!(snapshot.workers, 2);
+    assert_eq!(snapshot.verified_workers, 1);
+}
@@ -0,0 +1,47 @@
+//! Model requirements express what boolean flags like `llm_support` cannot
+
+use worker_capabilities::*;
+
+fn worker() -> Capabilities {
+    Capabilities::new("worker")
+        .with_model(ModelCapability::local("llama", 8_192))
+        .with_model(
+            ModelCapability::api("gpt", 128_000)
+                .with_model("gpt-4o")
+                .with_rate_limit(30_000),
+        )
+}
+
+#[test]
+fn local_large_context_models_must_be_local_and_large() {
+    let needs =
+        Requirements::new().requires_model(ModelRequirement::new().local().min_context(32_000));
+    assert!(!worker().meets_requirements(&needs));
+
+    let upgraded = worker().with_model(ModelCapability::local("mistral", 32_768));
+    assert!(upgraded.meets_requirements(&needs));
+}
+
+#[test]
+fn families_match_case_insensitively() {
+    let gpt = ModelRequirement::new().family("GPT").min_context(100_000);
+    assert!(gpt.is_satisfied_by(&worker().models[1]));
+    assert!(!gpt.is_satisfied_by(&worker().models[0]));
+}
+
+#[test]
+fn rate_limits_only_exclude_slower_models() {
+    let fast = ModelRequirement::new().min_rate(50_000);
+    assert!(!fast.is_satisfied_by(&worker().models[1]));
+    assert!(fast.is_satisfied_by(&worker().models[0]));
+}
+
+#[test]
+fn every_model_requirement_must_be_met() {
+    let both = Requirements::new()
+        .requires_model(ModelRequirement::new().family("llama"))
+        .requires_model(ModelRequirement::new().family("claude"));
+    assert!(!worker().meets_requirements(&both));
+    assert!(!Capabilities::new("none")
+        .meets_requirements(&Requirements::new().requires_model(ModelRequirement::new())));
+}
@@ -0,0 +1,58 @@
+//! Jobs needing external networks are only matched to workers that reach them
+
+use worker_capabilities::*;
+
+fn air_gapped() -> Capabilities {
+    Capabilities::new("air-gapped").with_network(NetworkReachability::air_gapped())
+}
+
+fn connected() -> Capabilities {
+    Capabilities::new("connected").with_network(
+        NetworkReachability::internet()
+            .with_endpoint("github.com")
+            .with_endpoint("GitHub.com"),
+    )
+}
+
+#[test]
+fn probe_records_reachable_endpoints_only() {
+    let reachable = ["1.1.1.1", "internal-artifactory"];
+    let network = NetworkReachability::probe(
+        Some("1.1.1.1"),
+        &["internal-artifactory", "github.com"],
+        &|endpoint: &str| reachable.contains(&endpoint),
+    );
+
+    assert!(network.internet);
+    assert_eq!(network.endpoints, vec!["internal-artifactory"]);
+    assert!(network.probed_at.is_some());
+    assert!(!network.is_air_gapped());
+}
+
+#[test]
+fn endpoints_are_matched_case_insensitively_and_once() {
+    let network = connected().network;
+    assert_eq!(network.endpoints.len(), 1);
+    assert!(network.can_reach("GITHUB.COM"));
+    assert!(!network.can_reach("gitlab.com"));
+}
+
+#[test]
+fn cloning_jobs_skip_air_gapped_workers() {
+    let clone = Requirements::new()
+        .requires_internet()
+        .requires_endpoint("github.com");
+    assert!(air_gapped().network.is_air_gapped());
+    assert!(!air_gapped().meets_requirements(&clone));
+    assert!(connected().meets_requirements(&clone));
+
+    let mut registry = CapabilityRegistry::new();
+    registry.register(air_gapped());
+    registry.register(connected());
+    let matched: Vec<_> = registry
+        .find_meeting_requirements(&clone)
+        .into_iter()
+        .map(|caps| caps.id.as_str())
+        .collect();
+    assert_eq!(matched, vec!["connected"]);
+}
@@ -0,0 +1,105 @@
+//! Nomad node meta and job constraints carry the same capability definitions
+
+use std::collections::HashMap;
+
+use worker_capabilities::*;
+
+fn map(entries: &[(&str, &str)]) -> HashMap<String, String> {
+    entries
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+#[test]
+fn node_meta_and_attributes_are_imported() {
+    let attributes = map(&[
+        ("kernel.name", "linux"),
+        ("cpu.arch", "amd64"),
+        ("driver.docker", "1"),
+    ]);
+    let meta = map(&[
+        ("capability.tools.security_scanning", "trivy, cargo-audit"),
+        ("capability.flags", "ast_support"),
+        ("capability.network.internet", "true"),
+        ("capability.network.endpoints", "github.com"),
+        ("capability.accepts", "git_url,container_image"),
+        ("capability.unknown", "ignored"),
+        ("rack", "r12"),
+    ]);
+    let caps = Capabilities::from_nomad_node("node-1", &attributes, &meta);
+
+    let tools: Vec<_> = caps
+        .security_scanning_tools
+        .iter()
+        .map(|tool| tool.tool_name.as_str())
+        .collect();
+    assert_eq!(tools, vec!["trivy", "cargo-audit"]);
+    assert!(caps
+        .security_scanning_tools
+        .iter()
+        .all(|tool| !tool.required));
+    assert!(caps.has_flag("ast_support"));
+    assert!(caps.network.internet && caps.network.can_reach("github.com"));
+    assert_eq!(caps.sandbox.technology, SandboxTechnology::Docker);
+    assert!(caps.accepts_format(&ArtifactFormat::ContainerImage));
+    assert_eq!(caps.get_metadata("platform"), Some(&"linux".to_string()));
+    assert_eq!(caps.get_metadata("rack"), Some(&"r12".to_string()));
+    assert_eq!(caps.get_metadata("capability.unknown"), None);
+}
+
+#[test]
+fn declared_sandbox_overrides_the_docker_driver() {
+    let attributes = map(&[("driver.docker", "1")]);
+    let meta = map(&[("capability.sandbox", "Firecracker")]);
+    let caps = Capabilities::from_nomad_node("node-1", &attributes, &meta);
+    assert_eq!(caps.sandbox.technology, SandboxTechnology::Firecracker);
+}
+
+#[test]
+fn exported_meta_round_trips() {
+    let caps = Capabilities::new("node-1")
+        .with_security_tool("trivy", true)
+        .with_flag("ast_support")
+        .with_network(NetworkReachability::internet().with_endpoint("github.com"))
+        .with_sandbox(SandboxCapability::new(SandboxTechnology::GVisor))
+        .emitting(ReportFormat::Sarif);
+    let imported = Capabilities::from_nomad_node("node-1", &HashMap::new(), &caps.to_nomad_meta());
+
+    assert_eq!(imported.security_scanning_tools[0].tool_name, "trivy");
+    assert!(imported.has_flag("ast_support"));
+    assert_eq!(imported.network, caps.network);
+    assert_eq!(imported.sandbox.technology, SandboxTechnology::GVisor);
+    assert_eq!(imported.emits, caps.emits);
+}
+
+#[test]
+fn requirements_become_constraints_on_exported_meta() {
+    let requirements = Requirements::new()
+        .requires_internet()
+        .requires_isolation(IsolationLevel::Kernel)
+        .with_output(ReportFormat::Sarif);
+    let constraints = requirements.to_nomad_constraints();
+
+    assert_eq!(
+        constraints,
+        vec![
+            NomadConstraint {
+                l_target: "${meta.capability.network.internet}".to_string(),
+                operand: "=".to_string(),
+                r_target: "true".to_string(),
+            },
+            NomadConstraint {
+                l_target: "${meta.capability.sandbox}".to_string(),
+                operand: "regexp".to_string(),
+                r_target: "^(gvisor|firecracker)$".to_string(),
+            },
+            NomadConstraint {
+                l_target: "${meta.capability.emits}".to_string(),
+                operand: "set_contains".to_string(),
+                r_target: "sarif".to_string(),
+            },
+        ]
+    );
+    assert!(Requirements::new().to_nomad_constraints().is_empty());
+}
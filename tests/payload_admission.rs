@@ -0,0 +1,54 @@
+//! Jobs are only routed to workers whose payload limits admit them
+
+use worker_capabilities::*;
+
+const GB: u64 = 1024 * 1024 * 1024;
+
+fn edge() -> Capabilities {
+    Capabilities::new("edge").with_payload_limits(
+        PayloadLimits::unlimited()
+            .with_max_input_size_mb(2 * 1024)
+            .with_max_repo_file_count(50_000),
+    )
+}
+
+fn datacenter() -> Capabilities {
+    Capabilities::new("datacenter")
+}
+
+#[test]
+fn limits_are_inclusive_and_unset_limits_admit_anything() {
+    let limits = edge().payload;
+    assert_eq!(limits.max_input_size_bytes, Some(2 * GB));
+    assert!(limits.admits_input_size(2 * GB));
+    assert!(!limits.admits_input_size(2 * GB + 1));
+    assert!(limits.admits_repo_file_count(50_000));
+    assert!(!limits.admits_repo_file_count(50_001));
+    assert!(PayloadLimits::unlimited().admits_input_size(u64::MAX));
+}
+
+#[test]
+fn oversized_jobs_skip_small_workers() {
+    let monorepo = Requirements::new().with_input_size(20 * GB);
+    let large_repo = Requirements::new().with_repo_file_count(200_000);
+    let small_job = Requirements::new()
+        .with_input_size(GB)
+        .with_repo_file_count(1_000);
+
+    let mut registry = CapabilityRegistry::new();
+    registry.register(edge());
+    registry.register(datacenter());
+    let matched = |requirements: &Requirements| {
+        let mut ids: Vec<_> = registry
+            .find_meeting_requirements(requirements)
+            .into_iter()
+            .map(|caps| caps.id.clone())
+            .collect();
+        ids.sort();
+        ids
+    };
+
+    assert_eq!(matched(&monorepo), vec!["datacenter"]);
+    assert_eq!(matched(&large_repo), vec!["datacenter"]);
+    assert_eq!(matched(&small_job), vec!["datacenter", "edge"]);
+}
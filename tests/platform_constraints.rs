@@ -0,0 +1,74 @@
+//! Platform-aware matching skips workers and tools that cannot run the target
+
+use worker_capabilities::*;
+
+fn worker(id: &str, os: &str, arch: &str) -> Capabilities {
+    let mut caps = Capabilities::new(id).with_platform(os, arch);
+    caps.add_tools_from(
+        CAPABILITY_DYNAMIC_ANALYSIS,
+        &[ToolCapability::new("valgrind", true)
+            .with_platform("linux", "x86_64")
+            .with_platform("linux", "aarch64")],
+    );
+    caps
+}
+
+fn installed(_: &str) -> bool {
+    true
+}
+
+#[test]
+fn tools_without_platforms_run_anywhere() {
+    let windows = Platform::new("windows", "x86_64");
+    assert!(ToolCapability::new("semgrep", true).supports_platform(&windows));
+    let valgrind = &worker("linux", "linux", "x86_64").dynamic_analysis_tools[0];
+    assert!(valgrind.supports_platform(&Platform::new("linux", "aarch64")));
+    assert!(!valgrind.supports_platform(&windows));
+}
+
+#[test]
+fn workers_must_declare_a_matching_platform() {
+    let caps = worker("mac", "macos", "aarch64");
+    assert_eq!(caps.platform(), Some("macos-aarch64"));
+    assert!(caps.supports_platform(&Platform::new("macos", "aarch64")));
+    assert!(!Capabilities::new("bare").supports_platform(&Platform::current()));
+}
+
+#[test]
+fn registry_returns_only_workers_whose_tools_run_on_the_platform() {
+    let mut registry = CapabilityRegistry::new();
+    registry.register(worker("linux-amd", "linux", "x86_64"));
+    registry.register(worker("linux-arm", "linux", "aarch64"));
+    registry.register(worker("mac", "macos", "aarch64"));
+
+    let ids = |platform: Platform| -> Vec<String> {
+        let mut ids: Vec<_> = registry
+            .find_with_capability_for_platform(CAPABILITY_DYNAMIC_ANALYSIS, &installed, &platform)
+            .into_iter()
+            .map(|caps| caps.id.clone())
+            .collect();
+        ids.sort();
+        ids
+    };
+
+    assert_eq!(ids(Platform::new("linux", "aarch64")), vec!["linux-arm"]);
+    // The mac worker declares valgrind, but valgrind does not run there
+    assert!(ids(Platform::new("macos", "aarch64")).is_empty());
+}
+
+#[test]
+fn requirements_platform_applies_to_tools() {
+    let caps = worker("mac", "macos", "aarch64");
+    let job = Requirements::new()
+        .requires(CAPABILITY_DYNAMIC_ANALYSIS)
+        .requires_os("macos");
+    assert!(!caps.satisfies(&job, &installed).is_match());
+    assert!(worker("linux", "linux", "x86_64")
+        .satisfies(
+            &Requirements::new()
+                .requires(CAPABILITY_DYNAMIC_ANALYSIS)
+                .requires_os("linux"),
+            &installed
+        )
+        .is_match());
+}
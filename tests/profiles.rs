@@ -0,0 +1,41 @@
+//! Ecosystem profiles declare standard tools with their usual alternatives
+
+use worker_capabilities::well_known::{AST_SUPPORT, EVM_SUPPORT};
+use worker_capabilities::*;
+
+#[test]
+fn rust_profile_is_satisfied_by_common_installations() {
+    let caps = profiles::rust_worker("rust-1");
+    assert_eq!(caps.id, "rust-1");
+    assert!(caps.has_flag(AST_SUPPORT));
+
+    let rustup_default = |tool: &str| matches!(tool, "cargo" | "cargo-clippy" | "cargo-audit");
+    assert!(caps.has_all_required_tools(&rustup_default));
+    assert!(!caps.has_all_required_tools(&|tool: &str| tool == "cargo"));
+    assert_eq!(
+        caps.find_tool("clippy").unwrap().alternatives,
+        vec!["cargo-clippy", "clippy-driver"]
+    );
+}
+
+#[test]
+fn python_and_solidity_profiles_cover_their_categories() {
+    let python = profiles::python_worker("py-1");
+    assert_eq!(python.security_scanning_tools.len(), 2);
+    assert!(python.find_tool("pytest").unwrap().required);
+
+    let solidity = profiles::solidity_worker("sol-1");
+    assert!(solidity.has_flag(EVM_SUPPORT));
+    assert!(solidity.has_capability(CAPABILITY_SECURITY_SCANNING, &|tool: &str| tool == "myth"));
+    assert!(solidity.has_capability(CAPABILITY_FUZZING, &|tool: &str| tool == "medusa"));
+}
+
+#[test]
+fn profiles_extend_with_the_regular_builders() {
+    let caps = profiles::rust_worker("rust-1")
+        .with_dynamic_tool("valgrind", false)
+        .with_flag("gpu");
+    assert!(caps.find_tool("valgrind").is_some());
+    assert!(caps.find_tool("cargo-fuzz").is_some());
+    assert!(caps.has_flag("gpu"));
+}
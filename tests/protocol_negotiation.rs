@@ -0,0 +1,75 @@
+//! Peers negotiate a shared protocol version and down-level documents with a loss report
+
+use worker_capabilities::*;
+
+fn peer(min: u32, max: u32) -> Handshake {
+    Handshake {
+        protocol_version: max,
+        min_protocol_version: min,
+    }
+}
+
+#[test]
+fn peers_agree_on_the_newest_shared_version() {
+    let local = Handshake::new();
+    assert_eq!(local.negotiate(&local), Ok(PROTOCOL_VERSION));
+    assert_eq!(local.negotiate(&peer(1, 1)), Ok(1));
+    assert_eq!(
+        local.negotiate(&peer(1, PROTOCOL_VERSION + 5)),
+        Ok(PROTOCOL_VERSION)
+    );
+
+    let future = peer(PROTOCOL_VERSION + 1, PROTOCOL_VERSION + 2);
+    assert_eq!(
+        local.negotiate(&future),
+        Err(ProtocolError::Incompatible {
+            local,
+            peer: future
+        })
+    );
+}
+
+#[test]
+fn plain_documents_down_level_losslessly() {
+    let caps = Capabilities::new("worker-1")
+        .with_static_analysis("clippy", true)
+        .with_flag("ast_support");
+    let (document, report) = caps.to_protocol_version(1).unwrap();
+
+    assert!(report.is_lossless());
+    assert_eq!(report.target_version, 1);
+    assert_eq!(document["static_analysis_tools"][0]["tool_name"], "clippy");
+    assert!(document.get("network").is_none());
+    assert!(document.get("sandbox").is_none());
+}
+
+#[test]
+fn newer_sections_are_dropped_and_reported() {
+    let mut caps = Capabilities::new("worker-1")
+        .with_network(NetworkReachability::internet())
+        .with_sandbox(SandboxCapability::new(SandboxTechnology::Docker));
+    caps.static_analysis_tools
+        .push(ToolCapability::new("clippy", true).with_version("0.1.75"));
+
+    let (document, report) = caps.to_peer(&Handshake::new(), &peer(1, 1)).unwrap();
+    assert_eq!(
+        report.dropped,
+        vec!["network", "sandbox", "static_analysis.clippy.version"]
+    );
+    assert!(document["static_analysis_tools"][0]
+        .get("version")
+        .is_none());
+
+    let (_, current) = caps.to_protocol_version(PROTOCOL_VERSION).unwrap();
+    assert!(current.is_lossless());
+}
+
+#[test]
+fn unknown_versions_are_rejected() {
+    let caps = Capabilities::new("worker-1");
+    assert_eq!(
+        caps.to_protocol_version(PROTOCOL_VERSION + 1),
+        Err(ProtocolError::UnsupportedVersion(PROTOCOL_VERSION + 1))
+    );
+    assert!(caps.to_protocol_version(0).is_err());
+}
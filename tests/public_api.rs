@@ -0,0 +1,61 @@
+//! Each type has one definition, reachable from its module, the crate root and the prelude
+
+use std::any::TypeId;
+
+use worker_capabilities::prelude;
+
+fn same<A: 'static, B: 'static>() -> bool {
+    TypeId::of::<A>() == TypeId::of::<B>()
+}
+
+#[test]
+fn root_and_module_paths_name_the_same_types() {
+    assert!(same::<
+        worker_capabilities::Capabilities,
+        worker_capabilities::capabilities::Capabilities,
+    >());
+    assert!(same::<
+        worker_capabilities::ToolCapability,
+        worker_capabilities::types::ToolCapability,
+    >());
+    assert!(same::<
+        worker_capabilities::CapabilityRegistry,
+        worker_capabilities::registry::CapabilityRegistry,
+    >());
+    assert!(same::<
+        worker_capabilities::Requirements,
+        worker_capabilities::requirements::Requirements,
+    >());
+}
+
+#[test]
+fn prelude_reexports_the_canonical_types() {
+    assert!(same::<
+        prelude::Capabilities,
+        worker_capabilities::Capabilities,
+    >());
+    assert!(same::<
+        prelude::ToolCapability,
+        worker_capabilities::ToolCapability,
+    >());
+    assert!(same::<
+        prelude::CapabilityRegistry,
+        worker_capabilities::CapabilityRegistry,
+    >());
+    assert!(same::<prelude::MatchResult, worker_capabilities::MatchResult>());
+    assert!(same::<
+        prelude::CapabilityScope,
+        worker_capabilities::CapabilityScope,
+    >());
+}
+
+#[test]
+fn prelude_alone_covers_registration_and_matching() {
+    use worker_capabilities::prelude::*;
+
+    let mut registry = CapabilityRegistry::new();
+    registry.register(Capabilities::new("worker-1").with_static_analysis("clippy", true));
+    let requirements = Requirements::new().requires("static_analysis");
+    let matched = registry.find_satisfying(&requirements, &|_: &str| true);
+    assert_eq!(matched.len(), 1);
+}
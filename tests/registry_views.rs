@@ -0,0 +1,72 @@
+//! Registry views filter what untrusted consumers can see
+
+use worker_capabilities::*;
+
+fn registry() -> CapabilityRegistry {
+    let ci = CapabilitySigner::new("ci-key", "ci");
+    let mut registry = CapabilityRegistry::new();
+    registry.register(
+        Capabilities::new("worker-1")
+            .with_attested_tool(CAPABILITY_STATIC_ANALYSIS, "clippy", true, &ci)
+            .with_metadata("region", "eu-west-1")
+            .with_metadata("secret.api_token", "hunter2"),
+    );
+    registry
+}
+
+#[test]
+fn hidden_metadata_cannot_be_read_or_probed() {
+    let registry = registry();
+    let view = registry.view(VisibilityProfile::full().with_hidden_metadata_prefix("secret."));
+
+    let caps = view.get("worker-1").unwrap();
+    assert_eq!(
+        caps.get_metadata("region").map(String::as_str),
+        Some("eu-west-1")
+    );
+    assert!(caps.get_metadata("secret.api_token").is_none());
+    assert_eq!(
+        view.find_workers_with_metadata("region", "eu-west-1").len(),
+        1
+    );
+    assert!(view
+        .find_workers_with_metadata("secret.api_token", "hunter2")
+        .is_empty());
+}
+
+#[test]
+fn keys_and_attestations_can_be_hidden() {
+    let registry = registry();
+
+    let keyless = registry.view(VisibilityProfile::full().with_hidden_keys());
+    let attestation = keyless.get("worker-1").unwrap().static_analysis_tools[0]
+        .attestation
+        .clone()
+        .unwrap();
+    assert!(attestation.signature.is_empty());
+    assert!(attestation.public_key.is_empty());
+    assert_eq!(attestation.attester, "ci");
+
+    let restricted = registry.view(VisibilityProfile::restricted());
+    let caps = restricted.get("worker-1").unwrap();
+    assert!(caps.static_analysis_tools[0].attestation.is_none());
+    assert!(caps.metadata.is_empty());
+}
+
+#[test]
+fn matching_runs_on_the_full_capabilities() {
+    let registry = registry();
+    let view = registry.view(VisibilityProfile::restricted());
+
+    assert_eq!(view.list_ids(), vec!["worker-1"]);
+    assert_eq!(
+        view.find_with_capability(CAPABILITY_STATIC_ANALYSIS, &|tool: &str| tool == "clippy")
+            .len(),
+        1
+    );
+    assert_eq!(view.get_statistics().total_workers, 1);
+    // The registry itself is untouched
+    assert!(registry.get("worker-1").unwrap().static_analysis_tools[0]
+        .attestation
+        .is_some());
+}
@@ -0,0 +1,121 @@
+//! Registration must reject replayed, counterless and stale attestations
+
+use std::time::Duration;
+
+use worker_capabilities::clock::{self, MockClock};
+use worker_capabilities::*;
+
+fn signer() -> CapabilitySigner {
+    CapabilitySigner::new("key", "ci")
+}
+
+fn algorithms() -> AlgorithmRegistry {
+    AlgorithmRegistry::empty().with_algorithm(DEFAULT_ATTESTATION_ALGORITHM, MockVerifier)
+}
+
+/// Clippy attested with the given issuance counter
+fn clippy(counter: Option<u64>) -> ToolCapability {
+    let tool = ToolCapability::new("clippy", true);
+    let attestation = signer().attest_hash_with(&tool.generate_capability_hash(), None, counter);
+    tool.with_attestation(attestation)
+}
+
+fn worker_with(tool: ToolCapability) -> Capabilities {
+    let mut caps = Capabilities::new("worker-1");
+    caps.add_tools_from(CAPABILITY_STATIC_ANALYSIS, &[tool]);
+    caps
+}
+
+fn worker(counter: Option<u64>) -> Capabilities {
+    worker_with(clippy(counter))
+}
+
+#[test]
+fn lower_or_equal_counter_is_rejected_as_replay() {
+    let mut registry = CapabilityRegistry::new().with_algorithms(algorithms());
+    registry.try_register(worker(Some(5))).unwrap();
+    assert_eq!(
+        registry.last_attestation_counter("worker-1", "clippy"),
+        Some(5)
+    );
+
+    assert_eq!(
+        registry.try_register(worker(Some(4))),
+        Err(vec![ValidationError::ReplayedAttestation {
+            tool: "clippy".to_string(),
+            counter: 4,
+            last_seen: 5,
+        }])
+    );
+    assert_eq!(
+        registry.try_register(worker(Some(5))),
+        Err(vec![ValidationError::ReplayedAttestation {
+            tool: "clippy".to_string(),
+            counter: 5,
+            last_seen: 5,
+        }])
+    );
+    registry.try_register(worker(Some(6))).unwrap();
+}
+
+#[test]
+fn counter_cannot_be_omitted_once_seen() {
+    let mut registry = CapabilityRegistry::new().with_algorithms(algorithms());
+    registry.try_register(worker(Some(5))).unwrap();
+
+    assert_eq!(
+        registry.try_register(worker(None)),
+        Err(vec![ValidationError::MissingAttestationCounter {
+            tool: "clippy".to_string(),
+            last_seen: 5,
+        }])
+    );
+}
+
+#[test]
+fn unverified_attestations_do_not_advance_counters() {
+    let mut registry = CapabilityRegistry::new().with_algorithms(algorithms());
+    let mut forged = clippy(Some(u64::MAX));
+    forged.attestation.as_mut().unwrap().signature = "forged".to_string();
+    registry.try_register(worker_with(forged)).unwrap();
+    assert_eq!(
+        registry.last_attestation_counter("worker-1", "clippy"),
+        None
+    );
+
+    // The legitimate worker is not locked out by the forged counter
+    registry.try_register(worker(Some(1))).unwrap();
+    assert_eq!(
+        registry.last_attestation_counter("worker-1", "clippy"),
+        Some(1)
+    );
+}
+
+#[test]
+fn max_age_grants_the_expiration_tolerance() {
+    let clock = MockClock::at_unix(1_700_000_000);
+    clock::with_clock(clock.clone(), || {
+        let caps = worker(None);
+        clock.advance(Duration::from_secs(90));
+
+        let mut strict = CapabilityRegistry::new()
+            .with_algorithms(algorithms())
+            .with_max_attestation_age(60);
+        assert_eq!(
+            strict.try_register(caps.clone()),
+            Err(vec![ValidationError::StaleAttestation {
+                tool: "clippy".to_string(),
+            }])
+        );
+
+        let mut tolerant = CapabilityRegistry::new()
+            .with_algorithms(
+                algorithms().with_expiration_tolerance(ExpirationTolerance::new(0, 60)),
+            )
+            .with_max_attestation_age(60);
+        tolerant.try_register(caps.clone()).unwrap();
+
+        clock.advance(Duration::from_secs(60));
+        assert!(tolerant.try_register(caps).is_err());
+    });
+}
@@ -0,0 +1,42 @@
+//! Jobs demanding a report format only go to workers that emit it
+
+use worker_capabilities::*;
+
+#[test]
+fn sarif_jobs_go_to_sarif_emitters() {
+    let sarif = Requirements::new().with_output(ReportFormat::Sarif);
+    let emitter = Capabilities::new("semgrep")
+        .emitting(ReportFormat::Sarif)
+        .emitting(ReportFormat::Json);
+    let json_only = Capabilities::new("custom").emitting(ReportFormat::Json);
+
+    assert!(emitter.emits_format(&ReportFormat::Sarif));
+    assert!(emitter.meets_requirements(&sarif));
+    assert!(!json_only.meets_requirements(&sarif));
+    assert!(Capabilities::new("any").meets_requirements(&Requirements::new()));
+}
+
+#[test]
+fn formats_are_declared_once() {
+    let caps = Capabilities::new("worker")
+        .emitting(ReportFormat::JunitXml)
+        .emitting(ReportFormat::JunitXml);
+    assert_eq!(caps.emits, vec![ReportFormat::JunitXml]);
+}
+
+#[test]
+fn identifiers_round_trip_and_keep_unknown_formats() {
+    for format in [
+        ReportFormat::Sarif,
+        ReportFormat::Json,
+        ReportFormat::JunitXml,
+        ReportFormat::CycloneDx,
+    ] {
+        assert_eq!(ReportFormat::parse(format.as_str()), format);
+    }
+    assert_eq!(ReportFormat::parse("SARIF"), ReportFormat::Sarif);
+    assert_eq!(
+        ReportFormat::parse("spdx"),
+        ReportFormat::Other("spdx".to_string())
+    );
+}
@@ -0,0 +1,74 @@
+//! Scoped workers and tools only match targets within scope, and scope is attested
+
+use worker_capabilities::*;
+
+fn signer() -> CapabilitySigner {
+    CapabilitySigner::new("key", "ci")
+}
+
+fn any_tool(_: &str) -> bool {
+    true
+}
+
+#[test]
+fn scopes_match_patterns_and_organizations() {
+    let scope = CapabilityScope::new()
+        .with_repository("github.com/acme/*")
+        .with_organization("Initech");
+
+    assert!(scope.allows("https://github.com/acme/widgets.git"));
+    assert!(scope.allows("github.com/initech/tps-reports"));
+    assert!(!scope.allows("github.com/globex/widgets"));
+    assert!(!scope.allows("gitlab.com/acme/widgets"));
+    assert!(CapabilityScope::new().allows("anything"));
+}
+
+#[test]
+fn matching_enforces_worker_and_tool_scope() {
+    let acme = CapabilityScope::new().with_organization("acme");
+    let mut registry = CapabilityRegistry::new();
+    registry.register(
+        Capabilities::new("acme-only")
+            .with_static_analysis("clippy", true)
+            .with_scope(acme.clone()),
+    );
+    let mut tool_scoped = Capabilities::new("tool-scoped");
+    tool_scoped
+        .static_analysis_tools
+        .push(ToolCapability::new("clippy", true).with_scope(acme));
+    registry.register(tool_scoped);
+    registry.register(Capabilities::new("open").with_static_analysis("clippy", true));
+
+    let ids = |repository: &str| {
+        let mut ids: Vec<String> = registry
+            .find_with_capability_for_target(CAPABILITY_STATIC_ANALYSIS, &any_tool, repository)
+            .into_iter()
+            .map(|caps| caps.id.clone())
+            .collect();
+        ids.sort();
+        ids
+    };
+    assert_eq!(
+        ids("github.com/acme/widgets"),
+        vec!["acme-only", "open", "tool-scoped"]
+    );
+    assert_eq!(ids("github.com/globex/widgets"), vec!["open"]);
+}
+
+#[test]
+fn widening_scope_breaks_attestations() {
+    let tool = ToolCapability::new("clippy", true)
+        .with_scope(CapabilityScope::new().with_repository("github.com/acme/widgets"));
+    let mut tool = tool.clone().with_attestation(signer().attest(&tool));
+    assert!(tool.verify_attestation_with(&MockVerifier));
+    tool.scope = tool.scope.clone().with_organization("acme");
+    assert!(!tool.verify_attestation_with(&MockVerifier));
+
+    let mut caps = Capabilities::new("worker-1")
+        .with_scope(CapabilityScope::new().with_organization("acme"))
+        .attest(&signer())
+        .unwrap();
+    assert!(caps.verify_set_attestation_with(&MockVerifier));
+    caps.scope = CapabilityScope::new();
+    assert!(!caps.verify_set_attestation_with(&MockVerifier));
+}
@@ -0,0 +1,42 @@
+//! Required tools declared with alternatives are satisfied by any of them
+
+use worker_capabilities::*;
+
+fn formatter() -> Capabilities {
+    Capabilities::new("worker").with_required_alternative("rustfmt", vec!["cargo-fmt"])
+}
+
+#[test]
+fn required_alternative_is_required() {
+    let caps = formatter();
+    let tool = caps.find_tool("rustfmt").unwrap();
+    assert!(tool.required);
+    assert_eq!(tool.alternatives, vec!["cargo-fmt"]);
+    assert!(
+        !Capabilities::new("worker")
+            .with_alternative("rustfmt", vec!["cargo-fmt"])
+            .find_tool("rustfmt")
+            .unwrap()
+            .required
+    );
+}
+
+#[test]
+fn any_alternative_satisfies_the_requirement() {
+    let caps = formatter();
+    assert!(caps.has_all_required_tools(&|name: &str| name == "rustfmt"));
+    assert!(caps.has_all_required_tools(&|name: &str| name == "cargo-fmt"));
+    assert!(!caps.has_all_required_tools(&|name: &str| name == "prettier"));
+}
+
+#[test]
+fn optional_alternatives_are_not_required() {
+    let caps = Capabilities::new("worker").with_alternative("rustfmt", vec!["cargo-fmt"]);
+    assert!(caps.has_all_required_tools(&|_: &str| false));
+
+    let built = CapabilitiesBuilder::new("worker")
+        .with_required_alternative("rustfmt", vec!["cargo-fmt"])
+        .build()
+        .unwrap();
+    assert!(!built.has_all_required_tools(&|_: &str| false));
+}
@@ -0,0 +1,72 @@
+//! Requirements are matched clause by clause, reporting the tools used
+
+use worker_capabilities::*;
+
+fn job() -> Requirements {
+    Requirements::new()
+        .requires(CAPABILITY_STATIC_ANALYSIS)
+        .requires_any_of([CAPABILITY_FUZZING, CAPABILITY_DYNAMIC_ANALYSIS])
+}
+
+#[test]
+fn clauses_report_the_category_and_tool_used() {
+    let caps = Capabilities::new("worker-1")
+        .with_static_analysis("clippy", true)
+        .with_dynamic_tool("valgrind", true);
+    let result = caps.satisfies(&job(), &|tool: &str| tool != "cargo-fuzz");
+
+    assert!(result.is_match());
+    assert_eq!(result.worker_id, "worker-1");
+    assert_eq!(result.tools_used(), vec!["clippy", "valgrind"]);
+    assert_eq!(
+        result.clauses[1].category.as_deref(),
+        Some(CAPABILITY_DYNAMIC_ANALYSIS)
+    );
+    assert!(result.clauses[1].status.as_ref().unwrap().available);
+}
+
+#[test]
+fn alternatives_in_a_clause_are_tried_in_order() {
+    let caps = Capabilities::new("worker-1")
+        .with_static_analysis("clippy", true)
+        .with_fuzzing_tool("cargo-fuzz", true)
+        .with_dynamic_tool("valgrind", true);
+    let result = caps.satisfies(&job(), &|_: &str| true);
+    assert_eq!(
+        result.clauses[1].category.as_deref(),
+        Some(CAPABILITY_FUZZING)
+    );
+}
+
+#[test]
+fn unavailable_tools_leave_clauses_unmatched() {
+    let caps = Capabilities::new("worker-1")
+        .with_static_analysis("clippy", true)
+        .with_fuzzing_tool("cargo-fuzz", true);
+    let result = caps.satisfies(&job(), &|tool: &str| tool == "clippy");
+
+    assert!(!result.is_match());
+    assert_eq!(
+        result.unmatched_clauses(),
+        vec![&RequirementClause {
+            any_of: vec![
+                CAPABILITY_FUZZING.to_string(),
+                CAPABILITY_DYNAMIC_ANALYSIS.to_string()
+            ],
+        }]
+    );
+}
+
+#[test]
+fn environment_requirements_are_part_of_the_match() {
+    let caps = Capabilities::new("worker-1").with_static_analysis("clippy", true);
+    let result = caps.satisfies(
+        &Requirements::new()
+            .requires(CAPABILITY_STATIC_ANALYSIS)
+            .requires_internet(),
+        &|_: &str| true,
+    );
+    assert!(result.clauses[0].is_matched());
+    assert!(!result.environment_met);
+    assert!(!result.is_match());
+}
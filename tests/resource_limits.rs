@@ -0,0 +1,39 @@
+//! Declared limits that cannot be represented are rejected, not wrapped
+
+use worker_capabilities::*;
+
+#[test]
+fn oversized_memory_limits_are_rejected() {
+    let permissions = CapabilityPermissions {
+        memory_limit_mb: u64::MAX / 1024,
+        ..CapabilityPermissions::default()
+    };
+    assert_eq!(
+        permissions.memory_limit_bytes(),
+        Err(LimitError::MemoryLimitTooLarge {
+            limit_mb: u64::MAX / 1024
+        })
+    );
+
+    let default = CapabilityPermissions::default();
+    assert_eq!(
+        default.memory_limit_bytes(),
+        Ok(default.memory_limit_mb * 1024 * 1024)
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn limits_are_not_applied_when_the_memory_limit_overflows() {
+    let mut child = std::process::Command::new("true").spawn().unwrap();
+    let permissions = CapabilityPermissions {
+        memory_limit_mb: u64::MAX,
+        ..CapabilityPermissions::default()
+    };
+    let result = permissions.apply_limits_in(&child, std::path::Path::new("/nonexistent"));
+    assert!(matches!(
+        result,
+        Err(LimitError::MemoryLimitTooLarge { .. })
+    ));
+    child.wait().unwrap();
+}
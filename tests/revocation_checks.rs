@@ -0,0 +1,90 @@
+//! Revoked workers must not verify, and unknown revocation status fails closed
+
+use worker_capabilities::*;
+
+fn algorithms() -> AlgorithmRegistry {
+    AlgorithmRegistry::empty().with_algorithm(DEFAULT_ATTESTATION_ALGORITHM, MockVerifier)
+}
+
+fn attested_worker(id: &str) -> Capabilities {
+    Capabilities::new(id).with_attested_tool(
+        CAPABILITY_STATIC_ANALYSIS,
+        "clippy",
+        true,
+        &CapabilitySigner::new("key", "ci"),
+    )
+}
+
+fn list(sequence: u64) -> RevocationList {
+    RevocationList::new("coordinator-a")
+        .with_sequence(sequence)
+        .with_entry(RevocationEntry::worker("worker-1", "compromised"))
+}
+
+#[test]
+fn checkers_match_revoked_workers() {
+    let caps = attested_worker("worker-1");
+    let attestation = caps
+        .iter_tools()
+        .next()
+        .unwrap()
+        .1
+        .attestation
+        .clone()
+        .unwrap();
+    let list = list(1);
+
+    // Attestations carry no worker ID, so the hash-only lookup misses the entry
+    assert_eq!(list.status(&attestation), Ok(RevocationStatus::Good));
+    assert!(matches!(
+        list.worker_status("worker-1", &attestation),
+        Ok(RevocationStatus::Revoked { .. })
+    ));
+    assert_eq!(
+        list.worker_status("worker-2", &attestation),
+        Ok(RevocationStatus::Good)
+    );
+}
+
+#[test]
+fn registry_verification_consults_the_checker() {
+    let mut registry = CapabilityRegistry::new()
+        .with_algorithms(algorithms())
+        .with_revocation_checker(list(1));
+    registry.register(attested_worker("worker-1"));
+    registry.register(attested_worker("worker-2"));
+
+    let verified = registry.verify_all_workers();
+    assert_eq!(verified.get("worker-1"), Some(&false));
+    assert_eq!(verified.get("worker-2"), Some(&true));
+}
+
+/// Checker whose revocation service is unreachable
+struct Unreachable;
+
+impl RevocationChecker for Unreachable {
+    fn status(
+        &self,
+        _attestation: &CapabilityAttestation,
+    ) -> Result<RevocationStatus, RevocationCheckError> {
+        Err(RevocationCheckError::Transport(
+            "connection refused".to_string(),
+        ))
+    }
+}
+
+#[test]
+fn unknown_revocation_status_fails_closed_by_default() {
+    let mut hard_fail = CapabilityRegistry::new()
+        .with_algorithms(algorithms())
+        .with_revocation_checker(Unreachable);
+    hard_fail.register(attested_worker("worker-1"));
+    assert!(hard_fail.find_verified_workers().is_empty());
+
+    let mut soft_fail = CapabilityRegistry::new()
+        .with_algorithms(algorithms())
+        .with_revocation_checker(Unreachable)
+        .with_revocation_failure_policy(RevocationFailurePolicy::SoftFail);
+    soft_fail.register(attested_worker("worker-1"));
+    assert_eq!(soft_fail.find_verified_workers().len(), 1);
+}
@@ -0,0 +1,155 @@
+//! Revocation lists must be signed by a trust anchor and must not go backwards
+
+use worker_capabilities::*;
+
+fn coordinator() -> CapabilitySigner {
+    CapabilitySigner::new("coordinator-key", "coordinator-a")
+}
+
+fn attested_worker(id: &str) -> Capabilities {
+    Capabilities::new(id).with_attested_tool(
+        CAPABILITY_STATIC_ANALYSIS,
+        "clippy",
+        true,
+        &CapabilitySigner::new("key", "ci"),
+    )
+}
+
+/// Registry pinning the coordinator's key as a trust anchor
+fn registry() -> CapabilityRegistry {
+    let anchor = TrustAnchor {
+        name: "coordinator-a".to_string(),
+        public_key: coordinator().public_key(),
+    };
+    let mut registry = CapabilityRegistry::new().with_trust_anchor(anchor);
+    registry.register(attested_worker("worker-1"));
+    registry
+}
+
+fn list(sequence: u64) -> RevocationList {
+    RevocationList::new("coordinator-a")
+        .with_sequence(sequence)
+        .with_entry(RevocationEntry::worker("worker-1", "compromised"))
+}
+
+fn is_revoked(registry: &CapabilityRegistry) -> bool {
+    registry
+        .get("worker-1")
+        .unwrap()
+        .iter_tools()
+        .all(|(_, tool)| tool.is_revoked())
+}
+
+#[test]
+fn anchor_signed_list_is_imported() {
+    let mut registry = registry();
+    assert_eq!(
+        registry.import_revocations(&list(1).sign(&coordinator()), &MockVerifier),
+        Ok(1)
+    );
+    assert!(is_revoked(&registry));
+}
+
+#[test]
+fn unsigned_and_tampered_lists_are_rejected() {
+    let mut registry = registry();
+    assert_eq!(
+        registry.import_revocations(&list(1), &MockVerifier),
+        Err(RevocationListError::DigestMismatch)
+    );
+
+    let mut tampered = list(1).sign(&coordinator());
+    tampered
+        .entries
+        .push(RevocationEntry::worker("worker-2", "spite"));
+    assert_eq!(
+        registry.import_revocations(&tampered, &MockVerifier),
+        Err(RevocationListError::DigestMismatch)
+    );
+
+    let mut forged = list(1).sign(&coordinator());
+    forged.signature = "forged".to_string();
+    assert_eq!(
+        registry.import_revocations(&forged, &MockVerifier),
+        Err(RevocationListError::InvalidSignature)
+    );
+    assert!(!is_revoked(&registry));
+}
+
+#[test]
+fn lists_signed_by_unpinned_keys_are_rejected() {
+    let mut registry = registry();
+    let impostor = CapabilitySigner::new("other-key", "coordinator-a");
+    assert_eq!(
+        registry.import_revocations(&list(1).sign(&impostor), &MockVerifier),
+        Err(RevocationListError::UntrustedSigner(
+            "coordinator-a".to_string()
+        ))
+    );
+
+    // Without trust anchors no list is trusted
+    let mut unpinned = CapabilityRegistry::new();
+    assert!(matches!(
+        unpinned.import_revocations(&list(1).sign(&coordinator()), &MockVerifier),
+        Err(RevocationListError::UntrustedSigner(_))
+    ));
+}
+
+#[test]
+fn older_lists_are_rejected() {
+    let mut registry = registry();
+    let empty = |sequence| {
+        RevocationList::new("coordinator-a")
+            .with_sequence(sequence)
+            .sign(&coordinator())
+    };
+    assert_eq!(registry.import_revocations(&empty(2), &MockVerifier), Ok(0));
+    assert_eq!(registry.import_revocations(&empty(2), &MockVerifier), Ok(0));
+    assert_eq!(
+        registry.import_revocations(&list(1).sign(&coordinator()), &MockVerifier),
+        Err(RevocationListError::StaleSequence {
+            issuer: "coordinator-a".to_string(),
+            current: 2,
+            received: 1,
+        })
+    );
+    assert!(!is_revoked(&registry));
+}
+
+#[test]
+fn lists_must_name_their_signing_anchor() {
+    let other = CapabilitySigner::new("other-key", "coordinator-b");
+    let mut registry = registry().with_trust_anchor(TrustAnchor {
+        name: "coordinator-b".to_string(),
+        public_key: other.public_key(),
+    });
+
+    // Coordinator B cannot block coordinator A's lists by issuing in its name
+    let spoofed = list(u64::MAX).sign(&other);
+    assert_eq!(
+        registry.import_revocations(&spoofed, &MockVerifier),
+        Err(RevocationListError::IssuerMismatch {
+            issuer: "coordinator-a".to_string(),
+            anchor: "coordinator-b".to_string(),
+        })
+    );
+    assert_eq!(
+        registry.import_revocations(&list(1).sign(&coordinator()), &MockVerifier),
+        Ok(1)
+    );
+}
+
+#[test]
+fn outdated_lists_are_rejected() {
+    let mut registry = registry().with_max_revocation_list_age(60 * 60);
+    let mut old = list(1);
+    old.issued_at -= 2 * 24 * 60 * 60;
+    let old = old.sign(&coordinator());
+    assert_eq!(
+        registry.import_revocations(&old, &MockVerifier),
+        Err(RevocationListError::Outdated {
+            issued_at: old.issued_at
+        })
+    );
+    assert!(!is_revoked(&registry));
+}
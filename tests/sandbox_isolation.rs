@@ -0,0 +1,53 @@
+//! Jobs are matched on the isolation and limits of a worker's sandbox
+
+use std::collections::HashMap;
+
+use worker_capabilities::well_known::{DOCKER_SUPPORT, FIRECRACKER_SUPPORT};
+use worker_capabilities::*;
+
+fn worker(technology: SandboxTechnology, memory_limit_mb: Option<u64>) -> Capabilities {
+    Capabilities::new("worker").with_sandbox(SandboxCapability::new(technology).with_limits(
+        SandboxLimits {
+            memory_limit_mb,
+            ..SandboxLimits::default()
+        },
+    ))
+}
+
+#[test]
+fn stronger_isolation_satisfies_weaker_requirements() {
+    let needs_vm = Requirements::new().requires_isolation(IsolationLevel::VirtualMachine);
+    let needs_container = Requirements::new().requires_isolation(IsolationLevel::Container);
+
+    let firecracker = worker(SandboxTechnology::Firecracker, None);
+    let docker = worker(SandboxTechnology::Docker, None);
+    assert!(firecracker.meets_requirements(&needs_vm));
+    assert!(firecracker.meets_requirements(&needs_container));
+    assert!(!docker.meets_requirements(&needs_vm));
+    assert!(docker.meets_requirements(&needs_container));
+    assert!(!worker(SandboxTechnology::None, None).meets_requirements(&needs_container));
+}
+
+#[test]
+fn sandbox_memory_must_be_declared_and_sufficient() {
+    let needs_memory = Requirements::new().requires_sandbox_memory(2048);
+    assert!(worker(SandboxTechnology::GVisor, Some(4096)).meets_requirements(&needs_memory));
+    assert!(!worker(SandboxTechnology::GVisor, Some(1024)).meets_requirements(&needs_memory));
+    assert!(!worker(SandboxTechnology::GVisor, None).meets_requirements(&needs_memory));
+}
+
+#[test]
+fn legacy_flags_convert_to_the_strongest_technology() {
+    let flags = HashMap::from([
+        (DOCKER_SUPPORT.to_string(), true),
+        (FIRECRACKER_SUPPORT.to_string(), true),
+    ]);
+    let sandbox = SandboxCapability::from_legacy_flags(&flags);
+    assert_eq!(sandbox.technology, SandboxTechnology::Firecracker);
+
+    let disabled = HashMap::from([(FIRECRACKER_SUPPORT.to_string(), false)]);
+    assert_eq!(
+        SandboxCapability::from_legacy_flags(&disabled).technology,
+        SandboxTechnology::None
+    );
+}
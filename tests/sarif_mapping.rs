@@ -0,0 +1,59 @@
+//! SARIF runs are traced back to the registered tools that produced them
+
+use serde_json::json;
+use worker_capabilities::*;
+
+fn worker() -> Capabilities {
+    let mut caps = Capabilities::new("worker").with_security_tool("trivy", true);
+    caps.static_analysis_tools
+        .push(ToolCapability::new("semgrep", true).with_version("v1.50.0"));
+    caps.static_analysis_tools
+        .push(ToolCapability::new("clippy", true).with_version("0.1.75"));
+    caps
+}
+
+#[test]
+fn drivers_map_to_tool_names_and_back() {
+    let driver = SarifDriver::for_tool("codeql");
+    assert_eq!(driver.name, "CodeQL");
+    assert_eq!(driver.tool_name(), "codeql");
+    assert!(driver.information_uri.is_some());
+
+    let unknown = SarifDriver::for_tool("MyLinter");
+    assert_eq!(unknown.name, "MyLinter");
+    assert_eq!(unknown.information_uri, None);
+    assert_eq!(unknown.tool_name(), "mylinter");
+}
+
+#[test]
+fn runs_are_correlated_with_declared_versions() {
+    let log = json!({
+        "version": "2.1.0",
+        "runs": [
+            { "tool": { "driver": { "name": "Semgrep OSS", "semanticVersion": "1.50.0" } } },
+            { "tool": { "driver": { "name": "clippy", "version": "0.1.76" } } },
+            { "tool": { "driver": { "name": "Trivy" } } },
+            { "tool": { "driver": { "name": "Bandit", "version": "1.7.5" } } },
+        ]
+    });
+    let runs = worker().correlate_sarif_log(&log);
+    assert_eq!(runs.len(), 4);
+
+    assert_eq!(runs[0].tool_name.as_deref(), Some("semgrep"));
+    assert_eq!(runs[0].version_matches, Some(true));
+
+    assert_eq!(runs[1].tool_name.as_deref(), Some("clippy"));
+    assert_eq!(runs[1].reported_version.as_deref(), Some("0.1.76"));
+    assert_eq!(runs[1].version_matches, Some(false));
+
+    assert_eq!(runs[2].tool_name.as_deref(), Some("trivy"));
+    assert_eq!(runs[2].version_matches, None);
+
+    assert_eq!(runs[3].run_index, 3);
+    assert_eq!(runs[3].tool_name, None);
+}
+
+#[test]
+fn logs_without_runs_have_no_drivers() {
+    assert!(SarifDriver::from_sarif_log(&json!({ "version": "2.1.0" })).is_empty());
+}
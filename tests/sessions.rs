@@ -0,0 +1,97 @@
+//! Sessions live as long as heartbeats arrive, up to the tool's timeout
+
+use std::time::Duration;
+
+use worker_capabilities::clock::MockClock;
+use worker_capabilities::*;
+
+const LIFETIME: u64 = 3600;
+
+fn registry(clock: &MockClock) -> CapabilityRegistry {
+    let mut fuzzer = ToolCapability::new("cargo-fuzz", true);
+    fuzzer.permissions.timeout_seconds = LIFETIME;
+    let mut caps = Capabilities::new("worker-1");
+    caps.fuzzing_tools.push(fuzzer);
+
+    let mut registry = CapabilityRegistry::new().with_clock(clock.clone());
+    registry.register(caps);
+    registry
+}
+
+fn open(registry: &mut CapabilityRegistry) -> String {
+    registry
+        .open_session("worker-1", CAPABILITY_FUZZING, &|_: &str| true)
+        .unwrap()
+}
+
+fn seconds(seconds: u64) -> Duration {
+    Duration::from_secs(seconds)
+}
+
+#[test]
+fn heartbeats_extend_sessions_and_record_progress() {
+    let clock = MockClock::at_unix(1_700_000_000);
+    let mut registry = registry(&clock);
+    let id = open(&mut registry);
+    let session = registry.get_session(&id).unwrap();
+    assert_eq!(session.tool_name, "cargo-fuzz");
+    assert_eq!(session.max_lifetime_seconds, LIFETIME);
+
+    clock.advance(seconds(DEFAULT_SESSION_HEARTBEAT_TIMEOUT_SECONDS));
+    registry
+        .session_heartbeat(&id, Some(150), Some("corpus 1200".to_string()))
+        .unwrap();
+    clock.advance(seconds(DEFAULT_SESSION_HEARTBEAT_TIMEOUT_SECONDS));
+    assert!(registry.expire_sessions().is_empty());
+
+    let session = registry.get_session(&id).unwrap();
+    assert_eq!(session.progress_percent, Some(100));
+    assert_eq!(session.progress_message.as_deref(), Some("corpus 1200"));
+
+    clock.advance(seconds(1));
+    assert_eq!(registry.expire_sessions(), vec![id.clone()]);
+    let session = registry.get_session(&id).unwrap();
+    assert_eq!(session.state, SessionState::Expired);
+    assert_eq!(session.end_reason.as_deref(), Some("heartbeat timeout"));
+}
+
+#[test]
+fn heartbeats_cannot_outlive_the_tool_timeout() {
+    let clock = MockClock::at_unix(1_700_000_000);
+    let mut registry = registry(&clock);
+    let id = open(&mut registry);
+
+    for _ in 0..LIFETIME / 100 {
+        clock.advance(seconds(100));
+        registry.session_heartbeat(&id, None, None).unwrap();
+    }
+    assert!(registry.expire_sessions().is_empty());
+
+    clock.advance(seconds(1));
+    assert_eq!(registry.expire_sessions(), vec![id.clone()]);
+    assert_eq!(
+        registry.get_session(&id).unwrap().end_reason.as_deref(),
+        Some("maximum lifetime exceeded")
+    );
+}
+
+#[test]
+fn cancelled_sessions_stop_accepting_heartbeats() {
+    let clock = MockClock::at_unix(1_700_000_000);
+    let mut registry = registry(&clock);
+    let id = open(&mut registry);
+    assert_eq!(registry.active_sessions_for_worker("worker-1").len(), 1);
+
+    registry
+        .cancel_session(&id, "campaign aborted".to_string())
+        .unwrap();
+    assert!(registry.active_sessions_for_worker("worker-1").is_empty());
+    assert_eq!(
+        registry.session_heartbeat(&id, None, None),
+        Err(SessionError::NotActive(SessionState::Cancelled))
+    );
+
+    clock.advance(seconds(SESSION_RETENTION_SECONDS + 1));
+    registry.expire_sessions();
+    assert!(registry.get_session(&id).is_none());
+}
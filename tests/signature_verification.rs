@@ -0,0 +1,202 @@
+//! Signature verification must reject forged and tampered attestations
+
+use worker_capabilities::*;
+
+fn signer() -> CapabilitySigner {
+    CapabilitySigner::new("key", "ci")
+}
+
+/// Tool attested with `SHA256-RSA`, carrying a signature nobody made
+fn forged_tool() -> ToolCapability {
+    let tool = ToolCapability::new("clippy", true);
+    let mut attestation = signer().attest(&tool);
+    attestation.signature = "forged".to_string();
+    tool.with_attestation(attestation)
+}
+
+#[test]
+fn builtin_algorithms_reject_unverifiable_signatures() {
+    let algorithms = AlgorithmRegistry::new();
+    assert!(!algorithms.supports(DEFAULT_ATTESTATION_ALGORITHM));
+    assert!(!forged_tool().verify_attestation_with(&algorithms));
+
+    // Even a signature the mock scheme accepts needs a registered verifier
+    let tool = ToolCapability::new("clippy", true);
+    let tool = tool.clone().with_attestation(signer().attest(&tool));
+    assert!(tool.verify_attestation_with(&MockVerifier));
+    assert!(!tool.verify_attestation_with(&algorithms));
+}
+
+#[test]
+fn default_registry_does_not_verify_forged_workers() {
+    let mut caps = Capabilities::new("worker-1");
+    caps.add_tools_from("static_analysis", &[forged_tool()]);
+    let mut registry = CapabilityRegistry::new();
+    registry.register(caps);
+
+    assert_eq!(registry.verify_all_workers().get("worker-1"), Some(&false));
+    assert!(registry.find_verified_workers().is_empty());
+}
+
+fn algorithms() -> AlgorithmRegistry {
+    AlgorithmRegistry::empty()
+        .with_algorithm(DEFAULT_ATTESTATION_ALGORITHM, MockVerifier)
+        .with_algorithm(ED25519_ATTESTATION_ALGORITHM, Ed25519Verifier)
+}
+
+#[test]
+fn bundle_signatures_dispatch_on_their_declared_algorithm() {
+    let ed25519 = Ed25519Signer::new([7; 32], "coordinator");
+    let bundle = TrustBundle::new(1).sign(&ed25519);
+    assert_eq!(bundle.verify_signature(&algorithms()), Ok(()));
+
+    // A mock signature cannot pass as the Ed25519 key's signature
+    let mut forged = TrustBundle::new(1).sign(&signer());
+    forged.public_key = ed25519.public_key();
+    forged.algorithm = ED25519_ATTESTATION_ALGORITHM.to_string();
+    assert_eq!(
+        forged.verify_signature(&algorithms()),
+        Err(TrustBundleError::InvalidSignature)
+    );
+
+    // Without a declared algorithm no verifier is consulted
+    let mut unlabeled = TrustBundle::new(1).sign(&signer());
+    unlabeled.algorithm.clear();
+    assert_eq!(
+        unlabeled.verify_signature(&algorithms()),
+        Err(TrustBundleError::InvalidSignature)
+    );
+    assert!(!algorithms().verify(
+        &signer().public_key(),
+        b"message",
+        &signer().sign(b"message")
+    ));
+}
+
+#[test]
+fn signatures_cover_algorithm_and_attester() {
+    let tool = ToolCapability::new("clippy", true);
+    let attestation = Ed25519Signer::new([7; 32], "ci").attest(&tool);
+    let tool = tool.with_attestation(attestation);
+    assert!(tool.verify_attestation_with(&algorithms()));
+
+    let mut renamed = tool.clone();
+    renamed.attestation.as_mut().unwrap().attester = "release".to_string();
+    assert!(!renamed.verify_attestation_with(&algorithms()));
+
+    let bundle = TrustBundle::new(1).sign(&Ed25519Signer::new([7; 32], "coordinator"));
+    let mut renamed = bundle.clone();
+    renamed.signer = "root".to_string();
+    assert_eq!(
+        renamed.verify_signature(&algorithms()),
+        Err(TrustBundleError::InvalidSignature)
+    );
+}
+
+#[test]
+fn separators_in_values_do_not_collide() {
+    let tool = |rules: Vec<PathRule>| {
+        let mut tool = ToolCapability::new("clippy", true);
+        tool.permissions.filesystem_paths = rules;
+        tool.generate_capability_hash()
+    };
+    // "/etc:rw,/src" must not read as two rules
+    assert_ne!(
+        tool(vec![PathRule::read_only("/etc:rw,/src")]),
+        tool(vec![
+            PathRule::read_only("/src"),
+            PathRule::read_write("/etc")
+        ])
+    );
+}
+
+#[test]
+fn adjacent_limits_do_not_collide() {
+    let tool = |memory, cpu, timeout| {
+        let mut tool = ToolCapability::new("clippy", true);
+        tool.permissions.memory_limit_mb = memory;
+        tool.permissions.cpu_limit_percent = cpu;
+        tool.permissions.timeout_seconds = timeout;
+        tool.generate_capability_hash()
+    };
+    // Both read "1285030" when the limits are concatenated
+    assert_ne!(tool(128, 50, 30), tool(1, 28, 5030));
+}
+
+#[test]
+fn alternatives_are_hashed_separately() {
+    let tool = |alternatives: &[&str]| {
+        let mut tool = ToolCapability::new("clippy", true);
+        tool.alternatives = alternatives.iter().map(|name| name.to_string()).collect();
+        tool.generate_capability_hash()
+    };
+    assert_ne!(tool(&["a,b"]), tool(&["a", "b"]));
+    assert_ne!(tool(&[]), tool(&[""]));
+}
+
+#[test]
+fn capability_hash_covers_expiry() {
+    let tool = ToolCapability::new("clippy", true);
+    let mut extended = tool.clone();
+    extended.expiration.expires_at = u64::MAX;
+    assert_ne!(
+        tool.generate_capability_hash(),
+        extended.generate_capability_hash()
+    );
+}
+
+#[test]
+fn set_hash_ignores_runtime_state() {
+    let mut caps = Capabilities::new("worker-1");
+    caps.add_tools_from("static_analysis", &[ToolCapability::new("clippy", true)]);
+    let hash = caps.canonical_hash().unwrap();
+
+    let mut probed = caps.clone();
+    probed.network.probed_at = Some(1_700_000_000);
+    probed.static_analysis_tools[0].verified = true;
+    probed.static_analysis_tools[0].suspended = true;
+    assert_eq!(probed.canonical_hash().unwrap(), hash);
+
+    let mut changed = caps.clone();
+    changed.static_analysis_tools[0].required = false;
+    assert_ne!(changed.canonical_hash().unwrap(), hash);
+}
+
+#[test]
+fn issuance_counters_saturate_instead_of_wrapping() {
+    let counting = CountingSigner::new(signer(), u64::MAX - 1);
+    assert_eq!(counting.attest_hash("hash").counter, Some(u64::MAX));
+    assert_eq!(counting.attest_hash("hash").counter, Some(u64::MAX));
+    assert_eq!(counting.last_issued(), u64::MAX);
+}
+
+#[test]
+fn attested_tools_declare_custom_categories() {
+    let caps =
+        Capabilities::new("worker-1").with_attested_tool("secrets", "gitleaks", true, &signer());
+    let tools = caps.tools_in_category("secrets");
+    assert_eq!(tools.len(), 1);
+    assert!(tools[0].verify_attestation_with(&MockVerifier));
+}
+
+#[test]
+fn reports_verify_with_the_given_verifier() {
+    let caps = Capabilities::new("worker-1").with_attested_tool(
+        CAPABILITY_STATIC_ANALYSIS,
+        "clippy",
+        true,
+        &signer(),
+    );
+    assert_eq!(caps.statistics_with(&MockVerifier).verified_tools, 1);
+    assert!(caps.security_report_with(&MockVerifier)["clippy"].attestation_verified);
+    assert_eq!(
+        caps.statistics_with(&AlgorithmRegistry::empty())
+            .verified_tools,
+        0
+    );
+
+    let mut registry = CapabilityRegistry::new().with_algorithms(algorithms());
+    registry.register(caps);
+    assert_eq!(registry.get_statistics().total_verified_tools, 1);
+    assert!(registry.get_security_report()["worker-1"]["clippy"].attestation_verified);
+}
@@ -0,0 +1,217 @@
+//! Sigstore bundles verify offline against Fulcio roots and the Rekor key
+
+#![cfg(all(feature = "sigstore", feature = "ed25519"))]
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use rcgen::{
+    BasicConstraints, Certificate, CertificateParams, IsCa, KeyPair, SanType, PKCS_ED25519,
+};
+use ring::signature::{Ed25519KeyPair, KeyPair as _};
+use sha2::{Digest, Sha256};
+use worker_capabilities::clock::{self, MockClock};
+use worker_capabilities::*;
+
+const SECRET: [u8; 32] = [7; 32];
+const REKOR_SEED: [u8; 32] = [9; 32];
+const LOGGED_AT: u64 = 1_700_000_000;
+const WORKFLOW: &str =
+    "https://github.com/acme/workers/.github/workflows/release.yml@refs/heads/main";
+
+struct Fulcio {
+    root: Certificate,
+    chain: String,
+}
+
+/// Root CA and a leaf certificate for the signing key, issued to the workflow
+fn fulcio() -> Fulcio {
+    let root_key = KeyPair::generate_for(&PKCS_ED25519).unwrap();
+    let mut root_params = CertificateParams::new(Vec::new()).unwrap();
+    root_params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+    let root = root_params.self_signed(&root_key).unwrap();
+
+    let pkcs8 = [
+        &[
+            0x30, 0x2e, 0x02, 0x01, 0x00, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x04, 0x22,
+            0x04, 0x20,
+        ][..],
+        &SECRET,
+    ]
+    .concat();
+    let leaf_key = KeyPair::try_from(pkcs8.as_slice()).unwrap();
+    let mut leaf_params = CertificateParams::new(Vec::new()).unwrap();
+    leaf_params.subject_alt_names = vec![SanType::URI(WORKFLOW.try_into().unwrap())];
+    let leaf = leaf_params.signed_by(&leaf_key, &root, &root_key).unwrap();
+    Fulcio {
+        chain: leaf.pem(),
+        root,
+    }
+}
+
+fn merkle(parts: &[&[u8]]) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher.finalize().to_vec()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn rekor_key_pem() -> String {
+    let key = Ed25519KeyPair::from_seed_unchecked(&REKOR_SEED).unwrap();
+    let spki = [
+        &[
+            0x30, 0x2a, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x21, 0x00,
+        ][..],
+        key.public_key().as_ref(),
+    ]
+    .concat();
+    format!(
+        "-----BEGIN PUBLIC KEY-----\n{}\n-----END PUBLIC KEY-----\n",
+        STANDARD.encode(spki)
+    )
+}
+
+/// Bundle for a signature logged as the first of two entries
+fn bundle(chain: &str, message: &[u8]) -> SigstoreBundle {
+    let signature = Ed25519Signer::new(SECRET, "ci").sign_raw(message);
+    let body = serde_json::json!({
+        "apiVersion": "0.0.1",
+        "kind": "hashedrekord",
+        "spec": {
+            "data": { "hash": { "algorithm": "sha256", "value": hex(&Sha256::digest(message)) } },
+            "signature": {
+                "content": STANDARD.encode(&signature),
+                "publicKey": { "content": STANDARD.encode(chain) },
+            },
+        },
+    })
+    .to_string();
+
+    let sibling = merkle(&[&[0x00], b"another entry"]);
+    let root = merkle(&[&[0x01], &merkle(&[&[0x00], body.as_bytes()]), &sibling]);
+    let body = STANDARD.encode(&body);
+    let payload = format!(
+        r#"{{"body":"{}","integratedTime":{},"logID":"{}","logIndex":{}}}"#,
+        body, LOGGED_AT, "c0ffee", 0
+    );
+    let rekor = Ed25519KeyPair::from_seed_unchecked(&REKOR_SEED).unwrap();
+
+    SigstoreBundle {
+        signature: STANDARD.encode(&signature),
+        tlog_entry: RekorEntry {
+            body,
+            integrated_time: LOGGED_AT,
+            log_id: "c0ffee".to_string(),
+            log_index: 0,
+            verification: RekorVerification {
+                inclusion_proof: InclusionProof {
+                    log_index: 0,
+                    tree_size: 2,
+                    root_hash: hex(&root),
+                    hashes: vec![hex(&sibling)],
+                },
+                signed_entry_timestamp: STANDARD.encode(rekor.sign(payload.as_bytes())),
+            },
+        },
+    }
+}
+
+fn verifier(fulcio: &Fulcio) -> SigstoreVerifier {
+    SigstoreVerifier::new(X509RootStore::from_pem(&fulcio.root.pem()).unwrap())
+        .with_rekor_key(&rekor_key_pem())
+        .unwrap()
+}
+
+#[test]
+fn logged_attestations_verify_offline() {
+    let fulcio = fulcio();
+    let tool = ToolCapability::new("cargo", true);
+    let mut attestation = CapabilityAttestation {
+        capability_hash: tool.generate_capability_hash(),
+        signature: String::new(),
+        public_key: fulcio.chain.clone(),
+        timestamp: LOGGED_AT,
+        algorithm: SIGSTORE_ATTESTATION_ALGORITHM.to_string(),
+        attester: WORKFLOW.to_string(),
+        nonce: None,
+        counter: None,
+    };
+    let message = attestation.signed_message();
+    attestation.signature =
+        serde_json::to_string(&bundle(&fulcio.chain, message.as_bytes())).unwrap();
+    let tool = tool.with_attestation(attestation);
+
+    let algorithms = |verifier: SigstoreVerifier| {
+        AlgorithmRegistry::empty()
+            .with_algorithm(SIGSTORE_ATTESTATION_ALGORITHM, verifier)
+            .with_clock(MockClock::at_unix(LOGGED_AT + 60))
+    };
+    assert!(tool.verify_attestation_with(&algorithms(verifier(&fulcio).with_identity(WORKFLOW))));
+    assert!(!tool.verify_attestation_with(&algorithms(
+        verifier(&fulcio).with_identity("https://github.com/acme/other")
+    )));
+}
+
+#[test]
+fn unlogged_or_forged_bundles_are_rejected() {
+    let fulcio = fulcio();
+    let bundle = bundle(&fulcio.chain, b"message");
+    let verify = |verifier: &SigstoreVerifier, message: &[u8], bundle: &SigstoreBundle| {
+        clock::with_clock(MockClock::at_unix(LOGGED_AT), || {
+            verifier.verify_bundle(&fulcio.chain, message, bundle)
+        })
+    };
+    assert_eq!(verify(&verifier(&fulcio), b"message", &bundle), Ok(()));
+    assert_eq!(
+        verify(&verifier(&fulcio), b"another message", &bundle),
+        Err(SigstoreError::InvalidSignature)
+    );
+
+    let mut unproven = bundle.clone();
+    unproven
+        .tlog_entry
+        .verification
+        .inclusion_proof
+        .hashes
+        .clear();
+    assert!(matches!(
+        verify(&verifier(&fulcio), b"message", &unproven),
+        Err(SigstoreError::NotLogged(_))
+    ));
+
+    let without_rekor = SigstoreVerifier::new(X509RootStore::from_pem(&fulcio.root.pem()).unwrap());
+    assert!(matches!(
+        verify(&without_rekor, b"message", &bundle),
+        Err(SigstoreError::NotLogged(_))
+    ));
+
+    let other_roots = verifier(&self::fulcio());
+    assert!(matches!(
+        verify(&other_roots, b"message", &bundle),
+        Err(SigstoreError::UntrustedCertificate(_))
+    ));
+}
+
+#[test]
+fn inclusion_proofs_cover_the_last_leaf_of_odd_trees() {
+    let leaf = |data: &[u8]| merkle(&[&[0x00], data]);
+    let left = merkle(&[&[0x01], &leaf(b"a"), &leaf(b"b")]);
+    let root = merkle(&[&[0x01], &left, &leaf(b"c")]);
+    let proof = InclusionProof {
+        log_index: 2,
+        tree_size: 3,
+        root_hash: hex(&root),
+        hashes: vec![hex(&left)],
+    };
+    assert!(proof.verify(b"c"));
+    assert!(!proof.verify(b"a"));
+    assert!(!InclusionProof {
+        log_index: 3,
+        ..proof
+    }
+    .verify(b"c"));
+}
@@ -0,0 +1,56 @@
+//! Tool declarations only cover declarations they can fully stand in for
+
+use worker_capabilities::*;
+
+fn clippy() -> ToolCapability {
+    ToolCapability::new("clippy", true)
+}
+
+#[test]
+fn identical_declarations_cover_each_other() {
+    let tool = clippy()
+        .with_version("1.80.0")
+        .with_platform("linux", "x86_64")
+        .with_scope(CapabilityScope::new().with_organization("acme"));
+    assert!(tool.is_subset_of(&tool.clone()));
+}
+
+#[test]
+fn earlier_expiry_does_not_cover() {
+    let tool = clippy();
+    let mut expiring = tool.clone();
+    expiring.expiration.expires_at = tool.expiration.expires_at - 1;
+    assert!(!tool.is_subset_of(&expiring));
+    assert!(expiring.is_subset_of(&tool));
+}
+
+#[test]
+fn other_versions_and_pins_do_not_cover() {
+    let tool = clippy().with_version("1.80.0");
+    assert!(!tool.is_subset_of(&clippy().with_version("1.79.0")));
+    assert!(!tool.is_subset_of(&clippy()));
+
+    let constrained = clippy().with_version_req(">=1.80").unwrap();
+    assert!(!clippy().is_subset_of(&constrained));
+    assert!(constrained.is_subset_of(&clippy()));
+
+    let pinned = clippy().with_binary_sha256("ab".repeat(32));
+    assert!(!clippy().is_subset_of(&pinned));
+    assert!(pinned.is_subset_of(&clippy()));
+}
+
+#[test]
+fn narrower_platforms_and_scope_do_not_cover() {
+    let linux = clippy().with_platform("linux", "x86_64");
+    assert!(!clippy().is_subset_of(&linux));
+    assert!(linux.is_subset_of(&clippy()));
+    assert!(!clippy()
+        .with_platform("macos", "aarch64")
+        .is_subset_of(&linux));
+
+    let acme = clippy().with_scope(CapabilityScope::new().with_organization("acme"));
+    let repository = clippy().with_scope(CapabilityScope::new().with_repository("acme/app"));
+    assert!(!clippy().is_subset_of(&acme));
+    assert!(repository.is_subset_of(&acme));
+    assert!(!acme.is_subset_of(&repository));
+}
@@ -0,0 +1,56 @@
+//! Tags label workers and tools, and requirements can demand worker tags
+
+use worker_capabilities::*;
+
+fn gpu_worker() -> Capabilities {
+    let mut caps = Capabilities::new("gpu-1")
+        .with_tag("gpu")
+        .with_tag("eu-west")
+        .with_tag("gpu");
+    caps.add_tools_from(
+        CAPABILITY_STATIC_ANALYSIS,
+        &[ToolCapability::new("codeql", true).with_tags(["licensed", "slow", "licensed"])],
+    );
+    caps
+}
+
+#[test]
+fn tags_are_kept_once() {
+    let caps = gpu_worker();
+    assert_eq!(caps.tags, vec!["gpu", "eu-west"]);
+    assert_eq!(
+        caps.find_tool("codeql").unwrap().tags,
+        vec!["licensed", "slow"]
+    );
+    assert!(caps.has_tag("eu-west"));
+    assert!(!caps.has_tag("GPU"));
+}
+
+#[test]
+fn tools_are_found_by_tag() {
+    let caps = gpu_worker().with_security_tool("semgrep", false);
+    let licensed: Vec<&str> = caps
+        .tools_with_tag("licensed")
+        .iter()
+        .map(|tool| tool.tool_name.as_str())
+        .collect();
+    assert_eq!(licensed, vec!["codeql"]);
+    assert!(caps.tools_with_tag("fast").is_empty());
+}
+
+#[test]
+fn requirements_need_every_tag() {
+    let mut registry = CapabilityRegistry::new();
+    registry.register(gpu_worker());
+    registry.register(Capabilities::new("cpu-1").with_tag("eu-west"));
+
+    assert_eq!(registry.find_workers_with_tag("eu-west").len(), 2);
+    let gpu_in_eu = Requirements::new().requires_tags(["gpu", "eu-west"]);
+    let matched: Vec<_> = registry
+        .find_meeting_requirements(&gpu_in_eu)
+        .into_iter()
+        .map(|caps| caps.id.as_str())
+        .collect();
+    assert_eq!(matched, vec!["gpu-1"]);
+    assert!(!gpu_worker().meets_requirements(&Requirements::new().requires_tag("us-east")));
+}
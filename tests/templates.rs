@@ -0,0 +1,80 @@
+//! Workers extend base templates, declaring only their deltas
+
+use worker_capabilities::*;
+
+fn base() -> Capabilities {
+    let mut clippy = ToolCapability::new("clippy", true);
+    clippy.permissions.memory_limit_mb = 4096;
+    let mut base = Capabilities::new("base-linux-worker")
+        .with_flag("docker_support")
+        .with_metadata("os", "linux")
+        .with_payload_limits(PayloadLimits::unlimited().with_max_input_size_mb(1024));
+    base.add_tools_from(CAPABILITY_STATIC_ANALYSIS, &[clippy]);
+    base
+}
+
+#[test]
+fn deltas_resolve_against_the_base() {
+    let mut clippy = ToolCapability::new("clippy", true);
+    clippy.permissions.memory_limit_mb = 1024;
+    let mut delta = Capabilities::new("worker-1")
+        .with_fuzzing_tool("cargo-fuzz", false)
+        .with_metadata("os", "linux-musl")
+        .with_payload_limits(PayloadLimits::unlimited().with_max_input_size_mb(4096));
+    delta.add_tools_from(CAPABILITY_STATIC_ANALYSIS, &[clippy]);
+
+    let resolved = delta.extends(&base());
+    assert_eq!(resolved.id, "worker-1");
+    assert!(resolved.has_flag("docker_support"));
+    assert!(resolved.find_tool("cargo-fuzz").is_some());
+    assert_eq!(
+        resolved
+            .find_tool("clippy")
+            .unwrap()
+            .permissions
+            .memory_limit_mb,
+        1024
+    );
+    assert_eq!(
+        resolved.get_metadata("os").map(String::as_str),
+        Some("linux-musl")
+    );
+    assert_eq!(
+        resolved.payload.max_input_size_bytes,
+        Some(4096 * 1024 * 1024)
+    );
+}
+
+#[test]
+fn undeclared_sections_are_inherited() {
+    let resolved = Capabilities::new("worker-1").extends(&base());
+    assert_eq!(resolved.payload, base().payload);
+    assert_eq!(resolved.template, None);
+}
+
+#[test]
+fn registries_resolve_templates_by_name() {
+    let mut registry = CapabilityRegistry::new()
+        .with_template("base-linux-worker", base())
+        .unwrap();
+    registry
+        .register_template(
+            "base-rust-worker",
+            Capabilities::new("base-rust-worker")
+                .with_template("base-linux-worker")
+                .with_security_tool("cargo-audit", true),
+        )
+        .unwrap();
+    registry.register(Capabilities::new("worker-1").with_template("base-rust-worker"));
+
+    let worker = registry.get("worker-1").unwrap();
+    assert!(worker.find_tool("clippy").is_some());
+    assert!(worker.find_tool("cargo-audit").is_some());
+
+    assert_eq!(
+        registry.try_register(Capabilities::new("worker-2").with_template("base-macos-worker")),
+        Err(vec![ValidationError::UnknownTemplate {
+            name: "base-macos-worker".to_string(),
+        }])
+    );
+}
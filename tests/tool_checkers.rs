@@ -0,0 +1,84 @@
+//! Stateful checkers work wherever closures did
+
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::fs;
+
+use worker_capabilities::*;
+
+/// Checker with configuration and state: an inventory and a log of checked tools
+struct Inventory {
+    installed: HashSet<&'static str>,
+    checked: RefCell<Vec<String>>,
+}
+
+impl Inventory {
+    fn new(installed: &[&'static str]) -> Self {
+        Self {
+            installed: installed.iter().copied().collect(),
+            checked: RefCell::new(Vec::new()),
+        }
+    }
+}
+
+impl ToolChecker for Inventory {
+    fn is_available(&self, tool: &str) -> bool {
+        self.checked.borrow_mut().push(tool.to_string());
+        self.installed.contains(tool)
+    }
+}
+
+#[test]
+fn structs_and_closures_check_tools_alike() {
+    let caps = Capabilities::new("worker-1")
+        .with_required_alternative("rustfmt", vec!["cargo-fmt"])
+        .with_fuzzing_tool("cargo-fuzz", true);
+    let inventory = Inventory::new(&["cargo-fmt"]);
+
+    assert!(caps.static_analysis_tools[0].is_satisfied(&inventory));
+    assert!(caps.has_capability(CAPABILITY_STATIC_ANALYSIS, &inventory));
+    assert!(!caps.has_capability(CAPABILITY_FUZZING, &inventory));
+    assert!(inventory.checked.borrow().contains(&"rustfmt".to_string()));
+
+    let closure = |tool: &str| tool == "cargo-fmt";
+    assert_eq!(
+        caps.has_capability(CAPABILITY_STATIC_ANALYSIS, &closure),
+        caps.has_capability(CAPABILITY_STATIC_ANALYSIS, &inventory)
+    );
+}
+
+#[test]
+fn registry_matchers_accept_trait_objects() {
+    let mut registry = CapabilityRegistry::new();
+    registry.register(Capabilities::new("worker-1").with_static_analysis("clippy", true));
+    let inventory = Inventory::new(&["clippy"]);
+    let checker: &dyn ToolChecker = &inventory;
+
+    assert_eq!(
+        registry
+            .find_with_capability(CAPABILITY_STATIC_ANALYSIS, checker)
+            .len(),
+        1
+    );
+    assert_eq!(
+        registry.find_workers_with_all_required_tools(checker).len(),
+        1
+    );
+}
+
+#[test]
+fn path_checker_reports_where_tools_were_found() {
+    let dir = std::env::temp_dir().join(format!("worker-capabilities-path-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("clippy-driver"), b"#!/bin/sh\n").unwrap();
+
+    let checker = PathChecker::with_dirs([&dir]);
+    let status = checker.status("clippy-driver");
+    assert!(status.available);
+    assert_eq!(status.path, Some(dir.join("clippy-driver")));
+    assert!(status.sha256.is_some());
+    assert!(!checker.is_available("semgrep"));
+    assert!(!checker.is_available("../clippy-driver"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,87 @@
+//! Health tracking must follow the registered capability sets and steer matching
+
+use std::time::Duration;
+
+use worker_capabilities::*;
+
+fn registry() -> CapabilityRegistry {
+    let mut registry = CapabilityRegistry::new();
+    registry.set_demotion_policy(DemotionPolicy {
+        min_samples: 2,
+        ..DemotionPolicy::default()
+    });
+    registry.register(Capabilities::new("worker-1").with_static_analysis("clippy", true));
+    for _ in 0..2 {
+        registry.record_outcome("worker-1", "clippy", false, Duration::from_secs(1));
+    }
+    registry
+}
+
+fn is_suspended(registry: &CapabilityRegistry) -> bool {
+    registry
+        .get("worker-1")
+        .and_then(|caps| caps.find_tool("clippy"))
+        .is_some_and(|tool| tool.suspended)
+}
+
+#[test]
+fn re_registration_keeps_suspension() {
+    let mut registry = registry();
+    assert!(is_suspended(&registry));
+
+    registry.register(Capabilities::new("worker-1").with_static_analysis("clippy", true));
+    assert!(is_suspended(&registry));
+    assert!(
+        registry
+            .tool_health("worker-1", "clippy")
+            .unwrap()
+            .suspended
+    );
+
+    for _ in 0..3 {
+        registry.record_health_check("worker-1", "clippy", true);
+    }
+    assert!(!is_suspended(&registry));
+}
+
+#[test]
+fn health_of_undeclared_tools_is_forgotten() {
+    let mut registry = registry();
+    registry.register(Capabilities::new("worker-1").with_static_analysis("semgrep", true));
+    assert!(registry.tool_health("worker-1", "clippy").is_none());
+
+    registry.register(Capabilities::new("worker-1").with_static_analysis("clippy", true));
+    assert!(!is_suspended(&registry));
+}
+
+#[test]
+fn removing_a_worker_forgets_its_tool_health() {
+    let mut registry = registry();
+    registry.remove_worker("worker-1");
+    assert!(registry.tool_health("worker-1", "clippy").is_none());
+}
+
+#[test]
+fn unhealthy_workers_are_not_selected() {
+    let mut registry = CapabilityRegistry::new();
+    registry.register(Capabilities::new("worker-1").with_static_analysis("clippy", true));
+    let missing = |_: &str| false;
+    let installed = |_: &str| true;
+
+    registry.verify_worker_health("worker-1", &missing);
+    assert_eq!(
+        registry.worker_health("worker-1").unwrap().status,
+        WorkerHealth::Unhealthy
+    );
+    assert!(registry
+        .find_with_capability(CAPABILITY_STATIC_ANALYSIS, &installed)
+        .is_empty());
+
+    registry.verify_worker_health("worker-1", &installed);
+    assert_eq!(
+        registry
+            .find_with_capability(CAPABILITY_STATIC_ANALYSIS, &installed)
+            .len(),
+        1
+    );
+}
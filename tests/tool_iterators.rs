@@ -0,0 +1,48 @@
+//! Tools iterate with their category, built-in categories first
+
+use worker_capabilities::*;
+
+fn worker() -> Capabilities {
+    Capabilities::new("worker-1")
+        .with_tool_in("zk", "circom", true)
+        .with_tool_in("gpu", "cuda-memcheck", false)
+        .with_fuzzing_tool("cargo-fuzz", false)
+        .with_static_analysis("clippy", true)
+}
+
+#[test]
+fn tools_come_with_their_category() {
+    let caps = worker();
+    let tools: Vec<(&str, &str)> = caps
+        .iter_tools()
+        .map(|(category, tool)| (category, tool.tool_name.as_str()))
+        .collect();
+    assert_eq!(
+        tools,
+        vec![
+            (CAPABILITY_STATIC_ANALYSIS, "clippy"),
+            (CAPABILITY_FUZZING, "cargo-fuzz"),
+            ("gpu", "cuda-memcheck"),
+            ("zk", "circom"),
+        ]
+    );
+
+    let required: Vec<&str> = caps
+        .iter_tools()
+        .filter(|(_, tool)| tool.required)
+        .map(|(category, _)| category)
+        .collect();
+    assert_eq!(required, vec![CAPABILITY_STATIC_ANALYSIS, "zk"]);
+}
+
+#[test]
+fn tools_can_be_changed_in_place() {
+    let mut caps = worker();
+    for (category, tool) in caps.iter_tools_mut() {
+        if category != CAPABILITY_STATIC_ANALYSIS {
+            tool.required = false;
+        }
+    }
+    assert!(caps.find_tool("clippy").unwrap().required);
+    assert!(!caps.find_tool("circom").unwrap().required);
+}
@@ -0,0 +1,76 @@
+//! Tools carry their own metadata, queryable on workers and registries
+
+use worker_capabilities::*;
+
+fn worker(id: &str, license: &str) -> Capabilities {
+    let mut caps = Capabilities::new(id).with_metadata("region", "eu-west");
+    caps.add_tools_from(
+        CAPABILITY_STATIC_ANALYSIS,
+        &[ToolCapability::new("codeql", true)
+            .with_metadata("license", license)
+            .with_metadata("install_path", "/opt/codeql")],
+    );
+    caps
+}
+
+#[test]
+fn metadata_is_set_per_tool() {
+    let caps = worker("w1", "commercial").with_security_tool("semgrep", false);
+    let codeql = caps.find_tool("codeql").unwrap();
+    assert_eq!(
+        codeql.get_metadata("install_path").map(String::as_str),
+        Some("/opt/codeql")
+    );
+    assert!(caps
+        .find_tool("semgrep")
+        .unwrap()
+        .get_metadata("license")
+        .is_none());
+    assert_eq!(caps.tools_with_metadata("license", "commercial").len(), 1);
+    assert!(caps.tools_with_metadata("license", "mit").is_empty());
+}
+
+#[test]
+fn registry_separates_worker_and_tool_metadata() {
+    let mut registry = CapabilityRegistry::new();
+    registry.register(worker("w1", "commercial"));
+    registry.register(worker("w2", "mit"));
+
+    let ids = |workers: Vec<&Capabilities>| {
+        let mut ids: Vec<String> = workers.into_iter().map(|caps| caps.id.clone()).collect();
+        ids.sort();
+        ids
+    };
+    assert_eq!(
+        ids(registry.find_workers_with_tool_metadata("license", "mit")),
+        vec!["w2"]
+    );
+    assert!(registry
+        .find_workers_with_metadata("license", "mit")
+        .is_empty());
+    assert_eq!(
+        ids(registry.find_workers_with_metadata("region", "eu-west")),
+        vec!["w1", "w2"]
+    );
+    assert!(registry
+        .find_workers_with_tool_metadata("region", "eu-west")
+        .is_empty());
+}
+
+#[test]
+fn tools_without_metadata_still_deserialize() {
+    let json = serde_json::to_string(&worker("w1", "mit")).unwrap();
+    let back: Capabilities = serde_json::from_str(&json).unwrap();
+    assert_eq!(
+        back.find_tool("codeql")
+            .unwrap()
+            .get_metadata("license")
+            .map(String::as_str),
+        Some("mit")
+    );
+
+    let mut old = serde_json::to_value(ToolCapability::new("clippy", true)).unwrap();
+    old.as_object_mut().unwrap().remove("metadata");
+    let bare: ToolCapability = serde_json::from_value(old).unwrap();
+    assert!(bare.metadata.is_empty());
+}
@@ -0,0 +1,43 @@
+//! Version probes must not stall on commands with large output
+
+#![cfg(all(unix, feature = "probe"))]
+
+use std::time::{Duration, Instant};
+use worker_capabilities::*;
+
+#[test]
+fn large_output_is_drained_before_the_timeout() {
+    // Far more than a pipe buffer on both streams, then the version
+    let script =
+        "head -c 1000000 /dev/zero >&2; head -c 1000000 /dev/zero; echo; echo 'tool 1.2.3'";
+    let checker = ProbingToolChecker::new()
+        .with_command("tool", ["sh", "-c", script])
+        .with_timeout(Duration::from_secs(10));
+
+    let started = Instant::now();
+    let status = checker.probe("tool");
+    assert!(status.available);
+    assert_eq!(status.version.as_deref(), Some("1.2.3"));
+    assert!(started.elapsed() < Duration::from_secs(5));
+}
+
+#[test]
+fn probes_that_outlive_the_timeout_are_missing() {
+    let checker = ProbingToolChecker::new()
+        .with_command("tool", ["sh", "-c", "sleep 5; echo 'tool 1.2.3'"])
+        .with_timeout(Duration::from_millis(200));
+    assert!(!checker.probe("tool").available);
+}
+
+#[test]
+fn timed_out_probes_kill_the_processes_they_started() {
+    let marker = std::env::temp_dir().join(format!("probe-survivor-{}", std::process::id()));
+    let script = format!("(sleep 1; touch '{}') & sleep 30", marker.display());
+    let checker = ProbingToolChecker::new()
+        .with_command("tool", ["sh".to_string(), "-c".to_string(), script])
+        .with_timeout(Duration::from_millis(200));
+
+    assert!(!checker.probe("tool").available);
+    std::thread::sleep(Duration::from_millis(1500));
+    assert!(!marker.exists());
+}
@@ -0,0 +1,69 @@
+//! Structured tool statuses carry where a tool was found and which version it is
+
+use std::path::PathBuf;
+
+use worker_capabilities::clock::{self, MockClock};
+use worker_capabilities::*;
+
+fn inventory(tool: &str) -> ToolStatus {
+    match tool {
+        "semgrep" => ToolStatus::found()
+            .with_version("1.50.0")
+            .with_path("/usr/local/bin/semgrep")
+            .with_sha256(" ABCDEF "),
+        _ => ToolStatus::missing(),
+    }
+}
+
+#[test]
+fn statuses_are_stamped_with_the_check_time() {
+    clock::with_clock(MockClock::at_unix(1_700_000_000), || {
+        let status = inventory("semgrep");
+        assert!(status.available);
+        assert_eq!(status.checked_at, 1_700_000_000);
+        assert_eq!(status.sha256.as_deref(), Some("abcdef"));
+
+        let missing = inventory("codeql");
+        assert!(!missing.available && missing.path.is_none());
+        assert_eq!(missing.checked_at, 1_700_000_000);
+    });
+}
+
+#[test]
+fn status_checkers_answer_the_bool_and_version_queries() {
+    let checker = StatusChecker::new(inventory);
+    assert!(checker.is_available("semgrep"));
+    assert!(!checker.is_available("codeql"));
+    assert_eq!(
+        checker.detected_version("semgrep").as_deref(),
+        Some("1.50.0")
+    );
+}
+
+#[test]
+fn closures_report_minimal_statuses() {
+    let status = (|tool: &str| tool == "clippy").status("clippy");
+    assert!(status.available);
+    assert_eq!(status.version, None);
+    assert_eq!(status.path, None);
+}
+
+#[test]
+fn matches_and_explanations_show_the_status_of_the_tool_used() {
+    let caps = Capabilities::new("worker-1").with_static_analysis("semgrep", true);
+    let checker = StatusChecker::new(inventory);
+
+    let result = caps.satisfies(
+        &Requirements::new().requires(CAPABILITY_STATIC_ANALYSIS),
+        &checker,
+    );
+    let status = result.clauses[0].status.as_ref().unwrap();
+    assert_eq!(status.path, Some(PathBuf::from("/usr/local/bin/semgrep")));
+
+    let report = caps.explain(&checker);
+    assert_eq!(
+        report.tools[0].status.as_ref().unwrap().version.as_deref(),
+        Some("1.50.0")
+    );
+    assert!(report.to_string().contains("semgrep 1.50.0"));
+}
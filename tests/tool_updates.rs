@@ -0,0 +1,41 @@
+//! Tools can be removed, updated or replaced after construction
+
+use worker_capabilities::*;
+
+fn worker() -> Capabilities {
+    Capabilities::new("worker-1")
+        .with_static_analysis("semgrep", true)
+        .with_security_tool("semgrep", false)
+        .with_fuzzing_tool("cargo-fuzz", false)
+}
+
+#[test]
+fn removal_covers_every_category() {
+    let mut caps = worker();
+    assert!(caps.remove_tool("semgrep"));
+    assert!(caps.find_tool("semgrep").is_none());
+    assert!(caps.find_tool("cargo-fuzz").is_some());
+    assert!(!caps.remove_tool("semgrep"));
+}
+
+#[test]
+fn updates_apply_to_every_declaration() {
+    let mut caps = worker();
+    assert!(caps.update_tool("semgrep", |tool| tool.version = Some("1.50.0".to_string())));
+    assert!(caps
+        .iter_tools()
+        .filter(|(_, tool)| tool.tool_name == "semgrep")
+        .all(|(_, tool)| tool.version.as_deref() == Some("1.50.0")));
+    assert!(!caps.update_tool("bandit", |tool| tool.required = true));
+}
+
+#[test]
+fn replacement_never_adds_tools() {
+    let mut caps = worker();
+    let replacement = ToolCapability::new("cargo-fuzz", true).with_version("0.11.0");
+    assert!(caps.replace_tool("cargo-fuzz", replacement.clone()));
+    assert_eq!(caps.fuzzing_tools, vec![replacement]);
+
+    assert!(!caps.replace_tool("afl", ToolCapability::new("afl", true)));
+    assert!(caps.find_tool("afl").is_none());
+}
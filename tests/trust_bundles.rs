@@ -0,0 +1,79 @@
+//! Trust bundles are only accepted from pinned anchors, and their settings govern verification
+
+use worker_capabilities::*;
+
+fn coordinator() -> CapabilitySigner {
+    CapabilitySigner::new("coordinator-key", "coordinator")
+}
+
+fn anchor() -> TrustAnchor {
+    TrustAnchor {
+        name: "coordinator".to_string(),
+        public_key: coordinator().public_key(),
+    }
+}
+
+fn attested_worker(id: &str, attester: &CapabilitySigner) -> Capabilities {
+    Capabilities::new(id).with_attested_tool(CAPABILITY_STATIC_ANALYSIS, "clippy", true, attester)
+}
+
+#[test]
+fn bundles_require_a_pinned_anchor() {
+    let bundle = TrustBundle::new(1)
+        .with_anchor(anchor())
+        .sign(&coordinator());
+
+    let mut unpinned = CapabilityRegistry::new();
+    assert_eq!(
+        unpinned.apply_trust_bundle(&bundle, &MockVerifier),
+        Err(TrustBundleError::UntrustedSigner("coordinator".to_string()))
+    );
+
+    let mut pinned = CapabilityRegistry::new().with_trust_anchor(anchor());
+    assert_eq!(pinned.apply_trust_bundle(&bundle, &MockVerifier), Ok(0));
+}
+
+#[test]
+fn trust_on_first_use_accepts_only_the_first_bundle() {
+    let mut registry = CapabilityRegistry::new().with_trust_on_first_use();
+    let bundle = TrustBundle::new(1)
+        .with_anchor(anchor())
+        .sign(&coordinator());
+    assert_eq!(registry.apply_trust_bundle(&bundle, &MockVerifier), Ok(0));
+
+    let impostor = CapabilitySigner::new("other-key", "impostor");
+    let takeover = TrustBundle::new(2).sign(&impostor);
+    assert_eq!(
+        registry.apply_trust_bundle(&takeover, &MockVerifier),
+        Err(TrustBundleError::UntrustedSigner("impostor".to_string()))
+    );
+}
+
+#[test]
+fn bundle_settings_reject_untrusted_attesters() {
+    let ci = CapabilitySigner::new("ci-key", "ci");
+    let rogue = CapabilitySigner::new("rogue-key", "rogue");
+    let mut registry = CapabilityRegistry::new()
+        .with_algorithms(
+            AlgorithmRegistry::empty().with_algorithm(DEFAULT_ATTESTATION_ALGORITHM, MockVerifier),
+        )
+        .with_trust_anchor(anchor());
+    registry.register(attested_worker("trusted", &ci));
+    registry.register(attested_worker("untrusted", &rogue));
+    assert_eq!(registry.find_verified_workers().len(), 2);
+
+    let bundle = TrustBundle::new(1)
+        .with_anchor(anchor())
+        .with_attester(AttesterEntry::new("ci", ci.public_key()))
+        .with_settings(TrustSettings {
+            require_trusted_attester: true,
+            max_attestation_age_days: Some(30),
+        })
+        .sign(&coordinator());
+    registry.apply_trust_bundle(&bundle, &MockVerifier).unwrap();
+
+    let verified = registry.find_verified_workers();
+    assert_eq!(verified.len(), 1);
+    assert_eq!(verified[0].id, "trusted");
+    assert_eq!(registry.max_attestation_age(), Some(30 * 24 * 60 * 60));
+}
@@ -0,0 +1,74 @@
+//! 0.1 capability sets convert losslessly into the current model
+
+use std::collections::HashMap;
+
+use worker_capabilities::{prelude, v1, Capabilities, CapabilityExpiration, ToolCapability};
+
+fn legacy_tool(name: &str) -> v1::ToolCapability {
+    v1::ToolCapability {
+        tool_name: name.to_string(),
+        required: true,
+        alternatives: vec![format!("{}-alt", name)],
+        attestation: None,
+        permissions: Default::default(),
+        expiration: Default::default(),
+        verified: true,
+    }
+}
+
+fn legacy() -> v1::Capabilities {
+    let mut expired = legacy_tool("afl");
+    expired.expiration = CapabilityExpiration {
+        expires_at: 1,
+        ..Default::default()
+    };
+    let mut limited = legacy_tool("valgrind");
+    limited.permissions.memory_limit_mb = 512;
+
+    v1::Capabilities {
+        id: "legacy-1".to_string(),
+        static_analysis_tools: vec![legacy_tool("clippy")],
+        security_scanning_tools: vec![],
+        dynamic_analysis_tools: vec![limited],
+        fuzzing_tools: vec![expired],
+        test_framework_tools: vec![],
+        flags: HashMap::from([("ast_support".to_string(), true)]),
+        metadata: HashMap::from([("region".to_string(), "eu".to_string())]),
+    }
+}
+
+#[test]
+fn security_fields_are_carried_across() {
+    let caps = Capabilities::from(legacy());
+    assert_eq!(caps.id, "legacy-1");
+    assert!(caps.has_flag("ast_support"));
+    assert_eq!(caps.get_metadata("region").map(String::as_str), Some("eu"));
+
+    let clippy = caps.find_tool("clippy").unwrap();
+    assert!(clippy.verified);
+    assert_eq!(clippy.alternatives, vec!["clippy-alt"]);
+    assert_eq!(
+        caps.find_tool("valgrind")
+            .unwrap()
+            .permissions
+            .memory_limit_mb,
+        512
+    );
+    assert!(caps.find_tool("afl").unwrap().is_expired());
+}
+
+#[test]
+fn tools_convert_on_their_own() {
+    let tool = ToolCapability::from(legacy_tool("semgrep"));
+    assert_eq!(tool.tool_name, "semgrep");
+    assert!(tool.required);
+    assert!(tool.version.is_none());
+    assert!(tool.platforms.is_empty());
+}
+
+#[test]
+fn there_is_a_single_current_model() {
+    // The prelude and the crate root name the same types
+    let caps: prelude::Capabilities = Capabilities::from(legacy());
+    let _: &prelude::ToolCapability = &caps.static_analysis_tools[0];
+}
@@ -0,0 +1,73 @@
+//! Capability sets are checked against the documented limits
+
+use worker_capabilities::*;
+
+fn oversized() -> Capabilities {
+    let long_name = "x".repeat(MAX_TOOL_NAME_LENGTH + 1);
+    let alternatives = (0..=MAX_ALTERNATIVE_TOOLS)
+        .map(|i| format!("alt-{}", i))
+        .collect();
+    let mut caps = Capabilities::new(" ").with_static_analysis(long_name, true);
+    caps.add_tools_from(
+        CAPABILITY_STATIC_ANALYSIS,
+        &[ToolCapability::new("semgrep", true).with_alternatives(alternatives)],
+    );
+    caps
+}
+
+#[test]
+fn every_violation_is_reported() {
+    let errors = oversized().validate().unwrap_err();
+    assert_eq!(errors.len(), 3);
+    assert_eq!(errors[0], ValidationError::EmptyId);
+    assert!(matches!(
+        errors[1],
+        ValidationError::ToolNameTooLong { length, .. } if length == MAX_TOOL_NAME_LENGTH + 1
+    ));
+    assert_eq!(
+        errors[2],
+        ValidationError::TooManyAlternatives {
+            tool: "semgrep".to_string(),
+            count: MAX_ALTERNATIVE_TOOLS + 1,
+        }
+    );
+}
+
+#[test]
+fn metadata_and_tool_counts_are_limited() {
+    let mut caps = Capabilities::new("worker-1");
+    for i in 0..=MAX_METADATA_ENTRIES {
+        caps = caps.with_metadata(format!("key-{}", i), "value");
+    }
+    assert_eq!(
+        caps.validate(),
+        Err(vec![ValidationError::TooManyMetadataEntries {
+            count: MAX_METADATA_ENTRIES + 1,
+        }])
+    );
+
+    let tools = (0..=MAX_TOOLS_PER_WORKER).map(|i| (format!("tool-{}", i), false));
+    let caps = Capabilities::new("worker-1").with_tools(CAPABILITY_STATIC_ANALYSIS, tools);
+    assert_eq!(
+        caps.validate(),
+        Err(vec![ValidationError::TooManyTools {
+            count: MAX_TOOLS_PER_WORKER + 1,
+        }])
+    );
+    assert!(Capabilities::new("worker-1").validate().is_ok());
+}
+
+#[test]
+fn validating_registries_reject_invalid_sets() {
+    let mut lenient = CapabilityRegistry::new();
+    assert!(lenient.try_register(oversized()).is_ok());
+
+    let mut strict = CapabilityRegistry::new().with_validation();
+    assert_eq!(strict.try_register(oversized()).unwrap_err().len(), 3);
+    strict.register(oversized());
+    assert!(strict.get(" ").is_none());
+
+    let rejection = strict.registration_rejections().next().unwrap();
+    assert_eq!(rejection.worker_id, " ");
+    assert_eq!(strict.get_statistics().rejected_registrations, 1);
+}
@@ -0,0 +1,83 @@
+//! Verification reports why a capability failed instead of a bare bool
+
+use worker_capabilities::*;
+
+fn verifier() -> AlgorithmRegistry {
+    AlgorithmRegistry::empty().with_algorithm(DEFAULT_ATTESTATION_ALGORITHM, MockVerifier)
+}
+
+fn attested(tool: &str) -> ToolCapability {
+    let tool = ToolCapability::new(tool, true);
+    let attestation = CapabilitySigner::new("ci-key", "ci").attest(&tool);
+    tool.with_attestation(attestation)
+}
+
+#[test]
+fn attestation_failures_name_the_reason() {
+    assert_eq!(
+        attested("clippy").try_verify_attestation_with(&verifier()),
+        Ok(())
+    );
+    assert_eq!(
+        ToolCapability::new("clippy", true).try_verify_attestation_with(&verifier()),
+        Err(VerificationError::MissingAttestation {
+            tool: "clippy".to_string()
+        })
+    );
+
+    let tampered = attested("clippy").with_alternatives(vec!["clippy-driver".to_string()]);
+    let error = tampered
+        .try_verify_attestation_with(&verifier())
+        .unwrap_err();
+    assert_eq!(
+        error,
+        VerificationError::HashMismatch {
+            tool: "clippy".to_string()
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        "attestation of tool 'clippy' does not match its capability hash"
+    );
+}
+
+#[test]
+fn set_verification_reports_expired_and_revoked_tools() {
+    let mut caps = Capabilities::new("worker-1");
+    caps.add_tools_from(CAPABILITY_STATIC_ANALYSIS, &[attested("clippy")]);
+    assert!(caps.try_verify_all_capabilities_with(&verifier()).is_ok());
+
+    caps.find_tool_mut("clippy").unwrap().expiration.expires_at = 1;
+    assert_eq!(
+        caps.try_verify_all_capabilities_with(&verifier()),
+        Err(VerificationError::Expired {
+            tool: "clippy".to_string()
+        })
+    );
+
+    let mut caps = Capabilities::new("worker-1");
+    caps.add_tools_from(CAPABILITY_STATIC_ANALYSIS, &[attested("clippy")]);
+    caps.find_tool_mut("clippy")
+        .unwrap()
+        .revoke("compromised".to_string(), "security".to_string());
+    assert_eq!(
+        caps.try_verify_all_capabilities_with(&verifier()),
+        Err(VerificationError::Revoked {
+            tool: "clippy".to_string(),
+            reason: Some("compromised".to_string()),
+        })
+    );
+}
+
+#[test]
+fn untrusted_attesters_are_named() {
+    let tool = attested("clippy");
+    let policy = TrustPolicy::new().with_attester(AttesterEntry::new("release", "release-key"));
+    assert_eq!(
+        tool.try_verify_trusted(CAPABILITY_STATIC_ANALYSIS, &policy),
+        Err(VerificationError::UntrustedAttester {
+            tool: "clippy".to_string(),
+            attester: "ci".to_string(),
+        })
+    );
+}
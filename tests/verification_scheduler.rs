@@ -0,0 +1,128 @@
+//! The verification scheduler records worker health and reports status transitions,
+//! and spawned sweepers prune expired attestations
+
+#![cfg(feature = "tokio")]
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, RwLock};
+use std::time::Duration;
+
+use worker_capabilities::clock::{self, MockClock};
+use worker_capabilities::*;
+
+fn registry() -> Arc<RwLock<CapabilityRegistry>> {
+    let mut registry = CapabilityRegistry::new();
+    registry.register(
+        Capabilities::new("worker-1")
+            .with_static_analysis("clippy", true)
+            .with_security_tool("semgrep", false),
+    );
+    Arc::new(RwLock::new(registry))
+}
+
+/// Checker where clippy is always installed and semgrep only while `semgrep` is set
+fn checker(semgrep: &Arc<AtomicBool>) -> impl Fn(&str) -> bool + Send + Sync + 'static {
+    let semgrep = semgrep.clone();
+    move |tool: &str| tool == "clippy" || (tool == "semgrep" && semgrep.load(Ordering::SeqCst))
+}
+
+#[test]
+fn only_status_changes_are_reported() {
+    let registry = registry();
+    let semgrep = Arc::new(AtomicBool::new(false));
+    let scheduler = VerificationScheduler::new(registry.clone(), checker(&semgrep));
+
+    let transitions = scheduler.run_once();
+    assert_eq!(transitions.len(), 1);
+    assert_eq!(transitions[0].from, None);
+    assert_eq!(transitions[0].to, WorkerHealth::Degraded);
+    assert_eq!(transitions[0].report.unavailable_optional, vec!["semgrep"]);
+    assert!(scheduler.run_once().is_empty());
+
+    semgrep.store(true, Ordering::SeqCst);
+    let transitions = scheduler.run_once();
+    assert_eq!(transitions[0].from, Some(WorkerHealth::Degraded));
+    assert_eq!(
+        registry
+            .read()
+            .unwrap()
+            .worker_health("worker-1")
+            .unwrap()
+            .status,
+        WorkerHealth::Healthy
+    );
+}
+
+#[test]
+fn spawned_schedulers_call_back_on_transitions() {
+    let (sender, received) = mpsc::channel();
+    let semgrep = Arc::new(AtomicBool::new(true));
+    let scheduler = VerificationScheduler::new(registry(), checker(&semgrep))
+        .with_interval(Duration::from_millis(10))
+        .on_transition(move |transition| {
+            let _ = sender.send(transition.to);
+        });
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .unwrap();
+    let statuses = runtime.block_on(async {
+        let handle = scheduler.spawn();
+        let mut statuses = Vec::new();
+        for _ in 0..500 {
+            statuses.extend(received.try_iter());
+            if statuses.len() == 2 {
+                break;
+            }
+            if statuses.len() == 1 {
+                semgrep.store(false, Ordering::SeqCst);
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        handle.stop().await;
+        statuses
+    });
+
+    assert_eq!(
+        statuses,
+        vec![WorkerHealth::Healthy, WorkerHealth::Degraded]
+    );
+}
+
+#[test]
+fn spawned_sweepers_prune_with_the_spawning_clock() {
+    let clock = MockClock::at_unix(1_700_000_000);
+    let manager = clock::with_clock(clock.clone(), || {
+        let mut manager = AttestationManager::new();
+        manager.add_attestation(
+            "clippy".to_string(),
+            CapabilitySigner::new("key", "ci").attest_hash("a"),
+        );
+        Arc::new(RwLock::new(manager))
+    });
+    let events = manager.write().unwrap().subscribe();
+    clock.advance(Duration::from_secs(
+        (ATTESTATION_EXPIRY_DAYS + 1) * 24 * 60 * 60,
+    ));
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .unwrap();
+    runtime.block_on(async {
+        let handle = clock::with_clock(clock, || {
+            AttestationManager::spawn_sweeper(manager.clone(), Duration::from_millis(10))
+        });
+        for _ in 0..500 {
+            if manager.read().unwrap().count() == 0 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        handle.stop().await;
+    });
+
+    assert_eq!(manager.read().unwrap().count(), 0);
+    assert!(matches!(events.try_recv(), Ok(SweepEvent::Pruned { tool, .. }) if tool == "clippy"));
+}
@@ -0,0 +1,29 @@
+//! Declared versions stand in for detected ones, so attestations must cover them
+
+use worker_capabilities::*;
+
+fn attested(version: &str) -> ToolCapability {
+    let mut tool = ToolCapability::new("clippy", true)
+        .with_version_req(">=2.0")
+        .unwrap();
+    tool.version = Some(version.to_string());
+    let attestation = CapabilitySigner::new("key", "ci").attest(&tool);
+    tool.attestation = Some(attestation);
+    tool
+}
+
+#[test]
+fn undetected_versions_fall_back_to_the_declared_version() {
+    let no_versions = |_: &str| true;
+    assert!(attested("2.1.0").checked_version_matches(&no_versions, "clippy"));
+    assert!(!attested("1.9.0").checked_version_matches(&no_versions, "clippy"));
+}
+
+#[test]
+fn editing_the_declared_version_breaks_the_attestation() {
+    let mut tool = attested("1.9.0");
+    tool.version = Some("2.1.0".to_string());
+    assert!(tool.declared_version_matches());
+    assert!(!tool.verify_capability_hash());
+    assert!(!tool.verify_attestation_with(&MockVerifier));
+}
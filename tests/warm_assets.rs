@@ -0,0 +1,84 @@
+//! Workers already primed for a repository are ranked first while their assets are fresh
+
+use std::time::Duration;
+
+use worker_capabilities::clock::{self, MockClock};
+use worker_capabilities::*;
+
+const NOW: u64 = 1_700_000_000;
+const REPOSITORY: &str = "github.com/org/monorepo";
+
+fn registry() -> CapabilityRegistry {
+    let mut registry = CapabilityRegistry::new();
+    registry.register(Capabilities::new("a-cold"));
+    registry.register(
+        Capabilities::new("b-rules")
+            .with_warm_asset(WarmAsset::new(WarmAssetKind::RulePack, REPOSITORY).primed_at(NOW)),
+    );
+    registry.register(
+        Capabilities::new("c-primed")
+            .with_warm_asset(WarmAsset::new(WarmAssetKind::Repository, REPOSITORY).primed_at(NOW))
+            .with_warm_asset(
+                WarmAsset::new(WarmAssetKind::DependencyCache, REPOSITORY).primed_at(NOW - 7_200),
+            ),
+    );
+    registry
+}
+
+fn ranking(registry: &CapabilityRegistry, requirements: &Requirements) -> Vec<String> {
+    registry
+        .rank_workers(requirements)
+        .into_iter()
+        .map(|caps| caps.id.clone())
+        .collect()
+}
+
+#[test]
+fn primed_workers_are_preferred() {
+    clock::with_clock(MockClock::at_unix(NOW), || {
+        let registry = registry();
+        let requirements = Requirements::new().prefer_primed_for(REPOSITORY);
+        assert_eq!(
+            ranking(&registry, &requirements),
+            vec!["c-primed", "a-cold", "b-rules"]
+        );
+        assert_eq!(
+            registry
+                .get("c-primed")
+                .unwrap()
+                .preference_score(&requirements),
+            2
+        );
+    });
+}
+
+#[test]
+fn stale_assets_do_not_count() {
+    let clock = MockClock::at_unix(NOW);
+    clock::with_clock(clock.clone(), || {
+        let registry = registry();
+        let requirements = Requirements::new()
+            .prefer_primed_for(REPOSITORY)
+            .with_max_warm_age(3_600);
+        let primed = registry.get("c-primed").unwrap();
+        assert_eq!(primed.preference_score(&requirements), 1);
+
+        clock.advance(Duration::from_secs(DEFAULT_WARM_ASSET_MAX_AGE_SECONDS + 1));
+        assert_eq!(
+            primed.preference_score(&Requirements::new().prefer_primed_for(REPOSITORY)),
+            0
+        );
+        assert_eq!(
+            ranking(&registry, &requirements),
+            vec!["a-cold", "b-rules", "c-primed"]
+        );
+    });
+}
+
+#[test]
+fn freshness_is_measured_from_the_priming_time() {
+    let asset = WarmAsset::new(WarmAssetKind::RulePack, "semgrep-rules").primed_at(NOW);
+    assert!(asset.is_fresh(NOW + 60, 60));
+    assert!(!asset.is_fresh(NOW + 61, 60));
+    assert!(asset.is_fresh(NOW - 60, 0));
+}
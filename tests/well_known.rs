@@ -0,0 +1,44 @@
+//! Well-known flags and metadata keys have typed accessors
+
+use worker_capabilities::well_known::{self, EVM_SUPPORT, LLM_SUPPORT};
+use worker_capabilities::*;
+
+#[test]
+fn flag_accessors_read_the_well_known_flags() {
+    let caps = Capabilities::new("worker").with_flag(LLM_SUPPORT);
+    assert!(caps.llm_support());
+    assert!(!caps.evm_support());
+    assert!(!caps.ast_support());
+    assert!(Capabilities::new("evm")
+        .with_flag(EVM_SUPPORT)
+        .evm_support());
+}
+
+#[test]
+fn metadata_accessors_round_trip() {
+    let caps = Capabilities::new("worker")
+        .with_worker_version("2.4.0")
+        .with_platform("linux", "x86_64")
+        .with_max_concurrent_jobs(8)
+        .with_memory_limit_mb(16_384);
+
+    assert_eq!(caps.worker_version(), Some("2.4.0"));
+    assert_eq!(caps.platform(), Some("linux-x86_64"));
+    assert_eq!(caps.max_concurrent_jobs(), Some(8));
+    assert_eq!(caps.memory_limit_mb(), Some(16_384));
+    assert_eq!(
+        caps.get_metadata(well_known::MAX_CONCURRENT_JOBS)
+            .map(String::as_str),
+        Some("8")
+    );
+}
+
+#[test]
+fn malformed_numbers_parse_as_none() {
+    let caps = Capabilities::new("worker")
+        .with_metadata(well_known::MAX_CONCURRENT_JOBS, " 4 ")
+        .with_metadata(well_known::MEMORY_LIMIT_MB, "lots");
+    assert_eq!(caps.max_concurrent_jobs(), Some(4));
+    assert_eq!(caps.memory_limit_mb(), None);
+    assert_eq!(Capabilities::new("bare").max_concurrent_jobs(), None);
+}
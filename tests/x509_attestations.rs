@@ -0,0 +1,110 @@
+//! Certificate-backed attestations are anchored in a configured root store
+
+#![cfg(all(feature = "x509", feature = "ed25519"))]
+
+use rcgen::{
+    BasicConstraints, Certificate, CertificateParams, ExtendedKeyUsagePurpose, IsCa, KeyPair,
+    PKCS_ED25519,
+};
+use worker_capabilities::*;
+
+const SECRET: [u8; 32] = [7; 32];
+
+struct Pki {
+    root: Certificate,
+    intermediate: Certificate,
+    intermediate_key: KeyPair,
+}
+
+fn ca(params: CertificateParams) -> CertificateParams {
+    let mut params = params;
+    params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+    params
+}
+
+fn pki() -> Pki {
+    let root_key = KeyPair::generate_for(&PKCS_ED25519).unwrap();
+    let root = ca(CertificateParams::new(Vec::new()).unwrap())
+        .self_signed(&root_key)
+        .unwrap();
+    let intermediate_key = KeyPair::generate_for(&PKCS_ED25519).unwrap();
+    let intermediate = ca(CertificateParams::new(Vec::new()).unwrap())
+        .signed_by(&intermediate_key, &root, &root_key)
+        .unwrap();
+    Pki {
+        root,
+        intermediate,
+        intermediate_key,
+    }
+}
+
+/// Leaf certificate for the attestation key, followed by the intermediate
+fn chain(pki: &Pki, usage: Option<ExtendedKeyUsagePurpose>) -> String {
+    let pkcs8 = [
+        &[
+            0x30, 0x2e, 0x02, 0x01, 0x00, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x04, 0x22,
+            0x04, 0x20,
+        ][..],
+        &SECRET,
+    ]
+    .concat();
+    let leaf_key = KeyPair::try_from(pkcs8.as_slice()).unwrap();
+    let mut params = CertificateParams::new(vec!["ci.example.com".to_string()]).unwrap();
+    params.extended_key_usages = usage.into_iter().collect();
+    let leaf = params
+        .signed_by(&leaf_key, &pki.intermediate, &pki.intermediate_key)
+        .unwrap();
+    format!("{}{}", leaf.pem(), pki.intermediate.pem())
+}
+
+fn attested(signer: &dyn AttestationSigner) -> ToolCapability {
+    let tool = ToolCapability::new("clippy", true);
+    tool.clone().with_attestation(signer.attest(&tool))
+}
+
+fn algorithms(pki: &Pki) -> AlgorithmRegistry {
+    AlgorithmRegistry::new().with_x509_roots(X509RootStore::from_pem(&pki.root.pem()).unwrap())
+}
+
+#[test]
+fn chains_through_intermediates_verify() {
+    let pki = pki();
+    let chain = chain(&pki, Some(ExtendedKeyUsagePurpose::CodeSigning));
+    let roots = X509RootStore::from_pem(&pki.root.pem()).unwrap();
+    assert_eq!(roots.verify_chain(&chain), Ok(()));
+
+    let signer = X509Signer::new(Ed25519Signer::new(SECRET, "ci"), chain).unwrap();
+    let tool = attested(&signer);
+    assert_eq!(tool.attestation.as_ref().unwrap().algorithm, "X509/Ed25519");
+    assert!(tool.verify_attestation_with(&algorithms(&pki)));
+
+    // A chain from another PKI is not trusted
+    let other = self::pki();
+    assert!(!tool.verify_attestation_with(&algorithms(&other)));
+}
+
+#[test]
+fn leaves_must_allow_code_signing_and_match_the_key() {
+    let pki = pki();
+    let server_only = chain(&pki, Some(ExtendedKeyUsagePurpose::ServerAuth));
+    let signer = X509Signer::new(Ed25519Signer::new(SECRET, "ci"), server_only).unwrap();
+    assert!(!attested(&signer).verify_attestation_with(&algorithms(&pki)));
+
+    let unrestricted = chain(&pki, None);
+    let wrong_key = X509Signer::new(Ed25519Signer::new([8; 32], "ci"), unrestricted).unwrap();
+    assert!(!attested(&wrong_key).verify_attestation_with(&algorithms(&pki)));
+}
+
+#[test]
+fn signers_need_a_certificate() {
+    let signer = || Ed25519Signer::new(SECRET, "ci");
+    assert_eq!(
+        X509Signer::new(signer(), "").err(),
+        Some(X509Error::EmptyChain)
+    );
+    assert!(X509RootStore::from_pem("no certificates")
+        .unwrap()
+        .is_empty());
+    let garbled = "-----BEGIN CERTIFICATE-----\n!!!\n-----END CERTIFICATE-----\n";
+    assert!(X509RootStore::from_pem(garbled).is_err());
+}